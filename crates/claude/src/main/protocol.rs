@@ -145,6 +145,10 @@ mod tests {
                 body: "hi".into(),
                 created_at: "now".into(),
                 acked_at: None,
+                archived_at: None,
+                reply_to: None,
+                thread_id: None,
+                payload: None,
             }),
         );
         assert_eq!(ok["result"]["isError"], Value::Null);
@@ -0,0 +1,28 @@
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub(crate) enum AttachmentsCommand {
+    /// Remove attachments no message, memory, task, or avatar references
+    /// (after a 24h grace period) and files no attachment uses.
+    Gc {
+        /// Report what would be removed without deleting anything.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+    /// Search attachment text and filenames; each hit lists the messages
+    /// that embed it.
+    Search {
+        query: String,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Stored vs. deduplicated bytes and the configured quota.
+    Usage,
+    /// Show, set, or clear the total-size quota for stored attachment bytes.
+    Quota {
+        #[arg(long, conflicts_with = "clear")]
+        set: Option<u64>,
+        #[arg(long, default_value_t = false)]
+        clear: bool,
+    },
+}
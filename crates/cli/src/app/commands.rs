@@ -2,6 +2,7 @@
 //! of the old app/mod.rs, split for the 500-LoC cap, #158). The
 //! top-level Cli/Command shells stay in the parent module.
 
+use super::PageArgs;
 use clap::{ArgAction, Subcommand};
 use std::path::PathBuf;
 
@@ -10,9 +11,15 @@ pub(crate) enum AuditCommand {
     /// Watch a directory recursively until interrupted.
     Watch { root: PathBuf },
     /// List changes that have not yet been owner-approved or quarantined.
-    Pending,
+    Pending {
+        #[command(flatten)]
+        page: PageArgs,
+    },
     /// List all recorded changes.
-    List,
+    List {
+        #[command(flatten)]
+        page: PageArgs,
+    },
     /// Mark one event approved, or approve all currently pending events.
     Approve {
         id: Option<String>,
@@ -46,6 +53,12 @@ pub(crate) enum InboxCommand {
         #[arg(long = "forward-arg", action = ArgAction::Append)]
         forward_args: Vec<String>,
     },
+    /// Unread counts per channel, work session and DM, from the agent's
+    /// read markers, with unread @mentions.
+    Summary {
+        #[arg(long)]
+        agent: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -114,6 +127,8 @@ pub(crate) enum TaskCommand {
     List {
         #[arg(long)]
         status: Option<String>,
+        #[command(flatten)]
+        page: PageArgs,
     },
     Get {
         id: String,
@@ -154,13 +169,16 @@ pub(crate) enum MemoryCommand {
         /// short_term | episodic | semantic
         #[arg(long, default_value = "short_term")]
         tier: String,
-        /// global | workspace
+        /// global | workspace | session | agent_private
         #[arg(long, default_value = "global")]
         scope: String,
         #[arg(long)]
         agent: Option<String>,
         #[arg(long)]
         workspace: Option<String>,
+        /// Work session id (required for --scope session).
+        #[arg(long)]
+        session: Option<String>,
         #[arg(long)]
         title: Option<String>,
         #[arg(long, value_delimiter = ',')]
@@ -168,7 +186,7 @@ pub(crate) enum MemoryCommand {
         body: String,
     },
     List {
-        /// global | workspace
+        /// global | workspace | session | agent_private
         #[arg(long)]
         scope: Option<String>,
         /// short_term | episodic | semantic
@@ -178,9 +196,27 @@ pub(crate) enum MemoryCommand {
         workspace: Option<String>,
         #[arg(long, default_value_t = false)]
         include_stale: bool,
+        /// Read as this agent: adds its private and work-session memories.
+        #[arg(long = "as")]
+        reader: Option<String>,
+        /// Paging flags list newest first instead of by relevance.
+        #[command(flatten)]
+        page: PageArgs,
     },
     Search {
         query: String,
+        /// Read as this agent: adds its private and work-session memories.
+        #[arg(long = "as")]
+        reader: Option<String>,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Show one memory with its relevance, recording the read.
+    Show {
+        id: String,
+        /// Read as this agent (needed for session and private memories).
+        #[arg(long = "as")]
+        reader: Option<String>,
     },
     Stale {
         id: String,
@@ -202,6 +238,24 @@ pub(crate) enum MemoryCommand {
     Compact {
         #[arg(long, default_value_t = 50)]
         keep: usize,
+        /// Only print promote/stale candidates by usage and relevance.
+        #[arg(long, default_value_t = false)]
+        suggest: bool,
+    },
+    /// Find near-duplicate memories; with --apply, merge each cluster into its oldest member.
+    Dedup {
+        #[arg(long)]
+        workspace: Option<String>,
+        /// short_term | episodic | semantic
+        #[arg(long)]
+        tier: Option<String>,
+        #[arg(long, default_value_t = hub::DEFAULT_DEDUP_THRESHOLD)]
+        threshold: f64,
+        #[arg(long, default_value_t = false)]
+        apply: bool,
+        /// Read as this agent: adds its private and work-session memories.
+        #[arg(long = "as")]
+        reader: Option<String>,
     },
     /// Permanently delete memories marked stale.
     PurgeStale,
@@ -227,11 +281,24 @@ pub(crate) enum MemoryCommand {
     Links {
         memory_id: String,
     },
+    /// List messages that cite a memory (works after it is deleted).
+    Citations {
+        memory_id: String,
+    },
+    /// Messages whose cited memories went stale, were deleted, or were revised since.
+    CitationWarnings {
+        /// Only messages this agent sent or received.
+        #[arg(long)]
+        agent: Option<String>,
+    },
     /// Walk related memories out to a given hop depth (0 is a no-op).
     Related {
         memory_id: String,
         #[arg(long, default_value_t = 1)]
         depth: u8,
+        /// Read as this agent: adds its private and work-session memories.
+        #[arg(long = "as")]
+        reader: Option<String>,
     },
     /// Group memories whose title, body, or tags match a topic query.
     Topic {
@@ -251,109 +318,10 @@ pub(crate) enum MemoryCommand {
         #[arg(long, default_value_t = 10)]
         limit: usize,
     },
-}
-
-#[derive(Subcommand)]
-pub(crate) enum MsgCommand {
-    Send {
-        #[arg(long)]
-        from: String,
-        #[arg(long)]
-        to: String,
-        #[arg(long, default_value = "message")]
-        kind: String,
-        #[arg(long)]
-        subject: Option<String>,
-        #[arg(long)]
-        workspace: Option<String>,
-        #[arg(long)]
-        task: Option<String>,
-        body: String,
-    },
-    /// C11: send a task- and/or wake-tagged message, enforcing the same
-    /// rules as the Chat & Memory composer — task targets must already be
-    /// present team/session members (rejected otherwise, no spawn); wake
-    /// may enroll a new identity before delivery, subject to wake policy.
-    Tag {
-        #[arg(long)]
-        from: String,
-        /// Comma-separated recipient agent ids.
-        #[arg(long, value_delimiter = ',')]
-        to: Vec<String>,
-        #[arg(long, default_value_t = false)]
-        task: bool,
-        #[arg(long, default_value_t = false)]
-        wake: bool,
-        #[arg(long)]
-        subject: Option<String>,
-        #[arg(long)]
-        workspace: Option<String>,
-        #[arg(long)]
-        task_id: Option<String>,
-        #[arg(long)]
-        session: Option<String>,
-        /// Inject each accepted target through its harness after the durable
-        /// tagged-send policy accepts delivery. Requires an absolute
-        /// --workspace; rejected targets are never dispatched.
-        #[arg(long, requires = "workspace", default_value_t = false)]
-        dispatch: bool,
-        body: String,
-    },
-    Poll {
-        #[arg(long)]
-        to: String,
-        #[arg(long, default_value_t = false)]
-        no_ack: bool,
-    },
-    List {
-        #[arg(long)]
-        to: Option<String>,
-        #[arg(long)]
-        status: Option<String>,
-    },
-    /// List one Messager-style channel (`channel:<name>` messages only).
-    Channel {
-        channel: String,
-        #[arg(long, default_value_t = 100)]
-        limit: usize,
-    },
-    /// Resolve durable memory references embedded in a message body.
-    Memories { message_id: String },
-    /// Mark that `agent` has read `scope` (a channel id, work session id, or
-    /// `dm-<agent>` pairing) as of now, or an explicit `--at` RFC 3339
-    /// timestamp. Never regresses an existing, more recent marker.
-    Read {
-        #[arg(long)]
-        agent: String,
-        #[arg(long)]
-        scope: String,
-        #[arg(long)]
-        at: Option<String>,
-    },
-    /// List every team member's read marker for `scope`.
-    Readers { scope: String },
-    /// Mark a message done/acked/cancelled.
-    Status {
-        id: String,
-        #[arg(long)]
-        status: String,
-    },
-    /// Edit a message (and every sibling copy of the same team/channel
-    /// broadcast). Only Harbinger's own posts may be edited (CA-106/CA-109).
-    Edit {
-        #[arg(long)]
-        id: String,
-        #[arg(long)]
-        from: String,
-        body: String,
-    },
-    /// Delete (cancel) a message and every sibling copy of the same
-    /// team/channel broadcast. Only Harbinger's own posts may be deleted.
-    Delete {
-        #[arg(long)]
-        id: String,
-        #[arg(long)]
-        from: String,
+    /// Whole-graph export and queries over memory links.
+    Graph {
+        #[command(subcommand)]
+        action: super::MemoryGraphCommand,
     },
 }
 
@@ -374,6 +342,8 @@ pub(crate) enum WakeCommand {
         target: Option<String>,
         #[arg(long, default_value_t = false)]
         pending_only: bool,
+        #[command(flatten)]
+        page: PageArgs,
     },
     /// Mark a wake delivered or cancelled.
     Resolve {
@@ -0,0 +1,51 @@
+use clap::{Args, Subcommand};
+use hub::SecretReference;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub(crate) enum EncryptionCommand {
+    /// Show whether the hub is encrypted and whether its key loads.
+    Status,
+    /// Generate a random key (64 hex characters).
+    Keygen {
+        /// Write it to this file (mode 0600) instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Encrypt existing content and keep new content encrypted.
+    Enable {
+        #[command(flatten)]
+        key: KeyArgs,
+    },
+    /// Re-encrypt everything under a new key; the current key must load.
+    Rotate {
+        #[command(flatten)]
+        key: KeyArgs,
+    },
+    /// Decrypt everything and stop encrypting.
+    Disable,
+}
+
+/// Where the hub key is read from. Only the reference is stored.
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub(crate) struct KeyArgs {
+    /// Environment variable holding the key.
+    #[arg(long)]
+    key_env: Option<String>,
+    /// File holding the key.
+    #[arg(long)]
+    key_file: Option<PathBuf>,
+}
+
+impl KeyArgs {
+    pub(crate) fn reference(self) -> anyhow::Result<SecretReference> {
+        Ok(match (self.key_env, self.key_file) {
+            (Some(name), _) => SecretReference::EnvVar { name },
+            (None, Some(path)) => SecretReference::KeyFile {
+                path: std::path::absolute(path)?.to_string_lossy().to_string(),
+            },
+            (None, None) => anyhow::bail!("give --key-env or --key-file"),
+        })
+    }
+}
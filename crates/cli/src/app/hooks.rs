@@ -0,0 +1,44 @@
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub(crate) enum HooksCommand {
+    /// List installed hooks, hub-wide and for --workspace.
+    List {
+        #[arg(long)]
+        workspace: Option<String>,
+    },
+    /// Show recent hook runs, newest first.
+    Runs {
+        /// pre-send | post-send | pre-task-advance | post-task-advance |
+        /// wake-resolved | memory-written | audit-event
+        #[arg(long)]
+        event: Option<String>,
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Show or change how hooks run.
+    Config {
+        /// Seconds a hook may run before it is killed (a `pre-` hook that
+        /// times out vetoes).
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+        /// Stop running hooks.
+        #[arg(long, default_value_t = false, conflicts_with = "on")]
+        off: bool,
+        /// Run hooks again after --off.
+        #[arg(long, default_value_t = false)]
+        on: bool,
+        /// Ignore hooks in workspaces' .agent/hooks/ (the default).
+        #[arg(long, default_value_t = false, conflicts_with = "workspace_hooks")]
+        no_workspace_hooks: bool,
+        /// Run hooks in trusted workspaces' .agent/hooks/.
+        #[arg(long, default_value_t = false)]
+        workspace_hooks: bool,
+        /// Trust a workspace's hooks; read its .agent/hooks/ first.
+        #[arg(long, value_name = "WORKSPACE")]
+        trust: Vec<String>,
+        /// Stop trusting a workspace's hooks.
+        #[arg(long, value_name = "WORKSPACE")]
+        untrust: Vec<String>,
+    },
+}
@@ -0,0 +1,130 @@
+//! `ca hub` subcommand payloads. Encryption, webhook and hook payloads are
+//! their own sibling modules.
+
+use super::{EncryptionCommand, HooksCommand, WebhookCommand};
+use clap::Subcommand;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub(crate) enum HubCommand {
+    /// Snapshot the hub (online, while agents keep writing) with a hash manifest.
+    Backup {
+        /// Directory to create the backup in (default: <home>/hub-backups).
+        #[arg(long)]
+        to: Option<PathBuf>,
+        /// Backups to keep there; defaults to settings `storage.backup_retention`.
+        #[arg(long)]
+        retention: Option<u32>,
+    },
+    /// List backups, newest first.
+    Backups {
+        /// Directory to list (default: <home>/hub-backups).
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Check a backup's file hashes, database integrity, and audit chain.
+    Verify { backup: PathBuf },
+    /// Verify a backup, then swap it in for the live hub. The replaced files
+    /// are kept under hub-backups/pre-restore-<stamp>/.
+    Restore { backup: PathBuf },
+    /// Write the whole hub as versioned JSON Lines (docs/HUB_EXPORT_FORMAT.md).
+    Export {
+        /// File to write (default: stdout, with the summary on stderr).
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Merge a JSON Lines export into this hub, remapping colliding ids.
+    Import {
+        file: PathBuf,
+        /// Report what would be imported without keeping anything.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+    /// Enforce settings `orchestration.retention_days`: archive, then delete,
+    /// rows past the effective retention for their workspace. Audit events
+    /// are never swept. Reports only unless --apply is given.
+    Retention {
+        /// Count expired rows without changing anything (the default).
+        #[arg(long, default_value_t = false, conflicts_with = "apply")]
+        dry_run: bool,
+        /// Archive and delete expired rows.
+        #[arg(long, default_value_t = false)]
+        apply: bool,
+        /// Export the whole hub first: markdown | jsonl (--apply only).
+        #[arg(long, requires = "apply")]
+        export: Option<String>,
+    },
+    /// Encryption at rest for message, memory, journal and attachment content.
+    Encryption {
+        #[command(subcommand)]
+        action: EncryptionCommand,
+    },
+    /// Look for leaked secrets (API keys, tokens, private keys) in stored
+    /// messages, memories, captures, gate approvals and attachment text.
+    Scan {
+        /// Replace what is found with `[REDACTED:<detector>]` placeholders.
+        #[arg(long, default_value_t = false)]
+        redact: bool,
+    },
+    /// Show or change the secret detectors applied to captures, channel
+    /// replies and memory writes.
+    Redaction {
+        /// Add or replace a custom detector, as NAME=REGEX.
+        #[arg(long, value_name = "NAME=REGEX")]
+        add: Vec<String>,
+        /// Remove a custom detector.
+        #[arg(long, value_name = "NAME")]
+        remove: Vec<String>,
+        /// Turn off a detector (built-in, custom, or `high_entropy`).
+        #[arg(long, value_name = "NAME")]
+        disable: Vec<String>,
+        /// Turn a disabled detector back on.
+        #[arg(long, value_name = "NAME")]
+        enable: Vec<String>,
+        /// Shortest token the entropy detector considers.
+        #[arg(long)]
+        entropy_min_length: Option<usize>,
+        /// Shannon entropy (bits per character) a token needs to be redacted.
+        #[arg(long)]
+        entropy_min_bits: Option<f64>,
+        /// Stop redacting altogether.
+        #[arg(long, default_value_t = false, conflicts_with = "on")]
+        off: bool,
+        /// Redact again after --off.
+        #[arg(long, default_value_t = false)]
+        on: bool,
+    },
+    /// Show or change what an @mention in a channel or session post does
+    /// besides notifying the mentioned agent.
+    Mentions {
+        /// Also wake mentioned agents, subject to wake policy.
+        #[arg(long, default_value_t = false, conflicts_with = "no_wake")]
+        wake: bool,
+        /// Notify only.
+        #[arg(long, default_value_t = false)]
+        no_wake: bool,
+    },
+    /// Show or change how long each message kind may stay unacked before
+    /// it escalates (re-wake, then allocators, then the human).
+    Escalation {
+        /// Set a kind's ack deadline, as KIND=MINUTES (e.g. handoff=30).
+        #[arg(long, value_name = "KIND=MINUTES")]
+        sla: Vec<String>,
+        /// Stop escalating a kind.
+        #[arg(long, value_name = "KIND")]
+        clear: Vec<String>,
+    },
+    /// Take every escalation step that is due now and print them.
+    Escalate,
+    /// Outbound webhook sinks that hub events are POSTed to.
+    Webhook {
+        #[command(subcommand)]
+        action: WebhookCommand,
+    },
+    /// Hook scripts run on hub events (<home>/hooks/<event>,
+    /// <workspace>/.agent/hooks/<event>).
+    Hooks {
+        #[command(subcommand)]
+        action: HooksCommand,
+    },
+}
@@ -0,0 +1,58 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+/// Node/edge filters shared by every `ca memory graph` query.
+#[derive(Args)]
+pub(crate) struct GraphFilterArgs {
+    #[arg(long)]
+    pub(crate) workspace: Option<String>,
+    /// short_term | episodic | semantic
+    #[arg(long)]
+    pub(crate) tier: Option<String>,
+    /// Keep only edges with this relation (case-insensitive).
+    #[arg(long)]
+    pub(crate) relation: Option<String>,
+    #[arg(long, default_value_t = false)]
+    pub(crate) include_stale: bool,
+    /// Read as this agent: adds its private and work-session memories.
+    #[arg(long = "as")]
+    pub(crate) reader: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum MemoryGraphCommand {
+    /// Export the whole (filtered) memory graph.
+    Export {
+        /// dot | graphml | json
+        #[arg(long, default_value = "dot")]
+        format: String,
+        /// Write to a file instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        #[command(flatten)]
+        filter: GraphFilterArgs,
+    },
+    /// Shortest path between two memories, ignoring edge direction.
+    Path {
+        from: String,
+        to: String,
+        /// Read as this agent: adds its private and work-session memories.
+        #[arg(long = "as")]
+        reader: Option<String>,
+    },
+    /// Connected groups of two or more linked memories, largest first.
+    Clusters {
+        #[command(flatten)]
+        filter: GraphFilterArgs,
+    },
+    /// Memories with no links at all.
+    Orphans {
+        #[command(flatten)]
+        filter: GraphFilterArgs,
+    },
+    /// Pairs of live memories linked as "contradicts".
+    Contradictions {
+        #[command(flatten)]
+        filter: GraphFilterArgs,
+    },
+}
@@ -1,27 +1,44 @@
 //! `ca` CLI argument surface: the top-level `Cli`/[`Command`] shells and
 //! the per-domain subcommand enums. The payload enums live in
-//! [`commands`] (split out for the 500-LoC cap, #158); agent/harness/
-//! journal payloads are their own sibling modules.
+//! [`commands`] (split out for the 500-LoC cap, #158); agent/attachments/
+//! encryption/harness/hooks/hub/journal/memory-graph/msg/webhook payloads
+//! and the shared page flags are their own sibling modules.
 
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 mod agent;
+mod attachments;
 mod commands;
+mod encryption;
 mod harness;
+mod hooks;
+mod hub;
 mod journal;
+mod memory_graph;
+mod msg;
+mod page;
+mod webhook;
 
 pub(crate) use agent::AgentCommand;
+pub(crate) use attachments::AttachmentsCommand;
 pub(crate) use commands::{
-    AuditCommand, BudgetCommand, InboxCommand, MemoryCommand, MsgCommand, TaskCommand, WakeCommand,
+    AuditCommand, BudgetCommand, InboxCommand, MemoryCommand, TaskCommand, WakeCommand,
 };
+pub(crate) use encryption::EncryptionCommand;
 pub(crate) use harness::HarnessCommand;
+pub(crate) use hooks::HooksCommand;
+pub(crate) use hub::HubCommand;
 pub(crate) use journal::JournalCommand;
+pub(crate) use memory_graph::{GraphFilterArgs, MemoryGraphCommand};
+pub(crate) use msg::MsgCommand;
+pub(crate) use page::PageArgs;
+pub(crate) use webhook::WebhookCommand;
 
 #[derive(Parser)]
 #[command(name = "ca", about = "Coding-Assistants shared hub CLI")]
 pub(crate) struct Cli {
-    /// Hub data directory (contains hub.db, journals/, markdown/, wake/).
+    /// Hub data directory (contains hub.db, markdown/, wake/, attachments/).
     /// Defaults to $CA_HOME or ~/.coding-assistants.
     #[arg(long, env = "CA_HOME")]
     pub(crate) home: Option<PathBuf>,
@@ -49,7 +66,7 @@ pub(crate) enum Command {
         #[command(subcommand)]
         action: WakeCommand,
     },
-    /// Append to a private, per-agent journal (never shared).
+    /// Append to a per-agent journal (an agent-private memory, never shared).
     Journal {
         #[command(subcommand)]
         action: JournalCommand,
@@ -98,6 +115,16 @@ pub(crate) enum Command {
         #[command(subcommand)]
         action: HarnessCommand,
     },
+    /// Content-addressed attachment storage: search, garbage collection, quota.
+    Attachments {
+        #[command(subcommand)]
+        action: AttachmentsCommand,
+    },
+    /// Hub-wide backup, verification, and restore.
+    Hub {
+        #[command(subcommand)]
+        action: HubCommand,
+    },
     /// Persist a cancellation/shutdown handoff so interrupted work is not lost (C6).
     Shutdown {
         #[arg(long)]
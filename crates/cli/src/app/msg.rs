@@ -0,0 +1,253 @@
+//! `ca msg` subcommand payloads (split from app/commands.rs for the
+//! 500-LoC cap, #158).
+
+use super::PageArgs;
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub(crate) enum MsgCommand {
+    Send {
+        #[arg(long)]
+        from: String,
+        #[arg(long)]
+        to: String,
+        #[arg(long, default_value = "message")]
+        kind: String,
+        #[arg(long)]
+        subject: Option<String>,
+        #[arg(long)]
+        workspace: Option<String>,
+        #[arg(long)]
+        task: Option<String>,
+        /// Message this one replies to; threads it under that message.
+        #[arg(long)]
+        reply_to: Option<String>,
+        /// JSON payload sent with the body, checked against the recipient's
+        /// card `input_schema` (and, for replies, the sender's
+        /// `output_format`). Team sends accept one only as a reply, where
+        /// every copy carries it.
+        #[arg(long, value_parser = parse_payload)]
+        payload: Option<serde_json::Value>,
+        body: String,
+    },
+    /// C11: send a task- and/or wake-tagged message, enforcing the same
+    /// rules as the Chat & Memory composer — task targets must already be
+    /// present team/session members (rejected otherwise, no spawn); wake
+    /// may enroll a new identity before delivery, subject to wake policy.
+    Tag {
+        #[arg(long)]
+        from: String,
+        /// Comma-separated recipient agent ids.
+        #[arg(long, value_delimiter = ',')]
+        to: Vec<String>,
+        #[arg(long, default_value_t = false)]
+        task: bool,
+        #[arg(long, default_value_t = false)]
+        wake: bool,
+        #[arg(long)]
+        subject: Option<String>,
+        #[arg(long)]
+        workspace: Option<String>,
+        #[arg(long)]
+        task_id: Option<String>,
+        #[arg(long)]
+        session: Option<String>,
+        /// Inject each accepted target through its harness after the durable
+        /// tagged-send policy accepts delivery. Requires an absolute
+        /// --workspace; rejected targets are never dispatched.
+        #[arg(long, requires = "workspace", default_value_t = false)]
+        dispatch: bool,
+        /// JSON payload; recipients whose card `input_schema` it does not
+        /// match are rejected.
+        #[arg(long, value_parser = parse_payload)]
+        payload: Option<serde_json::Value>,
+        body: String,
+    },
+    Poll {
+        #[arg(long)]
+        to: String,
+        #[arg(long, default_value_t = false)]
+        no_ack: bool,
+    },
+    List {
+        #[arg(long)]
+        to: Option<String>,
+        #[arg(long)]
+        status: Option<String>,
+        /// List archived messages instead (newest first).
+        #[arg(long, default_value_t = false, conflicts_with_all = ["to", "status"])]
+        archived: bool,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// List one Messager-style channel (`channel:<name>` messages only).
+    Channel {
+        channel: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// List tagged-send outcomes, newest first, for one subject or all.
+    Outcomes {
+        #[arg(long)]
+        subject: Option<String>,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Print a thread: its root, then replies oldest first. Any message
+    /// in the thread can be given.
+    Thread { id: String },
+    /// List threads with replies, most recently active first.
+    Threads {
+        /// Only threads rooted in this channel.
+        #[arg(long)]
+        channel: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Full-text search over message bodies, best match first, with
+    /// snippets. Work-session posts only match for the session's members.
+    Search {
+        /// Words that must all appear (each as a word prefix).
+        query: String,
+        /// Agent searching; sessions it is not in are left out.
+        #[arg(long = "as", default_value = "human")]
+        searcher: String,
+        #[arg(long)]
+        from: Option<String>,
+        #[arg(long)]
+        to: Option<String>,
+        /// Channel name, or `session:<id>`.
+        #[arg(long)]
+        scope: Option<String>,
+        #[arg(long)]
+        kind: Option<String>,
+        #[arg(long)]
+        task: Option<String>,
+        #[arg(long)]
+        workspace: Option<String>,
+        /// RFC 3339 lower bound on the send time.
+        #[arg(long)]
+        since: Option<String>,
+        /// RFC 3339 upper bound on the send time.
+        #[arg(long)]
+        until: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show per-recipient delivery receipts (queued, injected, read,
+    /// acked) for a message and the other copies of its broadcast.
+    Receipts { id: String },
+    /// List escalation steps taken for unacked messages, oldest first.
+    Escalations {
+        /// Only this message's escalations.
+        id: Option<String>,
+    },
+    /// Ask `to` a question, optionally limited to fixed choices and
+    /// answerable only until a deadline.
+    Ask {
+        #[arg(long)]
+        from: String,
+        #[arg(long, default_value = "human")]
+        to: String,
+        /// An accepted answer; repeat for each choice. Any answer when
+        /// omitted.
+        #[arg(long = "choice")]
+        choices: Vec<String>,
+        /// Minutes the question stays open.
+        #[arg(long)]
+        within: Option<u32>,
+        /// Absolute workspace of the asking harness; the answer is injected
+        /// into its live session there.
+        #[arg(long)]
+        workspace: Option<String>,
+        #[arg(long)]
+        task: Option<String>,
+        body: String,
+    },
+    /// List questions, oldest first: open ones unless --all.
+    Questions {
+        #[arg(long)]
+        to: Option<String>,
+        #[arg(long, default_value_t = false)]
+        all: bool,
+    },
+    /// Answer a question; the answer is threaded under it and routed back
+    /// to the asker's harness.
+    Answer {
+        id: String,
+        #[arg(long, default_value = "human")]
+        from: String,
+        answer: String,
+    },
+    /// List the @mentions of `agent` in channel and session posts, newest
+    /// first.
+    Mentions {
+        #[arg(long)]
+        agent: String,
+        /// Only mentions posted after the agent's read marker for the scope.
+        #[arg(long, default_value_t = false)]
+        unread: bool,
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Resolve durable memory references embedded in a message body.
+    Memories { message_id: String },
+    /// Mark that `agent` has read `scope` (a channel id, work session id, or
+    /// `dm-<agent>` pairing) as of now, or an explicit `--at` RFC 3339
+    /// timestamp. Never regresses an existing, more recent marker.
+    Read {
+        #[arg(long)]
+        agent: String,
+        #[arg(long)]
+        scope: String,
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// List every team member's read marker for `scope`.
+    Readers { scope: String },
+    /// Mark a message done/acked/cancelled.
+    Status {
+        id: String,
+        #[arg(long)]
+        status: String,
+    },
+    /// Edit a message (and every sibling copy of the same team/channel
+    /// broadcast). Only Harbinger's own posts may be edited (CA-106/CA-109).
+    Edit {
+        #[arg(long)]
+        id: String,
+        #[arg(long)]
+        from: String,
+        body: String,
+    },
+    /// Archive a message (with its broadcast copies), a channel, or a work
+    /// session and its chat. Needs a role granting can_archive_messages.
+    Archive {
+        /// Message id; omit when using --channel or --session.
+        #[arg(required_unless_present_any = ["channel", "session"])]
+        id: Option<String>,
+        #[arg(long, conflicts_with_all = ["id", "session"])]
+        channel: Option<String>,
+        /// Work session id.
+        #[arg(long, conflicts_with = "id")]
+        session: Option<String>,
+        /// Agent doing the archiving; its roles are checked.
+        #[arg(long = "as")]
+        actor: String,
+        /// Unarchive instead.
+        #[arg(long, default_value_t = false)]
+        undo: bool,
+    },
+    /// Delete (cancel) a message and every sibling copy of the same
+    /// team/channel broadcast. Only Harbinger's own posts may be deleted.
+    Delete {
+        #[arg(long)]
+        id: String,
+        #[arg(long)]
+        from: String,
+    },
+}
+
+fn parse_payload(raw: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(raw).map_err(|e| format!("payload is not valid JSON: {e}"))
+}
@@ -0,0 +1,33 @@
+use clap::Args;
+use hub::PageRequest;
+
+/// Keyset cursor flags shared by the list commands. A cursor is the
+/// `before`/`after` value printed to stderr after each page, or a bare
+/// RFC 3339 timestamp.
+#[derive(Args)]
+pub(crate) struct PageArgs {
+    /// Only rows older than this cursor.
+    #[arg(long)]
+    pub(crate) before: Option<String>,
+    /// Only rows newer than this cursor.
+    #[arg(long)]
+    pub(crate) after: Option<String>,
+    /// Rows per page (default 100, max 500).
+    #[arg(long)]
+    pub(crate) limit: Option<usize>,
+}
+
+impl PageArgs {
+    /// The requested page, or `None` when no flag was given and the
+    /// command should keep its unpaged output.
+    pub(crate) fn request(self) -> Option<PageRequest> {
+        if self.before.is_none() && self.after.is_none() && self.limit.is_none() {
+            return None;
+        }
+        Some(PageRequest {
+            before: self.before,
+            after: self.after,
+            limit: self.limit,
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use clap::Subcommand;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub(crate) enum WebhookCommand {
+    /// List configured sinks.
+    List,
+    /// Add or replace a sink.
+    Add {
+        name: String,
+        #[arg(long)]
+        url: String,
+        /// Event to POST (repeatable; every event when omitted):
+        /// message_to_human | gate_approval_pending | budget_paused | task_failed.
+        #[arg(long = "event", value_name = "EVENT")]
+        events: Vec<String>,
+        /// JSON payload template; `{{data.body}}`-style placeholders are
+        /// filled from the event.
+        #[arg(long)]
+        template: Option<String>,
+        /// Environment variable holding the HMAC signing key.
+        #[arg(long, conflicts_with = "secret_file")]
+        secret_env: Option<String>,
+        /// File holding the HMAC signing key.
+        #[arg(long)]
+        secret_file: Option<PathBuf>,
+        /// Keep the sink configured but send it nothing.
+        #[arg(long, default_value_t = false)]
+        disabled: bool,
+    },
+    /// Remove a sink; its queued deliveries fail on their next attempt.
+    Remove { name: String },
+    /// POST every delivery that is due now and print them.
+    Deliver,
+    /// Show recent deliveries, newest first.
+    Deliveries {
+        #[arg(long)]
+        sink: Option<String>,
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+}
@@ -0,0 +1,33 @@
+use crate::app::AttachmentsCommand;
+use hub::HubStore;
+
+pub(super) fn run(store: &HubStore, action: AttachmentsCommand) -> anyhow::Result<()> {
+    match action {
+        AttachmentsCommand::Gc { dry_run } => {
+            let report = store.gc_attachments(dry_run)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        AttachmentsCommand::Search { query, limit } => {
+            let hits = store.search_attachments(&query, limit)?;
+            println!("{}", serde_json::to_string_pretty(&hits)?);
+        }
+        AttachmentsCommand::Usage => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&store.attachment_usage()?)?
+            );
+        }
+        AttachmentsCommand::Quota { set, clear } => {
+            if clear {
+                store.set_attachment_quota(None)?;
+            } else if set.is_some() {
+                store.set_attachment_quota(set)?;
+            }
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&store.attachment_usage()?)?
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::app::EncryptionCommand;
+use hub::{generate_hub_key, HubStore};
+use std::io::Write;
+
+pub(super) fn run(store: &HubStore, action: EncryptionCommand) -> anyhow::Result<()> {
+    let change = match action {
+        EncryptionCommand::Status => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&store.encryption_status()?)?
+            );
+            return Ok(());
+        }
+        EncryptionCommand::Keygen { out: None } => {
+            println!("{}", generate_hub_key());
+            return Ok(());
+        }
+        EncryptionCommand::Keygen { out: Some(path) } => {
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            writeln!(options.open(&path)?, "{}", generate_hub_key())?;
+            println!("{}", path.display());
+            return Ok(());
+        }
+        EncryptionCommand::Enable { key } => store.enable_encryption(&key.reference()?)?,
+        EncryptionCommand::Rotate { key } => store.rotate_encryption_key(&key.reference()?)?,
+        EncryptionCommand::Disable => store.disable_encryption()?,
+    };
+    println!("{}", serde_json::to_string_pretty(&change)?);
+    Ok(())
+}
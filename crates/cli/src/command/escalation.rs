@@ -0,0 +1,36 @@
+//! `ca hub escalation` / `ca hub escalate` dispatch.
+
+use hub::HubStore;
+
+/// Apply `--sla KIND=MINUTES` and `--clear KIND`, then print the config.
+pub(super) fn configure(
+    store: &HubStore,
+    sla: Vec<String>,
+    clear: Vec<String>,
+) -> anyhow::Result<()> {
+    let mut config = store.escalation_config()?;
+    for spec in sla {
+        let Some((kind, minutes)) = spec.split_once('=') else {
+            anyhow::bail!("--sla expects KIND=MINUTES, got {spec:?}");
+        };
+        let minutes = minutes
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("--sla minutes must be a whole number: {spec:?}"))?;
+        config
+            .ack_within_minutes
+            .insert(kind.trim().to_string(), minutes);
+    }
+    for kind in clear {
+        config.ack_within_minutes.remove(&kind);
+    }
+    store.set_escalation_config(&config)?;
+    println!("{}", serde_json::to_string_pretty(&config)?);
+    Ok(())
+}
+
+pub(super) fn run(store: &HubStore) -> anyhow::Result<()> {
+    let taken = store.run_escalations(chrono::Utc::now())?;
+    println!("{}", serde_json::to_string_pretty(&taken)?);
+    Ok(())
+}
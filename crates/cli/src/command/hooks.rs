@@ -0,0 +1,46 @@
+use crate::app::HooksCommand;
+use hub::{HookEvent, HubStore};
+
+pub(super) fn run(store: &HubStore, action: HooksCommand) -> anyhow::Result<()> {
+    match action {
+        HooksCommand::List { workspace } => {
+            let hooks = store.list_hooks(workspace.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&hooks)?);
+        }
+        HooksCommand::Runs { event, limit } => {
+            let event = event.as_deref().map(HookEvent::parse).transpose()?;
+            let runs = store.list_hook_runs(event, limit)?;
+            println!("{}", serde_json::to_string_pretty(&runs)?);
+        }
+        HooksCommand::Config {
+            timeout_secs,
+            off,
+            on,
+            no_workspace_hooks,
+            workspace_hooks,
+            trust,
+            untrust,
+        } => {
+            let mut config = store.hook_config()?;
+            if let Some(secs) = timeout_secs {
+                config.timeout_secs = secs;
+            }
+            if off || on {
+                config.enabled = on;
+            }
+            if no_workspace_hooks || workspace_hooks {
+                config.workspace_hooks = workspace_hooks;
+            }
+            store.set_hook_config(&config)?;
+            for workspace in &trust {
+                store.trust_hook_workspace(workspace, true)?;
+            }
+            for workspace in &untrust {
+                store.trust_hook_workspace(workspace, false)?;
+            }
+            let config = store.hook_config()?;
+            println!("{}", serde_json::to_string_pretty(&config)?);
+        }
+    }
+    Ok(())
+}
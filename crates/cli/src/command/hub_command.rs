@@ -0,0 +1,135 @@
+use super::{encryption, escalation, hooks, webhook};
+use crate::app::{Command, HubCommand};
+use hub::{list_hub_backups, HubStore, RedactionDetector, RetentionExport, SettingsStore};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Restore must run with no `HubStore` open on `home`, so it is handled
+/// before the shared open in `run`.
+pub(super) fn restore_if_requested(command: &Command, home: &Path) -> Option<anyhow::Result<()>> {
+    match command {
+        Command::Hub {
+            action: HubCommand::Restore { backup },
+        } => Some(restore(home, backup)),
+        _ => None,
+    }
+}
+
+fn restore(home: &Path, backup: &Path) -> anyhow::Result<()> {
+    let outcome = HubStore::restore_backup(home, backup)?;
+    // Re-open once so the restored database is migrated to this build.
+    HubStore::open(home)?;
+    println!("{}", serde_json::to_string_pretty(&outcome)?);
+    Ok(())
+}
+
+pub(super) fn run(store: &HubStore, home: PathBuf, action: HubCommand) -> anyhow::Result<()> {
+    match action {
+        HubCommand::Backup { to, retention } => {
+            let retention = retention
+                .unwrap_or_else(|| SettingsStore::open(&home).effective(None).backup_retention);
+            let backup = store.backup(to.as_deref(), retention)?;
+            println!("{}", serde_json::to_string_pretty(&backup)?);
+        }
+        HubCommand::Backups { dir } => {
+            let dir = dir.unwrap_or_else(|| store.backups_dir());
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&list_hub_backups(&dir)?)?
+            );
+        }
+        HubCommand::Verify { backup } => {
+            let verification = HubStore::verify_backup(&backup)?;
+            println!("{}", serde_json::to_string_pretty(&verification)?);
+        }
+        HubCommand::Export { out } => match out {
+            Some(path) => {
+                let mut file = BufWriter::new(File::create(&path)?);
+                let summary = store.export_jsonl(&mut file)?;
+                file.flush()?;
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            }
+            None => {
+                let mut stdout = std::io::stdout().lock();
+                let summary = store.export_jsonl(&mut stdout)?;
+                eprintln!("{}", serde_json::to_string_pretty(&summary)?);
+            }
+        },
+        HubCommand::Import { file, dry_run } => {
+            let report = store.import_jsonl(BufReader::new(File::open(&file)?), dry_run)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        HubCommand::Retention {
+            dry_run: _,
+            apply,
+            export,
+        } => {
+            let export = export.as_deref().map(RetentionExport::parse).transpose()?;
+            let settings = SettingsStore::open(&home);
+            let report = store.sweep_retention(&settings, !apply, export)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        HubCommand::Encryption { action } => encryption::run(store, action)?,
+        HubCommand::Scan { redact } => {
+            let report = store.scan_for_secrets(redact)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        HubCommand::Redaction {
+            add,
+            remove,
+            disable,
+            enable,
+            entropy_min_length,
+            entropy_min_bits,
+            off,
+            on,
+        } => {
+            let mut config = store.redaction_config()?;
+            for spec in add {
+                let Some((name, pattern)) = spec.split_once('=') else {
+                    anyhow::bail!("--add expects NAME=REGEX, got {spec:?}");
+                };
+                config.custom_detectors.retain(|d| d.name != name);
+                config.custom_detectors.push(RedactionDetector {
+                    name: name.trim().to_string(),
+                    pattern: pattern.to_string(),
+                });
+            }
+            config
+                .custom_detectors
+                .retain(|d| !remove.contains(&d.name));
+            config.disabled_detectors.retain(|d| !enable.contains(d));
+            for name in disable {
+                if !config.disabled_detectors.contains(&name) {
+                    config.disabled_detectors.push(name);
+                }
+            }
+            if let Some(min_length) = entropy_min_length {
+                config.entropy.min_length = min_length;
+            }
+            if let Some(min_bits) = entropy_min_bits {
+                config.entropy.min_bits_per_char = min_bits;
+            }
+            if off || on {
+                config.enabled = on;
+            }
+            store.set_redaction_config(&config)?;
+            println!("{}", serde_json::to_string_pretty(&config)?);
+        }
+        HubCommand::Mentions { wake, no_wake } => {
+            let mut config = store.mention_config()?;
+            if wake || no_wake {
+                config.wake = wake;
+                store.set_mention_config(&config)?;
+            }
+            println!("{}", serde_json::to_string_pretty(&config)?);
+        }
+        HubCommand::Escalation { sla, clear } => escalation::configure(store, sla, clear)?,
+        HubCommand::Escalate => escalation::run(store)?,
+        HubCommand::Webhook { action } => webhook::run(store, &home, action)?,
+        HubCommand::Hooks { action } => hooks::run(store, action)?,
+        HubCommand::Restore { .. } => unreachable!("restore returns before HubStore::open"),
+    }
+    Ok(())
+}
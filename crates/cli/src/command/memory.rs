@@ -1,8 +1,11 @@
 //! ca memory subcommand dispatch (split from command/mod.rs for
 //! the 500-LoC cap, #158).
 
-use crate::app::MemoryCommand;
-use hub::{HubStore, LinkSuggestionMode, MemoryScope, MemoryTier};
+use super::page::print_page;
+use crate::app::{GraphFilterArgs, MemoryCommand, MemoryGraphCommand};
+use hub::{
+    GraphExportFormat, HubStore, LinkSuggestionMode, MemoryGraphFilter, MemoryScope, MemoryTier,
+};
 
 pub(super) fn run(store: &HubStore, action: MemoryCommand) -> anyhow::Result<()> {
     match action {
@@ -11,21 +14,35 @@ pub(super) fn run(store: &HubStore, action: MemoryCommand) -> anyhow::Result<()>
             scope,
             agent,
             workspace,
+            session,
             title,
             tags,
             body,
         } => {
             let tier = MemoryTier::parse(&tier)?;
             let scope = MemoryScope::parse(&scope)?;
-            let record = store.write_memory(
-                tier,
-                scope,
-                agent.as_deref(),
-                workspace.as_deref(),
-                title.as_deref(),
-                &body,
-                &tags,
-            )?;
+            let record = if scope == MemoryScope::Session {
+                let session =
+                    session.ok_or_else(|| anyhow::anyhow!("--scope session requires --session"))?;
+                store.write_session_memory(
+                    &session,
+                    tier,
+                    agent.as_deref(),
+                    title.as_deref(),
+                    &body,
+                    &tags,
+                )?
+            } else {
+                store.write_memory(
+                    tier,
+                    scope,
+                    agent.as_deref(),
+                    workspace.as_deref(),
+                    title.as_deref(),
+                    &body,
+                    &tags,
+                )?
+            };
             println!("{}", serde_json::to_string_pretty(&record)?);
         }
         MemoryCommand::List {
@@ -33,17 +50,52 @@ pub(super) fn run(store: &HubStore, action: MemoryCommand) -> anyhow::Result<()>
             tier,
             workspace,
             include_stale,
+            reader,
+            page,
         } => {
             let scope = scope.map(|s| MemoryScope::parse(&s)).transpose()?;
             let tier = tier.map(|t| MemoryTier::parse(&t)).transpose()?;
-            let records = store.list_memories(scope, tier, workspace.as_deref(), include_stale)?;
-            println!("{}", serde_json::to_string_pretty(&records)?);
+            match page.request() {
+                Some(page) => print_page(&store.list_memories_page_as(
+                    reader.as_deref(),
+                    scope,
+                    tier,
+                    workspace.as_deref(),
+                    include_stale,
+                    &page,
+                )?)?,
+                None => {
+                    let records = store.list_memories_as(
+                        reader.as_deref(),
+                        scope,
+                        tier,
+                        workspace.as_deref(),
+                        include_stale,
+                    )?;
+                    println!("{}", serde_json::to_string_pretty(&records)?);
+                }
+            }
         }
-        MemoryCommand::Search { query } => {
-            println!(
+        MemoryCommand::Search {
+            query,
+            reader,
+            page,
+        } => match page.request() {
+            Some(page) => {
+                print_page(&store.search_memories_page_as(reader.as_deref(), &query, &page)?)?
+            }
+            None => println!(
                 "{}",
-                serde_json::to_string_pretty(&store.search_memories(&query)?)?
-            );
+                serde_json::to_string_pretty(
+                    &store.search_memories_as(reader.as_deref(), &query)?
+                )?
+            ),
+        },
+        MemoryCommand::Show { id, reader } => {
+            let record = store
+                .read_memory(&id, reader.as_deref())?
+                .ok_or_else(|| anyhow::anyhow!("memory not found: {id}"))?;
+            println!("{}", serde_json::to_string_pretty(&record)?);
         }
         MemoryCommand::Stale { id, unstale } => {
             store.mark_memory_stale(&id, !unstale)?;
@@ -58,8 +110,30 @@ pub(super) fn run(store: &HubStore, action: MemoryCommand) -> anyhow::Result<()>
             store.delete_memory(&id)?;
             println!("ok");
         }
-        MemoryCommand::Compact { keep } => {
-            let report = store.compact_short_term(keep)?;
+        MemoryCommand::Compact { keep, suggest } => {
+            if suggest {
+                let candidates = store.compaction_candidates()?;
+                println!("{}", serde_json::to_string_pretty(&candidates)?);
+            } else {
+                let report = store.compact_short_term(keep)?;
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+        }
+        MemoryCommand::Dedup {
+            workspace,
+            tier,
+            threshold,
+            apply,
+            reader,
+        } => {
+            let tier = tier.map(|t| MemoryTier::parse(&t)).transpose()?;
+            let report = store.dedup_memories(
+                reader.as_deref(),
+                workspace.as_deref(),
+                tier,
+                threshold,
+                apply,
+            )?;
             println!("{}", serde_json::to_string_pretty(&report)?);
         }
         MemoryCommand::PurgeStale => {
@@ -89,12 +163,24 @@ pub(super) fn run(store: &HubStore, action: MemoryCommand) -> anyhow::Result<()>
                 serde_json::to_string_pretty(&store.list_memory_links(&memory_id)?)?
             );
         }
-        MemoryCommand::Related { memory_id, depth } => {
+        MemoryCommand::Citations { memory_id } => {
             println!(
                 "{}",
-                serde_json::to_string_pretty(&store.related_memories(&memory_id, depth)?)?
+                serde_json::to_string_pretty(&store.memory_citations(&memory_id)?)?
             );
         }
+        MemoryCommand::CitationWarnings { agent } => {
+            let warnings = store.citation_warnings(agent.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&warnings)?);
+        }
+        MemoryCommand::Related {
+            memory_id,
+            depth,
+            reader,
+        } => {
+            let related = store.related_memories(reader.as_deref(), &memory_id, depth)?;
+            println!("{}", serde_json::to_string_pretty(&related)?);
+        }
         MemoryCommand::Topic { query } => {
             println!(
                 "{}",
@@ -116,6 +202,59 @@ pub(super) fn run(store: &HubStore, action: MemoryCommand) -> anyhow::Result<()>
             let suggestions = store.apply_link_suggestions(&memory_id, mode, limit)?;
             println!("{}", serde_json::to_string_pretty(&suggestions)?);
         }
+        MemoryCommand::Graph { action } => run_graph(store, action)?,
+    }
+    Ok(())
+}
+
+/// Split the CLI flags into the reader and the [`MemoryGraphFilter`].
+fn graph_filter(args: GraphFilterArgs) -> anyhow::Result<(Option<String>, MemoryGraphFilter)> {
+    let filter = MemoryGraphFilter {
+        workspace_path: args.workspace,
+        tier: args.tier.map(|t| MemoryTier::parse(&t)).transpose()?,
+        relation: args.relation,
+        include_stale: args.include_stale,
+    };
+    Ok((args.reader, filter))
+}
+
+fn run_graph(store: &HubStore, action: MemoryGraphCommand) -> anyhow::Result<()> {
+    match action {
+        MemoryGraphCommand::Export {
+            format,
+            out,
+            filter,
+        } => {
+            let format = GraphExportFormat::parse(&format)?;
+            let (reader, filter) = graph_filter(filter)?;
+            let rendered = store.export_memory_graph(reader.as_deref(), &filter, format)?;
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, rendered)?;
+                    println!("exported to {}", path.display());
+                }
+                None => print!("{rendered}"),
+            }
+        }
+        MemoryGraphCommand::Path { from, to, reader } => {
+            let path = store.memory_path(reader.as_deref(), &from, &to)?;
+            println!("{}", serde_json::to_string_pretty(&path)?);
+        }
+        MemoryGraphCommand::Clusters { filter } => {
+            let (reader, filter) = graph_filter(filter)?;
+            let clusters = store.memory_clusters(reader.as_deref(), &filter)?;
+            println!("{}", serde_json::to_string_pretty(&clusters)?);
+        }
+        MemoryGraphCommand::Orphans { filter } => {
+            let (reader, filter) = graph_filter(filter)?;
+            let orphans = store.orphaned_memories(reader.as_deref(), &filter)?;
+            println!("{}", serde_json::to_string_pretty(&orphans)?);
+        }
+        MemoryGraphCommand::Contradictions { filter } => {
+            let (reader, filter) = graph_filter(filter)?;
+            let report = store.contradiction_report(reader.as_deref(), &filter)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
     }
     Ok(())
 }
@@ -2,19 +2,30 @@ use crate::app::*;
 use crate::helpers::{audit_file_hash, audit_operation, audit_process_context, default_home};
 use hub::{HubStore, TaskStatus, WakeStatus, WorkflowStep};
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use page::print_page;
 
+mod attachments;
+mod encryption;
+mod escalation;
 mod harness;
+mod hooks;
+mod hub_command;
 mod memory;
 mod msg;
+mod page;
 mod preflight;
 mod shutdown;
 mod tui_command;
+mod webhook;
 pub(crate) fn run(cli: Cli) -> anyhow::Result<()> {
     let home = cli.home.clone().unwrap_or_else(default_home);
     let command = cli.command;
     if let Some(result) = preflight::run_if_requested(&command, home.clone()) {
         return result;
     }
+    if let Some(result) = hub_command::restore_if_requested(&command, &home) {
+        return result;
+    }
     let store = HubStore::open(&home)?;
 
     match command {
@@ -66,10 +77,16 @@ pub(crate) fn run(cli: Cli) -> anyhow::Result<()> {
             WakeCommand::List {
                 target,
                 pending_only,
-            } => {
-                let records = store.list_wakes(target.as_deref(), pending_only)?;
-                println!("{}", serde_json::to_string_pretty(&records)?);
-            }
+                page,
+            } => match page.request() {
+                Some(page) => {
+                    print_page(&store.list_wakes_page(target.as_deref(), pending_only, &page)?)?
+                }
+                None => {
+                    let records = store.list_wakes(target.as_deref(), pending_only)?;
+                    println!("{}", serde_json::to_string_pretty(&records)?);
+                }
+            },
             WakeCommand::Resolve { id, status } => {
                 let status = match status.as_str() {
                     "delivered" => WakeStatus::Delivered,
@@ -99,8 +116,8 @@ pub(crate) fn run(cli: Cli) -> anyhow::Result<()> {
         },
         Command::Journal { action } => match action {
             JournalCommand::Append { agent, entry } => {
-                let path = store.append_private_journal(&agent, &entry)?;
-                println!("appended to {}", path.display());
+                let record = store.append_private_journal(&agent, &entry)?;
+                println!("{}", serde_json::to_string_pretty(&record)?);
             }
         },
         Command::Task { action } => match action {
@@ -122,10 +139,15 @@ pub(crate) fn run(cli: Cli) -> anyhow::Result<()> {
                 )?;
                 println!("{}", serde_json::to_string_pretty(&record)?);
             }
-            TaskCommand::List { status } => {
+            TaskCommand::List { status, page } => {
                 let status = status.map(|s| TaskStatus::parse(&s)).transpose()?;
-                let records = store.list_tasks(status)?;
-                println!("{}", serde_json::to_string_pretty(&records)?);
+                match page.request() {
+                    Some(page) => print_page(&store.list_tasks_page(status, &page)?)?,
+                    None => {
+                        let records = store.list_tasks(status)?;
+                        println!("{}", serde_json::to_string_pretty(&records)?);
+                    }
+                }
             }
             TaskCommand::Get { id } => {
                 let record = store
@@ -224,18 +246,20 @@ pub(crate) fn run(cli: Cli) -> anyhow::Result<()> {
                     }
                 }
             }
-            AuditCommand::Pending => {
-                println!(
+            AuditCommand::Pending { page } => match page.request() {
+                Some(page) => print_page(&store.list_audit_events_page(true, &page)?)?,
+                None => println!(
                     "{}",
                     serde_json::to_string_pretty(&store.list_audit_events(true)?)?
-                );
-            }
-            AuditCommand::List => {
-                println!(
+                ),
+            },
+            AuditCommand::List { page } => match page.request() {
+                Some(page) => print_page(&store.list_audit_events_page(false, &page)?)?,
+                None => println!(
                     "{}",
                     serde_json::to_string_pretty(&store.list_audit_events(false)?)?
-                );
-            }
+                ),
+            },
             AuditCommand::Approve { id, all } => {
                 if all {
                     for event in store.list_audit_events(true)? {
@@ -258,6 +282,8 @@ pub(crate) fn run(cli: Cli) -> anyhow::Result<()> {
         },
         Command::Inbox { action } => return crate::io::run(&store, action),
         Command::Harness { action } => harness::run(&store, action)?,
+        Command::Attachments { action } => attachments::run(&store, action)?,
+        Command::Hub { action } => hub_command::run(&store, home, action)?,
         Command::Shutdown {
             agent,
             task,
@@ -1,9 +1,13 @@
 //! ca msg subcommand dispatch (split from command/mod.rs for the
 //! 500-LoC cap, #158).
 
+use super::page::print_page;
 use crate::app::MsgCommand;
 use crate::helpers::{require_human_authored, tagged_dispatch_workspace};
-use hub::{inject_harness_with_store, HarnessInjectRequest, HubStore, MessageKind, MessageStatus};
+use hub::{
+    inject_harness_with_store, HarnessInjectRequest, HubStore, MessageKind, MessageSearch,
+    MessageStatus,
+};
 
 pub(super) fn run(store: &HubStore, action: MsgCommand) -> anyhow::Result<()> {
     match action {
@@ -14,6 +18,8 @@ pub(super) fn run(store: &HubStore, action: MsgCommand) -> anyhow::Result<()> {
             subject,
             workspace,
             task,
+            reply_to,
+            payload,
             body,
         } => {
             let kind = MessageKind::parse(&kind)?;
@@ -24,26 +30,55 @@ pub(super) fn run(store: &HubStore, action: MsgCommand) -> anyhow::Result<()> {
                     );
             }
             if to == "team" {
-                let records = store.send_message_to_team(
+                if payload.is_some() && reply_to.is_none() {
+                    anyhow::bail!("--payload to team is only accepted with --reply-to");
+                }
+                let records = match reply_to {
+                    Some(parent) => store.send_reply_to_team(
+                        &parent,
+                        &from,
+                        kind,
+                        &body,
+                        payload.as_ref(),
+                        subject.as_deref(),
+                        workspace.as_deref(),
+                        task.as_deref(),
+                    )?,
+                    None => store.send_message_to_team(
+                        &from,
+                        kind,
+                        &body,
+                        subject.as_deref(),
+                        workspace.as_deref(),
+                        task.as_deref(),
+                    )?,
+                };
+                println!("{}", serde_json::to_string_pretty(&records)?);
+                return Ok(());
+            }
+            let record = match reply_to {
+                Some(parent) => store.send_reply(
+                    &parent,
                     &from,
+                    &to,
                     kind,
                     &body,
+                    payload.as_ref(),
                     subject.as_deref(),
                     workspace.as_deref(),
                     task.as_deref(),
-                )?;
-                println!("{}", serde_json::to_string_pretty(&records)?);
-                return Ok(());
-            }
-            let record = store.send_message(
-                &from,
-                &to,
-                kind,
-                &body,
-                subject.as_deref(),
-                workspace.as_deref(),
-                task.as_deref(),
-            )?;
+                )?,
+                None => store.send_message_with_payload(
+                    &from,
+                    &to,
+                    kind,
+                    &body,
+                    payload.as_ref(),
+                    subject.as_deref(),
+                    workspace.as_deref(),
+                    task.as_deref(),
+                )?,
+            };
             println!("{}", serde_json::to_string_pretty(&record)?);
         }
         MsgCommand::Tag {
@@ -56,15 +91,17 @@ pub(super) fn run(store: &HubStore, action: MsgCommand) -> anyhow::Result<()> {
             task_id,
             session,
             dispatch,
+            payload,
             body,
         } => {
             let dispatch_workspace = tagged_dispatch_workspace(dispatch, workspace.as_deref())?;
-            let outcomes = store.send_tagged_message(
+            let outcomes = store.send_tagged_message_with_payload(
                 &from,
                 &to,
                 task,
                 wake,
                 &body,
+                payload.as_ref(),
                 subject.as_deref(),
                 workspace.as_deref(),
                 task_id.as_deref(),
@@ -109,14 +146,118 @@ pub(super) fn run(store: &HubStore, action: MsgCommand) -> anyhow::Result<()> {
             let records = store.poll_messages(&to, !no_ack)?;
             println!("{}", serde_json::to_string_pretty(&records)?);
         }
-        MsgCommand::List { to, status } => {
+        MsgCommand::List {
+            archived: true,
+            page,
+            ..
+        } => {
+            let page = page.request().unwrap_or_default();
+            print_page(&store.list_archived_messages_page(&page)?)?;
+        }
+        MsgCommand::List {
+            to, status, page, ..
+        } => {
             let status = status.map(|s| MessageStatus::parse(&s)).transpose()?;
-            let records = store.list_messages(to.as_deref(), status)?;
-            println!("{}", serde_json::to_string_pretty(&records)?);
+            match page.request() {
+                Some(page) => {
+                    print_page(&store.list_messages_page(to.as_deref(), status, &page)?)?
+                }
+                None => {
+                    let records = store.list_messages(to.as_deref(), status)?;
+                    println!("{}", serde_json::to_string_pretty(&records)?);
+                }
+            }
         }
-        MsgCommand::Channel { channel, limit } => {
-            let records = store.list_channel_messages(&channel, limit)?;
-            println!("{}", serde_json::to_string_pretty(&records)?);
+        MsgCommand::Channel { channel, page } => {
+            let page = page.request().unwrap_or_default();
+            print_page(&store.list_channel_messages_page(&channel, &page)?)?;
+        }
+        MsgCommand::Outcomes { subject, page } => {
+            let page = page.request().unwrap_or_default();
+            print_page(&store.list_tagged_send_outcomes_page(subject.as_deref(), &page)?)?;
+        }
+        MsgCommand::Thread { id } => {
+            let thread = store.list_thread(&id)?;
+            println!("{}", serde_json::to_string_pretty(&thread)?);
+        }
+        MsgCommand::Threads { channel, limit } => {
+            let threads = store.list_threads(channel.as_deref(), limit)?;
+            println!("{}", serde_json::to_string_pretty(&threads)?);
+        }
+        MsgCommand::Search {
+            query,
+            searcher,
+            from,
+            to,
+            scope,
+            kind,
+            task,
+            workspace,
+            since,
+            until,
+            limit,
+        } => {
+            let search = MessageSearch {
+                query,
+                from_agent: from,
+                to_agent: to,
+                scope,
+                kind: kind.map(|k| MessageKind::parse(&k)).transpose()?,
+                task_id: task,
+                workspace_path: workspace,
+                since,
+                until,
+                limit,
+            };
+            let hits = store.search_messages(&searcher, &search)?;
+            println!("{}", serde_json::to_string_pretty(&hits)?);
+        }
+        MsgCommand::Receipts { id } => {
+            let receipts = store.message_receipts(&id)?;
+            println!("{}", serde_json::to_string_pretty(&receipts)?);
+        }
+        MsgCommand::Escalations { id } => {
+            let escalations = store.list_escalations(id.as_deref())?;
+            println!("{}", serde_json::to_string_pretty(&escalations)?);
+        }
+        MsgCommand::Ask {
+            from,
+            to,
+            choices,
+            within,
+            workspace,
+            task,
+            body,
+        } => {
+            let deadline = within.map(|minutes| {
+                (chrono::Utc::now() + chrono::Duration::minutes(minutes.into())).to_rfc3339()
+            });
+            let question = store.ask_question(
+                &from,
+                &to,
+                &body,
+                &choices,
+                deadline.as_deref(),
+                workspace.as_deref(),
+                task.as_deref(),
+            )?;
+            println!("{}", serde_json::to_string_pretty(&question)?);
+        }
+        MsgCommand::Questions { to, all } => {
+            let questions = store.list_questions(to.as_deref(), !all)?;
+            println!("{}", serde_json::to_string_pretty(&questions)?);
+        }
+        MsgCommand::Answer { id, from, answer } => {
+            let answered = store.answer_question(&id, &from, &answer)?;
+            println!("{}", serde_json::to_string_pretty(&answered)?);
+        }
+        MsgCommand::Mentions {
+            agent,
+            unread,
+            limit,
+        } => {
+            let mentions = store.list_mentions(&agent, unread, limit)?;
+            println!("{}", serde_json::to_string_pretty(&mentions)?);
         }
         MsgCommand::Read { agent, scope, at } => {
             let marker = store.mark_read(&agent, &scope, at.as_deref())?;
@@ -140,6 +281,26 @@ pub(super) fn run(store: &HubStore, action: MsgCommand) -> anyhow::Result<()> {
             let records = store.update_broadcast(&id, &body)?;
             println!("{}", serde_json::to_string_pretty(&records)?);
         }
+        MsgCommand::Archive {
+            id,
+            channel,
+            session,
+            actor,
+            undo,
+        } => {
+            let outcome = match (id, channel, session, undo) {
+                (_, Some(channel), _, false) => store.archive_channel(&actor, &channel)?,
+                (_, Some(channel), _, true) => store.unarchive_channel(&actor, &channel)?,
+                (_, _, Some(session), false) => store.archive_work_session(&actor, &session)?,
+                (_, _, Some(session), true) => store.unarchive_work_session(&actor, &session)?,
+                (Some(id), _, _, false) => store.archive_message(&actor, &id)?,
+                (Some(id), _, _, true) => store.unarchive_message(&actor, &id)?,
+                (None, None, None, _) => {
+                    anyhow::bail!("give a message id, --channel, or --session")
+                }
+            };
+            println!("{}", serde_json::to_string_pretty(&outcome)?);
+        }
         MsgCommand::Delete { id, from } => {
             require_human_authored(store, &from, &id)?;
             let count = store.delete_broadcast(&id)?;
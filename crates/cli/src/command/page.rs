@@ -0,0 +1,19 @@
+//! Output for paged list commands: the items stay a JSON array on stdout
+//! (the shape unpaged runs print), and the cursors go to stderr as one
+//! JSON line so scripts can fetch the next page.
+
+use hub::Page;
+use serde::Serialize;
+
+pub(super) fn print_page<T: Serialize>(page: &Page<T>) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(&page.items)?);
+    eprintln!(
+        "{}",
+        serde_json::json!({
+            "has_more": page.has_more,
+            "before": page.before,
+            "after": page.after,
+        })
+    );
+    Ok(())
+}
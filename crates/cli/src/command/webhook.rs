@@ -0,0 +1,60 @@
+use crate::app::WebhookCommand;
+use hub::{HubStore, SecretReference, SettingsStore, WebhookEvent, WebhookSink};
+use std::path::Path;
+
+pub(super) fn run(store: &HubStore, home: &Path, action: WebhookCommand) -> anyhow::Result<()> {
+    let mut settings = SettingsStore::open(home);
+    match action {
+        WebhookCommand::List => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&settings.list_webhooks())?
+            );
+        }
+        WebhookCommand::Add {
+            name,
+            url,
+            events,
+            template,
+            secret_env,
+            secret_file,
+            disabled,
+        } => {
+            let secret = match (secret_env, secret_file) {
+                (Some(name), _) => Some(SecretReference::EnvVar { name }),
+                (None, Some(path)) => Some(SecretReference::KeyFile {
+                    path: std::path::absolute(path)?.to_string_lossy().to_string(),
+                }),
+                (None, None) => None,
+            };
+            let sink = WebhookSink {
+                name,
+                url,
+                events: events
+                    .iter()
+                    .map(|event| WebhookEvent::parse(event))
+                    .collect::<Result<_, _>>()?,
+                template,
+                secret,
+                enabled: !disabled,
+            };
+            settings.upsert_webhook(sink.clone())?;
+            settings.save()?;
+            println!("{}", serde_json::to_string_pretty(&sink)?);
+        }
+        WebhookCommand::Remove { name } => {
+            let removed = settings.remove_webhook(&name)?;
+            settings.save()?;
+            println!("{}", serde_json::to_string_pretty(&removed)?);
+        }
+        WebhookCommand::Deliver => {
+            let attempted = store.deliver_webhooks(chrono::Utc::now())?;
+            println!("{}", serde_json::to_string_pretty(&attempted)?);
+        }
+        WebhookCommand::Deliveries { sink, limit } => {
+            let deliveries = store.list_webhook_deliveries(sink.as_deref(), limit)?;
+            println!("{}", serde_json::to_string_pretty(&deliveries)?);
+        }
+    }
+    Ok(())
+}
@@ -3,6 +3,11 @@ use hub::{HubStore, MessageStatus, WakeStatus};
 
 pub(crate) fn run(store: &HubStore, action: InboxCommand) -> anyhow::Result<()> {
     match action {
+        InboxCommand::Summary { agent } => {
+            let summary = store.unread_summary(&agent)?;
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+            Ok(())
+        }
         InboxCommand::Watch {
             agent,
             interval_ms,
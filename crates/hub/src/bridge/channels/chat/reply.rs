@@ -19,19 +19,24 @@ pub fn record_codex_reply(
             "Codex reply body must not be empty".into(),
         ));
     }
-    let recipient = match in_reply_to {
-        Some(id) => store
-            .get_message(id)?
-            .map(|message| message.from_agent)
-            .unwrap_or_else(|| "human".to_string()),
-        None => "human".to_string(),
+    let body = &store.redact_incoming("codex_reply", Some(CODEX_AGENT_ID), body)?;
+    let parent = match in_reply_to {
+        Some(id) => store.get_message(id)?,
+        None => None,
     };
+    let recipient = parent
+        .as_ref()
+        .map(|message| message.from_agent.clone())
+        .unwrap_or_else(|| "human".to_string());
     // Same reasoning as the Claude Channel's reply routing: a fixed subject
     // per session collides with itself across multiple replies, collapsing
     // earlier ones out of the desktop Chat & Memory view's per-post dedup.
     let subject = session_id.map(|id| format!("channel:session:{id}:reply:{}", Uuid::new_v4()));
+    // Threaded in the insert itself, so mentions, webhooks and hooks see
+    // the reply under the message it answers.
+    let parent_id = parent.as_ref().map(|message| message.id.as_str());
     if let Some(session_id) = session_id {
-        let sent = store.send_session_message(
+        let sent = store.fan_out_to_session(
             CODEX_AGENT_ID,
             session_id,
             &[recipient],
@@ -39,6 +44,7 @@ pub fn record_codex_reply(
             subject.as_deref(),
             None,
             None,
+            parent_id,
         )?;
         let _ = store.mark_read(
             CODEX_AGENT_ID,
@@ -49,14 +55,16 @@ pub fn record_codex_reply(
             .next()
             .ok_or_else(|| HubError::Invalid("Codex reply produced no recipient".into()))
     } else {
-        store.send_message(
+        store.insert_message(
             CODEX_AGENT_ID,
             &recipient,
             MessageKind::Message,
             body,
+            None,
             subject.as_deref(),
             None,
             None,
+            parent_id,
         )
     }
 }
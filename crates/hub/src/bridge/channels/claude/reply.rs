@@ -18,13 +18,15 @@ pub fn record_channel_reply(
             "Channel reply body must not be empty".into(),
         ));
     }
-    let recipient = match in_reply_to {
-        Some(id) => store
-            .get_message(id)?
-            .map(|message| message.from_agent)
-            .unwrap_or_else(|| "human".to_string()),
-        None => "human".to_string(),
+    let body = &store.redact_incoming("channel_reply", Some(CLAUDE_AGENT_ID), body)?;
+    let parent = match in_reply_to {
+        Some(id) => store.get_message(id)?,
+        None => None,
     };
+    let recipient = parent
+        .as_ref()
+        .map(|message| message.from_agent.clone())
+        .unwrap_or_else(|| "human".to_string());
     // Each reply needs its own unique subject — a fixed
     // "channel:session:<id>:reply" for every reply in the same session
     // collided with itself, and the desktop Chat & Memory view's per-post
@@ -34,8 +36,11 @@ pub fn record_channel_reply(
     // arrived. Mirrors the uuid-suffixed default `send_session_message`
     // already generates when no explicit subject is given.
     let subject = session_id.map(|id| format!("channel:session:{id}:reply:{}", Uuid::new_v4()));
+    // Threaded in the insert itself, so mentions, webhooks and hooks see
+    // the reply under the message it answers.
+    let parent_id = parent.as_ref().map(|message| message.id.as_str());
     if let Some(session_id) = session_id {
-        let sent = store.send_session_message(
+        let sent = store.fan_out_to_session(
             CLAUDE_AGENT_ID,
             session_id,
             &[recipient],
@@ -43,6 +48,7 @@ pub fn record_channel_reply(
             subject.as_deref(),
             None,
             None,
+            parent_id,
         )?;
         // Replying implies Claude has read the session up through now —
         // one less thing a human has to do manually for the read marker
@@ -56,14 +62,16 @@ pub fn record_channel_reply(
             .next()
             .ok_or_else(|| HubError::Invalid("Channel reply produced no recipient".into()))
     } else {
-        store.send_message(
+        store.insert_message(
             CLAUDE_AGENT_ID,
             &recipient,
             MessageKind::Message,
             body,
+            None,
             subject.as_deref(),
             None,
             None,
+            parent_id,
         )
     }
 }
@@ -17,12 +17,17 @@ pub fn inject_harness(request: &HarnessInjectRequest) -> Result<HarnessInjectRes
 }
 
 /// Same as [`inject_harness`], but Grok task delivery uses a registered
-/// active session through the leader ACP bridge when a store is provided.
+/// active session through the leader ACP bridge when a store is provided,
+/// and the outcome is recorded as the message's delivery receipt.
 pub fn inject_harness_with_store(
     store: &crate::HubStore,
     request: &HarnessInjectRequest,
 ) -> Result<HarnessInjectResult, HubError> {
-    inject_harness_inner(Some(store), request)
+    let result = inject_harness_inner(Some(store), request);
+    if let Some(message_id) = &request.message_id {
+        store.record_injection(message_id, &result)?;
+    }
+    result
 }
 
 fn inject_harness_inner(
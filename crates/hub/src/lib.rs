@@ -50,15 +50,35 @@ pub use settings::{
     EffectiveHarnessSettings, EffectiveOrchestrationPolicy, EffectiveSettings, FieldStatus,
     HarnessSettings, LinkSuggestionMode, LoadStatus, OrchestrationOverride, OrchestrationPolicy,
     ProfileSnapshot, ProviderProfile, SandboxStrictness, SecretReference, SecretSourceKind,
-    SettingsError, SettingsField, SettingsLoad, SettingsSnapshot, SettingsStore, WorkspaceOverride,
-    CURRENT_SETTINGS_SCHEMA, DEFAULT_BACKUP_RETENTION, MAX_BACKUP_RETENTION, MIN_BACKUP_RETENTION,
+    SettingsError, SettingsField, SettingsLoad, SettingsSnapshot, SettingsStore, WebhookEvent,
+    WebhookSink, WorkspaceOverride, CURRENT_SETTINGS_SCHEMA, DEFAULT_BACKUP_RETENTION,
+    MAX_BACKUP_RETENTION, MIN_BACKUP_RETENTION,
 };
 pub use store::{
-    parse_memory_references, AgentCard, AgentMetrics, AgentRecord, AttachmentRecord, AuditEvent,
-    BudgetPauseOutcome, BudgetStatus, ChannelRecord, CompactReport, EffectiveAgentPermissions,
-    GateVerdict, GitExportOutcome, HarnessSessionMode, HarnessSessionRegistration,
-    HarnessSessionState, HubError, HubStore, LinkSuggestion, MemoryLinkRecord, MemoryRecord,
-    MemoryScope, MemoryTier, MessageKind, MessageRecord, MessageStatus, PendingGateApproval,
-    ReadMarker, Role, RoleProviderDefault, SendOutcome, ShutdownOutcome, TaskRecord, TaskStatus,
-    WakePolicy, WakeRecord, WakeStatus, WorkSessionRecord, WorkflowStep, UNATTRIBUTED_AUTHOR,
+    conversation_scope, generate_hub_key, list_hub_backups, page_cursor, parse_memory_references,
+    parse_mentions, webhook_signature, AgentCard, AgentMetrics, AgentRecord, ArchiveOutcome,
+    AttachmentGcReport, AttachmentRecord, AttachmentSearchHit, AttachmentUsage, AuditEvent,
+    BackupFile, BackupManifest, BackupVerification, BudgetPauseOutcome, BudgetStatus,
+    ChannelRecord, CitationIssue, CitationWarning, CompactReport, CompactionCandidates,
+    DeliveryReceipt, DeliveryState, EffectiveAgentPermissions, EncryptionChange, EncryptionStatus,
+    EntropyRule, EscalationConfig, EscalationRecord, EscalationStep, GateVerdict, GitExportOutcome,
+    GraphExportFormat, HarnessSessionMode, HarnessSessionRegistration, HarnessSessionState,
+    HookConfig, HookEvent, HookRun, HookScript, HubBackend, HubBackup, HubError, HubExportHeader,
+    HubExportSummary, HubImportReport, HubStore, ImportKindReport, InMemoryHub, LinkSuggestion,
+    MemoryBackend, MemoryCitation, MemoryContradiction, MemoryDedupProposal, MemoryDedupReport,
+    MemoryDuplicate, MemoryGraph, MemoryGraphFilter, MemoryLinkRecord, MemoryMergeOutcome,
+    MemoryRecord, MemoryRelevance, MemoryScope, MemoryTier, MemoryUsageKind, MentionConfig,
+    MentionRecord, MessageBackend, MessageKind, MessageRecord, MessageSearch, MessageSearchHit,
+    MessageStatus, Page, PageRequest, PendingGateApproval, QuestionAnswer, QuestionRecord,
+    QuestionState, ReadMarker, ReceiptSummary, Redaction, RedactionConfig, RedactionDetector,
+    Redactor, RemappedId, RestoreOutcome, RetentionExport, RetentionReport, Role, RoleBackend,
+    RoleProviderDefault, SecretFinding, SecretScanReport, SendOutcome, ShutdownOutcome,
+    TaskBackend, TaskRecord, TaskStatus, ThreadSummary, UnreadScope, UnreadSummary, WakePolicy,
+    WakeRecord, WakeStatus, WebhookAttempt, WebhookDelivery, WebhookDeliveryStatus,
+    WorkSessionRecord, WorkflowStep, WorkspaceRetention, ATTACHMENT_GC_GRACE_HOURS,
+    BUILTIN_DETECTORS, CONTRADICTS_RELATION, DEFAULT_DEDUP_THRESHOLD, DEFAULT_PAGE_LIMIT,
+    ENTROPY_DETECTOR, HUB_BACKUP_DIR, HUB_BACKUP_FORMAT_VERSION, HUB_EXPORT_FORMAT,
+    HUB_EXPORT_FORMAT_VERSION, HUB_HOOKS_DIR, JOURNAL_TAG, MAX_INDEXED_TEXT_BYTES, MAX_PAGE_LIMIT,
+    RETENTION_ARCHIVE_DIR, RETENTION_ARCHIVE_FORMAT, STALE_CANDIDATE_BELOW, UNATTRIBUTED_AUTHOR,
+    WEBHOOK_BACKOFF_SECS, WEBHOOK_MAX_ATTEMPTS, WEBHOOK_SIGNATURE_HEADER, WORKSPACE_HOOKS_DIR,
 };
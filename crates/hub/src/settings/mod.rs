@@ -5,11 +5,13 @@
 //! settings. The desktop Settings window and its Agents tab belong to later
 //! UI slices. Typed IPC and audit fan-out live in
 //! `src-tauri/src/hub/commands/settings.rs` and
-//! `crates/hub/src/store/policies/settings_audit.rs`.
+//! `crates/hub/src/store/policies/settings_audit.rs`. Outbound webhook
+//! sinks (`[[webhook]]`) are configured here and delivered by the hub.
 
 mod model;
 mod profiles;
 mod store;
+mod webhooks;
 
 pub use model::{
     EffectiveHarnessSettings, EffectiveOrchestrationPolicy, EffectiveSettings, FieldStatus,
@@ -19,6 +21,7 @@ pub use model::{
     DEFAULT_BACKUP_RETENTION, MAX_BACKUP_RETENTION, MIN_BACKUP_RETENTION,
 };
 pub use store::{LoadStatus, SettingsLoad, SettingsStore};
+pub use webhooks::{WebhookEvent, WebhookSink};
 
 #[cfg(test)]
 mod tests;
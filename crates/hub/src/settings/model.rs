@@ -341,6 +341,7 @@ pub struct EffectiveSettings {
 pub enum SecretSourceKind {
     Keychain,
     EnvVar,
+    KeyFile,
     ProviderLogin,
 }
 
@@ -352,6 +353,8 @@ pub enum SecretSourceKind {
 pub enum SecretReference {
     Keychain { id: String },
     EnvVar { name: String },
+    /// Local file holding the secret; only the path is stored.
+    KeyFile { path: String },
     ProviderLogin,
 }
 
@@ -360,6 +363,7 @@ impl SecretReference {
         match self {
             Self::Keychain { .. } => SecretSourceKind::Keychain,
             Self::EnvVar { .. } => SecretSourceKind::EnvVar,
+            Self::KeyFile { .. } => SecretSourceKind::KeyFile,
             Self::ProviderLogin => SecretSourceKind::ProviderLogin,
         }
     }
@@ -369,6 +373,7 @@ impl SecretReference {
         match self {
             Self::Keychain { .. } => "Stored in System Keychain".into(),
             Self::EnvVar { name } => format!("Env Var ${name}"),
+            Self::KeyFile { path } => format!("Key file {path}"),
             Self::ProviderLogin => "Existing provider login".into(),
         }
     }
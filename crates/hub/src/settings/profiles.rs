@@ -58,6 +58,11 @@ pub fn validate_secret(secret: &SecretReference) -> Result<(), SettingsError> {
                 ));
             }
         }
+        SecretReference::KeyFile { path } => {
+            if path.trim().is_empty() {
+                return Err(SettingsError::Invalid("key file path must not be empty".into()));
+            }
+        }
         SecretReference::ProviderLogin => {}
     }
     Ok(())
@@ -130,7 +135,7 @@ pub fn validate_harness(settings: &HarnessSettings) -> Result<(), SettingsError>
     Ok(())
 }
 
-fn reject_secret_looking(value: &str, field: &str) -> Result<(), SettingsError> {
+pub(super) fn reject_secret_looking(value: &str, field: &str) -> Result<(), SettingsError> {
     let lower = value.to_ascii_lowercase();
     if lower.contains("sk-")
         || lower.contains("bearer ")
@@ -173,6 +178,10 @@ pub fn write_profile_fields(
                 table["secret_source"] = value("env_var");
                 table["secret_ref"] = value(name.as_str());
             }
+            SecretReference::KeyFile { path } => {
+                table["secret_source"] = value("key_file");
+                table["secret_ref"] = value(path.as_str());
+            }
             SecretReference::ProviderLogin => {
                 table["secret_source"] = value("provider_login");
             }
@@ -250,6 +259,15 @@ pub fn profiles_from_document(
                     })?
                     .to_string(),
             },
+            "key_file" => SecretReference::KeyFile {
+                path: table
+                    .get("secret_ref")
+                    .and_then(Item::as_str)
+                    .ok_or_else(|| {
+                        SettingsError::Invalid(format!("profile {name} missing secret_ref"))
+                    })?
+                    .to_string(),
+            },
             "provider_login" => SecretReference::ProviderLogin,
             other => {
                 return Err(SettingsError::Invalid(format!(
@@ -10,6 +10,7 @@ use super::profiles::{
     profiles_from_document, validate_harness, validate_profile, validate_profile_name,
     validate_provider, write_default_profiles, write_harness_fields, write_profile_fields,
 };
+use super::webhooks::{validate_webhook, webhooks_from_document, write_webhook_fields, WebhookSink};
 use chrono::Utc;
 use std::collections::BTreeMap;
 use std::fs::{self, File, OpenOptions};
@@ -44,10 +45,12 @@ pub struct SettingsStore {
     workspaces: BTreeMap<String, WorkspaceOverride>,
     profiles: BTreeMap<String, ProviderProfile>,
     harnesses: BTreeMap<String, HarnessSettings>,
+    webhooks: BTreeMap<String, WebhookSink>,
     load: SettingsLoad,
 }
 
 mod document;
 mod general;
 mod persistence;
+mod webhooks;
 mod workspace;
@@ -268,6 +268,7 @@ pub(super) fn parse_document(
         BTreeMap<String, WorkspaceOverride>,
         BTreeMap<String, ProviderProfile>,
         BTreeMap<String, HarnessSettings>,
+        BTreeMap<String, WebhookSink>,
     ),
     SettingsError,
 > {
@@ -283,7 +284,8 @@ pub(super) fn parse_document(
     }
     let profiles = profiles_from_document(&document)?;
     let harnesses = harnesses_from_document(&document)?;
-    Ok((document, snapshot, workspaces, profiles, harnesses))
+    let webhooks = webhooks_from_document(&document)?;
+    Ok((document, snapshot, workspaces, profiles, harnesses, webhooks))
 }
 
 pub(super) fn workspaces_from_document(
@@ -13,6 +13,7 @@ impl SettingsStore {
                 workspaces: BTreeMap::new(),
                 profiles: BTreeMap::new(),
                 harnesses: BTreeMap::new(),
+                webhooks: BTreeMap::new(),
                 load: SettingsLoad {
                     path,
                     status: LoadStatus::Missing,
@@ -25,6 +26,7 @@ impl SettingsStore {
                 workspaces: BTreeMap::new(),
                 profiles: BTreeMap::new(),
                 harnesses: BTreeMap::new(),
+                webhooks: BTreeMap::new(),
                 load: SettingsLoad {
                     path,
                     status: LoadStatus::Unreadable {
@@ -33,13 +35,14 @@ impl SettingsStore {
                 },
             },
             Ok(raw) => match parse_document(&raw) {
-                Ok((document, snapshot, workspaces, profiles, harnesses)) => Self {
+                Ok((document, snapshot, workspaces, profiles, harnesses, webhooks)) => Self {
                     home,
                     document,
                     snapshot,
                     workspaces,
                     profiles,
                     harnesses,
+                    webhooks,
                     load: SettingsLoad {
                         path,
                         status: LoadStatus::Loaded,
@@ -52,6 +55,7 @@ impl SettingsStore {
                     workspaces: BTreeMap::new(),
                     profiles: BTreeMap::new(),
                     harnesses: BTreeMap::new(),
+                    webhooks: BTreeMap::new(),
                     load: SettingsLoad {
                         path,
                         status: LoadStatus::Invalid {
@@ -76,13 +76,15 @@ impl SettingsStore {
             ));
         }
         let raw = fs::read_to_string(&backup_canon)?;
-        let (document, snapshot, workspaces, profiles, harnesses) = parse_document(&raw)?;
+        let (document, snapshot, workspaces, profiles, harnesses, webhooks) =
+            parse_document(&raw)?;
         snapshot.validate()?;
         self.document = document;
         self.snapshot = snapshot;
         self.workspaces = workspaces;
         self.profiles = profiles;
         self.harnesses = harnesses;
+        self.webhooks = webhooks;
         self.load.status = LoadStatus::Loaded;
         self.write_atomically(false)
     }
@@ -99,10 +101,14 @@ impl SettingsStore {
         for settings in self.harnesses.values() {
             validate_harness(settings)?;
         }
+        for sink in self.webhooks.values() {
+            validate_webhook(sink)?;
+        }
         write_snapshot_fields(&mut self.document, &self.snapshot);
         write_workspace_fields(&mut self.document, &self.workspaces);
         write_profile_fields(&mut self.document, &self.profiles);
         write_harness_fields(&mut self.document, &self.harnesses);
+        write_webhook_fields(&mut self.document, &self.webhooks);
         fs::create_dir_all(&self.home)?;
         if backup_current
             && self.load.path.exists()
@@ -0,0 +1,29 @@
+use super::*;
+
+impl SettingsStore {
+    pub fn list_webhooks(&self) -> Vec<WebhookSink> {
+        self.webhooks.values().cloned().collect()
+    }
+
+    pub fn webhook(&self, name: &str) -> Option<&WebhookSink> {
+        self.webhooks.get(name)
+    }
+
+    pub fn upsert_webhook(&mut self, sink: WebhookSink) -> Result<(), SettingsError> {
+        validate_webhook(&sink)?;
+        self.webhooks.insert(sink.name.clone(), sink);
+        write_webhook_fields(&mut self.document, &self.webhooks);
+        Ok(())
+    }
+
+    /// Remove a sink. Deliveries already queued for it fail on their next
+    /// attempt.
+    pub fn remove_webhook(&mut self, name: &str) -> Result<WebhookSink, SettingsError> {
+        let sink = self
+            .webhooks
+            .remove(name)
+            .ok_or_else(|| SettingsError::Invalid(format!("unknown webhook {name}")))?;
+        write_webhook_fields(&mut self.document, &self.webhooks);
+        Ok(sink)
+    }
+}
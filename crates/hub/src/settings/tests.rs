@@ -6,6 +6,8 @@ use tempfile::tempdir;
 
 #[path = "tests/profiles.rs"]
 mod profiles;
+#[path = "tests/webhooks.rs"]
+mod webhooks;
 
 #[test]
 fn missing_file_loads_defaults_and_does_not_create() {
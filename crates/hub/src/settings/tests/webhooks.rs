@@ -0,0 +1,70 @@
+use super::super::*;
+use std::fs;
+use tempfile::tempdir;
+
+fn sample_sink(name: &str) -> WebhookSink {
+    WebhookSink {
+        name: name.into(),
+        url: "https://hooks.example.com/hub".into(),
+        events: vec![WebhookEvent::MessageToHuman, WebhookEvent::TaskFailed],
+        template: Some(r#"{"text": "{{data.body}}"}"#.into()),
+        secret: Some(SecretReference::EnvVar {
+            name: "HUB_WEBHOOK_KEY".into(),
+        }),
+        enabled: true,
+    }
+}
+
+#[test]
+fn webhooks_round_trip_with_a_key_reference_only() {
+    let dir = tempdir().unwrap();
+    let mut store = SettingsStore::open(dir.path());
+    store.upsert_webhook(sample_sink("ops")).unwrap();
+    store
+        .upsert_webhook(WebhookSink {
+            events: vec![],
+            template: None,
+            secret: None,
+            enabled: false,
+            ..sample_sink("quiet")
+        })
+        .unwrap();
+    store.save().unwrap();
+    let raw = fs::read_to_string(store.path()).unwrap();
+    assert!(raw.contains("[[webhook]]"));
+    assert!(raw.contains("secret_ref = \"HUB_WEBHOOK_KEY\""));
+
+    let mut reloaded = SettingsStore::open(dir.path());
+    assert_eq!(reloaded.webhook("ops"), Some(&sample_sink("ops")));
+    let quiet = reloaded.webhook("quiet").unwrap();
+    assert!(!quiet.accepts(WebhookEvent::BudgetPaused));
+    assert!(reloaded.remove_webhook("quiet").is_ok());
+    assert_eq!(reloaded.list_webhooks().len(), 1);
+}
+
+#[test]
+fn webhooks_reject_bad_urls_templates_and_stored_keys() {
+    let dir = tempdir().unwrap();
+    let mut store = SettingsStore::open(dir.path());
+    for sink in [
+        WebhookSink {
+            url: "ftp://hooks.example.com".into(),
+            ..sample_sink("ops")
+        },
+        WebhookSink {
+            template: Some("{not json".into()),
+            ..sample_sink("ops")
+        },
+        WebhookSink {
+            secret: Some(SecretReference::Keychain {
+                id: "ca.webhook.ops".into(),
+            }),
+            ..sample_sink("ops")
+        },
+        sample_sink("has space"),
+    ] {
+        assert!(store.upsert_webhook(sink).is_err());
+    }
+    assert!(WebhookEvent::parse("task_failed").is_ok());
+    assert!(WebhookEvent::parse("everything").is_err());
+}
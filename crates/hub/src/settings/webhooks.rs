@@ -0,0 +1,230 @@
+//! Outbound webhook sinks (`[[webhook]]` tables): where hub events are
+//! POSTed. Delivery, templating and signing live in
+//! `crates/hub/src/store/webhooks.rs`; this module only validates and
+//! persists the configuration. The signing key is kept as a
+//! [`SecretReference`], never as a value.
+
+use super::model::{SecretReference, SettingsError};
+use super::profiles::{reject_secret_looking, validate_secret};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml_edit::{value, Array, ArrayOfTables, DocumentMut, Item, Table};
+
+/// Hub events a sink can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A message addressed to `human`.
+    MessageToHuman,
+    /// A tagged send held for human approval.
+    GateApprovalPending,
+    /// An agent paused on an exhausted budget.
+    BudgetPaused,
+    /// A task ran out of retries and was marked failed.
+    TaskFailed,
+}
+
+impl WebhookEvent {
+    pub const ALL: [Self; 4] = [
+        Self::MessageToHuman,
+        Self::GateApprovalPending,
+        Self::BudgetPaused,
+        Self::TaskFailed,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MessageToHuman => "message_to_human",
+            Self::GateApprovalPending => "gate_approval_pending",
+            Self::BudgetPaused => "budget_paused",
+            Self::TaskFailed => "task_failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, SettingsError> {
+        Self::ALL
+            .into_iter()
+            .find(|event| event.as_str() == value)
+            .ok_or_else(|| {
+                SettingsError::Invalid(format!(
+                    "unknown webhook event {value} (expected message_to_human, \
+                     gate_approval_pending, budget_paused or task_failed)"
+                ))
+            })
+    }
+}
+
+/// One configured sink.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookSink {
+    pub name: String,
+    pub url: String,
+    /// Events POSTed to `url`; every event when empty.
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    /// JSON payload template. `{{path}}` placeholders are filled from the
+    /// event envelope (`event`, `occurred_at`, `data.*`); a string that is
+    /// only a placeholder takes the value's JSON type. `None` posts the
+    /// envelope itself.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Key for the `X-Hub-Signature-256` HMAC header; unsigned when `None`.
+    #[serde(default)]
+    pub secret: Option<SecretReference>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl WebhookSink {
+    pub fn accepts(&self, event: WebhookEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+pub fn validate_webhook(sink: &WebhookSink) -> Result<(), SettingsError> {
+    let name = sink.name.as_str();
+    if name.is_empty()
+        || name.len() > 64
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '.' || ch == '_' || ch == '-')
+    {
+        return Err(SettingsError::Invalid(
+            "webhook name must be 1..=64 letters, digits, '.', '_' or '-'".into(),
+        ));
+    }
+    if !(sink.url.starts_with("https://") || sink.url.starts_with("http://"))
+        || sink.url.chars().any(char::is_whitespace)
+    {
+        return Err(SettingsError::Invalid(format!(
+            "webhook {name}: url must be an http(s) URL"
+        )));
+    }
+    reject_secret_looking(&sink.url, "webhook url")?;
+    if let Some(template) = &sink.template {
+        serde_json::from_str::<serde_json::Value>(template).map_err(|err| {
+            SettingsError::Invalid(format!("webhook {name}: template is not JSON: {err}"))
+        })?;
+    }
+    match &sink.secret {
+        Some(secret @ (SecretReference::EnvVar { .. } | SecretReference::KeyFile { .. })) => {
+            validate_secret(secret)?
+        }
+        Some(_) => {
+            return Err(SettingsError::Invalid(format!(
+                "webhook {name}: signing key must come from an env_var or key_file reference"
+            )))
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+pub fn write_webhook_fields(document: &mut DocumentMut, webhooks: &BTreeMap<String, WebhookSink>) {
+    if webhooks.is_empty() {
+        document.remove("webhook");
+        return;
+    }
+    let mut array = ArrayOfTables::new();
+    for sink in webhooks.values() {
+        let mut table = Table::new();
+        table["name"] = value(sink.name.as_str());
+        table["url"] = value(sink.url.as_str());
+        let mut events = Array::new();
+        for event in &sink.events {
+            events.push(event.as_str());
+        }
+        table["events"] = value(events);
+        if let Some(template) = &sink.template {
+            table["template"] = value(template.as_str());
+        }
+        match &sink.secret {
+            Some(SecretReference::EnvVar { name }) => {
+                table["secret_source"] = value("env_var");
+                table["secret_ref"] = value(name.as_str());
+            }
+            Some(SecretReference::KeyFile { path }) => {
+                table["secret_source"] = value("key_file");
+                table["secret_ref"] = value(path.as_str());
+            }
+            _ => {}
+        }
+        table["enabled"] = value(sink.enabled);
+        array.push(table);
+    }
+    document["webhook"] = Item::ArrayOfTables(array);
+}
+
+pub fn webhooks_from_document(
+    document: &DocumentMut,
+) -> Result<BTreeMap<String, WebhookSink>, SettingsError> {
+    let mut map = BTreeMap::new();
+    let Some(array) = document.get("webhook").and_then(Item::as_array_of_tables) else {
+        return Ok(map);
+    };
+    for table in array.iter() {
+        let name = table
+            .get("name")
+            .and_then(Item::as_str)
+            .ok_or_else(|| SettingsError::Invalid("webhook entry missing name".into()))?;
+        let url = table
+            .get("url")
+            .and_then(Item::as_str)
+            .ok_or_else(|| SettingsError::Invalid(format!("webhook {name} missing url")))?;
+        let mut events = Vec::new();
+        if let Some(item) = table.get("events") {
+            let array = item.as_array().ok_or_else(|| {
+                SettingsError::Invalid(format!("webhook {name} events must be an array"))
+            })?;
+            for event in array.iter() {
+                let event = event.as_str().ok_or_else(|| {
+                    SettingsError::Invalid(format!("webhook {name} events must be strings"))
+                })?;
+                events.push(WebhookEvent::parse(event)?);
+            }
+        }
+        let secret_ref = || {
+            table
+                .get("secret_ref")
+                .and_then(Item::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| SettingsError::Invalid(format!("webhook {name} missing secret_ref")))
+        };
+        let secret = match table.get("secret_source").and_then(Item::as_str) {
+            None => None,
+            Some("env_var") => Some(SecretReference::EnvVar {
+                name: secret_ref()?,
+            }),
+            Some("key_file") => Some(SecretReference::KeyFile {
+                path: secret_ref()?,
+            }),
+            Some(other) => {
+                return Err(SettingsError::Invalid(format!(
+                    "webhook {name} has unsupported secret_source {other}"
+                )))
+            }
+        };
+        let sink = WebhookSink {
+            name: name.to_string(),
+            url: url.to_string(),
+            events,
+            template: table
+                .get("template")
+                .and_then(Item::as_str)
+                .map(str::to_string),
+            secret,
+            enabled: table.get("enabled").and_then(Item::as_bool).unwrap_or(true),
+        };
+        validate_webhook(&sink)?;
+        if map.insert(sink.name.clone(), sink).is_some() {
+            return Err(SettingsError::Invalid(format!(
+                "duplicate webhook name {name}"
+            )));
+        }
+    }
+    Ok(map)
+}
@@ -64,8 +64,9 @@ impl HubStore {
         }
     }
 
-    /// Record a harness-authored message into the session transcript.
-    /// Duplicate polls of the same (harness, agent, session, body) are no-ops.
+    /// Record a harness-authored message into the session transcript, with
+    /// secrets redacted. Duplicate polls of the same (harness, agent,
+    /// session, body) are no-ops.
     pub fn record_harness_capture(
         &self,
         harness: &str,
@@ -99,6 +100,9 @@ impl HubStore {
         if existing.is_some() {
             return Ok(None);
         }
+        // The dedup hash stays over the raw text so a re-polled transcript
+        // still matches; only what gets stored is redacted.
+        let body = &self.redact_incoming("harness_capture", Some(agent_id), body)?;
 
         // Each capture needs its own unique subject — a fixed
         // "channel:session:<id>:capture" for every captured chunk, from
@@ -128,7 +132,7 @@ impl HubStore {
             r#"
             INSERT INTO harness_captures(
                 id, harness, agent_id, session_id, content_hash, message_id, body, created_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, hub_seal(?7), ?8)
             "#,
             params![
                 id,
@@ -171,13 +175,14 @@ impl HubStore {
         message_id: Option<&str>,
     ) -> Result<(), HubError> {
         let content_hash = harness_capture_content_hash(harness, agent_id, session_id, body);
+        let body = &self.redact_incoming("harness_capture", Some(agent_id), body)?;
         let id = Uuid::new_v4().to_string();
         let now = Utc::now().to_rfc3339();
         self.conn.execute(
             r#"
             INSERT OR IGNORE INTO harness_captures(
                 id, harness, agent_id, session_id, content_hash, message_id, body, created_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, hub_seal(?7), ?8)
             "#,
             params![id, harness, agent_id, session_id, content_hash, message_id, body, now],
         )?;
@@ -91,9 +91,10 @@ impl HubStore {
     }
 
     pub fn list_work_sessions(&self) -> Result<Vec<WorkSessionRecord>, HubError> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT id, name, created_at FROM work_sessions ORDER BY created_at DESC")?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, created_at FROM work_sessions
+             WHERE archived_at IS NULL ORDER BY created_at DESC",
+        )?;
         let sessions = stmt
             .query_map([], |row| {
                 Ok((
@@ -141,7 +142,7 @@ impl HubStore {
         self.seed_default_channels()?;
         let mut stmt = self.conn.prepare(
             "SELECT id, name, topic, builtin, created_at FROM chat_channels
-             WHERE deleted_at IS NULL
+             WHERE deleted_at IS NULL AND archived_at IS NULL
              ORDER BY builtin DESC, created_at ASC, id ASC",
         )?;
         let rows = stmt.query_map([], |row| {
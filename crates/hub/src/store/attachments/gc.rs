@@ -0,0 +1,282 @@
+//! Attachment reference counting, garbage collection and the total-size
+//! quota. References are computed on demand rather than stored, so deleting
+//! a message or clearing an avatar needs no bookkeeping: every
+//! `attachment://<id>` marker or desktop `[attachment:<id>:<name>]` token
+//! in message bodies, memory bodies and task steps, plus
+//! `agents.avatar_attachment_id`, counts once.
+
+use super::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, SystemTime};
+
+/// Unreferenced attachments (and stray files) younger than this survive a
+/// collection, so a file pasted into the composer is kept until its message
+/// is sent.
+pub const ATTACHMENT_GC_GRACE_HOURS: i64 = 24;
+
+const QUOTA_META_KEY: &str = "attachment_quota_bytes";
+/// Prefixes an attachment id follows in body text.
+const MARKERS: [&str; 2] = ["attachment://", "[attachment:"];
+
+/// Result of [`HubStore::attachment_usage`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentUsage {
+    pub attachments: usize,
+    pub blobs: usize,
+    /// Bytes on disk, each distinct blob counted once. This is what the
+    /// quota limits.
+    pub stored_bytes: u64,
+    /// Bytes the same uploads would take without deduplication.
+    pub logical_bytes: u64,
+    pub quota_bytes: Option<u64>,
+}
+
+/// Result of [`HubStore::gc_attachments`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentGcReport {
+    pub dry_run: bool,
+    /// Unreferenced attachments removed (or that would be, on a dry run).
+    pub removed: Vec<AttachmentRecord>,
+    /// Files under `attachments/` deleted because no attachment uses them.
+    pub removed_files: Vec<String>,
+    pub reclaimed_bytes: u64,
+    /// Unreferenced attachments kept because they are inside the grace
+    /// window.
+    pub kept_recent: usize,
+}
+
+fn count_markers(text: &str, counts: &mut BTreeMap<String, usize>) {
+    for marker in MARKERS {
+        for (start, _) in text.match_indices(marker) {
+            let id: String = text[start + marker.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect();
+            if let Some(count) = counts.get_mut(&id) {
+                *count += 1;
+            }
+        }
+    }
+}
+
+/// Every regular file under `root`, as `/`-separated paths relative to
+/// `base`.
+fn walk_files(base: &Path, root: &Path, out: &mut Vec<String>) -> Result<(), HubError> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            walk_files(base, &path, out)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            let parts: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            out.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+impl HubStore {
+    /// Attachment id → number of references, including every attachment
+    /// with zero. Markers naming unknown ids are ignored.
+    pub fn attachment_reference_counts(&self) -> Result<BTreeMap<String, usize>, HubError> {
+        let mut counts: BTreeMap<String, usize> = {
+            let mut stmt = self.conn.prepare("SELECT id FROM attachments")?;
+            let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+            rows.map(|id| id.map(|id| (id, 0)))
+                .collect::<Result<_, _>>()?
+        };
+        for sql in [
+            "SELECT hub_plain(body) FROM messages WHERE hub_plain(body) LIKE '%attachment:%'",
+            "SELECT hub_plain(body) FROM memories WHERE hub_plain(body) LIKE '%attachment:%'",
+            "SELECT title || char(10) || steps_json FROM tasks
+             WHERE title LIKE '%attachment:%' OR steps_json LIKE '%attachment:%'",
+        ] {
+            let mut stmt = self.conn.prepare(sql)?;
+            let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+            for text in rows {
+                count_markers(&text?, &mut counts);
+            }
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT avatar_attachment_id FROM agents WHERE avatar_attachment_id IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+        for id in rows {
+            if let Some(count) = counts.get_mut(&id?) {
+                *count += 1;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Total-size limit for stored attachment bytes, if one is set.
+    pub fn attachment_quota(&self) -> Result<Option<u64>, HubError> {
+        let raw: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![QUOTA_META_KEY],
+                |r| r.get(0),
+            )
+            .optional()?;
+        raw.map(|s| {
+            s.parse()
+                .map_err(|_| HubError::Invalid(format!("{QUOTA_META_KEY} corrupt: {s}")))
+        })
+        .transpose()
+    }
+
+    /// Set (`Some`) or remove (`None`) the attachment quota. Lowering it
+    /// below current usage is allowed; only new uploads are refused.
+    pub fn set_attachment_quota(&self, bytes: Option<u64>) -> Result<(), HubError> {
+        match bytes {
+            Some(0) => Err(HubError::Invalid(
+                "attachment quota must be > 0 bytes (clear it to remove the limit)".into(),
+            )),
+            Some(bytes) => {
+                self.conn.execute(
+                    r#"
+                    INSERT INTO meta(key, value) VALUES (?1, ?2)
+                    ON CONFLICT(key) DO UPDATE SET value = excluded.value
+                    "#,
+                    params![QUOTA_META_KEY, bytes.to_string()],
+                )?;
+                Ok(())
+            }
+            None => {
+                self.conn
+                    .execute("DELETE FROM meta WHERE key = ?1", params![QUOTA_META_KEY])?;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn attachment_usage(&self) -> Result<AttachmentUsage, HubError> {
+        let (attachments, logical_bytes): (i64, i64) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(byte_size), 0) FROM attachments",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        let (blobs, stored_bytes): (i64, i64) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM (
+                 SELECT MAX(byte_size) AS size FROM attachments GROUP BY relative_path
+             )",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        Ok(AttachmentUsage {
+            attachments: attachments as usize,
+            blobs: blobs as usize,
+            stored_bytes: stored_bytes as u64,
+            logical_bytes: logical_bytes as u64,
+            quota_bytes: self.attachment_quota()?,
+        })
+    }
+
+    /// Refuse a new blob of `additional` bytes that would push stored bytes
+    /// past the quota.
+    pub(super) fn check_attachment_quota(&self, additional: u64) -> Result<(), HubError> {
+        let usage = self.attachment_usage()?;
+        let Some(quota) = usage.quota_bytes else {
+            return Ok(());
+        };
+        let needed = usage.stored_bytes + additional;
+        if needed > quota {
+            return Err(HubError::Invalid(format!(
+                "attachment quota exceeded: storing {additional} more bytes would use {needed} of \
+                 {quota} bytes ({} already stored); run `ca attachments gc` or raise the limit \
+                 with `ca attachments quota --set <BYTES>`",
+                usage.stored_bytes
+            )));
+        }
+        Ok(())
+    }
+
+    /// Remove attachments nothing references (older than
+    /// [`ATTACHMENT_GC_GRACE_HOURS`]), then every file under `attachments/`
+    /// no remaining attachment points at. `dry_run` reports the same result
+    /// without deleting anything.
+    pub fn gc_attachments(&self, dry_run: bool) -> Result<AttachmentGcReport, HubError> {
+        let counts = self.attachment_reference_counts()?;
+        let cutoff = Utc::now() - chrono::Duration::hours(ATTACHMENT_GC_GRACE_HOURS);
+        let dir = self.attachments_dir();
+        let rows: Vec<(AttachmentRecord, String)> = {
+            let mut stmt = self
+                .conn
+                .prepare(&format!("SELECT {ATTACHMENT_COLUMNS} FROM attachments"))?;
+            let rows = stmt.query_map([], |r| Ok((row_to_attachment(r, &dir)?, r.get(4)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let mut removed = Vec::new();
+        let mut removed_paths = BTreeSet::new();
+        let mut kept_paths = BTreeSet::new();
+        let mut kept_recent = 0;
+        for (record, relative_path) in rows {
+            if counts.get(&record.id).copied().unwrap_or(0) > 0 {
+                kept_paths.insert(relative_path);
+                continue;
+            }
+            let recent = chrono::DateTime::parse_from_rfc3339(&record.created_at)
+                .is_ok_and(|created| created > cutoff);
+            if recent {
+                kept_recent += 1;
+                kept_paths.insert(relative_path);
+            } else {
+                removed_paths.insert(relative_path);
+                removed.push(record);
+            }
+        }
+
+        // Blobs of removed rows go now; any other stray file only once it
+        // is past the grace window, since a blob is written just before its
+        // row is inserted.
+        let stray_cutoff = SystemTime::now()
+            - Duration::from_secs(ATTACHMENT_GC_GRACE_HOURS.unsigned_abs() * 3600);
+        let mut files = Vec::new();
+        walk_files(&dir, &dir, &mut files)?;
+        files.sort();
+        let mut removed_files = Vec::new();
+        let mut reclaimed_bytes = 0;
+        for file in files {
+            if kept_paths.contains(&file) {
+                continue;
+            }
+            let metadata = fs::metadata(dir.join(&file))?;
+            let stale = metadata.modified().map_or(true, |m| m < stray_cutoff);
+            if removed_paths.contains(&file) || stale {
+                reclaimed_bytes += metadata.len();
+                removed_files.push(file);
+            }
+        }
+
+        if !dry_run {
+            let tx = self.conn.unchecked_transaction()?;
+            for record in &removed {
+                tx.execute("DELETE FROM attachments WHERE id = ?1", params![record.id])?;
+            }
+            tx.execute(
+                "DELETE FROM attachment_texts
+                 WHERE sha256 NOT IN (SELECT sha256 FROM attachments WHERE sha256 IS NOT NULL)",
+                [],
+            )?;
+            tx.commit()?;
+            for file in &removed_files {
+                fs::remove_file(dir.join(file))?;
+            }
+        }
+        Ok(AttachmentGcReport {
+            dry_run,
+            removed,
+            removed_files,
+            reclaimed_bytes,
+            kept_recent,
+        })
+    }
+}
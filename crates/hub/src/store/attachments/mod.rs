@@ -1,14 +1,25 @@
 //! Message attachments (images and other files pasted or picked in the
-//! desktop composer). Stored as plain files under `<hub_home>/attachments/`
-//! and indexed in the `attachments` table; a message references one by
-//! embedding an `attachment://<id>` marker in its body text, the same
-//! pattern already used for `[Memory #<id>]` links — no change to the
-//! `messages` table or any existing `send_message`/`send_tagged_message`
-//! call site.
+//! desktop composer). Indexed in the `attachments` table; a message
+//! references one by embedding an `attachment://<id>` marker in its body
+//! text, the same pattern already used for `[Memory #<id>]` links — no
+//! change to the `messages` table or any existing
+//! `send_message`/`send_tagged_message` call site.
+//!
+//! Bytes are content-addressed: every row points at
+//! `attachments/sha256/<aa>/<sha256>`, so pasting the same screenshot twice
+//! adds a row but no second file. Reference counting, garbage collection
+//! and the size quota live in [`gc`]; text extraction and search in
+//! [`text`].
 
 use super::*;
 use std::fs;
 
+mod gc;
+mod text;
+pub use gc::{AttachmentGcReport, AttachmentUsage, ATTACHMENT_GC_GRACE_HOURS};
+pub use text::{AttachmentSearchHit, MAX_INDEXED_TEXT_BYTES};
+pub(crate) use text::snippet;
+
 /// Desktop uploads above this size are rejected outright rather than
 /// silently truncated or slowly written.
 const MAX_ATTACHMENT_BYTES: usize = 20 * 1024 * 1024;
@@ -33,6 +44,12 @@ fn sanitize_filename(name: &str) -> String {
     }
 }
 
+/// Blob path for `sha256`, relative to `attachments_dir()`. Fanned out by
+/// the first two hex digits so no single directory grows unbounded.
+fn blob_relative_path(sha256: &str) -> String {
+    format!("sha256/{}/{sha256}", &sha256[..2])
+}
+
 fn row_to_attachment(row: &rusqlite::Row, base_dir: &Path) -> rusqlite::Result<AttachmentRecord> {
     let relative_path: String = row.get(4)?;
     Ok(AttachmentRecord {
@@ -42,17 +59,21 @@ fn row_to_attachment(row: &rusqlite::Row, base_dir: &Path) -> rusqlite::Result<A
         byte_size: row.get(3)?,
         absolute_path: base_dir.join(&relative_path).to_string_lossy().to_string(),
         created_at: row.get(5)?,
+        sha256: row.get(6)?,
     })
 }
 
-const ATTACHMENT_COLUMNS: &str = "id, filename, mime, byte_size, relative_path, created_at";
+const ATTACHMENT_COLUMNS: &str =
+    "id, filename, mime, byte_size, relative_path, created_at, sha256";
 
 impl HubStore {
     pub fn attachments_dir(&self) -> PathBuf {
         self.data_dir.join("attachments")
     }
 
-    /// Writes `data` to disk under `attachments_dir()` and indexes it.
+    /// Indexes `data` as a new attachment, writing its blob under
+    /// `attachments_dir()` only when no attachment with the same SHA-256
+    /// exists yet (new bytes count against the quota; duplicates do not).
     /// Returns the durable record (including the absolute on-disk path)
     /// the caller embeds into a message body as `attachment://<id>`.
     pub fn save_attachment(
@@ -77,21 +98,82 @@ impl HubStore {
         };
         let id = Uuid::new_v4().to_string();
         let safe_name = sanitize_filename(filename);
-        let dir = self.attachments_dir();
-        fs::create_dir_all(&dir)?;
-        let relative_path = format!("{id}__{safe_name}");
-        fs::write(dir.join(&relative_path), data)?;
+        let sha256 = sha256_hex(data);
+        let relative_path = blob_relative_path(&sha256);
+        if !self.attachments_dir().join(&relative_path).is_file() {
+            self.check_attachment_quota(data.len() as u64)?;
+        }
+        self.write_blob(&relative_path, data)?;
+        self.index_attachment_text(&sha256, &safe_name, mime, data)?;
         let created_at = Utc::now().to_rfc3339();
         self.conn.execute(
             &format!(
-                "INSERT INTO attachments ({ATTACHMENT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+                "INSERT INTO attachments ({ATTACHMENT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
             ),
-            params![id, safe_name, mime, data.len() as i64, relative_path, created_at],
+            params![id, safe_name, mime, data.len() as i64, relative_path, created_at, sha256],
         )?;
         self.get_attachment_record(&id)?
             .ok_or_else(|| HubError::NotFound(id))
     }
 
+    /// Write a blob unless it is already on disk. Writes go through a
+    /// temporary file so a crash never leaves a truncated blob behind a
+    /// valid hash name.
+    fn write_blob(&self, relative_path: &str, data: &[u8]) -> Result<(), HubError> {
+        let path = self.attachments_dir().join(relative_path);
+        if path.is_file() {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let staging = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        fs::write(&staging, self.cipher().seal_blob(data)?)?;
+        fs::rename(&staging, &path)?;
+        Ok(())
+    }
+
+    /// Move attachments saved before content addressing (one
+    /// `<id>__<name>` file per row) into blobs. Rows whose file is missing
+    /// keep their old path and are retried on the next open.
+    pub(crate) fn ensure_content_addressed_attachments(&self) -> Result<(), HubError> {
+        self.conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_attachments_sha256 ON attachments(sha256);",
+        )?;
+        if self.encryption_locked() {
+            return Ok(());
+        }
+        let legacy: Vec<(String, String)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, relative_path FROM attachments WHERE sha256 IS NULL")?;
+            let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let dir = self.attachments_dir();
+        for (id, old_path) in legacy {
+            let Ok(data) = fs::read(dir.join(&old_path)) else {
+                continue;
+            };
+            let sha256 = sha256_hex(&data);
+            let relative_path = blob_relative_path(&sha256);
+            self.write_blob(&relative_path, &data)?;
+            self.conn.execute(
+                "UPDATE attachments SET sha256 = ?1, relative_path = ?2 WHERE id = ?3",
+                params![sha256, relative_path, id],
+            )?;
+            let still_used: bool = self.conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM attachments WHERE relative_path = ?1)",
+                params![old_path],
+                |r| r.get(0),
+            )?;
+            if !still_used {
+                fs::remove_file(dir.join(&old_path))?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_attachment_record(&self, id: &str) -> Result<Option<AttachmentRecord>, HubError> {
         let dir = self.attachments_dir();
         self.conn
@@ -104,13 +186,14 @@ impl HubStore {
             .map_err(HubError::from)
     }
 
-    /// Reads the attachment's metadata and raw bytes back from disk,
-    /// for the composer/message stream to render or hand to a harness.
+    /// Reads the attachment's metadata and bytes back from disk (decrypted
+    /// when the hub is encrypted), for the composer/message stream to
+    /// render or hand to a harness.
     pub fn read_attachment(&self, id: &str) -> Result<Option<(AttachmentRecord, Vec<u8>)>, HubError> {
         let Some(record) = self.get_attachment_record(id)? else {
             return Ok(None);
         };
-        let data = fs::read(&record.absolute_path)?;
+        let data = self.cipher().open_blob(fs::read(&record.absolute_path)?)?;
         Ok(Some((record, data)))
     }
 }
@@ -130,6 +213,7 @@ mod tests {
         assert_eq!(record.filename, "name.png");
         assert_eq!(record.byte_size, 10);
         assert!(Path::new(&record.absolute_path).is_file());
+        assert!(record.absolute_path.ends_with(&sha256_hex(b"fake-bytes")));
 
         let (fetched, data) = store.read_attachment(&record.id).unwrap().unwrap();
         assert_eq!(fetched.id, record.id);
@@ -0,0 +1,264 @@
+//! Searchable text for attachments. Every blob is examined once, when it is
+//! saved (or, for blobs saved before this index existed, when the hub
+//! opens): its bytes are sniffed rather than trusted to the declared mime
+//! or filename, and text-like content is stored in `attachment_texts`,
+//! capped at [`MAX_INDEXED_TEXT_BYTES`]. Binary blobs get a row with no text
+//! so they are not re-read on every open.
+
+use super::*;
+
+/// Text kept per blob; longer attachments are indexed by their head.
+pub const MAX_INDEXED_TEXT_BYTES: usize = 256 * 1024;
+
+/// Bytes inspected when deciding whether a blob is text.
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Characters of context shown either side of a match.
+const SNIPPET_CONTEXT: usize = 60;
+
+/// Signatures of common binary formats that can still pass a UTF-8 check on
+/// their first few kilobytes.
+const BINARY_MAGIC: [&[u8]; 8] = [
+    b"\x89PNG",
+    b"\xff\xd8\xff",
+    b"GIF8",
+    b"%PDF",
+    b"PK\x03\x04",
+    b"\x1f\x8b",
+    b"\x7fELF",
+    b"RIFF",
+];
+
+/// Declared `application/*` types that are text.
+const TEXT_APPLICATION_MIMES: [&str; 8] = [
+    "application/json",
+    "application/xml",
+    "application/yaml",
+    "application/x-yaml",
+    "application/toml",
+    "application/javascript",
+    "application/x-sh",
+    "application/sql",
+];
+
+/// One attachment whose text or filename matched, with the messages that
+/// embed it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentSearchHit {
+    pub attachment: AttachmentRecord,
+    /// Sniffed text type; `None` for binary attachments matched by filename.
+    pub text_mime: Option<String>,
+    pub snippet: String,
+    /// True when the attachment is longer than the indexed text.
+    pub truncated: bool,
+    /// Messages carrying an `attachment://<id>` marker or
+    /// `[attachment:<id>:<name>]` token for this attachment, newest first.
+    pub messages: Vec<MessageRecord>,
+}
+
+/// The text type of `data`, or `None` if it is binary. Content wins over
+/// the declared mime; the filename only picks among text types.
+fn sniff_text_mime(filename: &str, declared: &str, data: &[u8]) -> Option<String> {
+    if BINARY_MAGIC.iter().any(|magic| data.starts_with(magic)) {
+        return None;
+    }
+    let head = &data[..data.len().min(SNIFF_BYTES)];
+    if head.contains(&0) {
+        return None;
+    }
+    if let Err(err) = std::str::from_utf8(head) {
+        // A multi-byte character cut by the sniff window is fine.
+        if err.error_len().is_some() {
+            return None;
+        }
+    }
+    let declared = declared.trim().to_ascii_lowercase();
+    if declared.starts_with("text/") || TEXT_APPLICATION_MIMES.contains(&declared.as_str()) {
+        return Some(declared);
+    }
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    let mime = match extension.as_str() {
+        "md" | "markdown" => "text/markdown",
+        "json" => "application/json",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        _ => "text/plain",
+    };
+    Some(mime.to_string())
+}
+
+/// The indexed head of `data` and whether it was cut short.
+fn extract_text(data: &[u8]) -> (String, bool) {
+    if data.len() <= MAX_INDEXED_TEXT_BYTES {
+        return (String::from_utf8_lossy(data).into_owned(), false);
+    }
+    let head = &data[..MAX_INDEXED_TEXT_BYTES];
+    let end = match std::str::from_utf8(head) {
+        Ok(_) => head.len(),
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        Err(_) => head.len(),
+    };
+    (String::from_utf8_lossy(&head[..end]).into_owned(), true)
+}
+
+/// Up to [`SNIPPET_CONTEXT`] characters either side of the first
+/// ASCII-case-insensitive match, on one line.
+pub(crate) fn snippet(text: &str, query: &str) -> String {
+    let needle = query.trim().to_ascii_lowercase();
+    let at = text.to_ascii_lowercase().find(&needle).unwrap_or(0);
+    let mut start = at.saturating_sub(SNIPPET_CONTEXT);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (at + needle.len() + SNIPPET_CONTEXT).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    let body = text[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "{}{body}{}",
+        if start > 0 { "…" } else { "" },
+        if end < text.len() { "…" } else { "" }
+    )
+}
+
+impl HubStore {
+    /// Record the text (or the absence of text) of the blob `sha256`. A blob
+    /// already examined is left alone.
+    pub(super) fn index_attachment_text(
+        &self,
+        sha256: &str,
+        filename: &str,
+        mime: &str,
+        data: &[u8],
+    ) -> Result<(), HubError> {
+        let text_mime = sniff_text_mime(filename, mime, data);
+        let (body, truncated) = match text_mime {
+            Some(_) => {
+                let (body, truncated) = extract_text(data);
+                (Some(body), truncated)
+            }
+            None => (None, false),
+        };
+        self.conn.execute(
+            "INSERT OR IGNORE INTO attachment_texts (sha256, text_mime, body, truncated, indexed_at)
+             VALUES (?1, ?2, hub_seal(?3), ?4, ?5)",
+            params![
+                sha256,
+                text_mime,
+                body,
+                truncated as i64,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Examine blobs that have no `attachment_texts` row yet. Blobs whose
+    /// file is missing are retried on the next open.
+    pub(crate) fn ensure_attachment_text_index(&self) -> Result<(), HubError> {
+        if self.encryption_locked() {
+            return Ok(());
+        }
+        let pending: Vec<(String, String, String, String)> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT a.sha256, MIN(a.filename), MIN(a.mime), MIN(a.relative_path)
+                 FROM attachments a
+                 LEFT JOIN attachment_texts t ON t.sha256 = a.sha256
+                 WHERE a.sha256 IS NOT NULL AND t.sha256 IS NULL
+                 GROUP BY a.sha256",
+            )?;
+            let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let dir = self.attachments_dir();
+        for (sha256, filename, mime, relative_path) in pending {
+            if let Ok(data) = fs::read(dir.join(relative_path)) {
+                let data = self.cipher().open_blob(data)?;
+                self.index_attachment_text(&sha256, &filename, &mime, &data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Attachments whose indexed text or filename contains `query`, newest
+    /// first, each with the messages that embed it.
+    pub fn search_attachments(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<AttachmentSearchHit>, HubError> {
+        if query.trim().is_empty() {
+            return Err(HubError::Invalid("search query must not be empty".into()));
+        }
+        let pattern = format!("%{}%", query.trim());
+        let columns: Vec<String> = ATTACHMENT_COLUMNS
+            .split(", ")
+            .map(|c| format!("a.{c}"))
+            .collect();
+        let dir = self.attachments_dir();
+        let found: Vec<(AttachmentRecord, Option<String>, Option<String>, bool)> = {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {}, t.text_mime, hub_plain(t.body), IFNULL(t.truncated, 0)
+                 FROM attachments a
+                 LEFT JOIN attachment_texts t ON t.sha256 = a.sha256
+                 WHERE IFNULL(hub_plain(t.body), '') LIKE ?1 OR a.filename LIKE ?1
+                 ORDER BY a.created_at DESC
+                 LIMIT ?2",
+                columns.join(", ")
+            ))?;
+            let rows = stmt.query_map(params![pattern, limit as i64], |r| {
+                Ok((
+                    row_to_attachment(r, &dir)?,
+                    r.get(7)?,
+                    r.get(8)?,
+                    r.get::<_, i64>(9)? != 0,
+                ))
+            })?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let mut hits = Vec::with_capacity(found.len());
+        for (attachment, text_mime, body, truncated) in found {
+            let snippet = match &body {
+                Some(body)
+                    if body
+                        .to_ascii_lowercase()
+                        .contains(&query.trim().to_ascii_lowercase()) =>
+                {
+                    snippet(body, query)
+                }
+                _ => attachment.filename.clone(),
+            };
+            let messages = self.messages_embedding_attachment(&attachment.id)?;
+            hits.push(AttachmentSearchHit {
+                attachment,
+                text_mime,
+                snippet,
+                truncated,
+                messages,
+            });
+        }
+        Ok(hits)
+    }
+
+    fn messages_embedding_attachment(&self, id: &str) -> Result<Vec<MessageRecord>, HubError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {MESSAGE_COLUMNS} FROM messages
+             WHERE hub_plain(body) LIKE '%attachment://' || ?1 || '%'
+                OR hub_plain(body) LIKE '%[attachment:' || ?1 || ':%'
+             ORDER BY created_at DESC"
+        ))?;
+        let rows = stmt.query_map(params![id], Self::row_to_message)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
@@ -0,0 +1,524 @@
+//! A hub backend held entirely in memory. Nothing is persisted, and the
+//! SQLite-only layers (encryption, redaction, audit events) do not apply,
+//! so records come back exactly as written. Memory usage and links are not
+//! tracked either, so relevance is the recency term alone.
+
+use super::super::roles::CTO_ROLE_ID;
+use super::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Default)]
+struct State {
+    messages: BTreeMap<String, MessageRecord>,
+    memories: BTreeMap<String, MemoryRecord>,
+    tasks: BTreeMap<String, TaskRecord>,
+    roles: BTreeMap<String, Role>,
+    /// `(agent_id, role_id)`.
+    assignments: BTreeSet<(String, String)>,
+}
+
+/// An in-memory hub. Like a freshly opened [`HubStore`], it starts with
+/// the builtin `cto` role assigned to `human`.
+pub struct InMemoryHub {
+    state: Mutex<State>,
+}
+
+impl Default for InMemoryHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryHub {
+    pub fn new() -> Self {
+        let now = Utc::now().to_rfc3339();
+        let mut state = State::default();
+        state.roles.insert(
+            CTO_ROLE_ID.into(),
+            Role {
+                id: CTO_ROLE_ID.into(),
+                display_name: "CTO".into(),
+                is_builtin: true,
+                daily_ungated_quota: None,
+                max_broadcast_recipients: None,
+                can_archive_messages: true,
+                can_update_agent_roles: true,
+                can_allocate_tasks: true,
+                responsibilities: Vec::new(),
+                created_at: now.clone(),
+                updated_at: now,
+            },
+        );
+        state
+            .assignments
+            .insert(("human".into(), CTO_ROLE_ID.into()));
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // A panic mid-update cannot leave a half-written record behind
+        // (every update replaces whole values), so a poisoned lock is safe.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn newest_first<T>(rows: &mut [T], key: impl Fn(&T) -> (&str, &str)) {
+    rows.sort_by(|a, b| key(b).cmp(&key(a)));
+}
+
+impl MessageBackend for InMemoryHub {
+    fn send_message(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        kind: MessageKind,
+        body: &str,
+        subject: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+    ) -> Result<MessageRecord, HubError> {
+        if body.trim().is_empty() {
+            return Err(HubError::Invalid("message body must not be empty".into()));
+        }
+        let message = MessageRecord {
+            id: Uuid::new_v4().to_string(),
+            from_agent: from_agent.into(),
+            to_agent: to_agent.into(),
+            workspace_path: workspace_path.map(str::to_string),
+            task_id: task_id.map(str::to_string),
+            kind: kind.as_str().into(),
+            status: MessageStatus::Pending.as_str().into(),
+            subject: subject.map(str::to_string),
+            body: body.into(),
+            created_at: Utc::now().to_rfc3339(),
+            acked_at: None,
+            archived_at: None,
+            reply_to: None,
+            thread_id: None,
+            payload: None,
+        };
+        self.state()
+            .messages
+            .insert(message.id.clone(), message.clone());
+        Ok(message)
+    }
+
+    fn get_message(&self, id: &str) -> Result<Option<MessageRecord>, HubError> {
+        Ok(self.state().messages.get(id).cloned())
+    }
+
+    fn list_messages_page(
+        &self,
+        to_agent: Option<&str>,
+        status: Option<MessageStatus>,
+        page: &PageRequest,
+    ) -> Result<Page<MessageRecord>, HubError> {
+        let rows = self
+            .state()
+            .messages
+            .values()
+            .filter(|m| m.archived_at.is_none())
+            .filter(|m| to_agent.is_none_or(|to| m.to_agent == to))
+            .filter(|m| status.is_none_or(|s| m.status == s.as_str()))
+            .cloned()
+            .collect();
+        page_rows(rows, page, |m| (&m.created_at, &m.id))
+    }
+
+    fn ack_message(&self, id: &str) -> Result<Option<MessageRecord>, HubError> {
+        let mut state = self.state();
+        Ok(state.messages.get_mut(id).map(|m| {
+            m.status = MessageStatus::Acked.as_str().into();
+            m.acked_at = Some(Utc::now().to_rfc3339());
+            m.clone()
+        }))
+    }
+
+    fn poll_messages(
+        &self,
+        to_agent: &str,
+        mark_acked: bool,
+    ) -> Result<Vec<MessageRecord>, HubError> {
+        let pending = self.list_messages_page(
+            Some(to_agent),
+            Some(MessageStatus::Pending),
+            &PageRequest::latest(200),
+        )?;
+        if !mark_acked {
+            return Ok(pending.items);
+        }
+        let mut acked = Vec::with_capacity(pending.items.len());
+        for message in pending.items {
+            acked.extend(self.ack_message(&message.id)?);
+        }
+        Ok(acked)
+    }
+
+    fn set_message_status(
+        &self,
+        id: &str,
+        status: MessageStatus,
+    ) -> Result<MessageRecord, HubError> {
+        let mut state = self.state();
+        let message = state
+            .messages
+            .get_mut(id)
+            .ok_or_else(|| HubError::NotFound(id.into()))?;
+        message.status = status.as_str().into();
+        if matches!(status, MessageStatus::Acked | MessageStatus::Done) {
+            message.acked_at = Some(Utc::now().to_rfc3339());
+        }
+        Ok(message.clone())
+    }
+}
+
+impl MemoryBackend for InMemoryHub {
+    fn write_memory(
+        &self,
+        tier: MemoryTier,
+        scope: MemoryScope,
+        agent_id: Option<&str>,
+        workspace_path: Option<&str>,
+        title: Option<&str>,
+        body: &str,
+        tags: &[String],
+    ) -> Result<MemoryRecord, HubError> {
+        match scope {
+            MemoryScope::Session => {
+                return Err(HubError::Invalid(
+                    "session scope requires a work session; use write_session_memory".into(),
+                ))
+            }
+            MemoryScope::Workspace if workspace_path.is_none() => {
+                return Err(HubError::Invalid(
+                    "workspace scope requires --workspace".into(),
+                ))
+            }
+            MemoryScope::AgentPrivate if agent_id.is_none_or(|a| a.trim().is_empty()) => {
+                return Err(HubError::Invalid(
+                    "agent_private scope requires --agent".into(),
+                ))
+            }
+            _ => {}
+        }
+        if body.trim().is_empty() {
+            return Err(HubError::Invalid("memory body must not be empty".into()));
+        }
+        let now = Utc::now().to_rfc3339();
+        let memory = MemoryRecord {
+            id: Uuid::new_v4().to_string(),
+            scope: scope.as_str().into(),
+            workspace_path: workspace_path.map(str::to_string),
+            tier: tier.as_str().into(),
+            agent_id: agent_id.map(str::to_string),
+            title: title.map(str::to_string),
+            body: body.into(),
+            tags_json: serde_json::to_string(tags).unwrap_or_else(|_| "[]".into()),
+            created_at: now.clone(),
+            updated_at: now,
+            stale: false,
+            source_event_id: None,
+            session_id: None,
+            relevance: None,
+        };
+        self.state()
+            .memories
+            .insert(memory.id.clone(), memory.clone());
+        Ok(memory)
+    }
+
+    fn get_memory(&self, id: &str) -> Result<Option<MemoryRecord>, HubError> {
+        Ok(self.state().memories.get(id).cloned())
+    }
+
+    fn update_memory(
+        &self,
+        id: &str,
+        title: Option<&str>,
+        body: &str,
+        tags: Option<&[String]>,
+    ) -> Result<MemoryRecord, HubError> {
+        if body.trim().is_empty() {
+            return Err(HubError::Invalid("memory body must not be empty".into()));
+        }
+        let mut state = self.state();
+        let memory = state
+            .memories
+            .get_mut(id)
+            .ok_or_else(|| HubError::NotFound(id.to_string()))?;
+        memory.title = title.map(str::to_string);
+        memory.body = body.into();
+        memory.updated_at = Utc::now().to_rfc3339();
+        if let Some(tags) = tags {
+            memory.tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".into());
+        }
+        Ok(memory.clone())
+    }
+
+    fn list_memories(
+        &self,
+        scope: Option<MemoryScope>,
+        tier: Option<MemoryTier>,
+        workspace_path: Option<&str>,
+        include_stale: bool,
+    ) -> Result<Vec<MemoryRecord>, HubError> {
+        let mut rows: Vec<MemoryRecord> = self
+            .state()
+            .memories
+            .values()
+            .filter(|m| m.scope == "global" || m.scope == "workspace")
+            .filter(|m| include_stale || !m.stale)
+            .filter(|m| scope.is_none_or(|s| m.scope == s.as_str()))
+            .filter(|m| tier.is_none_or(|t| m.tier == t.as_str()))
+            .filter(|m| workspace_path.is_none_or(|ws| m.workspace_path.as_deref() == Some(ws)))
+            .cloned()
+            .map(|mut m| {
+                m.relevance = Some(unused_relevance(&m));
+                m
+            })
+            .collect();
+        sort_by_relevance(&mut rows);
+        rows.truncate(200);
+        Ok(rows)
+    }
+
+    fn search_memories(&self, query: &str) -> Result<Vec<MemoryRecord>, HubError> {
+        if query.trim().is_empty() {
+            return Err(HubError::Invalid("search query must not be empty".into()));
+        }
+        let needle = query.trim().to_lowercase();
+        let mut rows: Vec<MemoryRecord> = self
+            .list_memories(None, None, None, false)?
+            .into_iter()
+            .filter(|m| {
+                [
+                    m.body.as_str(),
+                    m.title.as_deref().unwrap_or(""),
+                    &m.tags_json,
+                ]
+                .iter()
+                .any(|field| field.to_lowercase().contains(&needle))
+            })
+            .collect();
+        newest_first(&mut rows, |m| (&m.created_at, &m.id));
+        rows.truncate(100);
+        Ok(rows)
+    }
+
+    fn mark_memory_stale(&self, id: &str, stale: bool) -> Result<(), HubError> {
+        let mut state = self.state();
+        let memory = state
+            .memories
+            .get_mut(id)
+            .ok_or_else(|| HubError::NotFound(id.into()))?;
+        memory.stale = stale;
+        memory.updated_at = Utc::now().to_rfc3339();
+        Ok(())
+    }
+
+    fn delete_memory(&self, id: &str) -> Result<(), HubError> {
+        self.state()
+            .memories
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| HubError::NotFound(id.into()))
+    }
+}
+
+impl TaskBackend for InMemoryHub {
+    fn create_task(
+        &self,
+        title: &str,
+        workspace_path: Option<&str>,
+        steps: &[WorkflowStep],
+    ) -> Result<TaskRecord, HubError> {
+        if title.trim().is_empty() {
+            return Err(HubError::Invalid("task title must not be empty".into()));
+        }
+        if steps.is_empty() {
+            return Err(HubError::Invalid(
+                "task needs at least one workflow step".into(),
+            ));
+        }
+        for (i, s) in steps.iter().enumerate() {
+            if s.agent.trim().is_empty() {
+                return Err(HubError::Invalid(format!("step {i}: agent required")));
+            }
+            if s.instruction.trim().is_empty() {
+                return Err(HubError::Invalid(format!("step {i}: instruction required")));
+            }
+        }
+        let now = Utc::now().to_rfc3339();
+        let task = TaskRecord {
+            id: Uuid::new_v4().to_string(),
+            title: title.into(),
+            workspace_path: workspace_path.map(str::to_string),
+            status: TaskStatus::Pending.as_str().into(),
+            step_index: 0,
+            steps: steps.to_vec(),
+            created_at: now.clone(),
+            updated_at: now,
+            last_message_id: None,
+            attempts: Default::default(),
+            open_agents: Vec::new(),
+            pending_agents: Vec::new(),
+            max_parallel: 4,
+            require_human_approval: true,
+        };
+        self.state().tasks.insert(task.id.clone(), task.clone());
+        Ok(task)
+    }
+
+    fn get_task(&self, id: &str) -> Result<Option<TaskRecord>, HubError> {
+        Ok(self.state().tasks.get(id).cloned())
+    }
+
+    fn list_tasks(&self, status: Option<TaskStatus>) -> Result<Vec<TaskRecord>, HubError> {
+        let mut rows: Vec<TaskRecord> = self
+            .state()
+            .tasks
+            .values()
+            .filter(|t| status.is_none_or(|s| t.status == s.as_str()))
+            .cloned()
+            .collect();
+        newest_first(&mut rows, |t| (&t.updated_at, &t.id));
+        rows.truncate(100);
+        Ok(rows)
+    }
+
+    fn cancel_task(&self, id: &str) -> Result<TaskRecord, HubError> {
+        let mut state = self.state();
+        let task = state
+            .tasks
+            .get_mut(id)
+            .ok_or_else(|| HubError::NotFound(id.into()))?;
+        task.status = TaskStatus::Cancelled.as_str().into();
+        task.updated_at = Utc::now().to_rfc3339();
+        task.open_agents.clear();
+        task.pending_agents.clear();
+        Ok(task.clone())
+    }
+}
+
+impl RoleBackend for InMemoryHub {
+    fn upsert_role(
+        &self,
+        id: &str,
+        display_name: &str,
+        daily_ungated_quota: Option<i64>,
+        max_broadcast_recipients: Option<i64>,
+        can_archive_messages: bool,
+        can_update_agent_roles: bool,
+        can_allocate_tasks: bool,
+        responsibilities: &[String],
+    ) -> Result<Role, HubError> {
+        let id = id.trim();
+        let display_name = display_name.trim();
+        if id.is_empty() || display_name.is_empty() {
+            return Err(HubError::Invalid(
+                "role id and display_name must not be empty".into(),
+            ));
+        }
+        if id == CTO_ROLE_ID {
+            return Err(HubError::Invalid(
+                "the cto role is protected and cannot be created or edited directly".into(),
+            ));
+        }
+        let mut state = self.state();
+        let now = Utc::now().to_rfc3339();
+        let created_at = match state.roles.get(id) {
+            Some(existing) if existing.is_builtin => {
+                return Err(HubError::Invalid(format!(
+                    "role {id} is builtin and cannot be edited"
+                )))
+            }
+            Some(existing) => existing.created_at.clone(),
+            None => now.clone(),
+        };
+        let role = Role {
+            id: id.into(),
+            display_name: display_name.into(),
+            is_builtin: false,
+            daily_ungated_quota,
+            max_broadcast_recipients,
+            can_archive_messages,
+            can_update_agent_roles,
+            can_allocate_tasks,
+            responsibilities: responsibilities.to_vec(),
+            created_at,
+            updated_at: now,
+        };
+        state.roles.insert(role.id.clone(), role.clone());
+        Ok(role)
+    }
+
+    fn get_role(&self, id: &str) -> Result<Option<Role>, HubError> {
+        Ok(self.state().roles.get(id).cloned())
+    }
+
+    fn list_roles(&self) -> Result<Vec<Role>, HubError> {
+        let mut roles: Vec<Role> = self.state().roles.values().cloned().collect();
+        roles.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+        Ok(roles)
+    }
+
+    fn delete_role(&self, id: &str) -> Result<(), HubError> {
+        let mut state = self.state();
+        let role = state
+            .roles
+            .get(id)
+            .ok_or_else(|| HubError::NotFound(id.to_string()))?;
+        if role.is_builtin {
+            return Err(HubError::Invalid(format!(
+                "role {id} is builtin and cannot be deleted"
+            )));
+        }
+        let assignees = state.assignments.iter().filter(|(_, r)| r == id).count();
+        if assignees > 0 {
+            return Err(HubError::Invalid(format!(
+                "role {id} is still assigned to {assignees} agent(s); unassign first"
+            )));
+        }
+        state.roles.remove(id);
+        Ok(())
+    }
+
+    fn assign_agent_role(&self, agent_id: &str, role_id: &str) -> Result<(), HubError> {
+        let mut state = self.state();
+        if !state.roles.contains_key(role_id) {
+            return Err(HubError::NotFound(role_id.to_string()));
+        }
+        state
+            .assignments
+            .insert((agent_id.to_string(), role_id.to_string()));
+        Ok(())
+    }
+
+    fn unassign_agent_role(&self, agent_id: &str, role_id: &str) -> Result<(), HubError> {
+        if agent_id == "human" && role_id == CTO_ROLE_ID {
+            return Err(HubError::Invalid(
+                "the cto role cannot be unassigned from human".into(),
+            ));
+        }
+        self.state()
+            .assignments
+            .remove(&(agent_id.to_string(), role_id.to_string()));
+        Ok(())
+    }
+
+    fn list_agent_roles(&self, agent_id: &str) -> Result<Vec<Role>, HubError> {
+        let assigned: BTreeSet<String> = self
+            .state()
+            .assignments
+            .iter()
+            .filter(|(agent, _)| agent == agent_id)
+            .map(|(_, role)| role.clone())
+            .collect();
+        let mut roles = self.list_roles()?;
+        roles.retain(|role| assigned.contains(&role.id));
+        Ok(roles)
+    }
+}
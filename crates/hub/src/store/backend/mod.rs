@@ -0,0 +1,151 @@
+//! Storage-backend traits for the core hub records: messages, memories,
+//! tasks and roles. [`HubStore`] (SQLite) implements all of them and stays
+//! the hub's default backend; [`InMemoryHub`] keeps the same records in
+//! plain collections for fast unit tests and for embedding the hub in
+//! tools that do not want a database on disk.
+//!
+//! The traits cover the record lifecycle only. Features built on top of
+//! it (workflows, gates, archiving, encryption, redaction, audit, exports)
+//! stay on [`HubStore`]. Write code against [`HubBackend`] when it only
+//! needs the lifecycle, so it runs on either backend.
+
+use super::*;
+
+mod memory;
+mod sqlite;
+pub use memory::InMemoryHub;
+
+#[allow(clippy::too_many_arguments)]
+pub trait MessageBackend {
+    fn send_message(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        kind: MessageKind,
+        body: &str,
+        subject: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+    ) -> Result<MessageRecord, HubError>;
+
+    fn get_message(&self, id: &str) -> Result<Option<MessageRecord>, HubError>;
+
+    /// Unarchived messages, newest first.
+    fn list_messages_page(
+        &self,
+        to_agent: Option<&str>,
+        status: Option<MessageStatus>,
+        page: &PageRequest,
+    ) -> Result<Page<MessageRecord>, HubError>;
+
+    fn ack_message(&self, id: &str) -> Result<Option<MessageRecord>, HubError>;
+
+    /// `to_agent`'s pending messages, newest first; `mark_acked` acks them.
+    fn poll_messages(
+        &self,
+        to_agent: &str,
+        mark_acked: bool,
+    ) -> Result<Vec<MessageRecord>, HubError>;
+
+    fn set_message_status(
+        &self,
+        id: &str,
+        status: MessageStatus,
+    ) -> Result<MessageRecord, HubError>;
+}
+
+#[allow(clippy::too_many_arguments)]
+pub trait MemoryBackend {
+    /// Write a global, workspace or agent-private memory.
+    fn write_memory(
+        &self,
+        tier: MemoryTier,
+        scope: MemoryScope,
+        agent_id: Option<&str>,
+        workspace_path: Option<&str>,
+        title: Option<&str>,
+        body: &str,
+        tags: &[String],
+    ) -> Result<MemoryRecord, HubError>;
+
+    fn get_memory(&self, id: &str) -> Result<Option<MemoryRecord>, HubError>;
+
+    /// Replace title and body; `tags: None` keeps the current tags.
+    fn update_memory(
+        &self,
+        id: &str,
+        title: Option<&str>,
+        body: &str,
+        tags: Option<&[String]>,
+    ) -> Result<MemoryRecord, HubError>;
+
+    /// Shared-scope (global/workspace) memories, at most 200, most
+    /// relevant first (newest first among equals) with `relevance` filled
+    /// in. A backend that tracks no usage or links scores recency alone.
+    fn list_memories(
+        &self,
+        scope: Option<MemoryScope>,
+        tier: Option<MemoryTier>,
+        workspace_path: Option<&str>,
+        include_stale: bool,
+    ) -> Result<Vec<MemoryRecord>, HubError>;
+
+    /// Fresh shared-scope memories whose title, body or tags contain
+    /// `query`, at most 100, newest first, with `relevance` filled in.
+    fn search_memories(&self, query: &str) -> Result<Vec<MemoryRecord>, HubError>;
+
+    fn mark_memory_stale(&self, id: &str, stale: bool) -> Result<(), HubError>;
+
+    fn delete_memory(&self, id: &str) -> Result<(), HubError>;
+}
+
+pub trait TaskBackend {
+    fn create_task(
+        &self,
+        title: &str,
+        workspace_path: Option<&str>,
+        steps: &[WorkflowStep],
+    ) -> Result<TaskRecord, HubError>;
+
+    fn get_task(&self, id: &str) -> Result<Option<TaskRecord>, HubError>;
+
+    /// The 100 most recently updated tasks.
+    fn list_tasks(&self, status: Option<TaskStatus>) -> Result<Vec<TaskRecord>, HubError>;
+
+    fn cancel_task(&self, id: &str) -> Result<TaskRecord, HubError>;
+}
+
+#[allow(clippy::too_many_arguments)]
+pub trait RoleBackend {
+    /// Create or update a non-builtin role.
+    fn upsert_role(
+        &self,
+        id: &str,
+        display_name: &str,
+        daily_ungated_quota: Option<i64>,
+        max_broadcast_recipients: Option<i64>,
+        can_archive_messages: bool,
+        can_update_agent_roles: bool,
+        can_allocate_tasks: bool,
+        responsibilities: &[String],
+    ) -> Result<Role, HubError>;
+
+    fn get_role(&self, id: &str) -> Result<Option<Role>, HubError>;
+
+    /// Every role, by display name.
+    fn list_roles(&self) -> Result<Vec<Role>, HubError>;
+
+    /// Builtin roles and roles still assigned to an agent cannot be deleted.
+    fn delete_role(&self, id: &str) -> Result<(), HubError>;
+
+    fn assign_agent_role(&self, agent_id: &str, role_id: &str) -> Result<(), HubError>;
+
+    fn unassign_agent_role(&self, agent_id: &str, role_id: &str) -> Result<(), HubError>;
+
+    fn list_agent_roles(&self, agent_id: &str) -> Result<Vec<Role>, HubError>;
+}
+
+/// Every record trait; implemented for anything that implements them all.
+pub trait HubBackend: MessageBackend + MemoryBackend + TaskBackend + RoleBackend {}
+
+impl<T: MessageBackend + MemoryBackend + TaskBackend + RoleBackend> HubBackend for T {}
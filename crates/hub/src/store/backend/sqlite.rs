@@ -0,0 +1,194 @@
+//! The SQLite backend: each trait method is the `HubStore` method of the
+//! same name.
+
+use super::*;
+
+impl MessageBackend for HubStore {
+    fn send_message(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        kind: MessageKind,
+        body: &str,
+        subject: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+    ) -> Result<MessageRecord, HubError> {
+        HubStore::send_message(
+            self,
+            from_agent,
+            to_agent,
+            kind,
+            body,
+            subject,
+            workspace_path,
+            task_id,
+        )
+    }
+
+    fn get_message(&self, id: &str) -> Result<Option<MessageRecord>, HubError> {
+        HubStore::get_message(self, id)
+    }
+
+    fn list_messages_page(
+        &self,
+        to_agent: Option<&str>,
+        status: Option<MessageStatus>,
+        page: &PageRequest,
+    ) -> Result<Page<MessageRecord>, HubError> {
+        HubStore::list_messages_page(self, to_agent, status, page)
+    }
+
+    fn ack_message(&self, id: &str) -> Result<Option<MessageRecord>, HubError> {
+        HubStore::ack_message(self, id)
+    }
+
+    fn poll_messages(
+        &self,
+        to_agent: &str,
+        mark_acked: bool,
+    ) -> Result<Vec<MessageRecord>, HubError> {
+        HubStore::poll_messages(self, to_agent, mark_acked)
+    }
+
+    fn set_message_status(
+        &self,
+        id: &str,
+        status: MessageStatus,
+    ) -> Result<MessageRecord, HubError> {
+        HubStore::set_message_status(self, id, status)
+    }
+}
+
+impl MemoryBackend for HubStore {
+    fn write_memory(
+        &self,
+        tier: MemoryTier,
+        scope: MemoryScope,
+        agent_id: Option<&str>,
+        workspace_path: Option<&str>,
+        title: Option<&str>,
+        body: &str,
+        tags: &[String],
+    ) -> Result<MemoryRecord, HubError> {
+        HubStore::write_memory(
+            self,
+            tier,
+            scope,
+            agent_id,
+            workspace_path,
+            title,
+            body,
+            tags,
+        )
+    }
+
+    fn get_memory(&self, id: &str) -> Result<Option<MemoryRecord>, HubError> {
+        HubStore::get_memory(self, id)
+    }
+
+    fn update_memory(
+        &self,
+        id: &str,
+        title: Option<&str>,
+        body: &str,
+        tags: Option<&[String]>,
+    ) -> Result<MemoryRecord, HubError> {
+        HubStore::update_memory(self, id, title, body, tags)
+    }
+
+    fn list_memories(
+        &self,
+        scope: Option<MemoryScope>,
+        tier: Option<MemoryTier>,
+        workspace_path: Option<&str>,
+        include_stale: bool,
+    ) -> Result<Vec<MemoryRecord>, HubError> {
+        HubStore::list_memories(self, scope, tier, workspace_path, include_stale)
+    }
+
+    fn search_memories(&self, query: &str) -> Result<Vec<MemoryRecord>, HubError> {
+        HubStore::search_memories(self, query)
+    }
+
+    fn mark_memory_stale(&self, id: &str, stale: bool) -> Result<(), HubError> {
+        HubStore::mark_memory_stale(self, id, stale)
+    }
+
+    fn delete_memory(&self, id: &str) -> Result<(), HubError> {
+        HubStore::delete_memory(self, id)
+    }
+}
+
+impl TaskBackend for HubStore {
+    fn create_task(
+        &self,
+        title: &str,
+        workspace_path: Option<&str>,
+        steps: &[WorkflowStep],
+    ) -> Result<TaskRecord, HubError> {
+        HubStore::create_task(self, title, workspace_path, steps)
+    }
+
+    fn get_task(&self, id: &str) -> Result<Option<TaskRecord>, HubError> {
+        HubStore::get_task(self, id)
+    }
+
+    fn list_tasks(&self, status: Option<TaskStatus>) -> Result<Vec<TaskRecord>, HubError> {
+        HubStore::list_tasks(self, status)
+    }
+
+    fn cancel_task(&self, id: &str) -> Result<TaskRecord, HubError> {
+        HubStore::cancel_task(self, id)
+    }
+}
+
+impl RoleBackend for HubStore {
+    fn upsert_role(
+        &self,
+        id: &str,
+        display_name: &str,
+        daily_ungated_quota: Option<i64>,
+        max_broadcast_recipients: Option<i64>,
+        can_archive_messages: bool,
+        can_update_agent_roles: bool,
+        can_allocate_tasks: bool,
+        responsibilities: &[String],
+    ) -> Result<Role, HubError> {
+        HubStore::upsert_role(
+            self,
+            id,
+            display_name,
+            daily_ungated_quota,
+            max_broadcast_recipients,
+            can_archive_messages,
+            can_update_agent_roles,
+            can_allocate_tasks,
+            responsibilities,
+        )
+    }
+
+    fn get_role(&self, id: &str) -> Result<Option<Role>, HubError> {
+        HubStore::get_role(self, id)
+    }
+
+    fn list_roles(&self) -> Result<Vec<Role>, HubError> {
+        HubStore::list_roles(self)
+    }
+
+    fn delete_role(&self, id: &str) -> Result<(), HubError> {
+        HubStore::delete_role(self, id)
+    }
+
+    fn assign_agent_role(&self, agent_id: &str, role_id: &str) -> Result<(), HubError> {
+        HubStore::assign_agent_role(self, agent_id, role_id)
+    }
+
+    fn unassign_agent_role(&self, agent_id: &str, role_id: &str) -> Result<(), HubError> {
+        HubStore::unassign_agent_role(self, agent_id, role_id)
+    }
+
+    fn list_agent_roles(&self, agent_id: &str) -> Result<Vec<Role>, HubError> {
+        HubStore::list_agent_roles(self, agent_id)
+    }
+}
@@ -0,0 +1,378 @@
+//! Online hub backups and verified restore. A backup is one directory under
+//! `<hub_home>/hub-backups/` (or `--to`): `hub.db` copied with SQLite's
+//! online backup API, so agents can keep writing while it runs, plus copies
+//! of `attachments/`, `markdown/` and `wake/`, and a `manifest.json` with
+//! the SHA-256 of every file. Backups rotate like the settings store's
+//! `settings-backups/`, keeping the newest `backup_retention`.
+
+use super::*;
+use crate::settings::{MAX_BACKUP_RETENTION, MIN_BACKUP_RETENTION};
+use rusqlite::backup::Backup;
+use std::path::Component;
+use std::time::Duration;
+
+/// Directory under the hub home that holds rotated backups.
+pub const HUB_BACKUP_DIR: &str = "hub-backups";
+
+/// Bumped when the backup directory layout or manifest shape changes.
+pub const HUB_BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const DB_FILE: &str = "hub.db";
+const BACKUP_PREFIX: &str = "hub-";
+
+/// Side directories copied alongside the database.
+const DATA_DIRS: [&str; 3] = ["attachments", "markdown", "wake"];
+
+/// Pages copied per online-backup step; the sleep between steps lets
+/// concurrent writers in.
+const BACKUP_STEP_PAGES: std::os::raw::c_int = 256;
+const BACKUP_STEP_PAUSE: Duration = Duration::from_millis(5);
+
+/// One file in a backup, relative to the backup directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub path: String,
+    pub sha256: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: String,
+    pub schema_version: i64,
+    /// `hub.db` first, then every side-directory file in path order.
+    pub files: Vec<BackupFile>,
+}
+
+/// Result of [`HubStore::backup`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubBackup {
+    pub path: PathBuf,
+    pub manifest: BackupManifest,
+    /// Older backups removed by rotation.
+    pub pruned: Vec<PathBuf>,
+}
+
+/// Result of [`HubStore::verify_backup`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerification {
+    pub path: PathBuf,
+    pub files_checked: usize,
+    pub audit_events: usize,
+}
+
+/// Result of [`HubStore::restore_backup`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreOutcome {
+    pub verification: BackupVerification,
+    /// Where the replaced hub files were moved, so a restore is undoable.
+    pub previous: PathBuf,
+}
+
+pub(super) fn backup_stamp() -> String {
+    let now = Utc::now();
+    format!(
+        "{}{:03}Z",
+        now.format("%Y%m%dT%H%M%S"),
+        now.timestamp_subsec_millis()
+    )
+}
+
+/// Every regular file under `root`, as `/`-separated paths relative to
+/// `base`, sorted.
+fn collect_files(base: &Path, root: &Path, out: &mut Vec<String>) -> Result<(), HubError> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(base, &path, out)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            let parts: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            out.push(parts.join("/"));
+        }
+    }
+    out.sort();
+    Ok(())
+}
+
+fn hash_file(base: &Path, relative: &str) -> Result<BackupFile, HubError> {
+    let bytes = fs::read(base.join(relative))?;
+    Ok(BackupFile {
+        path: relative.to_string(),
+        sha256: sha256_hex(&bytes),
+        bytes: bytes.len() as u64,
+    })
+}
+
+/// A manifest path may only name something inside the backup: relative,
+/// with every component a plain name. An absolute path, a drive prefix, or
+/// `..` would make `backup_dir.join(path)` resolve outside the backup.
+fn is_contained_path(path: &str) -> bool {
+    let mut components = Path::new(path).components().peekable();
+    components.peek().is_some() && components.all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn read_manifest(backup_dir: &Path) -> Result<BackupManifest, HubError> {
+    let raw = fs::read_to_string(backup_dir.join(MANIFEST_FILE)).map_err(|_| {
+        HubError::NotFound(format!("no {MANIFEST_FILE} under {}", backup_dir.display()))
+    })?;
+    let manifest: BackupManifest = serde_json::from_str(&raw)
+        .map_err(|e| HubError::Invalid(format!("unreadable backup manifest: {e}")))?;
+    if manifest.format_version != HUB_BACKUP_FORMAT_VERSION {
+        return Err(HubError::Invalid(format!(
+            "unsupported backup format version {} (expected {HUB_BACKUP_FORMAT_VERSION})",
+            manifest.format_version
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Backups in `dir`, newest first. Only `hub-*` directories with a manifest
+/// count, so rotation never touches anything else placed there.
+pub fn list_hub_backups(dir: &Path) -> Result<Vec<PathBuf>, HubError> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut backups = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_backup = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(BACKUP_PREFIX));
+        if is_backup && path.join(MANIFEST_FILE).is_file() {
+            backups.push(path);
+        }
+    }
+    backups.sort();
+    backups.reverse();
+    Ok(backups)
+}
+
+impl HubStore {
+    pub fn backups_dir(&self) -> PathBuf {
+        self.data_dir.join(HUB_BACKUP_DIR)
+    }
+
+    /// Snapshot the hub into a new directory under `to` (default
+    /// [`Self::backups_dir`]) while it stays writable, then drop all but the
+    /// newest `retention` backups there.
+    pub fn backup(&self, to: Option<&Path>, retention: u32) -> Result<HubBackup, HubError> {
+        if !(MIN_BACKUP_RETENTION..=MAX_BACKUP_RETENTION).contains(&retention) {
+            return Err(HubError::Invalid(format!(
+                "backup retention {retention} is outside {MIN_BACKUP_RETENTION}..={MAX_BACKUP_RETENTION}"
+            )));
+        }
+        let root = to
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.backups_dir());
+        let target = root.join(format!("{BACKUP_PREFIX}{}", backup_stamp()));
+        if target.exists() {
+            return Err(HubError::Invalid(format!(
+                "backup {} already exists",
+                target.display()
+            )));
+        }
+        fs::create_dir_all(&target)?;
+
+        {
+            let mut dst = Connection::open(target.join(DB_FILE))?;
+            let backup = Backup::new(&self.conn, &mut dst)?;
+            backup.run_to_completion(BACKUP_STEP_PAGES, BACKUP_STEP_PAUSE, None)?;
+        }
+        let mut relative = Vec::new();
+        for dir in DATA_DIRS {
+            collect_files(&self.data_dir, &self.data_dir.join(dir), &mut relative)?;
+        }
+        let mut files = vec![hash_file(&target, DB_FILE)?];
+        for path in &relative {
+            let dest = target.join(path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(self.data_dir.join(path), &dest)?;
+            files.push(hash_file(&target, path)?);
+        }
+
+        let manifest = BackupManifest {
+            format_version: HUB_BACKUP_FORMAT_VERSION,
+            created_at: Utc::now().to_rfc3339(),
+            schema_version: SCHEMA_VERSION,
+            files,
+        };
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| HubError::Invalid(e.to_string()))?;
+        fs::write(target.join(MANIFEST_FILE), json)?;
+
+        let pruned: Vec<PathBuf> = list_hub_backups(&root)?
+            .into_iter()
+            .skip(retention as usize)
+            .collect();
+        for old in &pruned {
+            fs::remove_dir_all(old)?;
+        }
+        Ok(HubBackup {
+            path: target,
+            manifest,
+            pruned,
+        })
+    }
+
+    /// Check a backup without touching the live hub: every manifest hash
+    /// matches, the database passes `PRAGMA integrity_check`, and its audit
+    /// hash chain verifies.
+    pub fn verify_backup(backup_dir: &Path) -> Result<BackupVerification, HubError> {
+        let manifest = read_manifest(backup_dir)?;
+        if !manifest.files.iter().any(|f| f.path == DB_FILE) {
+            return Err(HubError::Invalid(format!(
+                "backup manifest lists no {DB_FILE}"
+            )));
+        }
+        for file in &manifest.files {
+            if !is_contained_path(&file.path) {
+                return Err(HubError::Invalid(format!(
+                    "backup manifest path escapes the backup: {}",
+                    file.path
+                )));
+            }
+            let actual = hash_file(backup_dir, &file.path)
+                .map_err(|_| HubError::Invalid(format!("backup file missing: {}", file.path)))?;
+            if actual.sha256 != file.sha256 || actual.bytes != file.bytes {
+                return Err(HubError::Invalid(format!(
+                    "backup file does not match its manifest hash: {}",
+                    file.path
+                )));
+            }
+        }
+
+        let snapshot = Self::open_existing_read_only(backup_dir)?;
+        let integrity: String = snapshot
+            .conn
+            .query_row("PRAGMA integrity_check", [], |r| r.get(0))?;
+        if integrity != "ok" {
+            return Err(HubError::Invalid(format!(
+                "backup database failed integrity_check: {integrity}"
+            )));
+        }
+        let audit_events = snapshot.verify_audit_chain()?;
+        Ok(BackupVerification {
+            path: backup_dir.to_path_buf(),
+            files_checked: manifest.files.len(),
+            audit_events,
+        })
+    }
+
+    /// Replace the hub at `data_dir` with a verified backup. Nothing is
+    /// moved unless [`Self::verify_backup`] passes; the replaced `hub.db`
+    /// (with its WAL sidecars) and side directories are moved to
+    /// `hub-backups/pre-restore-<stamp>/` rather than deleted. If the swap
+    /// fails partway, every entry already moved is put back before the
+    /// error is returned. No `HubStore` may have `data_dir` open while
+    /// this runs.
+    pub fn restore_backup(data_dir: &Path, backup_dir: &Path) -> Result<RestoreOutcome, HubError> {
+        let verification = Self::verify_backup(backup_dir)?;
+        let manifest = read_manifest(backup_dir)?;
+        let stamp = backup_stamp();
+
+        // Stage next to the live files so the swap is a rename on one
+        // filesystem.
+        let staging = data_dir.join(format!(".restore-{stamp}"));
+        let staged = manifest.files.iter().try_for_each(|file| {
+            let dest = staging.join(&file.path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(backup_dir.join(&file.path), dest).map(|_| ())
+        });
+        if let Err(error) = staged {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(error.into());
+        }
+
+        let previous = data_dir
+            .join(HUB_BACKUP_DIR)
+            .join(format!("pre-restore-{stamp}"));
+        let mut journal = SwapJournal::default();
+        if let Err(error) = swap_in(data_dir, &staging, &previous, &mut journal) {
+            journal.roll_back(data_dir, &previous);
+            let _ = fs::remove_dir_all(&staging);
+            let _ = fs::remove_dir(&previous);
+            return Err(error);
+        }
+        fs::remove_dir_all(&staging)?;
+        Ok(RestoreOutcome {
+            verification,
+            previous,
+        })
+    }
+}
+
+/// What [`swap_in`] has done so far, so a failed restore can undo it.
+#[derive(Default)]
+struct SwapJournal {
+    /// Live entries moved into `previous`.
+    moved: Vec<String>,
+    /// Staged entries moved into the data directory.
+    placed: Vec<String>,
+}
+
+impl SwapJournal {
+    /// Drop what was placed, then move the live entries back, newest
+    /// first. Best effort: the restore is already failing.
+    fn roll_back(&self, data_dir: &Path, previous: &Path) {
+        for name in self.placed.iter().rev() {
+            let path = data_dir.join(name);
+            let _ = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+        }
+        for name in self.moved.iter().rev() {
+            let _ = fs::rename(previous.join(name), data_dir.join(name));
+        }
+    }
+}
+
+/// Move the live database and side directories into `previous`, then the
+/// staged ones into place, recording each step in `journal`.
+fn swap_in(
+    data_dir: &Path,
+    staging: &Path,
+    previous: &Path,
+    journal: &mut SwapJournal,
+) -> Result<(), HubError> {
+    fs::create_dir_all(previous)?;
+    let db_sidecars = [
+        DB_FILE.to_string(),
+        format!("{DB_FILE}-wal"),
+        format!("{DB_FILE}-shm"),
+    ];
+    for name in db_sidecars.iter().map(String::as_str).chain(DATA_DIRS) {
+        let live = data_dir.join(name);
+        if live.exists() {
+            fs::rename(&live, previous.join(name))?;
+            journal.moved.push(name.to_string());
+        }
+    }
+    fs::rename(staging.join(DB_FILE), data_dir.join(DB_FILE))?;
+    journal.placed.push(DB_FILE.to_string());
+    for dir in DATA_DIRS {
+        let staged = staging.join(dir);
+        if staged.exists() {
+            fs::rename(staged, data_dir.join(dir))?;
+        } else {
+            fs::create_dir_all(data_dir.join(dir))?;
+        }
+        journal.placed.push(dir.to_string());
+    }
+    Ok(())
+}
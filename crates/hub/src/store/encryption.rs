@@ -0,0 +1,489 @@
+//! Opt-in encryption at rest for message bodies and payloads, memory bodies
+//! (journal entries are memories), harness captures, gated sends awaiting approval, indexed
+//! attachment text, and attachment blobs. The key is 32 random bytes,
+//! hex-encoded, read through a [`SecretReference`] — an environment variable
+//! or a key file. The hub stores only that reference and a key id derived
+//! from the key, never the key.
+//!
+//! Sealed text is `hubenc1:` + base64(nonce || XChaCha20-Poly1305
+//! ciphertext); sealed blobs use the same layout behind a binary magic. SQL
+//! reaches the cipher through two functions registered on the connection:
+//! `hub_seal(x)` wraps every write of a [`SEALED_COLUMNS`] column and
+//! `hub_plain(x)` every read, so `LIKE` searches and equality checks still
+//! compare plaintext. Hashes are always taken over plaintext (attachment
+//! addressing, harness-capture dedup), so they survive key changes. Values
+//! written before encryption was enabled read back unchanged. Plaintext
+//! that itself starts with a sealed marker is stored behind a plain marker
+//! (`hubraw1:`, or the binary equivalent), so it is never mistaken for
+//! ciphertext.
+
+use super::*;
+use crate::settings::SecretReference;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rusqlite::functions::FunctionFlags;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+const KEY_REF_META: &str = "encryption_key_ref";
+const KEY_ID_META: &str = "encryption_key_id";
+const SEALED_TEXT_PREFIX: &str = "hubenc1:";
+const SEALED_BLOB_MAGIC: &[u8] = b"HUBENC1\0";
+/// Marks stored plaintext that would otherwise start with a marker.
+const PLAIN_TEXT_PREFIX: &str = "hubraw1:";
+const PLAIN_BLOB_MAGIC: &[u8] = b"HUBRAW1\0";
+const NONCE_LEN: usize = 24;
+
+/// `(table, column)` pairs stored sealed while encryption is on.
+pub(crate) const SEALED_COLUMNS: [(&str, &str); 8] = [
+    ("messages", "body"),
+    ("messages", "payload"),
+    ("memories", "body"),
+    ("harness_captures", "body"),
+    ("pending_gate_approvals", "body"),
+    ("attachment_texts", "body"),
+    ("webhook_deliveries", "payload"),
+    ("hook_runs", "output"),
+];
+
+pub(crate) fn is_sealed_column(table: &str, column: &str) -> bool {
+    SEALED_COLUMNS.contains(&(table, column))
+}
+
+/// A fresh random key, hex-encoded, for an env var or key file.
+pub fn generate_hub_key() -> String {
+    let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+    key.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Read the key `secret` points at. Keys are 64 hex characters; surrounding
+/// whitespace (a trailing newline in a key file) is ignored.
+pub fn resolve_hub_key(secret: &SecretReference) -> Result<[u8; 32], HubError> {
+    let raw = match secret {
+        SecretReference::EnvVar { name } => std::env::var(name).map_err(|_| {
+            HubError::Invalid(format!(
+                "hub encryption key: environment variable {name} is not set"
+            ))
+        })?,
+        SecretReference::KeyFile { path } => fs::read_to_string(path).map_err(|e| {
+            HubError::Invalid(format!("hub encryption key: cannot read {path}: {e}"))
+        })?,
+        SecretReference::Keychain { .. } | SecretReference::ProviderLogin => {
+            return Err(HubError::Invalid(
+                "hub encryption key must come from an env_var or key_file reference".into(),
+            ))
+        }
+    };
+    let raw = raw.trim();
+    let bytes: Option<Vec<u8>> = (raw.len() == 64 && raw.is_ascii())
+        .then(|| {
+            (0..64)
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&raw[i..i + 2], 16).ok())
+                .collect()
+        })
+        .flatten();
+    bytes
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            HubError::Invalid(format!(
+                "hub encryption key from {} must be 64 hex characters",
+                secret.badge()
+            ))
+        })
+}
+
+fn locked_reason(error: HubError) -> String {
+    match error {
+        HubError::Invalid(reason) => reason,
+        other => other.to_string(),
+    }
+}
+
+fn key_id(key: &[u8; 32]) -> String {
+    let mut input = b"coding-assistants-hub-key\0".to_vec();
+    input.extend_from_slice(key);
+    sha256_hex(&input)[..16].to_string()
+}
+
+/// The connection's view of the hub key, shared with the SQL functions.
+#[derive(Clone, Default)]
+pub(crate) enum Cipher {
+    #[default]
+    Off,
+    On {
+        key: [u8; 32],
+        key_id: String,
+    },
+    /// Encryption is on but the key could not be loaded; holds why.
+    Locked(String),
+}
+
+impl Cipher {
+    fn key(&self) -> Result<Option<&[u8; 32]>, HubError> {
+        match self {
+            Self::Off => Ok(None),
+            Self::On { key, .. } => Ok(Some(key)),
+            Self::Locked(reason) => Err(HubError::Invalid(format!(
+                "hub is encrypted and its key is unavailable: {reason}"
+            ))),
+        }
+    }
+
+    fn seal_bytes(&self, data: &[u8]) -> Result<Vec<u8>, HubError> {
+        let Some(key) = self.key()? else {
+            return Ok(data.to_vec());
+        };
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(key.into())
+            .encrypt(&nonce, data)
+            .map_err(|_| HubError::Invalid("encryption failed".into()))?;
+        let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    fn open_bytes(&self, sealed: &[u8]) -> Result<Vec<u8>, HubError> {
+        let key = self.key()?.ok_or_else(|| {
+            HubError::Invalid("hub holds encrypted data but encryption is off".into())
+        })?;
+        let undecryptable =
+            || HubError::Invalid("cannot decrypt hub data: wrong key or corrupted value".into());
+        if sealed.len() < NONCE_LEN {
+            return Err(undecryptable());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        XChaCha20Poly1305::new(key.into())
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| undecryptable())
+    }
+
+    pub(crate) fn seal_blob(&self, data: &[u8]) -> Result<Vec<u8>, HubError> {
+        if matches!(self, Self::Off) {
+            if data.starts_with(SEALED_BLOB_MAGIC) || data.starts_with(PLAIN_BLOB_MAGIC) {
+                return Ok([PLAIN_BLOB_MAGIC, data].concat());
+            }
+            return Ok(data.to_vec());
+        }
+        let mut out = SEALED_BLOB_MAGIC.to_vec();
+        out.extend(self.seal_bytes(data)?);
+        Ok(out)
+    }
+
+    pub(crate) fn open_blob(&self, stored: Vec<u8>) -> Result<Vec<u8>, HubError> {
+        if let Some(plain) = stored.strip_prefix(PLAIN_BLOB_MAGIC) {
+            return Ok(plain.to_vec());
+        }
+        match stored.strip_prefix(SEALED_BLOB_MAGIC) {
+            Some(sealed) => self.open_bytes(sealed),
+            None => Ok(stored),
+        }
+    }
+
+    fn seal_text(&self, text: &str) -> Result<String, HubError> {
+        if matches!(self, Self::Off) {
+            if text.starts_with(SEALED_TEXT_PREFIX) || text.starts_with(PLAIN_TEXT_PREFIX) {
+                return Ok(format!("{PLAIN_TEXT_PREFIX}{text}"));
+            }
+            return Ok(text.to_string());
+        }
+        let sealed = self.seal_bytes(text.as_bytes())?;
+        Ok(format!("{SEALED_TEXT_PREFIX}{}", STANDARD.encode(sealed)))
+    }
+
+    fn open_text(&self, stored: &str) -> Result<String, HubError> {
+        if let Some(plain) = stored.strip_prefix(PLAIN_TEXT_PREFIX) {
+            return Ok(plain.to_string());
+        }
+        let Some(encoded) = stored.strip_prefix(SEALED_TEXT_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let sealed = STANDARD
+            .decode(encoded)
+            .map_err(|_| HubError::Invalid("corrupted encrypted value".into()))?;
+        String::from_utf8(self.open_bytes(&sealed)?)
+            .map_err(|_| HubError::Invalid("decrypted value is not UTF-8".into()))
+    }
+}
+
+/// `ca hub encryption status`: whether encryption is on and the key loads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    /// Where the key is read from; never the key itself.
+    pub key_source: Option<SecretReference>,
+    pub key_id: Option<String>,
+    /// False when encryption is on but the key could not be loaded.
+    pub unlocked: bool,
+    pub error: Option<String>,
+}
+
+/// Result of enabling, rotating or disabling encryption.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionChange {
+    pub enabled: bool,
+    pub key_id: Option<String>,
+    /// Sealed-column values rewritten.
+    pub values: usize,
+    /// Attachment blobs rewritten.
+    pub attachments: usize,
+}
+
+fn register_cipher_functions(
+    conn: &Connection,
+    cipher: &Arc<Mutex<Cipher>>,
+) -> Result<(), HubError> {
+    for (name, seal) in [("hub_seal", true), ("hub_plain", false)] {
+        let cipher = Arc::clone(cipher);
+        conn.create_scalar_function(name, 1, FunctionFlags::SQLITE_UTF8, move |ctx| {
+            let value: Option<String> = ctx.get(0)?;
+            let cipher = cipher.lock().unwrap_or_else(|e| e.into_inner());
+            value
+                .map(|v| match seal {
+                    true => cipher.seal_text(&v),
+                    false => cipher.open_text(&v),
+                })
+                .transpose()
+                .map_err(|e| rusqlite::Error::UserFunctionError(Box::new(e)))
+        })?;
+    }
+    Ok(())
+}
+
+impl HubStore {
+    /// Wrap an open connection: register `hub_seal`/`hub_plain` and load
+    /// the key the hub is encrypted with, if any. A missing key does not
+    /// fail the open — only reads and writes of sealed values do.
+    pub(crate) fn from_connection(conn: Connection, data_dir: PathBuf) -> Result<Self, HubError> {
+        let cipher = Arc::new(Mutex::new(Cipher::Off));
+        register_cipher_functions(&conn, &cipher)?;
+        let store = Self {
+            conn,
+            data_dir,
+            cipher,
+        };
+        let loaded = match store.encryption_meta() {
+            Ok(None) => Cipher::Off,
+            Ok(Some((reference, expected))) => match resolve_hub_key(&reference) {
+                Ok(key) if key_id(&key) == expected => Cipher::On {
+                    key,
+                    key_id: expected,
+                },
+                Ok(key) => Cipher::Locked(format!(
+                    "the key from {} (id {}) is not this hub's key (id {expected})",
+                    reference.badge(),
+                    key_id(&key)
+                )),
+                Err(e) => Cipher::Locked(locked_reason(e)),
+            },
+            Err(e) => Cipher::Locked(locked_reason(e)),
+        };
+        *store.cipher() = loaded;
+        Ok(store)
+    }
+
+    pub(crate) fn cipher(&self) -> MutexGuard<'_, Cipher> {
+        self.cipher.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Open-time migrations that read or write sealed values skip while
+    /// this holds; each retries on the next open.
+    pub(crate) fn encryption_locked(&self) -> bool {
+        matches!(*self.cipher(), Cipher::Locked(_))
+    }
+
+    /// Whether sealed columns hold ciphertext (key loaded or not).
+    pub(crate) fn encryption_enabled(&self) -> bool {
+        !matches!(*self.cipher(), Cipher::Off)
+    }
+
+    /// The stored key reference and key id. A database without a `meta`
+    /// table yet (first open, before migrations) is unencrypted.
+    fn encryption_meta(&self) -> Result<Option<(SecretReference, String)>, HubError> {
+        let read = |key: &str| -> Option<String> {
+            self.conn
+                .query_row("SELECT value FROM meta WHERE key = ?1", params![key], |r| {
+                    r.get(0)
+                })
+                .ok()
+        };
+        let (Some(reference), Some(id)) = (read(KEY_REF_META), read(KEY_ID_META)) else {
+            return Ok(None);
+        };
+        let reference = serde_json::from_str(&reference)
+            .map_err(|e| HubError::Invalid(format!("{KEY_REF_META} corrupt: {e}")))?;
+        Ok(Some((reference, id)))
+    }
+
+    pub fn encryption_status(&self) -> Result<EncryptionStatus, HubError> {
+        let meta = self.encryption_meta();
+        let cipher = self.cipher().clone();
+        let (key_source, key_id) = match meta {
+            Ok(Some((reference, id))) => (Some(reference), Some(id)),
+            _ => (None, None),
+        };
+        Ok(EncryptionStatus {
+            enabled: !matches!(cipher, Cipher::Off),
+            key_source,
+            key_id,
+            unlocked: !matches!(cipher, Cipher::Locked(_)),
+            error: match cipher {
+                Cipher::Locked(reason) => Some(reason),
+                _ => None,
+            },
+        })
+    }
+
+    /// Encrypt every existing sealed value and attachment blob with the key
+    /// `key` points at, and keep new ones encrypted.
+    pub fn enable_encryption(&self, key: &SecretReference) -> Result<EncryptionChange, HubError> {
+        if !matches!(*self.cipher(), Cipher::Off) {
+            return Err(HubError::Invalid(
+                "hub is already encrypted; rotate the key instead".into(),
+            ));
+        }
+        let bytes = resolve_hub_key(key)?;
+        self.reseal(Some((key, bytes)))
+    }
+
+    /// Re-encrypt everything under a new key. The current key must load.
+    pub fn rotate_encryption_key(
+        &self,
+        key: &SecretReference,
+    ) -> Result<EncryptionChange, HubError> {
+        let current = self.cipher().clone();
+        current.key()?;
+        let Cipher::On {
+            key_id: current_id, ..
+        } = current
+        else {
+            return Err(HubError::Invalid(
+                "hub is not encrypted; enable encryption first".into(),
+            ));
+        };
+        let bytes = resolve_hub_key(key)?;
+        if key_id(&bytes) == current_id {
+            return Err(HubError::Invalid("the new key is the current key".into()));
+        }
+        self.reseal(Some((key, bytes)))
+    }
+
+    /// Decrypt everything and stop encrypting. The current key must load.
+    pub fn disable_encryption(&self) -> Result<EncryptionChange, HubError> {
+        let current = self.cipher().clone();
+        if current.key()?.is_none() {
+            return Err(HubError::Invalid("hub is not encrypted".into()));
+        }
+        self.reseal(None)
+    }
+
+    /// Rewrite every sealed value and blob from the current cipher to the
+    /// one for `target` (`None`: plaintext). Blobs are staged beside the
+    /// originals, then swapped in before the database commits, with each
+    /// original kept as a hard-linked `.bak`. If a swap or the commit fails,
+    /// the originals are put back, so blobs and `meta` never disagree about
+    /// the key.
+    fn reseal(
+        &self,
+        target: Option<(&SecretReference, [u8; 32])>,
+    ) -> Result<EncryptionChange, HubError> {
+        let current = self.cipher().clone();
+        let next = match target {
+            Some((_, key)) => Cipher::On {
+                key,
+                key_id: key_id(&key),
+            },
+            None => Cipher::Off,
+        };
+
+        let blobs: Vec<String> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT DISTINCT relative_path FROM attachments")?;
+            let rows = stmt.query_map([], |r| r.get(0))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let dir = self.attachments_dir();
+        let mut staged = Vec::new();
+        let mut swapped: Vec<(PathBuf, PathBuf)> = Vec::new();
+        let result = (|| {
+            for relative_path in blobs {
+                let path = dir.join(relative_path);
+                let Ok(stored) = fs::read(&path) else {
+                    continue;
+                };
+                let data = next.seal_blob(&current.open_blob(stored)?)?;
+                let staging = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+                fs::write(&staging, data)?;
+                staged.push((staging, path));
+            }
+            for (staging, path) in &staged {
+                let backup = path.with_extension(format!("{}.bak", Uuid::new_v4()));
+                fs::hard_link(path, &backup)?;
+                swapped.push((backup.clone(), path.clone()));
+                fs::rename(staging, path)?;
+            }
+
+            let tx = self.conn.unchecked_transaction()?;
+            let mut values = 0;
+            for (table, column) in SEALED_COLUMNS {
+                let rows: Vec<(i64, String)> = {
+                    let mut stmt = tx.prepare(&format!(
+                        "SELECT rowid, {column} FROM {table} WHERE {column} IS NOT NULL"
+                    ))?;
+                    let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+                    rows.collect::<Result<_, _>>()?
+                };
+                for (rowid, stored) in rows {
+                    tx.execute(
+                        &format!("UPDATE {table} SET {column} = ?1 WHERE rowid = ?2"),
+                        params![next.seal_text(&current.open_text(&stored)?)?, rowid],
+                    )?;
+                    values += 1;
+                }
+            }
+            tx.execute(
+                "DELETE FROM meta WHERE key IN (?1, ?2)",
+                params![KEY_REF_META, KEY_ID_META],
+            )?;
+            if let Some((reference, key)) = &target {
+                let reference = serde_json::to_string(reference)
+                    .map_err(|e| HubError::Invalid(e.to_string()))?;
+                tx.execute(
+                    "INSERT INTO meta(key, value) VALUES (?1, ?2), (?3, ?4)",
+                    params![KEY_REF_META, reference, KEY_ID_META, key_id(key)],
+                )?;
+            }
+            tx.commit()?;
+            Ok::<_, HubError>(values)
+        })();
+        let values = match result {
+            Ok(values) => values,
+            Err(e) => {
+                for (backup, path) in &swapped {
+                    let _ = fs::rename(backup, path);
+                }
+                for (staging, _) in &staged {
+                    let _ = fs::remove_file(staging);
+                }
+                return Err(e);
+            }
+        };
+        let attachments = staged.len();
+        for (backup, _) in swapped {
+            let _ = fs::remove_file(backup);
+        }
+        let key_id = match &next {
+            Cipher::On { key_id, .. } => Some(key_id.clone()),
+            _ => None,
+        };
+        *self.cipher() = next;
+        Ok(EncryptionChange {
+            enabled: key_id.is_some(),
+            key_id,
+            values,
+            attachments,
+        })
+    }
+}
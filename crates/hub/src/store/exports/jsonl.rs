@@ -0,0 +1,627 @@
+//! Versioned JSON Lines export/import of a whole hub, for moving a team's
+//! hub to another machine or merging two hubs (split from the Markdown
+//! export in `exports/mod.rs`). The line format is documented in
+//! `docs/HUB_EXPORT_FORMAT.md`; [`EXPORT_KINDS`] is the authoritative list
+//! of record kinds, their import order, and the columns each carries.
+
+use super::super::encryption::is_sealed_column;
+use super::super::*;
+use rusqlite::types::{Value, ValueRef};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufRead, Write};
+
+/// `format` field of the header line.
+pub const HUB_EXPORT_FORMAT: &str = "coding-assistants-hub";
+
+/// Bumped when a kind, column, or reference rule changes incompatibly.
+/// Adding a nullable or defaulted column, or a new id reference, is
+/// additive and keeps the version: import ignores unknown keys and fills
+/// missing columns with their defaults, so v1 files from before
+/// `archived_at`, `sha256`, `reply_to`/`thread_id` and `payload` existed
+/// still import, and newer files still import into older hubs.
+pub const HUB_EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// How a kind's key behaves when the importing hub already has it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KeyPolicy {
+    /// Generated ids: a colliding id gets a fresh UUID and every reference
+    /// to it in the import is rewritten.
+    Remap,
+    /// Natural keys (agent ids, role ids, channel slugs, ...): the existing
+    /// row wins and the imported one is skipped.
+    Merge,
+}
+
+struct ExportKind {
+    kind: &'static str,
+    table: &'static str,
+    key: &'static [&'static str],
+    policy: KeyPolicy,
+    columns: &'static [&'static str],
+}
+
+/// Record kinds in import order: every kind comes after the kinds it
+/// references, except the task ↔ message cycle, which id planning breaks.
+const EXPORT_KINDS: &[ExportKind] = &[
+    ExportKind {
+        kind: "agent",
+        table: "agents",
+        key: &["id"],
+        policy: KeyPolicy::Merge,
+        columns: &[
+            "id",
+            "display_name",
+            "created_at",
+            "card_json",
+            "team_member",
+            "avatar_attachment_id",
+        ],
+    },
+    ExportKind {
+        kind: "role",
+        table: "roles",
+        key: &["id"],
+        policy: KeyPolicy::Merge,
+        columns: &[
+            "id",
+            "display_name",
+            "is_builtin",
+            "daily_ungated_quota",
+            "max_broadcast_recipients",
+            "can_archive_messages",
+            "can_update_agent_roles",
+            "can_allocate_tasks",
+            "responsibilities_json",
+            "created_at",
+            "updated_at",
+        ],
+    },
+    ExportKind {
+        kind: "role_assignment",
+        table: "agent_role_assignments",
+        key: &["agent_id", "role_id"],
+        policy: KeyPolicy::Merge,
+        columns: &["agent_id", "role_id", "assigned_at"],
+    },
+    ExportKind {
+        kind: "channel",
+        table: "chat_channels",
+        key: &["id"],
+        policy: KeyPolicy::Merge,
+        columns: &[
+            "id",
+            "name",
+            "topic",
+            "builtin",
+            "created_at",
+            "deleted_at",
+            "archived_at",
+        ],
+    },
+    ExportKind {
+        kind: "work_session",
+        table: "work_sessions",
+        key: &["id"],
+        policy: KeyPolicy::Remap,
+        columns: &["id", "name", "created_at", "archived_at"],
+    },
+    ExportKind {
+        kind: "work_session_member",
+        table: "work_session_members",
+        key: &["session_id", "agent_id"],
+        policy: KeyPolicy::Merge,
+        columns: &["session_id", "agent_id", "created_at"],
+    },
+    ExportKind {
+        kind: "attachment",
+        table: "attachments",
+        key: &["id"],
+        policy: KeyPolicy::Remap,
+        columns: &[
+            "id",
+            "filename",
+            "mime",
+            "byte_size",
+            "relative_path",
+            "created_at",
+            "sha256",
+        ],
+    },
+    ExportKind {
+        kind: "memory",
+        table: "memories",
+        key: &["id"],
+        policy: KeyPolicy::Remap,
+        columns: &[
+            "id",
+            "scope",
+            "workspace_path",
+            "tier",
+            "agent_id",
+            "title",
+            "body",
+            "tags_json",
+            "created_at",
+            "updated_at",
+            "stale",
+            "source_event_id",
+            "session_id",
+        ],
+    },
+    ExportKind {
+        kind: "memory_link",
+        table: "memory_links",
+        key: &["id"],
+        policy: KeyPolicy::Remap,
+        columns: &[
+            "id",
+            "from_memory_id",
+            "to_memory_id",
+            "relation",
+            "created_by",
+            "created_at",
+        ],
+    },
+    ExportKind {
+        kind: "task",
+        table: "tasks",
+        key: &["id"],
+        policy: KeyPolicy::Remap,
+        columns: &[
+            "id",
+            "title",
+            "workspace_path",
+            "status",
+            "step_index",
+            "steps_json",
+            "created_at",
+            "updated_at",
+            "last_message_id",
+            "attempts_json",
+            "open_agents_json",
+            "pending_agents_json",
+            "max_parallel",
+            "require_human_approval",
+        ],
+    },
+    ExportKind {
+        kind: "message",
+        table: "messages",
+        key: &["id"],
+        policy: KeyPolicy::Remap,
+        columns: &[
+            "id",
+            "from_agent",
+            "to_agent",
+            "workspace_path",
+            "task_id",
+            "kind",
+            "status",
+            "subject",
+            "body",
+            "created_at",
+            "acked_at",
+            "archived_at",
+            "reply_to",
+            "thread_id",
+            "payload",
+        ],
+    },
+    ExportKind {
+        kind: "wake",
+        table: "wake_requests",
+        key: &["id"],
+        policy: KeyPolicy::Remap,
+        columns: &[
+            "id",
+            "target_agent",
+            "message_id",
+            "reason",
+            "status",
+            "requires_human_gate",
+            "created_at",
+        ],
+    },
+    ExportKind {
+        kind: "budget",
+        table: "agent_budgets",
+        key: &["agent_id"],
+        policy: KeyPolicy::Merge,
+        columns: &[
+            "agent_id",
+            "limit_units",
+            "spent_units",
+            "paused",
+            "updated_at",
+        ],
+    },
+];
+
+/// `(kind, column, referenced kind)`: columns holding another record's id,
+/// rewritten when that record is remapped.
+const ID_REFERENCES: &[(&str, &str, &str)] = &[
+    ("agent", "avatar_attachment_id", "attachment"),
+    ("work_session_member", "session_id", "work_session"),
+    ("memory", "session_id", "work_session"),
+    ("memory", "source_event_id", "memory"),
+    ("memory_link", "from_memory_id", "memory"),
+    ("memory_link", "to_memory_id", "memory"),
+    ("task", "last_message_id", "message"),
+    ("message", "task_id", "task"),
+    ("message", "reply_to", "message"),
+    ("message", "thread_id", "message"),
+    ("wake", "message_id", "message"),
+];
+
+/// The first line of an export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubExportHeader {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub schema_version: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportLine {
+    kind: String,
+    record: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Result of [`HubStore::export_jsonl`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubExportSummary {
+    pub header: HubExportHeader,
+    /// Records written per kind.
+    pub counts: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportKindReport {
+    pub read: usize,
+    pub inserted: usize,
+    /// Inserted under a new id because the old one was taken.
+    pub remapped: usize,
+    /// Natural-key records the hub already had.
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemappedId {
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Result of [`HubStore::import_jsonl`]. A dry run reports exactly what a
+/// real import would do; nothing is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubImportReport {
+    pub dry_run: bool,
+    pub format_version: u32,
+    pub kinds: BTreeMap<String, ImportKindReport>,
+    pub remapped_ids: Vec<RemappedId>,
+    /// Attachment records whose file is not in this hub's `attachments/`
+    /// yet — attachments travel by reference, so copy these over.
+    pub missing_attachment_files: Vec<String>,
+}
+
+pub(crate) fn sql_to_json(value: ValueRef<'_>) -> Result<serde_json::Value, HubError> {
+    Ok(match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::Value::from(i),
+        ValueRef::Real(f) => serde_json::Value::from(f),
+        ValueRef::Text(t) => serde_json::Value::from(String::from_utf8_lossy(t).to_string()),
+        ValueRef::Blob(_) => {
+            return Err(HubError::Invalid(
+                "blob columns are not part of the hub export format".into(),
+            ))
+        }
+    })
+}
+
+fn json_to_sql(value: &serde_json::Value) -> Option<Value> {
+    match value {
+        serde_json::Value::Null => Some(Value::Null),
+        serde_json::Value::Bool(b) => Some(Value::Integer(i64::from(*b))),
+        serde_json::Value::Number(n) => n
+            .as_i64()
+            .map(Value::Integer)
+            .or_else(|| n.as_f64().map(Value::Real)),
+        serde_json::Value::String(s) => Some(Value::Text(s.clone())),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => None,
+    }
+}
+
+fn json_line(value: &impl Serialize) -> Result<String, HubError> {
+    serde_json::to_string(value).map_err(|e| HubError::Invalid(e.to_string()))
+}
+
+fn invalid_line(line: usize, detail: impl std::fmt::Display) -> HubError {
+    HubError::Invalid(format!("import line {line}: {detail}"))
+}
+
+fn record_id(
+    record: &serde_json::Map<String, serde_json::Value>,
+    line: usize,
+) -> Result<String, HubError> {
+    record
+        .get("id")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| invalid_line(line, "record has no string `id`"))
+}
+
+impl HubStore {
+    /// Write the whole hub as JSON Lines: a [`HubExportHeader`] line, then
+    /// one `{"kind": ..., "record": {...}}` line per row, kinds in import
+    /// order. Attachments are exported as records only; their files stay
+    /// in `attachments/`. Encrypted bodies are written decrypted and are
+    /// re-encrypted on import into an encrypted hub.
+    pub fn export_jsonl(&self, out: &mut impl Write) -> Result<HubExportSummary, HubError> {
+        let header = HubExportHeader {
+            format: HUB_EXPORT_FORMAT.to_string(),
+            version: HUB_EXPORT_FORMAT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            schema_version: SCHEMA_VERSION,
+        };
+        writeln!(out, "{}", json_line(&header)?)?;
+
+        let mut counts = BTreeMap::new();
+        for kind in EXPORT_KINDS {
+            let columns: Vec<String> = kind
+                .columns
+                .iter()
+                .map(|c| match is_sealed_column(kind.table, c) {
+                    true => format!("hub_plain({c})"),
+                    false => c.to_string(),
+                })
+                .collect();
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {} FROM {} ORDER BY rowid",
+                columns.join(", "),
+                kind.table
+            ))?;
+            let mut rows = stmt.query([])?;
+            let mut written = 0usize;
+            while let Some(row) = rows.next()? {
+                let mut record = serde_json::Map::new();
+                for (i, column) in kind.columns.iter().enumerate() {
+                    record.insert(column.to_string(), sql_to_json(row.get_ref(i)?)?);
+                }
+                let line = ExportLine {
+                    kind: kind.kind.to_string(),
+                    record,
+                };
+                writeln!(out, "{}", json_line(&line)?)?;
+                written += 1;
+            }
+            counts.insert(kind.kind.to_string(), written);
+        }
+        Ok(HubExportSummary { header, counts })
+    }
+
+    /// Import a [`Self::export_jsonl`] stream (or anything following
+    /// `docs/HUB_EXPORT_FORMAT.md`) in one transaction. Generated ids that
+    /// collide with this hub are remapped to fresh UUIDs, with references,
+    /// `[Memory #id]` links, and `attachment://id` markers rewritten to
+    /// match. A record repeating an id already used earlier in the file
+    /// gets its own fresh id; references keep meaning the first. Natural keys that
+    /// already exist are skipped. `created_at` and every other column are
+    /// kept as written. With `dry_run` the transaction is rolled back.
+    pub fn import_jsonl(
+        &self,
+        input: impl BufRead,
+        dry_run: bool,
+    ) -> Result<HubImportReport, HubError> {
+        let mut lines = input.lines().enumerate();
+        let header: HubExportHeader = loop {
+            let Some((n, line)) = lines.next() else {
+                return Err(HubError::Invalid("import is empty".into()));
+            };
+            let line = line?;
+            if !line.trim().is_empty() {
+                break serde_json::from_str(&line)
+                    .map_err(|e| invalid_line(n + 1, format!("bad header: {e}")))?;
+            }
+        };
+        if header.format != HUB_EXPORT_FORMAT || header.version != HUB_EXPORT_FORMAT_VERSION {
+            return Err(HubError::Invalid(format!(
+                "unsupported export {} v{} (expected {HUB_EXPORT_FORMAT} v{HUB_EXPORT_FORMAT_VERSION})",
+                header.format, header.version
+            )));
+        }
+
+        let mut grouped: Vec<Vec<(usize, serde_json::Map<String, serde_json::Value>)>> =
+            EXPORT_KINDS.iter().map(|_| Vec::new()).collect();
+        for (n, line) in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed: ExportLine =
+                serde_json::from_str(&line).map_err(|e| invalid_line(n + 1, e))?;
+            let index = EXPORT_KINDS
+                .iter()
+                .position(|k| k.kind == parsed.kind)
+                .ok_or_else(|| invalid_line(n + 1, format!("unknown kind {}", parsed.kind)))?;
+            grouped[index].push((n + 1, parsed.record));
+        }
+
+        let mut report = HubImportReport {
+            dry_run,
+            format_version: header.version,
+            kinds: BTreeMap::new(),
+            remapped_ids: Vec::new(),
+            missing_attachment_files: Vec::new(),
+        };
+
+        // Plan every remap up front so references can point forward (a
+        // task's `last_message_id` is imported before the message).
+        // `remaps` rewrites references (old id → the first record's new
+        // id); `new_ids` gives each remapped record, by line, its own id.
+        let mut remaps: HashMap<&str, HashMap<String, String>> = HashMap::new();
+        let mut new_ids: HashMap<usize, String> = HashMap::new();
+        for (kind, records) in EXPORT_KINDS.iter().zip(&grouped) {
+            if kind.policy != KeyPolicy::Remap {
+                continue;
+            }
+            let mut seen = HashSet::new();
+            let map = remaps.entry(kind.kind).or_default();
+            for (line, record) in records {
+                let id = record_id(record, *line)?;
+                let taken: bool = self.conn.query_row(
+                    &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", kind.table),
+                    params![id],
+                    |r| r.get(0),
+                )?;
+                let first = seen.insert(id.clone());
+                if taken || !first {
+                    let fresh = Uuid::new_v4().to_string();
+                    report.remapped_ids.push(RemappedId {
+                        kind: kind.kind.to_string(),
+                        from: id.clone(),
+                        to: fresh.clone(),
+                    });
+                    new_ids.insert(*line, fresh.clone());
+                    if first {
+                        map.insert(id, fresh);
+                    }
+                }
+            }
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        for (kind, records) in EXPORT_KINDS.iter().zip(grouped) {
+            let counts = report.kinds.entry(kind.kind.to_string()).or_default();
+            for (line, mut record) in records {
+                counts.read += 1;
+                for (owner, column, target) in ID_REFERENCES {
+                    if *owner != kind.kind {
+                        continue;
+                    }
+                    if let Some(serde_json::Value::String(old)) = record.get(*column) {
+                        if let Some(new) = remaps.get(target).and_then(|m| m.get(old)) {
+                            record.insert(column.to_string(), new.clone().into());
+                        }
+                    }
+                }
+                if kind.policy == KeyPolicy::Remap {
+                    if let Some(new) = new_ids.get(&line) {
+                        record.insert("id".into(), new.clone().into());
+                        counts.remapped += 1;
+                    }
+                } else {
+                    let clause = kind
+                        .key
+                        .iter()
+                        .enumerate()
+                        .map(|(i, k)| format!("{k} = ?{}", i + 1))
+                        .collect::<Vec<_>>()
+                        .join(" AND ");
+                    let key_values = kind
+                        .key
+                        .iter()
+                        .map(|k| record.get(*k).and_then(json_to_sql).unwrap_or(Value::Null))
+                        .collect::<Vec<_>>();
+                    let exists: bool = tx.query_row(
+                        &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE {clause})", kind.table),
+                        rusqlite::params_from_iter(key_values),
+                        |r| r.get(0),
+                    )?;
+                    if exists {
+                        counts.skipped += 1;
+                        continue;
+                    }
+                }
+                if kind.kind == "memory" || kind.kind == "message" {
+                    rewrite_imported_text(&mut record, &remaps);
+                }
+
+                let mut columns = Vec::new();
+                let mut values = Vec::new();
+                for column in kind.columns {
+                    if let Some(value) = record.get(*column) {
+                        let value = json_to_sql(value).ok_or_else(|| {
+                            invalid_line(line, format!("`{column}` must be a scalar"))
+                        })?;
+                        columns.push(*column);
+                        values.push(value);
+                    }
+                }
+                let placeholders = (1..=columns.len())
+                    .zip(&columns)
+                    .map(|(i, c)| match is_sealed_column(kind.table, c) {
+                        true => format!("hub_seal(?{i})"),
+                        false => format!("?{i}"),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                tx.execute(
+                    &format!(
+                        "INSERT INTO {}({}) VALUES ({placeholders})",
+                        kind.table,
+                        columns.join(", ")
+                    ),
+                    rusqlite::params_from_iter(values),
+                )
+                .map_err(|e| invalid_line(line, format!("{}: {e}", kind.kind)))?;
+                counts.inserted += 1;
+
+                if kind.kind == "message" {
+                    let id = record_id(&record, line)?;
+                    if let Some(message) = self.get_message(&id)? {
+                        self.index_message_citations(&message)?;
+                    }
+                }
+                if kind.kind == "attachment" {
+                    if let Some(relative) = record.get("relative_path").and_then(|v| v.as_str()) {
+                        if !self.attachments_dir().join(relative).is_file() {
+                            report.missing_attachment_files.push(relative.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        if !dry_run {
+            tx.commit()?;
+        }
+        Ok(report)
+    }
+}
+
+/// Point `[Memory #id]` links, `attachment://id` markers,
+/// `[attachment:id:name]` tokens and `channel:session:<id>` subjects at
+/// remapped ids.
+fn rewrite_imported_text(
+    record: &mut serde_json::Map<String, serde_json::Value>,
+    remaps: &HashMap<&str, HashMap<String, String>>,
+) {
+    let empty = HashMap::new();
+    let memories = remaps.get("memory").unwrap_or(&empty);
+    let attachments = remaps.get("attachment").unwrap_or(&empty);
+    let sessions = remaps.get("work_session").unwrap_or(&empty);
+    if let Some(serde_json::Value::String(body)) = record.get_mut("body") {
+        for (old, new) in memories {
+            if body.contains("[Memory #") {
+                *body = rewrite_memory_references(body, old, new).0;
+            }
+        }
+        for (old, new) in attachments {
+            *body = body
+                .replace(
+                    &format!("attachment://{old}"),
+                    &format!("attachment://{new}"),
+                )
+                .replace(
+                    &format!("[attachment:{old}:"),
+                    &format!("[attachment:{new}:"),
+                );
+        }
+    }
+    if let Some(serde_json::Value::String(subject)) = record.get_mut("subject") {
+        for (old, new) in sessions {
+            *subject = subject.replace(&format!("session:{old}"), &format!("session:{new}"));
+        }
+    }
+}
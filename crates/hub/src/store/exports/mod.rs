@@ -1,4 +1,12 @@
 use super::*;
+
+mod jsonl;
+pub use jsonl::{
+    HubExportHeader, HubExportSummary, HubImportReport, ImportKindReport, RemappedId,
+    HUB_EXPORT_FORMAT, HUB_EXPORT_FORMAT_VERSION,
+};
+pub(super) use jsonl::sql_to_json;
+
 impl HubStore {
     pub fn export_markdown(&self, out_dir: Option<&Path>) -> Result<PathBuf, HubError> {
         let out = out_dir
@@ -0,0 +1,534 @@
+//! User-defined hook scripts, git-hooks style. An executable named after a
+//! [`HookEvent`] in `<hub_home>/hooks/` or `<workspace>/.agent/hooks/` is
+//! run whenever that event happens, hub hooks first. Workspace hooks ship
+//! with the repository, so like git the hub never runs them unless
+//! [`HookConfig::workspace_hooks`] is on and the workspace is listed in
+//! [`HookConfig::trusted_workspaces`]. Each run gets explicit
+//! argv (`<script> <event>`, never a shell), the JSON event on stdin, the
+//! workspace (or hub home) as working directory, and `CA_HOME` /
+//! `CA_HOOK_EVENT` in its environment. A run that outlives
+//! [`HookConfig::timeout_secs`] is killed.
+//!
+//! `pre-` hooks can veto: a non-zero exit, a timeout, or a script that
+//! cannot be started refuses the send or task advance, with the script's
+//! first line of output as the reason. Every other hook only observes;
+//! its failures are recorded but never undo the event. Every run is kept
+//! in `hook_runs`, output sealed like message bodies.
+//!
+//! Hooks run synchronously in the process that raised the event. An
+//! `audit-event` hook that writes into a watched workspace raises another
+//! audit event, so such hooks should write elsewhere.
+
+use super::*;
+use std::io::{Read, Write};
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Directory under the hub home holding hub-wide hooks.
+pub const HUB_HOOKS_DIR: &str = "hooks";
+/// Directory under a workspace holding that workspace's hooks.
+pub const WORKSPACE_HOOKS_DIR: &str = ".agent/hooks";
+const CONFIG_META: &str = "hook_config";
+const MAX_TIMEOUT_SECS: u64 = 600;
+/// Output kept per run, and the longest veto reason reported.
+const MAX_OUTPUT_BYTES: usize = 4096;
+/// How long to wait for a finished hook's output pipes to drain.
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    /// Before a message is stored; may veto.
+    PreSend,
+    /// After a message is stored.
+    PostSend,
+    /// Before a task moves to its next stage (or completes); may veto.
+    PreTaskAdvance,
+    /// After a task moved to its next stage (or completed).
+    PostTaskAdvance,
+    /// A wake left `pending` (delivered or cancelled).
+    WakeResolved,
+    /// A memory was created or updated.
+    MemoryWritten,
+    /// A file audit event was recorded.
+    AuditEvent,
+}
+
+impl HookEvent {
+    pub const ALL: [Self; 7] = [
+        Self::PreSend,
+        Self::PostSend,
+        Self::PreTaskAdvance,
+        Self::PostTaskAdvance,
+        Self::WakeResolved,
+        Self::MemoryWritten,
+        Self::AuditEvent,
+    ];
+
+    /// Also the hook's file name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PreSend => "pre-send",
+            Self::PostSend => "post-send",
+            Self::PreTaskAdvance => "pre-task-advance",
+            Self::PostTaskAdvance => "post-task-advance",
+            Self::WakeResolved => "wake-resolved",
+            Self::MemoryWritten => "memory-written",
+            Self::AuditEvent => "audit-event",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, HubError> {
+        Self::ALL
+            .into_iter()
+            .find(|event| event.as_str() == value)
+            .ok_or_else(|| {
+                HubError::Invalid(format!(
+                    "unknown hook event {value} (expected one of: {})",
+                    Self::ALL.map(Self::as_str).join(", ")
+                ))
+            })
+    }
+
+    /// Whether a failing hook refuses the event.
+    pub fn can_veto(self) -> bool {
+        matches!(self, Self::PreSend | Self::PreTaskAdvance)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookConfig {
+    /// Run hooks at all.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Also run hooks found in a trusted workspace's `.agent/hooks/`.
+    /// Off by default.
+    #[serde(default)]
+    pub workspace_hooks: bool,
+    /// Absolute workspace paths whose hooks may run. Add a workspace only
+    /// after reading its `.agent/hooks/`.
+    #[serde(default)]
+    pub trusted_workspaces: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+/// `path` made absolute and, when it exists, with symlinks resolved, so
+/// one workspace has one spelling in [`HookConfig::trusted_workspaces`].
+fn normalize_workspace(path: &str) -> String {
+    let path = Path::new(path);
+    fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+impl HookConfig {
+    /// Whether hooks in `workspace`'s `.agent/hooks/` may run.
+    pub fn runs_workspace_hooks(&self, workspace: &Path) -> bool {
+        self.workspace_hooks
+            && workspace.is_absolute()
+            && self
+                .trusted_workspaces
+                .contains(&normalize_workspace(&workspace.to_string_lossy()))
+    }
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            workspace_hooks: false,
+            trusted_workspaces: Vec::new(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
+
+/// An installed hook script.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookScript {
+    pub event: HookEvent,
+    pub path: String,
+    /// `hub` or `workspace`.
+    pub scope: String,
+}
+
+/// One run of a hook script.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookRun {
+    pub id: String,
+    pub event: HookEvent,
+    pub hook_path: String,
+    /// `None` when the script could not be started or was killed.
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// A `pre-` hook refused the event.
+    pub vetoed: bool,
+    /// Stdout then stderr, cut at 4 KiB.
+    pub output: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+}
+
+impl HookRun {
+    fn succeeded(&self) -> bool {
+        self.exit_code == Some(0) && !self.timed_out
+    }
+
+    fn veto_reason(&self) -> String {
+        if self.timed_out {
+            return "timed out".into();
+        }
+        match self.output.lines().map(str::trim).find(|l| !l.is_empty()) {
+            Some(line) => line.to_string(),
+            None => match self.exit_code {
+                Some(code) => format!("exit status {code}"),
+                None => "could not be run".into(),
+            },
+        }
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(meta) = fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        meta.is_file() && meta.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        meta.is_file()
+    }
+}
+
+fn truncate(mut text: String) -> String {
+    if text.len() > MAX_OUTPUT_BYTES {
+        let mut end = MAX_OUTPUT_BYTES;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        std::thread::spawn(move || {
+            let mut bytes = Vec::new();
+            let _ = pipe.read_to_end(&mut bytes);
+            let _ = tx.send(bytes);
+        });
+    }
+    rx
+}
+
+fn row_to_hook_run(r: &rusqlite::Row<'_>) -> rusqlite::Result<HookRun> {
+    let event: String = r.get(1)?;
+    Ok(HookRun {
+        id: r.get(0)?,
+        event: HookEvent::parse(&event).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        hook_path: r.get(2)?,
+        exit_code: r.get(3)?,
+        timed_out: r.get(4)?,
+        vetoed: r.get(5)?,
+        output: r.get(6)?,
+        started_at: r.get(7)?,
+        duration_ms: r.get(8)?,
+    })
+}
+
+impl HubStore {
+    pub(crate) fn ensure_hook_table(&self) -> Result<(), HubError> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS hook_runs (
+                id TEXT PRIMARY KEY,
+                event TEXT NOT NULL,
+                hook_path TEXT NOT NULL,
+                exit_code INTEGER,
+                timed_out INTEGER NOT NULL DEFAULT 0,
+                vetoed INTEGER NOT NULL DEFAULT 0,
+                output TEXT NOT NULL DEFAULT '',
+                started_at TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE INDEX IF NOT EXISTS hook_runs_started ON hook_runs(started_at);",
+        )?;
+        Ok(())
+    }
+
+    pub fn hook_config(&self) -> Result<HookConfig, HubError> {
+        let raw: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![CONFIG_META],
+                |r| r.get(0),
+            )
+            .optional()?;
+        match raw {
+            Some(s) => serde_json::from_str(&s)
+                .map_err(|e| HubError::Invalid(format!("{CONFIG_META} JSON corrupt: {e}"))),
+            None => Ok(HookConfig::default()),
+        }
+    }
+
+    /// Store `config`, with trusted workspaces normalized; returns what
+    /// was stored.
+    pub fn set_hook_config(&self, config: &HookConfig) -> Result<HookConfig, HubError> {
+        if !(1..=MAX_TIMEOUT_SECS).contains(&config.timeout_secs) {
+            return Err(HubError::Invalid(format!(
+                "hook timeout must be between 1 and {MAX_TIMEOUT_SECS} seconds"
+            )));
+        }
+        let mut config = config.clone();
+        config.trusted_workspaces = config
+            .trusted_workspaces
+            .iter()
+            .map(|path| normalize_workspace(path))
+            .collect();
+        config.trusted_workspaces.sort();
+        config.trusted_workspaces.dedup();
+        let json = serde_json::to_string(&config)
+            .map_err(|e| HubError::Invalid(format!("{CONFIG_META} serialize: {e}")))?;
+        self.conn.execute(
+            "INSERT INTO meta(key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![CONFIG_META, json],
+        )?;
+        Ok(config)
+    }
+
+    /// Add `workspace` to, or remove it from, the trusted workspaces.
+    pub fn trust_hook_workspace(&self, workspace: &str, trusted: bool) -> Result<(), HubError> {
+        let mut config = self.hook_config()?;
+        let workspace = normalize_workspace(workspace);
+        config.trusted_workspaces.retain(|path| *path != workspace);
+        if trusted {
+            config.trusted_workspaces.push(workspace);
+        }
+        self.set_hook_config(&config)?;
+        Ok(())
+    }
+
+    pub fn hooks_dir(&self) -> PathBuf {
+        self.absolute_data_dir().join(HUB_HOOKS_DIR)
+    }
+
+    /// Hooks run in another working directory, so a relative `--home`
+    /// must not reach them as is.
+    fn absolute_data_dir(&self) -> PathBuf {
+        std::path::absolute(&self.data_dir).unwrap_or_else(|_| self.data_dir.clone())
+    }
+
+    /// Hook scripts `event` would run for `workspace`, in run order. Only
+    /// trusted absolute workspace paths are searched.
+    fn hook_scripts(
+        &self,
+        event: HookEvent,
+        workspace: Option<&str>,
+        config: &HookConfig,
+    ) -> Vec<HookScript> {
+        let mut scripts = vec![("hub", self.hooks_dir().join(event.as_str()))];
+        if let Some(workspace) = workspace
+            .map(Path::new)
+            .filter(|path| config.runs_workspace_hooks(path))
+        {
+            scripts.push((
+                "workspace",
+                workspace.join(WORKSPACE_HOOKS_DIR).join(event.as_str()),
+            ));
+        }
+        scripts
+            .into_iter()
+            .filter(|(_, path)| is_executable(path))
+            .map(|(scope, path)| HookScript {
+                event,
+                path: path.to_string_lossy().into(),
+                scope: scope.into(),
+            })
+            .collect()
+    }
+
+    /// Every installed hook for `workspace` (hub hooks only when `None`).
+    pub fn list_hooks(&self, workspace: Option<&str>) -> Result<Vec<HookScript>, HubError> {
+        let config = self.hook_config()?;
+        Ok(HookEvent::ALL
+            .into_iter()
+            .flat_map(|event| self.hook_scripts(event, workspace, &config))
+            .collect())
+    }
+
+    /// Run `event`'s hooks with `data` as the event's `data` field. A
+    /// vetoing `pre-` hook stops the remaining hooks and returns
+    /// [`HubError::Invalid`] with its reason.
+    pub(crate) fn run_hooks(
+        &self,
+        event: HookEvent,
+        workspace: Option<&str>,
+        data: &impl Serialize,
+    ) -> Result<(), HubError> {
+        let config = self.hook_config()?;
+        if !config.enabled {
+            return Ok(());
+        }
+        let scripts = self.hook_scripts(event, workspace, &config);
+        if scripts.is_empty() {
+            return Ok(());
+        }
+        let input = serde_json::to_vec(&serde_json::json!({
+            "event": event.as_str(),
+            "occurred_at": Utc::now().to_rfc3339(),
+            "workspace_path": workspace,
+            "data": serde_json::to_value(data)
+                .map_err(|e| HubError::Invalid(format!("hook event data: {e}")))?,
+        }))
+        .map_err(|e| HubError::Invalid(format!("hook event: {e}")))?;
+        let cwd = workspace
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute() && path.is_dir())
+            .unwrap_or_else(|| self.absolute_data_dir());
+        let timeout = Duration::from_secs(config.timeout_secs);
+        for script in scripts {
+            let mut run = self.run_hook(event, &script.path, &cwd, &input, timeout);
+            run.vetoed = event.can_veto() && !run.succeeded();
+            self.conn.execute(
+                "INSERT INTO hook_runs(
+                    id, event, hook_path, exit_code, timed_out, vetoed, output,
+                    started_at, duration_ms
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, hub_seal(?7), ?8, ?9)",
+                params![
+                    run.id,
+                    event.as_str(),
+                    run.hook_path,
+                    run.exit_code,
+                    run.timed_out,
+                    run.vetoed,
+                    run.output,
+                    run.started_at,
+                    run.duration_ms,
+                ],
+            )?;
+            if run.vetoed {
+                return Err(HubError::Invalid(format!(
+                    "{} hook {} vetoed: {}",
+                    event.as_str(),
+                    script.path,
+                    truncate(run.veto_reason())
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn run_hook(
+        &self,
+        event: HookEvent,
+        path: &str,
+        cwd: &Path,
+        input: &[u8],
+        timeout: Duration,
+    ) -> HookRun {
+        let started_at = Utc::now().to_rfc3339();
+        let started = Instant::now();
+        let mut run = HookRun {
+            id: Uuid::new_v4().to_string(),
+            event,
+            hook_path: path.into(),
+            exit_code: None,
+            timed_out: false,
+            vetoed: false,
+            output: String::new(),
+            started_at,
+            duration_ms: 0,
+        };
+        let spawned = Command::new(path)
+            .arg(event.as_str())
+            .current_dir(cwd)
+            .env("CA_HOME", self.absolute_data_dir())
+            .env("CA_HOOK_EVENT", event.as_str())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(error) => {
+                run.output = format!("cannot run {path}: {error}");
+                return run;
+            }
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            let input = input.to_vec();
+            // A hook that never reads stdin must not block us on a full pipe.
+            std::thread::spawn(move || {
+                let _ = stdin.write_all(&input);
+            });
+        }
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    run.exit_code = status.code();
+                    break;
+                }
+                Ok(None) if started.elapsed() < timeout => {
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Ok(None) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    run.timed_out = true;
+                    break;
+                }
+                Err(error) => {
+                    run.output = format!("waiting for {path}: {error}");
+                    break;
+                }
+            }
+        }
+        let mut output = stdout.recv_timeout(OUTPUT_GRACE).unwrap_or_default();
+        output.extend(stderr.recv_timeout(OUTPUT_GRACE).unwrap_or_default());
+        run.output.push_str(&String::from_utf8_lossy(&output));
+        run.output = truncate(std::mem::take(&mut run.output));
+        run.duration_ms = started.elapsed().as_millis() as u64;
+        run
+    }
+
+    /// Recorded hook runs, newest first, for `event` or every event.
+    pub fn list_hook_runs(
+        &self,
+        event: Option<HookEvent>,
+        limit: usize,
+    ) -> Result<Vec<HookRun>, HubError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, event, hook_path, exit_code, timed_out, vetoed, hub_plain(output),
+                    started_at, duration_ms
+             FROM hook_runs WHERE ?1 IS NULL OR event = ?1
+             ORDER BY started_at DESC, rowid DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(
+            params![event.map(HookEvent::as_str), limit as i64],
+            row_to_hook_run,
+        )?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+}
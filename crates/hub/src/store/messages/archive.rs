@@ -0,0 +1,285 @@
+//! Archiving messages, channels and work sessions. Archived rows keep their
+//! status and content but drop out of the default listings (and so out of
+//! polling); the `list_archived_*` calls show them. Every operation needs
+//! an actor whose effective roles grant `can_archive_messages`.
+//!
+//! Archiving a channel or work session archives its messages with the same
+//! `archived_at` stamp, so unarchiving it restores exactly those messages
+//! and leaves ones archived on their own alone.
+
+use super::*;
+
+/// Result of an archive or unarchive call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveOutcome {
+    /// `message`, `channel` or `work_session`.
+    pub target: String,
+    pub id: String,
+    pub archived: bool,
+    /// Messages whose archive state changed.
+    pub messages: usize,
+}
+
+impl HubStore {
+    /// Add the `archived_at` columns to databases created before archiving.
+    pub(crate) fn ensure_archive_columns(&self) -> Result<(), HubError> {
+        for table in ["messages", "chat_channels", "work_sessions"] {
+            let _ = self.conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN archived_at TEXT"),
+                [],
+            );
+        }
+        self.conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_messages_archived ON messages(archived_at)",
+        )?;
+        Ok(())
+    }
+
+    fn require_archive_permission(
+        &self,
+        actor: &str,
+        workspace_path: Option<&str>,
+    ) -> Result<(), HubError> {
+        if self
+            .effective_agent_permissions(actor, workspace_path)?
+            .can_archive_messages
+        {
+            return Ok(());
+        }
+        Err(HubError::Invalid(format!(
+            "{actor} may not archive messages: none of its roles grants can_archive_messages"
+        )))
+    }
+
+    /// Archive a message and every sibling copy of the same team/channel
+    /// broadcast. Already-archived copies keep their original stamp.
+    pub fn archive_message(&self, actor: &str, id: &str) -> Result<ArchiveOutcome, HubError> {
+        self.set_message_archived(actor, id, true)
+    }
+
+    pub fn unarchive_message(&self, actor: &str, id: &str) -> Result<ArchiveOutcome, HubError> {
+        self.set_message_archived(actor, id, false)
+    }
+
+    fn set_message_archived(
+        &self,
+        actor: &str,
+        id: &str,
+        archived: bool,
+    ) -> Result<ArchiveOutcome, HubError> {
+        let message = self
+            .get_message(id)?
+            .ok_or_else(|| HubError::NotFound(id.into()))?;
+        self.require_archive_permission(actor, message.workspace_path.as_deref())?;
+        let stamp = archived.then(|| Utc::now().to_rfc3339());
+        let mut messages = 0;
+        for sibling in self.broadcast_group_ids(id)? {
+            messages += self.conn.execute(
+                "UPDATE messages SET archived_at = ?1
+                 WHERE id = ?2 AND (archived_at IS NULL) = ?3",
+                params![stamp, sibling, archived],
+            )?;
+        }
+        Ok(ArchiveOutcome {
+            target: "message".into(),
+            id: id.to_string(),
+            archived,
+            messages,
+        })
+    }
+
+    /// Archive a user-created channel and its messages. Built-in channels
+    /// cannot be archived.
+    pub fn archive_channel(&self, actor: &str, channel: &str) -> Result<ArchiveOutcome, HubError> {
+        let id = slug_channel_id(channel.trim().trim_start_matches('#'))?;
+        self.require_archive_permission(actor, None)?;
+        let builtin: bool = self
+            .conn
+            .query_row(
+                "SELECT builtin FROM chat_channels WHERE id = ?1 AND deleted_at IS NULL",
+                params![id],
+                |r| r.get::<_, i64>(0),
+            )
+            .optional()?
+            .map(|b| b != 0)
+            .ok_or_else(|| HubError::NotFound(id.clone()))?;
+        if builtin {
+            return Err(HubError::Invalid(
+                "built-in channels cannot be archived".into(),
+            ));
+        }
+        self.archive_conversation("chat_channels", "channel", &id, &format!("channel:{id}"))
+    }
+
+    pub fn unarchive_channel(
+        &self,
+        actor: &str,
+        channel: &str,
+    ) -> Result<ArchiveOutcome, HubError> {
+        let id = slug_channel_id(channel.trim().trim_start_matches('#'))?;
+        self.require_archive_permission(actor, None)?;
+        self.unarchive_conversation("chat_channels", "channel", &id, &format!("channel:{id}"))
+    }
+
+    /// Archive a work session and the messages in its chat.
+    pub fn archive_work_session(
+        &self,
+        actor: &str,
+        session_id: &str,
+    ) -> Result<ArchiveOutcome, HubError> {
+        self.require_archive_permission(actor, None)?;
+        let subject = format!("channel:session:{session_id}");
+        self.archive_conversation("work_sessions", "work_session", session_id, &subject)
+    }
+
+    pub fn unarchive_work_session(
+        &self,
+        actor: &str,
+        session_id: &str,
+    ) -> Result<ArchiveOutcome, HubError> {
+        self.require_archive_permission(actor, None)?;
+        let subject = format!("channel:session:{session_id}");
+        self.unarchive_conversation("work_sessions", "work_session", session_id, &subject)
+    }
+
+    fn archive_conversation(
+        &self,
+        table: &str,
+        target: &str,
+        id: &str,
+        subject: &str,
+    ) -> Result<ArchiveOutcome, HubError> {
+        let stamp = Utc::now().to_rfc3339();
+        let tx = self.conn.unchecked_transaction()?;
+        let updated = tx.execute(
+            &format!("UPDATE {table} SET archived_at = ?1 WHERE id = ?2 AND archived_at IS NULL"),
+            params![stamp, id],
+        )?;
+        if updated == 0 {
+            return Err(self.missing_or_already(table, id, true));
+        }
+        let messages = tx.execute(
+            "UPDATE messages SET archived_at = ?1
+             WHERE (subject = ?2 OR subject LIKE ?2 || ':%') AND archived_at IS NULL",
+            params![stamp, subject],
+        )?;
+        tx.commit()?;
+        Ok(ArchiveOutcome {
+            target: target.into(),
+            id: id.to_string(),
+            archived: true,
+            messages,
+        })
+    }
+
+    fn unarchive_conversation(
+        &self,
+        table: &str,
+        target: &str,
+        id: &str,
+        subject: &str,
+    ) -> Result<ArchiveOutcome, HubError> {
+        let stamp: Option<String> = self
+            .conn
+            .query_row(
+                &format!("SELECT archived_at FROM {table} WHERE id = ?1"),
+                params![id],
+                |r| r.get(0),
+            )
+            .optional()?
+            .flatten();
+        let Some(stamp) = stamp else {
+            return Err(self.missing_or_already(table, id, false));
+        };
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            &format!("UPDATE {table} SET archived_at = NULL WHERE id = ?1"),
+            params![id],
+        )?;
+        let messages = tx.execute(
+            "UPDATE messages SET archived_at = NULL
+             WHERE (subject = ?1 OR subject LIKE ?1 || ':%') AND archived_at = ?2",
+            params![subject, stamp],
+        )?;
+        tx.commit()?;
+        Ok(ArchiveOutcome {
+            target: target.into(),
+            id: id.to_string(),
+            archived: false,
+            messages,
+        })
+    }
+
+    fn missing_or_already(&self, table: &str, id: &str, archiving: bool) -> HubError {
+        let exists = self
+            .conn
+            .query_row(
+                &format!("SELECT 1 FROM {table} WHERE id = ?1"),
+                params![id],
+                |_| Ok(()),
+            )
+            .optional()
+            .ok()
+            .flatten()
+            .is_some();
+        match (exists, archiving) {
+            (false, _) => HubError::NotFound(id.into()),
+            (true, true) => HubError::Invalid(format!("{id} is already archived")),
+            (true, false) => HubError::Invalid(format!("{id} is not archived")),
+        }
+    }
+
+    /// Archived messages, newest first.
+    pub fn list_archived_messages_page(
+        &self,
+        page: &PageRequest,
+    ) -> Result<Page<MessageRecord>, HubError> {
+        let mut sql =
+            format!("SELECT {MESSAGE_COLUMNS} FROM messages WHERE archived_at IS NOT NULL");
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        let keyset = push_keyset(&mut sql, &mut params_vec, page, "created_at", "id")?;
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt
+            .query_map(params_refs.as_slice(), Self::row_to_message)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(finish_page(rows, keyset, |m| (&m.created_at, &m.id)))
+    }
+
+    pub fn list_archived_channels(&self) -> Result<Vec<ChannelRecord>, HubError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, topic, builtin, created_at FROM chat_channels
+             WHERE deleted_at IS NULL AND archived_at IS NOT NULL
+             ORDER BY archived_at DESC, id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ChannelRecord {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                topic: row.get(2)?,
+                builtin: row.get::<_, i64>(3)? != 0,
+                created_at: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub fn list_archived_work_sessions(&self) -> Result<Vec<WorkSessionRecord>, HubError> {
+        let ids: Vec<String> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT id FROM work_sessions WHERE archived_at IS NOT NULL
+                 ORDER BY archived_at DESC, id ASC",
+            )?;
+            let rows = stmt.query_map([], |r| r.get(0))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let mut sessions = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(session) = self.get_work_session(&id)? {
+                sessions.push(session);
+            }
+        }
+        Ok(sessions)
+    }
+}
@@ -0,0 +1,352 @@
+//! Escalation of unacknowledged messages. [`EscalationConfig`] gives an
+//! ack deadline per message kind (e.g. handoffs within 30 minutes). A
+//! pending message past its deadline escalates one step per deadline
+//! missed: after one period the recipient is woken again, after two every
+//! agent whose roles grant `can_allocate_tasks` is notified, after three
+//! the human. Each step sends a `system` message threaded under the
+//! original and keeps a row in `message_escalations`.
+//!
+//! Escalations run in passes ([`HubStore::run_escalations`]); the desktop
+//! app runs one every minute and `ca hub escalate` runs one on demand.
+//! Messages from `system` and messages to `human` or `system` never
+//! escalate.
+
+use super::*;
+use chrono::{DateTime, Duration};
+use std::collections::BTreeMap;
+
+const CONFIG_META: &str = "escalation_config";
+/// The longest ack deadline accepted: one year.
+const MAX_MINUTES: u64 = 365 * 24 * 60;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationConfig {
+    /// Minutes a message of each kind may stay pending before it
+    /// escalates, by kind (`handoff`, `message`, ...). Kinds not listed
+    /// never escalate.
+    #[serde(default)]
+    pub ack_within_minutes: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationStep {
+    /// Wake the recipient again.
+    Rewake,
+    /// Notify agents that can allocate tasks.
+    Allocators,
+    /// Notify the human.
+    Human,
+}
+
+impl EscalationStep {
+    const ALL: [Self; 3] = [Self::Rewake, Self::Allocators, Self::Human];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rewake => "rewake",
+            Self::Allocators => "allocators",
+            Self::Human => "human",
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, HubError> {
+        Self::ALL
+            .into_iter()
+            .find(|step| step.as_str() == s)
+            .ok_or_else(|| HubError::Invalid(format!("unknown escalation step: {s}")))
+    }
+}
+
+/// One escalation step taken for one message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationRecord {
+    pub id: String,
+    pub message_id: String,
+    /// 1 for the first step, up to 3.
+    pub level: u32,
+    pub step: EscalationStep,
+    /// Agents sent a notice.
+    pub notified: Vec<String>,
+    /// The `system` notices, one per notified agent.
+    pub notice_ids: Vec<String>,
+    pub wake_id: Option<String>,
+    /// Why the step fell short: a denied wake, no allocator to notify, or
+    /// a notice that could not be sent. A step that failed outright is
+    /// reported by `run_escalations` with an empty `id`.
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+const ESCALATION_COLUMNS: &str =
+    "id, message_id, level, step, notified_json, notice_ids_json, wake_id, error, created_at";
+
+fn row_to_escalation(r: &rusqlite::Row<'_>) -> rusqlite::Result<EscalationRecord> {
+    let json_list = |i: usize| -> rusqlite::Result<Vec<String>> {
+        Ok(serde_json::from_str(&r.get::<_, String>(i)?).unwrap_or_default())
+    };
+    let step: String = r.get(3)?;
+    Ok(EscalationRecord {
+        id: r.get(0)?,
+        message_id: r.get(1)?,
+        level: r.get(2)?,
+        step: EscalationStep::parse(&step).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        notified: json_list(4)?,
+        notice_ids: json_list(5)?,
+        wake_id: r.get(6)?,
+        error: r.get(7)?,
+        created_at: r.get(8)?,
+    })
+}
+
+impl HubStore {
+    pub(crate) fn ensure_escalation_table(&self) -> Result<(), HubError> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS message_escalations (
+                id TEXT PRIMARY KEY,
+                message_id TEXT NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+                level INTEGER NOT NULL,
+                step TEXT NOT NULL,
+                notified_json TEXT NOT NULL,
+                notice_ids_json TEXT NOT NULL,
+                wake_id TEXT,
+                error TEXT,
+                created_at TEXT NOT NULL,
+                UNIQUE(message_id, level)
+            );",
+        )?;
+        Ok(())
+    }
+
+    pub fn escalation_config(&self) -> Result<EscalationConfig, HubError> {
+        let raw: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![CONFIG_META],
+                |r| r.get(0),
+            )
+            .optional()?;
+        match raw {
+            Some(s) => serde_json::from_str(&s)
+                .map_err(|e| HubError::Invalid(format!("{CONFIG_META} JSON corrupt: {e}"))),
+            None => Ok(EscalationConfig::default()),
+        }
+    }
+
+    pub fn set_escalation_config(&self, config: &EscalationConfig) -> Result<(), HubError> {
+        for (kind, minutes) in &config.ack_within_minutes {
+            MessageKind::parse(kind)?;
+            if !(1..=MAX_MINUTES).contains(minutes) {
+                return Err(HubError::Invalid(format!(
+                    "{kind} ack deadline must be between 1 and {MAX_MINUTES} minutes"
+                )));
+            }
+        }
+        let json = serde_json::to_string(config)
+            .map_err(|e| HubError::Invalid(format!("{CONFIG_META} serialize: {e}")))?;
+        self.conn.execute(
+            "INSERT INTO meta(key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![CONFIG_META, json],
+        )?;
+        Ok(())
+    }
+
+    /// Escalations recorded for `message_id`, or every escalation when
+    /// `None`; oldest first.
+    pub fn list_escalations(
+        &self,
+        message_id: Option<&str>,
+    ) -> Result<Vec<EscalationRecord>, HubError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {ESCALATION_COLUMNS} FROM message_escalations
+             WHERE ?1 IS NULL OR message_id = ?1
+             ORDER BY created_at ASC, level ASC"
+        ))?;
+        let rows = stmt.query_map(params![message_id], row_to_escalation)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Take every escalation step due at `now`, at most one per message.
+    /// A step that fails is reported with its `error` set rather than
+    /// aborting the pass, so one bad message never starves the rest.
+    pub fn run_escalations(&self, now: DateTime<Utc>) -> Result<Vec<EscalationRecord>, HubError> {
+        let config = self.escalation_config()?;
+        let mut taken = Vec::new();
+        for (kind, minutes) in &config.ack_within_minutes {
+            let period = Duration::minutes((*minutes).min(MAX_MINUTES) as i64);
+            let first_due = now - period;
+            let overdue: Vec<(MessageRecord, u32)> = {
+                let mut stmt = self.conn.prepare(&format!(
+                    "SELECT {MESSAGE_COLUMNS},
+                            (SELECT COUNT(*) FROM message_escalations e WHERE e.message_id = messages.id)
+                     FROM messages
+                     WHERE kind = ?1 AND status = 'pending' AND archived_at IS NULL
+                       AND from_agent != 'system'
+                       AND to_agent NOT IN ('system', 'human')
+                       AND created_at <= ?2
+                     ORDER BY created_at ASC"
+                ))?;
+                let rows = stmt.query_map(params![kind, first_due.to_rfc3339()], |r| {
+                    Ok((Self::row_to_message(r)?, r.get(15)?))
+                })?;
+                rows.collect::<Result<_, _>>()?
+            };
+            for (message, done) in overdue {
+                let Some(step) = EscalationStep::ALL.get(done as usize).copied() else {
+                    continue;
+                };
+                let level = done + 1;
+                let due = DateTime::parse_from_rfc3339(&message.created_at)
+                    .map(|created| created.with_timezone(&Utc) + period * level as i32)
+                    .unwrap_or(now);
+                if due <= now {
+                    match self.escalate(&message, level, step, *minutes) {
+                        Ok(record) => taken.extend(record),
+                        Err(e) => taken.push(EscalationRecord {
+                            id: String::new(),
+                            message_id: message.id.clone(),
+                            level,
+                            step,
+                            notified: Vec::new(),
+                            notice_ids: Vec::new(),
+                            wake_id: None,
+                            error: Some(format!("escalation failed: {e}")),
+                            created_at: Utc::now().to_rfc3339(),
+                        }),
+                    }
+                }
+            }
+        }
+        Ok(taken)
+    }
+
+    /// Take one step. The `(message_id, level)` row is claimed and
+    /// committed before anyone is woken or notified, so a pass racing this
+    /// one for the same level (or one that finds the message acked
+    /// meanwhile) gets `None` and does nothing, and send hooks never run
+    /// under the write lock. Wake and notice failures land in the record's
+    /// `error`.
+    pub(crate) fn escalate(
+        &self,
+        message: &MessageRecord,
+        level: u32,
+        step: EscalationStep,
+        minutes: u64,
+    ) -> Result<Option<EscalationRecord>, HubError> {
+        let id = Uuid::new_v4().to_string();
+        let created_at = Utc::now().to_rfc3339();
+        let claimed = self.conn.execute(
+            &format!(
+                "INSERT INTO message_escalations({ESCALATION_COLUMNS})
+                 SELECT ?1, ?2, ?3, ?4, '[]', '[]', NULL, NULL, ?5
+                 WHERE EXISTS (SELECT 1 FROM messages
+                               WHERE id = ?2 AND status = 'pending' AND archived_at IS NULL)
+                 ON CONFLICT(message_id, level) DO NOTHING"
+            ),
+            params![id, message.id, level, step.as_str(), created_at],
+        )?;
+        if claimed == 0 {
+            return Ok(None);
+        }
+
+        let overdue = format!(
+            "{} {} from {} to {} ({}) has not been acked within {} min",
+            message.kind,
+            message.id,
+            message.from_agent,
+            message.to_agent,
+            message.subject.as_deref().unwrap_or("no subject"),
+            minutes * u64::from(level),
+        );
+        let mut wake_id = None;
+        let mut errors = Vec::new();
+        let notified: Vec<String> = match step {
+            EscalationStep::Rewake => {
+                match self.request_wake(&message.to_agent, Some(&overdue), Some(&message.id), false)
+                {
+                    Ok(wake) => wake_id = Some(wake.id),
+                    Err(e) => errors.push(format!("wake request denied: {e}")),
+                }
+                vec![message.to_agent.clone()]
+            }
+            EscalationStep::Allocators => match self.allocators_for(message) {
+                Ok(allocators) => {
+                    if allocators.is_empty() {
+                        errors.push("no agent holds a role with can_allocate_tasks".into());
+                    }
+                    allocators
+                }
+                Err(e) => {
+                    errors.push(format!("allocators unavailable: {e}"));
+                    Vec::new()
+                }
+            },
+            EscalationStep::Human => vec!["human".to_string()],
+        };
+
+        let mut notice_ids = Vec::new();
+        for agent in &notified {
+            match self.send_reply(
+                &message.id,
+                "system",
+                agent,
+                MessageKind::System,
+                &format!("Escalation {level}/3: {overdue}."),
+                None,
+                None,
+                message.workspace_path.as_deref(),
+                message.task_id.as_deref(),
+            ) {
+                Ok(notice) => notice_ids.push(notice.id),
+                Err(e) => errors.push(format!("notice to {agent} failed: {e}")),
+            }
+        }
+
+        let record = EscalationRecord {
+            id,
+            message_id: message.id.clone(),
+            level,
+            step,
+            notified,
+            notice_ids,
+            wake_id,
+            error: (!errors.is_empty()).then(|| errors.join("; ")),
+            created_at,
+        };
+        let json = |list: &[String]| serde_json::to_string(list).unwrap_or_else(|_| "[]".into());
+        self.conn.execute(
+            "UPDATE message_escalations
+             SET notified_json = ?2, notice_ids_json = ?3, wake_id = ?4, error = ?5
+             WHERE id = ?1",
+            params![
+                record.id,
+                json(&record.notified),
+                json(&record.notice_ids),
+                record.wake_id,
+                record.error,
+            ],
+        )?;
+        Ok(Some(record))
+    }
+
+    /// Agents other than the recipient whose roles grant
+    /// `can_allocate_tasks` in the message's workspace.
+    fn allocators_for(&self, message: &MessageRecord) -> Result<Vec<String>, HubError> {
+        let mut allocators = Vec::new();
+        for agent in self.list_agents()? {
+            if !["system", "human"].contains(&agent.id.as_str())
+                && agent.id != message.to_agent
+                && self
+                    .effective_agent_permissions(&agent.id, message.workspace_path.as_deref())?
+                    .can_allocate_tasks
+            {
+                allocators.push(agent.id);
+            }
+        }
+        Ok(allocators)
+    }
+}
@@ -0,0 +1,261 @@
+//! @mentions in channel and work-session posts. Each `@token` naming an
+//! agent id, an agent display name, or a role id (every agent holding the
+//! role) becomes a directed notification for that agent, once per post
+//! however many broadcast copies it fans out to. With
+//! [`MentionConfig::wake`] on, a mention also files a wake through the
+//! usual wake policy; a denial is kept on the mention, not raised.
+
+use super::*;
+
+const CONFIG_META: &str = "mention_config";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MentionConfig {
+    /// Wake each mentioned agent, subject to wake policy and budgets.
+    #[serde(default)]
+    pub wake: bool,
+}
+
+/// One agent mentioned in one post.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MentionRecord {
+    pub id: String,
+    pub message_id: String,
+    pub agent_id: String,
+    pub from_agent: String,
+    /// The token as written, without the `@`.
+    pub token: String,
+    /// Read-marker scope of the post: `channel:<name>` or
+    /// `channel:session:<id>`.
+    pub scope: String,
+    pub created_at: String,
+    pub wake_id: Option<String>,
+    /// Why the wake was not filed, when [`MentionConfig::wake`] is on.
+    pub wake_error: Option<String>,
+}
+
+const MENTION_COLUMNS: &str =
+    "id, message_id, agent_id, from_agent, token, scope, created_at, wake_id, wake_error";
+
+/// The read-marker scope of a channel or work-session subject, or `None`
+/// for anything else (DMs, team broadcasts, tagged sends).
+pub fn conversation_scope(subject: Option<&str>) -> Option<String> {
+    let rest = subject?.strip_prefix("channel:")?;
+    if let Some(session) = rest.strip_prefix("session:") {
+        let id = session.split(':').next().filter(|id| !id.is_empty())?;
+        return Some(format!("channel:session:{id}"));
+    }
+    let name = rest.split(':').next().filter(|name| !name.is_empty())?;
+    Some(format!("channel:{name}"))
+}
+
+/// `@tokens` in `body`, in order, without the `@`. An `@` inside a word
+/// (`ops@example.com`) is not a mention.
+pub fn parse_mentions(body: &str) -> Vec<String> {
+    let is_token_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':');
+    let mut tokens: Vec<String> = Vec::new();
+    let mut previous = None;
+    for (at, c) in body.char_indices() {
+        let starts = c == '@' && !previous.is_some_and(|p: char| p.is_alphanumeric() || p == '_');
+        previous = Some(c);
+        if !starts {
+            continue;
+        }
+        let rest = &body[at + 1..];
+        let end = rest.find(|c| !is_token_char(c)).unwrap_or(rest.len());
+        let token = rest[..end].trim_end_matches(['.', ':', '-']);
+        if !token.is_empty() && !tokens.iter().any(|t| t == token) {
+            tokens.push(token.to_string());
+        }
+    }
+    tokens
+}
+
+fn row_to_mention(r: &rusqlite::Row<'_>) -> rusqlite::Result<MentionRecord> {
+    Ok(MentionRecord {
+        id: r.get(0)?,
+        message_id: r.get(1)?,
+        agent_id: r.get(2)?,
+        from_agent: r.get(3)?,
+        token: r.get(4)?,
+        scope: r.get(5)?,
+        created_at: r.get(6)?,
+        wake_id: r.get(7)?,
+        wake_error: r.get(8)?,
+    })
+}
+
+impl HubStore {
+    pub(crate) fn ensure_mention_table(&self) -> Result<(), HubError> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS mentions (
+                id TEXT PRIMARY KEY,
+                message_id TEXT NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+                agent_id TEXT NOT NULL,
+                from_agent TEXT NOT NULL,
+                token TEXT NOT NULL,
+                scope TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                wake_id TEXT,
+                wake_error TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_mentions_agent ON mentions(agent_id, created_at);",
+        )?;
+        Ok(())
+    }
+
+    pub fn mention_config(&self) -> Result<MentionConfig, HubError> {
+        let raw: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![CONFIG_META],
+                |r| r.get(0),
+            )
+            .optional()?;
+        match raw {
+            Some(s) => serde_json::from_str(&s)
+                .map_err(|e| HubError::Invalid(format!("{CONFIG_META} JSON corrupt: {e}"))),
+            None => Ok(MentionConfig::default()),
+        }
+    }
+
+    pub fn set_mention_config(&self, config: &MentionConfig) -> Result<(), HubError> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| HubError::Invalid(format!("{CONFIG_META} serialize: {e}")))?;
+        self.conn.execute(
+            "INSERT INTO meta(key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![CONFIG_META, json],
+        )?;
+        Ok(())
+    }
+
+    /// Agents `token` names: an agent id or display name, or every agent
+    /// holding a role of that id. Case-insensitive.
+    pub fn resolve_mention(&self, token: &str) -> Result<Vec<String>, HubError> {
+        let mut agents: Vec<String> = self
+            .list_agents()?
+            .into_iter()
+            .filter(|a| {
+                a.id.eq_ignore_ascii_case(token) || a.display_name.eq_ignore_ascii_case(token)
+            })
+            .map(|a| a.id)
+            .collect();
+        let mut stmt = self.conn.prepare(
+            "SELECT agent_id FROM agent_role_assignments
+             WHERE role_id = ?1 COLLATE NOCASE ORDER BY agent_id",
+        )?;
+        for agent in stmt.query_map(params![token], |r| r.get::<_, String>(0))? {
+            let agent = agent?;
+            if !agents.contains(&agent) {
+                agents.push(agent);
+            }
+        }
+        Ok(agents)
+    }
+
+    /// Record the mentions in a freshly sent channel or session post.
+    pub(crate) fn record_mentions(&self, message: &MessageRecord) -> Result<(), HubError> {
+        let Some(scope) = conversation_scope(message.subject.as_deref()) else {
+            return Ok(());
+        };
+        let tokens = parse_mentions(&message.body);
+        if tokens.is_empty() {
+            return Ok(());
+        }
+        let mut mentioned: Vec<(String, String)> = Vec::new();
+        for token in tokens {
+            for agent in self.resolve_mention(&token)? {
+                if agent != message.from_agent
+                    && agent != "system"
+                    && !mentioned.iter().any(|(a, _)| *a == agent)
+                {
+                    mentioned.push((agent, token.clone()));
+                }
+            }
+        }
+        if mentioned.is_empty() {
+            return Ok(());
+        }
+        // Broadcast copies after the first find their mentions recorded.
+        let copies = self.broadcast_group_ids(&message.id)?;
+        let wake = self.mention_config()?.wake;
+        for (agent, token) in mentioned {
+            if self.already_mentioned(&agent, &copies)? {
+                continue;
+            }
+            let (wake_id, wake_error) = if wake {
+                let reason = format!("mentioned in {scope} by {}", message.from_agent);
+                match self.request_wake(&agent, Some(&reason), Some(&message.id), false) {
+                    Ok(wake) => (Some(wake.id), None),
+                    Err(error) => (None, Some(error.to_string())),
+                }
+            } else {
+                (None, None)
+            };
+            self.conn.execute(
+                &format!(
+                    "INSERT INTO mentions({MENTION_COLUMNS})
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+                ),
+                params![
+                    Uuid::new_v4().to_string(),
+                    message.id,
+                    agent,
+                    message.from_agent,
+                    token,
+                    scope,
+                    message.created_at,
+                    wake_id,
+                    wake_error,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn already_mentioned(&self, agent: &str, message_ids: &[String]) -> Result<bool, HubError> {
+        let ids = serde_json::to_string(message_ids)
+            .map_err(|e| HubError::Invalid(format!("mention ids serialize: {e}")))?;
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT 1 FROM mentions
+                 WHERE agent_id = ?1 AND message_id IN (SELECT value FROM json_each(?2))",
+                params![agent, ids],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    /// `agent`'s mentions in unarchived posts, newest first. `unread_only`
+    /// keeps those posted after the agent's read marker for their scope.
+    pub fn list_mentions(
+        &self,
+        agent: &str,
+        unread_only: bool,
+        limit: usize,
+    ) -> Result<Vec<MentionRecord>, HubError> {
+        let columns = MENTION_COLUMNS
+            .split(", ")
+            .map(|c| format!("n.{c}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {columns} FROM mentions n
+             JOIN messages m ON m.id = n.message_id
+             LEFT JOIN read_markers r ON r.agent_id = n.agent_id AND r.scope = n.scope
+             WHERE n.agent_id = ?1 AND m.archived_at IS NULL
+               AND (?2 = 0 OR r.last_read_at IS NULL OR n.created_at > r.last_read_at)
+             ORDER BY n.created_at DESC, n.id DESC
+             LIMIT ?3"
+        ))?;
+        let rows = stmt.query_map(
+            params![agent, unread_only, limit.clamp(1, 500) as i64],
+            row_to_mention,
+        )?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
@@ -1,10 +1,41 @@
 use super::*;
 
+mod archive;
+pub use archive::ArchiveOutcome;
+mod escalations;
+pub use escalations::{EscalationConfig, EscalationRecord, EscalationStep};
+mod mentions;
+mod payloads;
+pub use mentions::{conversation_scope, parse_mentions, MentionConfig, MentionRecord};
 mod queries;
+pub(crate) use queries::{message_columns, MESSAGE_COLUMNS};
+mod questions;
+pub use questions::{QuestionAnswer, QuestionRecord, QuestionState};
 mod read_markers;
+mod receipts;
+pub use receipts::{DeliveryReceipt, DeliveryState, ReceiptSummary};
+mod search;
+pub use search::{MessageSearch, MessageSearchHit};
+mod tagged;
+mod threads;
+pub use threads::ThreadSummary;
+mod unread;
+pub use unread::{UnreadScope, UnreadSummary};
 mod wakes;
 #[allow(clippy::too_many_arguments)]
 impl HubStore {
+    /// Columns and tables added around `messages` after the base schema.
+    pub(crate) fn ensure_message_extensions(&self) -> Result<(), HubError> {
+        self.ensure_archive_columns()?;
+        self.ensure_thread_columns()?;
+        self.ensure_payload_column()?;
+        self.ensure_mention_table()?;
+        self.ensure_message_index()?;
+        self.ensure_delivery_table()?;
+        self.ensure_escalation_table()?;
+        self.ensure_question_table()
+    }
+
     pub fn send_message(
         &self,
         from_agent: &str,
@@ -14,10 +45,87 @@ impl HubStore {
         subject: Option<&str>,
         workspace_path: Option<&str>,
         task_id: Option<&str>,
+    ) -> Result<MessageRecord, HubError> {
+        self.send_message_with_payload(
+            from_agent,
+            to_agent,
+            kind,
+            body,
+            None,
+            subject,
+            workspace_path,
+            task_id,
+        )
+    }
+
+    /// [`Self::send_message`] with a JSON payload, which must match the
+    /// recipient's `input_schema` when its card declares one.
+    pub fn send_message_with_payload(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        kind: MessageKind,
+        body: &str,
+        payload: Option<&serde_json::Value>,
+        subject: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+    ) -> Result<MessageRecord, HubError> {
+        self.insert_message(
+            from_agent,
+            to_agent,
+            kind,
+            body,
+            payload,
+            subject,
+            workspace_path,
+            task_id,
+            None,
+        )
+    }
+
+    /// Shared send path. `reply_to` threads the message under that parent
+    /// in the insert itself, so mentions, webhooks and post-send hooks
+    /// already see it threaded.
+    pub(crate) fn insert_message(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        kind: MessageKind,
+        body: &str,
+        payload: Option<&serde_json::Value>,
+        subject: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+        reply_to: Option<&str>,
     ) -> Result<MessageRecord, HubError> {
         if body.trim().is_empty() {
             return Err(HubError::Invalid("message body must not be empty".into()));
         }
+        let thread_id = reply_to.map(|parent| self.thread_root(parent)).transpose()?;
+        if reply_to.is_some() {
+            self.check_reply_payload(from_agent, payload)?;
+        }
+        if let Some(payload) = payload {
+            if let Some(reason) = self.payload_rejection(to_agent, payload)? {
+                return Err(HubError::Invalid(reason));
+            }
+        }
+        self.run_hooks(
+            HookEvent::PreSend,
+            workspace_path,
+            &serde_json::json!({
+                "from_agent": from_agent,
+                "to_agent": to_agent,
+                "kind": kind.as_str(),
+                "subject": subject,
+                "body": body,
+                "payload": payload,
+                "workspace_path": workspace_path,
+                "task_id": task_id,
+                "reply_to": reply_to,
+            }),
+        )?;
         self.upsert_agent(from_agent, from_agent)?;
         self.upsert_agent(to_agent, to_agent)?;
 
@@ -27,8 +135,10 @@ impl HubStore {
             r#"
             INSERT INTO messages(
                 id, from_agent, to_agent, workspace_path, task_id,
-                kind, status, subject, body, created_at, acked_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, NULL)
+                kind, status, subject, body, created_at, acked_at, payload,
+                reply_to, thread_id
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, hub_seal(?9), ?10, NULL, hub_seal(?11),
+                      ?12, ?13)
             "#,
             params![
                 id,
@@ -41,9 +151,25 @@ impl HubStore {
                 subject,
                 body,
                 now,
+                payload.map(|p| p.to_string()),
+                reply_to,
+                thread_id,
             ],
         )?;
-        self.get_message(&id)?.ok_or_else(|| HubError::NotFound(id))
+        let message = self
+            .get_message(&id)?
+            .ok_or_else(|| HubError::NotFound(id))?;
+        self.record_message_memory_usage(&message)?;
+        self.record_mentions(&message)?;
+        if message.to_agent == "human" {
+            self.emit_webhook_event(crate::WebhookEvent::MessageToHuman, &message)?;
+        }
+        self.run_hooks(
+            HookEvent::PostSend,
+            message.workspace_path.as_deref(),
+            &message,
+        )?;
+        Ok(message)
     }
 
     /// Fan out a team message while retaining one shared subject so clients
@@ -56,6 +182,33 @@ impl HubStore {
         subject: Option<&str>,
         workspace_path: Option<&str>,
         task_id: Option<&str>,
+    ) -> Result<Vec<MessageRecord>, HubError> {
+        self.fan_out_to_team(
+            from_agent,
+            kind,
+            body,
+            None,
+            subject,
+            workspace_path,
+            task_id,
+            None,
+        )
+    }
+
+    /// Send one copy of `body` to every team member. A `payload` is
+    /// checked for every recipient before any copy is written, so a
+    /// schema mismatch never leaves a partial broadcast.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn fan_out_to_team(
+        &self,
+        from_agent: &str,
+        kind: MessageKind,
+        body: &str,
+        payload: Option<&serde_json::Value>,
+        subject: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+        reply_to: Option<&str>,
     ) -> Result<Vec<MessageRecord>, HubError> {
         let subject = subject
             .map(str::to_string)
@@ -66,28 +219,42 @@ impl HubStore {
             .filter(|agent| agent.id != from_agent && agent.id != "system")
             .map(|agent| agent.id)
             .collect::<Vec<_>>();
+        if reply_to.is_some() {
+            self.check_reply_payload(from_agent, payload)?;
+        }
+        if let Some(payload) = payload {
+            for recipient in &recipients {
+                if let Some(reason) = self.payload_rejection(recipient, payload)? {
+                    return Err(HubError::Invalid(reason));
+                }
+            }
+        }
         if recipients.is_empty() {
-            return Ok(vec![self.send_message(
+            return Ok(vec![self.insert_message(
                 from_agent,
                 "team",
                 kind,
                 body,
+                payload,
                 Some(&subject),
                 workspace_path,
                 task_id,
+                reply_to,
             )?]);
         }
         recipients
             .into_iter()
             .map(|recipient| {
-                self.send_message(
+                self.insert_message(
                     from_agent,
                     &recipient,
                     kind,
                     body,
+                    payload,
                     Some(&subject),
                     workspace_path,
                     task_id,
+                    reply_to,
                 )
             })
             .collect()
@@ -118,179 +285,33 @@ impl HubStore {
             .is_some())
     }
 
-    /// C11: enforce distinct task vs. wake semantics per recipient.
-    ///
-    /// "Currently present" is defined as: enrolled on the standing team
-    /// (`agents.team_member`), and — when `session_id` is given — also a
-    /// member of that session. There is no live-heartbeat signal in this
-    /// schema yet, so presence is this durable enrollment state, not a
-    /// point-in-time process check.
-    ///
-    /// - Task-tagged recipients who are not currently present are rejected:
-    ///   no message is sent and no membership is mutated.
-    /// - Wake-tagged recipients who are not yet a team member are enrolled
-    ///   (and added to the session, if any) before delivery, then a durable
-    ///   wake request is filed through the existing policy/budget/human-gate
-    ///   path (`request_wake`) — a denial there does not undo the enrollment
-    ///   or the message send, it only leaves the recipient unwoken.
-    /// - Every recipient gets exactly one durable `tagged_send_outcomes` row,
-    ///   whether accepted or rejected.
+    /// C10: send an untagged work-session post to an explicit recipient set.
+    /// The set is recorded once by subject, rather than reconstructed later
+    /// from fan-out rows.
     #[allow(clippy::too_many_arguments)]
-    pub fn send_tagged_message(
+    pub fn send_session_message(
         &self,
         from_agent: &str,
+        session_id: &str,
         to: &[String],
-        is_task: bool,
-        is_wake: bool,
         body: &str,
         subject: Option<&str>,
         workspace_path: Option<&str>,
         task_id: Option<&str>,
-        session_id: Option<&str>,
-    ) -> Result<Vec<SendOutcome>, HubError> {
-        if body.trim().is_empty() {
-            return Err(HubError::Invalid("message body must not be empty".into()));
-        }
-        if !is_task && !is_wake {
-            return Err(HubError::Invalid(
-                "send_tagged_message requires at least one of task/wake".into(),
-            ));
-        }
-        let subject = subject
-            .map(str::to_string)
-            .unwrap_or_else(|| format!("tagged:{}", Uuid::new_v4()));
-        let subject = self.unique_recipient_subject(&subject)?;
-
-        let mut recipients: Vec<String> = Vec::new();
-        for id in to {
-            if id != "system" && id != from_agent && !recipients.contains(id) {
-                recipients.push(id.clone());
-            }
-        }
-        if recipients.is_empty() {
-            return Err(HubError::Invalid(
-                "send_tagged_message requires at least one recipient".into(),
-            ));
-        }
-        if let Some(session_id) = session_id {
-            if self.get_work_session(session_id)?.is_none() {
-                return Err(HubError::NotFound(format!(
-                    "work session {session_id} does not exist"
-                )));
-            }
-        }
-        self.record_recipient_set(&subject, session_id, &recipients)?;
-
-        // S5 / #131: a wake may only enroll a brand-new (not-yet-team-member)
-        // identity when Settings' orchestration policy allows it. Resolved
-        // once per send, not per recipient — it's the same policy value
-        // either way. Adding an *existing* team member to a session is a
-        // separate, always-allowed concern (not "auto-enrollment").
-        let auto_enrollment_allowed = crate::SettingsStore::open(self.data_dir())
-            .effective(workspace_path)
-            .orchestration
-            .auto_enrollment_allowed;
-
-        let mut outcomes = Vec::with_capacity(recipients.len());
-        for recipient in recipients {
-            let present = self.is_currently_present(&recipient, session_id)?;
-
-            if is_task && !present {
-                outcomes.push(self.record_send_outcome(
-                    &subject,
-                    from_agent,
-                    &recipient,
-                    is_task,
-                    is_wake,
-                    false,
-                    false,
-                    false,
-                    Some("task target is not a current team/session member".into()),
-                    "task_refused_not_present",
-                    None,
-                )?);
-                continue;
-            }
-
-            if is_wake && !self.is_team_member(&recipient)? && !auto_enrollment_allowed {
-                outcomes.push(self.record_send_outcome(
-                    &subject,
-                    from_agent,
-                    &recipient,
-                    is_task,
-                    is_wake,
-                    false,
-                    false,
-                    false,
-                    Some("auto-enrollment is disabled by orchestration policy".into()),
-                    "wake_refused_auto_enrollment_disabled",
-                    None,
-                )?);
-                continue;
-            }
-
-            let enrolled = if is_wake {
-                self.enroll_wake_recipient(&recipient, session_id)?
-            } else {
-                false
-            };
-
-            let kind = if is_wake {
-                MessageKind::Wake
-            } else {
-                MessageKind::Message
-            };
-            let message = self.send_message(
-                from_agent,
-                &recipient,
-                kind,
-                body,
-                Some(&subject),
-                workspace_path,
-                task_id,
-            )?;
-
-            let mut wake_requested = false;
-            let mut reason = None;
-            let mut policy_decision = if enrolled {
-                "wake_enrolled".to_string()
-            } else {
-                "accepted".to_string()
-            };
-            if is_wake {
-                let wake_reason = format!("tagged send: {subject}");
-                match self.request_wake(&recipient, Some(&wake_reason), Some(&message.id), false) {
-                    Ok(_) => wake_requested = true,
-                    Err(error) => {
-                        reason = Some(format!("wake request denied: {error}"));
-                        policy_decision = wake_denial_policy(&error).to_string();
-                    }
-                }
-            }
-
-            outcomes.push(self.record_send_outcome(
-                &subject,
-                from_agent,
-                &recipient,
-                is_task,
-                is_wake,
-                true,
-                enrolled,
-                wake_requested,
-                reason,
-                &policy_decision,
-                Some(message.id),
-            )?);
-        }
-
-        Ok(outcomes)
+    ) -> Result<Vec<MessageRecord>, HubError> {
+        self.fan_out_to_session(
+            from_agent,
+            session_id,
+            to,
+            body,
+            subject,
+            workspace_path,
+            task_id,
+            None,
+        )
     }
 
-    /// C10: send an untagged work-session post to an explicit recipient set.
-    /// The set is recorded once by subject, rather than reconstructed later
-    /// from fan-out rows.
-    #[allow(clippy::too_many_arguments)]
-    pub fn send_session_message(
+    pub(crate) fn fan_out_to_session(
         &self,
         from_agent: &str,
         session_id: &str,
@@ -299,6 +320,7 @@ impl HubStore {
         subject: Option<&str>,
         workspace_path: Option<&str>,
         task_id: Option<&str>,
+        reply_to: Option<&str>,
     ) -> Result<Vec<MessageRecord>, HubError> {
         if body.trim().is_empty() {
             return Err(HubError::Invalid("message body must not be empty".into()));
@@ -332,14 +354,16 @@ impl HubStore {
         recipients
             .iter()
             .map(|recipient| {
-                self.send_message(
+                self.insert_message(
                     from_agent,
                     recipient,
                     MessageKind::Message,
                     body,
+                    None,
                     Some(&subject),
                     workspace_path,
                     task_id,
+                    reply_to,
                 )
             })
             .collect()
@@ -359,142 +383,4 @@ impl HubStore {
         )?;
         Ok(())
     }
-
-    #[allow(clippy::too_many_arguments)]
-    fn record_send_outcome(
-        &self,
-        subject: &str,
-        from_agent: &str,
-        to_agent: &str,
-        is_task: bool,
-        is_wake: bool,
-        accepted: bool,
-        enrolled: bool,
-        wake_requested: bool,
-        reason: Option<String>,
-        policy_decision: &str,
-        message_id: Option<String>,
-    ) -> Result<SendOutcome, HubError> {
-        let id = Uuid::new_v4().to_string();
-        let created_at = Utc::now().to_rfc3339();
-        self.conn.execute(
-            r#"
-            INSERT INTO tagged_send_outcomes(
-                id, subject, from_agent, to_agent, is_task, is_wake,
-                accepted, enrolled, wake_requested, reason, policy_decision,
-                message_id, created_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
-            "#,
-            params![
-                id,
-                subject,
-                from_agent,
-                to_agent,
-                is_task as i64,
-                is_wake as i64,
-                accepted as i64,
-                enrolled as i64,
-                wake_requested as i64,
-                reason,
-                policy_decision,
-                message_id,
-                created_at,
-            ],
-        )?;
-        Ok(SendOutcome {
-            id,
-            subject: subject.to_string(),
-            from_agent: from_agent.to_string(),
-            to_agent: to_agent.to_string(),
-            is_task,
-            is_wake,
-            accepted,
-            enrolled,
-            wake_requested,
-            reason,
-            policy_decision: policy_decision.to_string(),
-            message_id,
-            created_at,
-        })
-    }
-
-    pub fn list_tagged_send_outcomes(&self, subject: &str) -> Result<Vec<SendOutcome>, HubError> {
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, subject, from_agent, to_agent, is_task, is_wake,
-                   accepted, enrolled, wake_requested, reason,
-                   COALESCE(policy_decision, ''), message_id, created_at
-            FROM tagged_send_outcomes
-            WHERE subject = ?1
-            ORDER BY created_at
-            "#,
-        )?;
-        let rows = stmt.query_map(params![subject], |row| {
-            Ok(SendOutcome {
-                id: row.get(0)?,
-                subject: row.get(1)?,
-                from_agent: row.get(2)?,
-                to_agent: row.get(3)?,
-                is_task: row.get::<_, i64>(4)? != 0,
-                is_wake: row.get::<_, i64>(5)? != 0,
-                accepted: row.get::<_, i64>(6)? != 0,
-                enrolled: row.get::<_, i64>(7)? != 0,
-                wake_requested: row.get::<_, i64>(8)? != 0,
-                reason: row.get(9)?,
-                policy_decision: row.get(10)?,
-                message_id: row.get(11)?,
-                created_at: row.get(12)?,
-            })
-        })?;
-        Ok(rows.collect::<Result<Vec<_>, _>>()?)
-    }
-
-    fn is_currently_present(
-        &self,
-        agent_id: &str,
-        session_id: Option<&str>,
-    ) -> Result<bool, HubError> {
-        if !self.is_team_member(agent_id)? {
-            return Ok(false);
-        }
-        match session_id {
-            Some(session_id) => self.is_session_member(session_id, agent_id),
-            None => Ok(true),
-        }
-    }
-
-    fn enroll_wake_recipient(
-        &self,
-        recipient: &str,
-        session_id: Option<&str>,
-    ) -> Result<bool, HubError> {
-        let mut enrolled = false;
-        if !self.is_team_member(recipient)? {
-            self.upsert_agent(recipient, recipient)?;
-            self.set_team_member(recipient, true)?;
-            enrolled = true;
-        }
-        if let Some(session_id) = session_id {
-            if !self.is_session_member(session_id, recipient)? {
-                if !self
-                    .list_agents()?
-                    .iter()
-                    .any(|agent| agent.id == recipient)
-                {
-                    self.upsert_agent(recipient, recipient)?;
-                }
-                self.add_work_session_member(session_id, recipient)?;
-                enrolled = true;
-            }
-        }
-        Ok(enrolled)
-    }
-}
-
-fn wake_denial_policy(error: &HubError) -> &'static str {
-    if error.to_string().contains("budget-paused") {
-        "wake_denied_budget"
-    } else {
-        "wake_denied_policy"
-    }
 }
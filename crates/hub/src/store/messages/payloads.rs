@@ -0,0 +1,224 @@
+//! Typed message payloads. A message may carry a JSON `payload` next to
+//! its text body. A payload sent to an agent whose [`AgentCard`] declares
+//! an `input_schema` must match it; a reply from an agent whose card sets
+//! `output_format` to a JSON schema (or to `json`) must carry a payload
+//! that matches. Payloads are sealed like bodies when encryption is on.
+//!
+//! Schemas are checked for the common JSON Schema keywords: `type`,
+//! `enum`, `const`, `required`, `properties`, `additionalProperties`,
+//! `items`, `minItems`/`maxItems`, `minLength`/`maxLength` and
+//! `minimum`/`maximum`. Other keywords (`$ref`, `oneOf`, `pattern`, ...)
+//! are ignored rather than rejected.
+
+use super::*;
+use serde_json::Value;
+
+fn type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Bool(false) => return errors.push(format!("{path}: not allowed")),
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(name)) => vec![name],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|name| type_matches(name, value)) {
+        return errors.push(format!(
+            "{path}: expected {}, got {}",
+            types.join(" or "),
+            type_name(value)
+        ));
+    }
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            errors.push(format!(
+                "{path}: must be one of {}",
+                Value::from(allowed.clone())
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{path}: must be {expected}"));
+        }
+    }
+    let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+    match value {
+        Value::Object(fields) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !fields.contains_key(name) {
+                        errors.push(format!("{path}: missing required field `{name}`"));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (name, field) in fields {
+                let field_path = format!("{path}/{name}");
+                match properties.and_then(|p| p.get(name)) {
+                    Some(field_schema) => check(field_schema, field, &field_path, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{path}: unexpected field `{name}`"))
+                        }
+                        Some(extra) => check(extra, field, &field_path, errors),
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if bound("minItems").is_some_and(|min| (items.len() as f64) < min) {
+                errors.push(format!("{path}: too few items ({})", items.len()));
+            }
+            if bound("maxItems").is_some_and(|max| (items.len() as f64) > max) {
+                errors.push(format!("{path}: too many items ({})", items.len()));
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{path}/{i}"), errors);
+                }
+            }
+        }
+        Value::String(text) => {
+            let len = text.chars().count() as f64;
+            if bound("minLength").is_some_and(|min| len < min) {
+                errors.push(format!("{path}: shorter than {}", schema["minLength"]));
+            }
+            if bound("maxLength").is_some_and(|max| len > max) {
+                errors.push(format!("{path}: longer than {}", schema["maxLength"]));
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if bound("minimum").is_some_and(|min| n < min) {
+                errors.push(format!("{path}: below minimum {}", schema["minimum"]));
+            }
+            if bound("maximum").is_some_and(|max| n > max) {
+                errors.push(format!("{path}: above maximum {}", schema["maximum"]));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Where `value` breaks `schema`, as `payload/<json pointer>: <problem>`
+/// lines; empty when it matches.
+pub(crate) fn schema_violations(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(schema, value, "payload", &mut errors);
+    errors
+}
+
+impl HubStore {
+    pub(crate) fn ensure_payload_column(&self) -> Result<(), HubError> {
+        let _ = self
+            .conn
+            .execute("ALTER TABLE messages ADD COLUMN payload TEXT", []);
+        Ok(())
+    }
+
+    /// `agent`'s registered card, if it has one.
+    pub fn agent_card(&self, agent: &str) -> Result<Option<AgentCard>, HubError> {
+        let card_json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT card_json FROM agents WHERE id = ?1",
+                params![agent],
+                |r| r.get(0),
+            )
+            .optional()?
+            .flatten();
+        card_json
+            .map(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|e| HubError::Invalid(format!("agent {agent} card JSON corrupt: {e}")))
+            })
+            .transpose()
+    }
+
+    /// Why `payload` may not be sent to `to_agent`, or `None` when it
+    /// matches the recipient's `input_schema` (or there is none).
+    pub fn payload_rejection(
+        &self,
+        to_agent: &str,
+        payload: &Value,
+    ) -> Result<Option<String>, HubError> {
+        let Some(schema) = self
+            .agent_card(to_agent)?
+            .and_then(|card| card.input_schema)
+        else {
+            return Ok(None);
+        };
+        let errors = schema_violations(&schema, payload);
+        Ok((!errors.is_empty()).then(|| {
+            format!(
+                "payload does not match {to_agent}'s input_schema: {}",
+                errors.join("; ")
+            )
+        }))
+    }
+
+    /// Check a reply from `from_agent` against its card's `output_format`:
+    /// a JSON schema there requires a matching payload, `json` requires any
+    /// payload, and any other text describes a free-form format that is
+    /// not checked.
+    pub fn check_reply_payload(
+        &self,
+        from_agent: &str,
+        payload: Option<&Value>,
+    ) -> Result<(), HubError> {
+        let Some(format) = self
+            .agent_card(from_agent)?
+            .and_then(|card| card.output_format)
+        else {
+            return Ok(());
+        };
+        let schema = match serde_json::from_str::<Value>(format.trim()) {
+            Ok(schema @ Value::Object(_)) => Some(schema),
+            _ if format.trim().eq_ignore_ascii_case("json") => None,
+            _ => return Ok(()),
+        };
+        let Some(payload) = payload else {
+            return Err(HubError::Invalid(format!(
+                "{from_agent}'s output_format requires replies to carry a JSON payload"
+            )));
+        };
+        let errors = schema.map_or_else(Vec::new, |s| schema_violations(&s, payload));
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(HubError::Invalid(format!(
+            "reply payload does not match {from_agent}'s output_format: {}",
+            errors.join("; ")
+        )))
+    }
+}
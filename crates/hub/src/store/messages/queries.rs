@@ -1,4 +1,22 @@
 use super::super::*;
+
+/// Columns [`HubStore::row_to_message`] reads, in order.
+pub(crate) const MESSAGE_COLUMNS: &str = "id, from_agent, to_agent, workspace_path, task_id, \
+     kind, status, subject, hub_plain(body), created_at, acked_at, archived_at, reply_to, thread_id, \
+     hub_plain(payload)";
+
+/// [`MESSAGE_COLUMNS`] qualified with a table alias, for joins.
+pub(crate) fn message_columns(alias: &str) -> String {
+    MESSAGE_COLUMNS
+        .split(", ")
+        .map(|c| match c.strip_prefix("hub_plain(") {
+            Some(inner) => format!("hub_plain({alias}.{inner}"),
+            None => format!("{alias}.{c}"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 impl HubStore {
     /// A recipient set represents one fan-out, while a channel/session prefix
     /// represents a conversation. Callers may intentionally reuse that prefix
@@ -18,45 +36,56 @@ impl HubStore {
     }
 
     pub fn get_message(&self, id: &str) -> Result<Option<MessageRecord>, HubError> {
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, from_agent, to_agent, workspace_path, task_id, kind, status,
-                   subject, body, created_at, acked_at
-            FROM messages WHERE id = ?1
-            "#,
-        )?;
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {MESSAGE_COLUMNS} FROM messages WHERE id = ?1"))?;
         let row = stmt
-            .query_row(params![id], |r| {
-                Ok(MessageRecord {
-                    id: r.get(0)?,
-                    from_agent: r.get(1)?,
-                    to_agent: r.get(2)?,
-                    workspace_path: r.get(3)?,
-                    task_id: r.get(4)?,
-                    kind: r.get(5)?,
-                    status: r.get(6)?,
-                    subject: r.get(7)?,
-                    body: r.get(8)?,
-                    created_at: r.get(9)?,
-                    acked_at: r.get(10)?,
-                })
-            })
+            .query_row(params![id], Self::row_to_message)
             .optional()?;
         Ok(row)
     }
 
+    pub(crate) fn row_to_message(r: &rusqlite::Row<'_>) -> rusqlite::Result<MessageRecord> {
+        Ok(MessageRecord {
+            id: r.get(0)?,
+            from_agent: r.get(1)?,
+            to_agent: r.get(2)?,
+            workspace_path: r.get(3)?,
+            task_id: r.get(4)?,
+            kind: r.get(5)?,
+            status: r.get(6)?,
+            subject: r.get(7)?,
+            body: r.get(8)?,
+            created_at: r.get(9)?,
+            acked_at: r.get(10)?,
+            archived_at: r.get(11)?,
+            reply_to: r.get(12)?,
+            thread_id: r.get(13)?,
+            payload: r
+                .get::<_, Option<String>>(14)?
+                .and_then(|json| serde_json::from_str(&json).ok()),
+        })
+    }
+
+    /// The newest 200 messages; see [`Self::list_messages_page`] for older
+    /// history.
     pub fn list_messages(
         &self,
         to_agent: Option<&str>,
         status: Option<MessageStatus>,
     ) -> Result<Vec<MessageRecord>, HubError> {
-        let mut sql = String::from(
-            r#"
-            SELECT id, from_agent, to_agent, workspace_path, task_id, kind, status,
-                   subject, body, created_at, acked_at
-            FROM messages WHERE 1=1
-            "#,
-        );
+        Ok(self
+            .list_messages_page(to_agent, status, &PageRequest::latest(200))?
+            .items)
+    }
+
+    pub fn list_messages_page(
+        &self,
+        to_agent: Option<&str>,
+        status: Option<MessageStatus>,
+        page: &PageRequest,
+    ) -> Result<Page<MessageRecord>, HubError> {
+        let mut sql = format!("SELECT {MESSAGE_COLUMNS} FROM messages WHERE archived_at IS NULL");
         let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
         if let Some(to) = to_agent {
             sql.push_str(" AND to_agent = ?");
@@ -66,27 +95,15 @@ impl HubStore {
             sql.push_str(" AND status = ?");
             params_vec.push(Box::new(st.as_str().to_string()));
         }
-        sql.push_str(" ORDER BY created_at DESC LIMIT 200");
+        let keyset = push_keyset(&mut sql, &mut params_vec, page, "created_at", "id")?;
 
         let mut stmt = self.conn.prepare(&sql)?;
         let params_refs: Vec<&dyn rusqlite::types::ToSql> =
             params_vec.iter().map(|p| p.as_ref()).collect();
-        let rows = stmt.query_map(params_refs.as_slice(), |r| {
-            Ok(MessageRecord {
-                id: r.get(0)?,
-                from_agent: r.get(1)?,
-                to_agent: r.get(2)?,
-                workspace_path: r.get(3)?,
-                task_id: r.get(4)?,
-                kind: r.get(5)?,
-                status: r.get(6)?,
-                subject: r.get(7)?,
-                body: r.get(8)?,
-                created_at: r.get(9)?,
-                acked_at: r.get(10)?,
-            })
-        })?;
-        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        let rows = stmt
+            .query_map(params_refs.as_slice(), Self::row_to_message)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(finish_page(rows, keyset, |m| (&m.created_at, &m.id)))
     }
 
     /// Lists one Messager-like channel without exposing similarly named channels.
@@ -97,6 +114,15 @@ impl HubStore {
         channel: &str,
         limit: usize,
     ) -> Result<Vec<MessageRecord>, HubError> {
+        let page = PageRequest::latest(limit.clamp(1, 200));
+        Ok(self.list_channel_messages_page(channel, &page)?.items)
+    }
+
+    pub fn list_channel_messages_page(
+        &self,
+        channel: &str,
+        page: &PageRequest,
+    ) -> Result<Page<MessageRecord>, HubError> {
         let channel = channel
             .trim()
             .strip_prefix("channel:")
@@ -106,33 +132,21 @@ impl HubStore {
         }
         let subject = format!("channel:{channel}");
         let subject_prefix = format!("{subject}:%");
-        let limit = limit.clamp(1, 200) as i64;
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, from_agent, to_agent, workspace_path, task_id, kind, status,
-                   subject, body, created_at, acked_at
-            FROM messages
-            WHERE subject = ?1 OR subject LIKE ?2
-            ORDER BY created_at DESC
-            LIMIT ?3
-            "#,
-        )?;
-        let rows = stmt.query_map(params![subject, subject_prefix, limit], |r| {
-            Ok(MessageRecord {
-                id: r.get(0)?,
-                from_agent: r.get(1)?,
-                to_agent: r.get(2)?,
-                workspace_path: r.get(3)?,
-                task_id: r.get(4)?,
-                kind: r.get(5)?,
-                status: r.get(6)?,
-                subject: r.get(7)?,
-                body: r.get(8)?,
-                created_at: r.get(9)?,
-                acked_at: r.get(10)?,
-            })
-        })?;
-        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        let mut sql = format!(
+            "SELECT {MESSAGE_COLUMNS} FROM messages
+             WHERE (subject = ? OR subject LIKE ?) AND archived_at IS NULL"
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> =
+            vec![Box::new(subject), Box::new(subject_prefix)];
+        let keyset = push_keyset(&mut sql, &mut params_vec, page, "created_at", "id")?;
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt
+            .query_map(params_refs.as_slice(), Self::row_to_message)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(finish_page(rows, keyset, |m| (&m.created_at, &m.id)))
     }
 
     /// Resolves the unique shared memories referenced by one message body.
@@ -151,8 +165,8 @@ impl HubStore {
             }
             let mut stmt = self.conn.prepare(
                 r#"
-                SELECT id, scope, workspace_path, tier, agent_id, title, body,
-                       tags_json, created_at, updated_at, stale, source_event_id
+                SELECT id, scope, workspace_path, tier, agent_id, title, hub_plain(body),
+                       tags_json, created_at, updated_at, stale, source_event_id, session_id
                 FROM memories WHERE id LIKE ?1 ORDER BY id ASC LIMIT 2
                 "#,
             )?;
@@ -171,6 +185,8 @@ impl HubStore {
                         updated_at: r.get(9)?,
                         stale: r.get::<_, i64>(10)? != 0,
                         source_event_id: r.get(11)?,
+                        session_id: r.get(12)?,
+                        relevance: None,
                     })
                 })?
                 .collect::<Result<Vec<_>, _>>()?;
@@ -222,4 +238,67 @@ impl HubStore {
             Ok(pending)
         }
     }
+
+    /// Every outcome recorded for `subject`, oldest first.
+    pub fn list_tagged_send_outcomes(&self, subject: &str) -> Result<Vec<SendOutcome>, HubError> {
+        let mut page = PageRequest::latest(MAX_PAGE_LIMIT);
+        let mut outcomes = Vec::new();
+        loop {
+            let next = self.list_tagged_send_outcomes_page(Some(subject), &page)?;
+            outcomes.extend(next.items);
+            if !next.has_more {
+                break;
+            }
+            page.before = next.before;
+        }
+        outcomes.reverse();
+        Ok(outcomes)
+    }
+
+    /// Tagged-send outcomes, newest first, for one `subject` or all.
+    pub fn list_tagged_send_outcomes_page(
+        &self,
+        subject: Option<&str>,
+        page: &PageRequest,
+    ) -> Result<Page<SendOutcome>, HubError> {
+        let mut sql = String::from(
+            r#"
+            SELECT id, subject, from_agent, to_agent, is_task, is_wake,
+                   accepted, enrolled, wake_requested, reason,
+                   COALESCE(policy_decision, ''), message_id, created_at
+            FROM tagged_send_outcomes
+            WHERE 1=1
+            "#,
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        if let Some(subject) = subject {
+            sql.push_str(" AND subject = ?");
+            params_vec.push(Box::new(subject.to_string()));
+        }
+        let keyset = push_keyset(&mut sql, &mut params_vec, page, "created_at", "id")?;
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok(SendOutcome {
+                    id: row.get(0)?,
+                    subject: row.get(1)?,
+                    from_agent: row.get(2)?,
+                    to_agent: row.get(3)?,
+                    is_task: row.get::<_, i64>(4)? != 0,
+                    is_wake: row.get::<_, i64>(5)? != 0,
+                    accepted: row.get::<_, i64>(6)? != 0,
+                    enrolled: row.get::<_, i64>(7)? != 0,
+                    wake_requested: row.get::<_, i64>(8)? != 0,
+                    reason: row.get(9)?,
+                    policy_decision: row.get(10)?,
+                    message_id: row.get(11)?,
+                    created_at: row.get(12)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(finish_page(rows, keyset, |o| (&o.created_at, &o.id)))
+    }
 }
@@ -0,0 +1,338 @@
+//! Questions: the hub-wide counterpart of the desktop orchestrator's
+//! `[[ASK_USER]]`. Any agent can ask another (usually the human) a
+//! `question`-kind message with optional fixed choices and a deadline
+//! ([`HubStore::ask_question`]). The answer is sent back as a reply
+//! threaded under the question and, when the asker is a harness agent and
+//! the question names a workspace, injected into that harness through the
+//! usual task inject path ([`crate::inject_harness_with_store`]), so a
+//! live session sees it without polling.
+//!
+//! A question is open until answered or until its deadline passes; late
+//! answers are rejected.
+
+use super::*;
+use crate::{HarnessInjectRequest, HarnessInjectResult};
+use chrono::DateTime;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionState {
+    Open,
+    Answered,
+    /// The deadline passed without an answer.
+    Expired,
+}
+
+/// A `question` message with its choices, deadline and answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionRecord {
+    pub question: MessageRecord,
+    /// Answers accepted; any answer when empty.
+    pub choices: Vec<String>,
+    pub deadline: Option<String>,
+    pub state: QuestionState,
+    /// The reply carrying the answer, once answered.
+    pub answer: Option<MessageRecord>,
+}
+
+/// Result of [`HubStore::answer_question`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionAnswer {
+    pub question: QuestionRecord,
+    /// Outcome of injecting the answer into the asker's harness; `None`
+    /// when the asker is the human or the question names no absolute
+    /// workspace (the answer then waits in its inbox).
+    pub delivery: Option<HarnessInjectResult>,
+    /// Why the injection failed, including an asker that is not a known
+    /// harness. The answer is recorded either way.
+    pub delivery_error: Option<String>,
+}
+
+fn is_past(deadline: &str) -> bool {
+    DateTime::parse_from_rfc3339(deadline).is_ok_and(|at| at.with_timezone(&Utc) <= Utc::now())
+}
+
+#[allow(clippy::too_many_arguments)]
+impl HubStore {
+    pub(crate) fn ensure_question_table(&self) -> Result<(), HubError> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS message_questions (
+                message_id TEXT PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+                choices_json TEXT NOT NULL DEFAULT '[]',
+                deadline TEXT,
+                answer_id TEXT REFERENCES messages(id) ON DELETE SET NULL,
+                answered_by TEXT
+            );",
+        )?;
+        Ok(())
+    }
+
+    /// Send `body` to `to_agent` as a question. `choices`, when given,
+    /// are the only answers accepted; `deadline` (RFC 3339) must lie in
+    /// the future.
+    pub fn ask_question(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        body: &str,
+        choices: &[String],
+        deadline: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+    ) -> Result<QuestionRecord, HubError> {
+        let mut seen = Vec::new();
+        for choice in choices {
+            let choice = choice.trim();
+            if choice.is_empty() {
+                return Err(HubError::Invalid(
+                    "question choices must not be empty".into(),
+                ));
+            }
+            if seen.iter().any(|c: &String| c.eq_ignore_ascii_case(choice)) {
+                return Err(HubError::Invalid(format!(
+                    "duplicate question choice: {choice}"
+                )));
+            }
+            seen.push(choice.to_string());
+        }
+        let deadline = deadline
+            .map(|raw| {
+                let at = DateTime::parse_from_rfc3339(raw)
+                    .map_err(|e| HubError::Invalid(format!("deadline {raw}: {e}")))?
+                    .with_timezone(&Utc);
+                if at <= Utc::now() {
+                    return Err(HubError::Invalid(format!(
+                        "deadline {raw} has already passed"
+                    )));
+                }
+                Ok(at.to_rfc3339())
+            })
+            .transpose()?;
+        let choices_json = serde_json::to_string(&seen)
+            .map_err(|e| HubError::Invalid(format!("question choices: {e}")))?;
+        // One transaction, so no one ever sees the question without its
+        // choices and deadline.
+        let tx = self.conn.unchecked_transaction()?;
+        let message = self.send_message(
+            from_agent,
+            to_agent,
+            MessageKind::Question,
+            body,
+            None,
+            workspace_path,
+            task_id,
+        )?;
+        tx.execute(
+            "INSERT INTO message_questions(message_id, choices_json, deadline) VALUES (?1, ?2, ?3)",
+            params![message.id, choices_json, deadline],
+        )?;
+        tx.commit()?;
+        self.get_question(&message.id)
+    }
+
+    fn question_record(&self, question: MessageRecord) -> Result<QuestionRecord, HubError> {
+        let row: Option<(String, Option<String>, Option<String>)> = self
+            .conn
+            .query_row(
+                "SELECT choices_json, deadline, answer_id FROM message_questions
+                 WHERE message_id = ?1",
+                params![question.id],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .optional()?;
+        let (choices_json, deadline, answer_id) = row.unwrap_or(("[]".into(), None, None));
+        let answer = match answer_id {
+            Some(id) => self.get_message(&id)?,
+            None => None,
+        };
+        let state = if answer.is_some() {
+            QuestionState::Answered
+        } else if deadline.as_deref().is_some_and(is_past) {
+            QuestionState::Expired
+        } else {
+            QuestionState::Open
+        };
+        Ok(QuestionRecord {
+            question,
+            choices: serde_json::from_str(&choices_json).unwrap_or_default(),
+            deadline,
+            state,
+            answer,
+        })
+    }
+
+    pub fn get_question(&self, id: &str) -> Result<QuestionRecord, HubError> {
+        let question = self
+            .get_message(id)?
+            .filter(|m| m.kind == MessageKind::Question.as_str())
+            .ok_or_else(|| HubError::NotFound(format!("question {id}")))?;
+        self.question_record(question)
+    }
+
+    /// Questions addressed to `to_agent` (every recipient when `None`),
+    /// oldest first; only open ones when `open_only`.
+    pub fn list_questions(
+        &self,
+        to_agent: Option<&str>,
+        open_only: bool,
+    ) -> Result<Vec<QuestionRecord>, HubError> {
+        let questions: Vec<MessageRecord> = {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {MESSAGE_COLUMNS} FROM messages
+                 WHERE kind = ?1 AND status != 'cancelled' AND archived_at IS NULL
+                   AND (?2 IS NULL OR to_agent = ?2)
+                 ORDER BY created_at ASC"
+            ))?;
+            let rows = stmt.query_map(
+                params![MessageKind::Question.as_str(), to_agent],
+                Self::row_to_message,
+            )?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let mut records = Vec::with_capacity(questions.len());
+        for question in questions {
+            let record = self.question_record(question)?;
+            if !open_only || record.state == QuestionState::Open {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Answer an open question as `from_agent`, who must be its recipient
+    /// or the human. With choices, `answer` must name one of them (case
+    /// aside). Checking the question is open and claiming it for this
+    /// answer happen in one transaction, so only one answer ever lands;
+    /// the answer is then sent to the asker threaded under the question
+    /// (send hooks run after the claim commits, not under the lock), the
+    /// question marked done, and the answer injected into the asker's
+    /// harness. A failed send releases the claim.
+    pub fn answer_question(
+        &self,
+        id: &str,
+        from_agent: &str,
+        answer: &str,
+    ) -> Result<QuestionAnswer, HubError> {
+        let tx = rusqlite::Transaction::new_unchecked(
+            &self.conn,
+            rusqlite::TransactionBehavior::Immediate,
+        )?;
+        let record = self.get_question(id)?;
+        let question = &record.question;
+        if from_agent != question.to_agent && from_agent != "human" {
+            return Err(HubError::Invalid(format!(
+                "question {id} was asked of {}, not {from_agent}",
+                question.to_agent
+            )));
+        }
+        match record.state {
+            QuestionState::Open => {}
+            QuestionState::Answered => {
+                return Err(HubError::Invalid(format!(
+                    "question {id} is already answered"
+                )))
+            }
+            QuestionState::Expired => {
+                return Err(HubError::Invalid(format!(
+                    "question {id} expired at {}",
+                    record.deadline.as_deref().unwrap_or_default()
+                )))
+            }
+        }
+        let answer = answer.trim();
+        let answer = if record.choices.is_empty() {
+            answer.to_string()
+        } else {
+            record
+                .choices
+                .iter()
+                .find(|c| c.eq_ignore_ascii_case(answer))
+                .cloned()
+                .ok_or_else(|| {
+                    HubError::Invalid(format!(
+                        "answer must be one of: {}",
+                        record.choices.join(", ")
+                    ))
+                })?
+        };
+        let claimed = tx.execute(
+            "INSERT INTO message_questions(message_id, answered_by) VALUES (?1, ?2)
+             ON CONFLICT(message_id) DO UPDATE SET answered_by = excluded.answered_by
+             WHERE message_questions.answer_id IS NULL
+               AND message_questions.answered_by IS NULL",
+            params![question.id, from_agent],
+        )?;
+        if claimed == 0 {
+            return Err(HubError::Invalid(format!(
+                "question {id} is already being answered"
+            )));
+        }
+        tx.commit()?;
+
+        let sent = self.send_reply(
+            &question.id,
+            from_agent,
+            &question.from_agent,
+            MessageKind::Message,
+            &answer,
+            None,
+            None,
+            question.workspace_path.as_deref(),
+            question.task_id.as_deref(),
+        );
+        let reply = match sent {
+            Ok(reply) => reply,
+            Err(error) => {
+                self.conn.execute(
+                    "UPDATE message_questions SET answered_by = NULL
+                     WHERE message_id = ?1 AND answer_id IS NULL",
+                    params![question.id],
+                )?;
+                return Err(error);
+            }
+        };
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE message_questions SET answer_id = ?2 WHERE message_id = ?1",
+            params![question.id, reply.id],
+        )?;
+        self.set_message_status(&question.id, MessageStatus::Done)?;
+        tx.commit()?;
+
+        // Delivered like a dispatched task: the asker's id names its
+        // harness, and an asker that is not one is a delivery error.
+        let workspace = question
+            .workspace_path
+            .as_deref()
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute() && question.from_agent != "human");
+        let (mut delivery, mut delivery_error) = (None, None);
+        if let Some(workspace) = workspace {
+            let result = crate::inject_harness_with_store(
+                self,
+                &HarnessInjectRequest {
+                    harness: question.from_agent.clone(),
+                    workspace,
+                    session_id: None,
+                    message_id: Some(reply.id.clone()),
+                    body: format!(
+                        "{from_agent} answered your question \"{}\": {answer}",
+                        question.body
+                    ),
+                    is_task: true,
+                    is_wake: false,
+                },
+            );
+            match result {
+                Ok(result) => delivery = Some(result),
+                Err(error) => delivery_error = Some(error.to_string()),
+            }
+        }
+        Ok(QuestionAnswer {
+            question: self.get_question(id)?,
+            delivery,
+            delivery_error,
+        })
+    }
+}
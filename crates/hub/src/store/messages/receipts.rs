@@ -0,0 +1,176 @@
+//! Delivery receipts. Each recipient copy of a message moves through
+//! `queued` → `injected` → `read` → `acked`: injected once a harness
+//! injection was attempted for it ([`HubStore::record_injection`], with the
+//! [`HarnessInjectResult`] status), read once the recipient's read marker
+//! for the conversation covers it, acked once the recipient acked it (or
+//! marked it done). A receipt reports the furthest state reached; a copy
+//! can be read without ever being injected.
+
+use super::*;
+use crate::HarnessInjectResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryState {
+    Queued,
+    Injected,
+    Read,
+    Acked,
+}
+
+impl DeliveryState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Injected => "injected",
+            Self::Read => "read",
+            Self::Acked => "acked",
+        }
+    }
+}
+
+/// Where one recipient copy stands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryReceipt {
+    pub message_id: String,
+    pub to_agent: String,
+    pub state: DeliveryState,
+    /// The copy's message status (`pending`, `acked`, `done`, `cancelled`).
+    pub status: String,
+    /// [`HarnessInjectResult::status`] of the latest injection, or `error`
+    /// when the injection itself failed.
+    pub inject_status: Option<String>,
+    pub inject_detail: Option<String>,
+    pub injected_at: Option<String>,
+    /// The recipient's read marker for the conversation, once it covers
+    /// the copy.
+    pub read_at: Option<String>,
+    pub acked_at: Option<String>,
+}
+
+/// Receipts for every copy of one send, with how many copies are in each
+/// state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptSummary {
+    pub message_id: String,
+    pub from_agent: String,
+    pub subject: Option<String>,
+    pub recipients: usize,
+    pub queued: usize,
+    pub injected: usize,
+    pub read: usize,
+    pub acked: usize,
+    pub receipts: Vec<DeliveryReceipt>,
+}
+
+impl HubStore {
+    pub(crate) fn ensure_delivery_table(&self) -> Result<(), HubError> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS message_deliveries (
+                message_id TEXT PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+                inject_status TEXT NOT NULL,
+                inject_detail TEXT,
+                injected_at TEXT NOT NULL
+            );",
+        )?;
+        Ok(())
+    }
+
+    /// Record a harness injection of `message_id`; a later injection
+    /// replaces an earlier one. Ids that name no message are ignored.
+    pub fn record_injection(
+        &self,
+        message_id: &str,
+        result: &Result<HarnessInjectResult, HubError>,
+    ) -> Result<(), HubError> {
+        let (status, detail) = match result {
+            Ok(result) => (result.status.clone(), result.detail.clone()),
+            Err(error) => ("error".to_string(), error.to_string()),
+        };
+        self.conn.execute(
+            "INSERT INTO message_deliveries(message_id, inject_status, inject_detail, injected_at)
+             SELECT ?1, ?2, ?3, ?4 WHERE EXISTS (SELECT 1 FROM messages WHERE id = ?1)
+             ON CONFLICT(message_id) DO UPDATE SET
+                 inject_status = excluded.inject_status,
+                 inject_detail = excluded.inject_detail,
+                 injected_at = excluded.injected_at",
+            params![message_id, status, detail, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn delivery_receipt(&self, message: MessageRecord) -> Result<DeliveryReceipt, HubError> {
+        let injection: Option<(String, Option<String>, String)> = self
+            .conn
+            .query_row(
+                "SELECT inject_status, inject_detail, injected_at
+                 FROM message_deliveries WHERE message_id = ?1",
+                params![message.id],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .optional()?;
+        let scope = conversation_scope(message.subject.as_deref())
+            .unwrap_or_else(|| format!("channel:dm-{}", message.from_agent));
+        let read_at: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT last_read_at FROM read_markers
+                 WHERE agent_id = ?1 AND scope = ?2 AND last_read_at >= ?3",
+                params![message.to_agent, scope, message.created_at],
+                |r| r.get(0),
+            )
+            .optional()?;
+        let acked = message.acked_at.is_some() || message.status == "done";
+        let state = if acked {
+            DeliveryState::Acked
+        } else if read_at.is_some() {
+            DeliveryState::Read
+        } else if injection.is_some() {
+            DeliveryState::Injected
+        } else {
+            DeliveryState::Queued
+        };
+        let (inject_status, inject_detail, injected_at) = match injection {
+            Some((status, detail, at)) => (Some(status), detail, Some(at)),
+            None => (None, None, None),
+        };
+        Ok(DeliveryReceipt {
+            message_id: message.id,
+            to_agent: message.to_agent,
+            state,
+            status: message.status,
+            inject_status,
+            inject_detail,
+            injected_at,
+            read_at,
+            acked_at: message.acked_at,
+        })
+    }
+
+    /// Receipts for `id` and every other copy of the same broadcast, one
+    /// per recipient.
+    pub fn message_receipts(&self, id: &str) -> Result<ReceiptSummary, HubError> {
+        let anchor = self
+            .get_message(id)?
+            .ok_or_else(|| HubError::NotFound(id.into()))?;
+        let mut receipts = Vec::new();
+        for copy in self.broadcast_group_ids(id)? {
+            if let Some(message) = self.get_message(&copy)? {
+                receipts.push(self.delivery_receipt(message)?);
+            }
+        }
+        receipts.sort_by(|a, b| a.to_agent.cmp(&b.to_agent));
+        let count = |state| receipts.iter().filter(|r| r.state == state).count();
+        Ok(ReceiptSummary {
+            message_id: anchor.id,
+            from_agent: anchor.from_agent,
+            subject: anchor.subject,
+            recipients: receipts.len(),
+            queued: count(DeliveryState::Queued),
+            injected: count(DeliveryState::Injected),
+            read: count(DeliveryState::Read),
+            acked: count(DeliveryState::Acked),
+            receipts,
+        })
+    }
+}
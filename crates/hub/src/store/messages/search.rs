@@ -0,0 +1,213 @@
+//! Ranked message search. Bodies are indexed in the FTS5 table
+//! `messages_fts` (rowid = `messages.rowid`), ranked by BM25. The index
+//! holds plaintext terms, so it is only kept while encryption at rest is
+//! off: sealing a body drops its row (the update trigger), and an encrypted
+//! hub searches by scanning decrypted bodies instead, ranked by how often
+//! the terms occur. Rows are indexed lazily, before each search, so every
+//! write path (sends, edits, redaction, imports) is covered without hooks.
+//!
+//! Posts in a work session only match for that session's members, and the
+//! copies of one team or channel broadcast count as a single hit.
+
+use super::*;
+use rusqlite::params_from_iter;
+use std::collections::HashMap;
+
+/// Filters for [`HubStore::search_messages`]; `None` leaves a field
+/// unfiltered.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageSearch {
+    /// Words that must all appear in the body, as prefixes.
+    pub query: String,
+    pub from_agent: Option<String>,
+    pub to_agent: Option<String>,
+    /// A channel (`general`, `#general`, `channel:general`) or a work
+    /// session (`session:<id>`).
+    pub scope: Option<String>,
+    pub kind: Option<MessageKind>,
+    pub task_id: Option<String>,
+    pub workspace_path: Option<String>,
+    /// RFC 3339 bounds on `created_at`, inclusive.
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageSearchHit {
+    pub message: MessageRecord,
+    pub snippet: String,
+    /// Higher is better; only comparable within one result list.
+    pub score: f64,
+}
+
+/// Filters shared by both search paths. `?1` is the searcher; `?2`..`?9`
+/// the [`MessageSearch`] fields in declaration order after `query`.
+const SEARCH_FILTERS: &str = "m.archived_at IS NULL
+    AND (?2 IS NULL OR m.from_agent = ?2)
+    AND (?3 IS NULL OR m.to_agent = ?3)
+    AND (?4 IS NULL OR m.subject = ?4 OR m.subject LIKE ?4 || ':%')
+    AND (?5 IS NULL OR m.kind = ?5)
+    AND (?6 IS NULL OR m.task_id = ?6)
+    AND (?7 IS NULL OR m.workspace_path = ?7)
+    AND (?8 IS NULL OR m.created_at >= ?8)
+    AND (?9 IS NULL OR m.created_at <= ?9)
+    AND (m.subject IS NULL OR m.subject NOT LIKE 'channel:session:%' OR EXISTS (
+        SELECT 1 FROM work_session_members w
+        WHERE w.agent_id = ?1
+          AND (m.subject = 'channel:session:' || w.session_id
+               OR m.subject LIKE 'channel:session:' || w.session_id || ':%')))";
+
+/// Search words: runs of letters, digits and `_`.
+fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Keep one hit per broadcast group (same sender, subject and body), at the
+/// best-ranked copy's position, preferring the copy addressed to
+/// `searcher`; then cut to `limit`.
+fn collapse_broadcasts(
+    hits: Vec<(MessageRecord, f64)>,
+    searcher: &str,
+    limit: usize,
+) -> Vec<(MessageRecord, f64)> {
+    let mut kept: Vec<(MessageRecord, f64)> = Vec::new();
+    let mut groups: HashMap<(String, String, String), usize> = HashMap::new();
+    for (message, score) in hits {
+        let Some(subject) = message.subject.clone() else {
+            kept.push((message, score));
+            continue;
+        };
+        let key = (message.from_agent.clone(), subject, message.body.clone());
+        match groups.get(&key) {
+            Some(&at) => {
+                if message.to_agent == searcher && kept[at].0.to_agent != searcher {
+                    kept[at].0 = message;
+                }
+            }
+            None => {
+                groups.insert(key, kept.len());
+                kept.push((message, score));
+            }
+        }
+    }
+    kept.truncate(limit);
+    kept
+}
+
+/// The subject prefix a [`MessageSearch::scope`] names.
+fn scope_subject(scope: &str) -> String {
+    let scope = scope.trim().trim_start_matches('#');
+    format!(
+        "channel:{}",
+        scope.strip_prefix("channel:").unwrap_or(scope)
+    )
+}
+
+impl HubStore {
+    pub(crate) fn ensure_message_index(&self) -> Result<(), HubError> {
+        self.conn.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(body);
+             CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+                 DELETE FROM messages_fts WHERE rowid = old.rowid;
+             END;
+             CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF body ON messages BEGIN
+                 DELETE FROM messages_fts WHERE rowid = old.rowid;
+             END;",
+        )?;
+        Ok(())
+    }
+
+    /// Index bodies not indexed yet. Only called with encryption off, when
+    /// every stored body is plaintext.
+    fn refresh_message_index(&self) -> Result<(), HubError> {
+        self.conn.execute(
+            "INSERT INTO messages_fts(rowid, body)
+             SELECT m.rowid, hub_plain(m.body) FROM messages m
+             WHERE NOT EXISTS (SELECT 1 FROM messages_fts f WHERE f.rowid = m.rowid)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Unarchived messages whose body contains every word of
+    /// `search.query`, best match first, as seen by `searcher`.
+    pub fn search_messages(
+        &self,
+        searcher: &str,
+        search: &MessageSearch,
+    ) -> Result<Vec<MessageSearchHit>, HubError> {
+        let terms = search_terms(&search.query);
+        if terms.is_empty() {
+            return Err(HubError::Invalid("search query must not be empty".into()));
+        }
+        let scope = search.scope.as_deref().map(scope_subject);
+        let kind = search.kind.map(|k| k.as_str());
+        let mut values: Vec<&dyn rusqlite::ToSql> = vec![
+            &searcher,
+            &search.from_agent,
+            &search.to_agent,
+            &scope,
+            &kind,
+            &search.task_id,
+            &search.workspace_path,
+            &search.since,
+            &search.until,
+        ];
+        let limit = search.limit.clamp(1, 200);
+        let columns = message_columns("m");
+
+        let hits: Vec<(MessageRecord, f64)> = if self.encryption_enabled() {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {columns} FROM messages m WHERE {SEARCH_FILTERS}
+                 ORDER BY m.created_at DESC"
+            ))?;
+            let rows = stmt.query_map(params_from_iter(values), Self::row_to_message)?;
+            let mut hits = Vec::new();
+            for message in rows {
+                let message = message?;
+                let body = message.body.to_lowercase();
+                let counts: Vec<usize> = terms.iter().map(|t| body.matches(t).count()).collect();
+                if counts.iter().all(|&n| n > 0) {
+                    hits.push((message, counts.iter().sum::<usize>() as f64));
+                }
+            }
+            hits.sort_by(|a, b| b.1.total_cmp(&a.1));
+            hits
+        } else {
+            self.refresh_message_index()?;
+            let query = terms
+                .iter()
+                .map(|t| format!("\"{t}\"*"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {columns}, -bm25(messages_fts)
+                 FROM messages_fts JOIN messages m ON m.rowid = messages_fts.rowid
+                 WHERE messages_fts MATCH ?10 AND {SEARCH_FILTERS}
+                 ORDER BY bm25(messages_fts), m.created_at DESC"
+            ))?;
+            // No SQL limit: broadcast copies are collapsed below, and a
+            // limit here would count every copy.
+            values.push(&query);
+            let rows = stmt.query_map(params_from_iter(values), |r| {
+                Ok((Self::row_to_message(r)?, r.get(15)?))
+            })?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        Ok(collapse_broadcasts(hits, searcher, limit)
+            .into_iter()
+            .map(|(message, score)| MessageSearchHit {
+                snippet: snippet(&message.body, &terms[0]),
+                message,
+                score,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,189 @@
+//! Threaded replies. A reply records the message it answers (`reply_to`)
+//! and its thread's root (`thread_id`), so threads stay one level deep
+//! however replies nest. Copies of one team/channel broadcast share a
+//! thread: the root is always the broadcast group's lowest message id,
+//! whichever copy was answered.
+
+use super::*;
+
+/// One thread at a glance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadSummary {
+    pub root: MessageRecord,
+    pub reply_count: usize,
+    /// Root author first, then each replier in order of first reply.
+    pub participants: Vec<String>,
+    pub last_reply_at: String,
+}
+
+impl HubStore {
+    /// Add the thread columns to databases created before threading.
+    pub(crate) fn ensure_thread_columns(&self) -> Result<(), HubError> {
+        for column in ["reply_to", "thread_id"] {
+            let _ = self.conn.execute(
+                &format!("ALTER TABLE messages ADD COLUMN {column} TEXT"),
+                [],
+            );
+        }
+        self.conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_messages_thread ON messages(thread_id, created_at)",
+        )?;
+        Ok(())
+    }
+
+    /// Root of the thread `id` belongs to (or would start).
+    pub fn thread_root(&self, id: &str) -> Result<String, HubError> {
+        let message = self
+            .get_message(id)?
+            .ok_or_else(|| HubError::NotFound(id.into()))?;
+        let root = message.thread_id.unwrap_or(message.id);
+        Ok(self
+            .broadcast_group_ids(&root)?
+            .into_iter()
+            .min()
+            .unwrap_or(root))
+    }
+
+    /// Mark `reply_id` as a reply to `parent_id`.
+    pub fn link_reply(&self, reply_id: &str, parent_id: &str) -> Result<MessageRecord, HubError> {
+        let root = self.thread_root(parent_id)?;
+        if root == reply_id || parent_id == reply_id {
+            return Err(HubError::Invalid(format!(
+                "message {reply_id} cannot reply to its own thread"
+            )));
+        }
+        let n = self.conn.execute(
+            "UPDATE messages SET reply_to = ?1, thread_id = ?2 WHERE id = ?3",
+            params![parent_id, root, reply_id],
+        )?;
+        if n == 0 {
+            return Err(HubError::NotFound(reply_id.into()));
+        }
+        self.get_message(reply_id)?
+            .ok_or_else(|| HubError::NotFound(reply_id.into()))
+    }
+
+    /// Send `body` as a reply to `parent_id`. The thread columns are set
+    /// by the insert, so nothing ever sees the reply unthreaded. The reply
+    /// is checked against the sender's `output_format`
+    /// ([`Self::check_reply_payload`]).
+    #[allow(clippy::too_many_arguments)]
+    pub fn send_reply(
+        &self,
+        parent_id: &str,
+        from_agent: &str,
+        to_agent: &str,
+        kind: MessageKind,
+        body: &str,
+        payload: Option<&serde_json::Value>,
+        subject: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+    ) -> Result<MessageRecord, HubError> {
+        self.insert_message(
+            from_agent,
+            to_agent,
+            kind,
+            body,
+            payload,
+            subject,
+            workspace_path,
+            task_id,
+            Some(parent_id),
+        )
+    }
+
+    /// [`Self::send_message_to_team`] with every copy threaded under
+    /// `parent_id`. Like [`Self::send_reply`], the reply must satisfy the
+    /// sender's `output_format`; `payload` goes on every copy and is
+    /// checked against each recipient's `input_schema`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn send_reply_to_team(
+        &self,
+        parent_id: &str,
+        from_agent: &str,
+        kind: MessageKind,
+        body: &str,
+        payload: Option<&serde_json::Value>,
+        subject: Option<&str>,
+        workspace_path: Option<&str>,
+        task_id: Option<&str>,
+    ) -> Result<Vec<MessageRecord>, HubError> {
+        self.fan_out_to_team(
+            from_agent,
+            kind,
+            body,
+            payload,
+            subject,
+            workspace_path,
+            task_id,
+            Some(parent_id),
+        )
+    }
+
+    /// The thread containing `id`: its root, then unarchived replies
+    /// oldest first.
+    pub fn list_thread(&self, id: &str) -> Result<Vec<MessageRecord>, HubError> {
+        let root_id = self.thread_root(id)?;
+        let root = self
+            .get_message(&root_id)?
+            .ok_or_else(|| HubError::NotFound(root_id.clone()))?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {MESSAGE_COLUMNS} FROM messages
+             WHERE thread_id = ?1 AND archived_at IS NULL
+             ORDER BY created_at ASC, id ASC"
+        ))?;
+        let replies = stmt.query_map(params![root_id], Self::row_to_message)?;
+        let mut thread = vec![root];
+        for reply in replies {
+            thread.push(reply?);
+        }
+        Ok(thread)
+    }
+
+    /// Threads with at least one reply, most recently active first. With
+    /// `channel`, only threads rooted in that channel.
+    pub fn list_threads(
+        &self,
+        channel: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ThreadSummary>, HubError> {
+        let subject = channel.map(|c| format!("channel:{}", c.trim().trim_start_matches('#')));
+        let roots: Vec<String> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT r.thread_id FROM messages r
+                 JOIN messages root ON root.id = r.thread_id
+                 WHERE r.archived_at IS NULL AND root.archived_at IS NULL
+                   AND (?1 IS NULL OR root.subject = ?1 OR root.subject LIKE ?1 || ':%')
+                 GROUP BY r.thread_id
+                 ORDER BY MAX(r.created_at) DESC
+                 LIMIT ?2",
+            )?;
+            let rows =
+                stmt.query_map(params![subject, limit.clamp(1, 200) as i64], |r| r.get(0))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        roots.iter().map(|root| self.thread_summary(root)).collect()
+    }
+
+    fn thread_summary(&self, root: &str) -> Result<ThreadSummary, HubError> {
+        let mut thread = self.list_thread(root)?.into_iter();
+        let root = thread.next().expect("list_thread starts with the root");
+        let mut participants = vec![root.from_agent.clone()];
+        let mut reply_count = 0;
+        let mut last_reply_at = root.created_at.clone();
+        for reply in thread {
+            reply_count += 1;
+            if !participants.contains(&reply.from_agent) {
+                participants.push(reply.from_agent);
+            }
+            last_reply_at = reply.created_at;
+        }
+        Ok(ThreadSummary {
+            root,
+            reply_count,
+            participants,
+            last_reply_at,
+        })
+    }
+}
@@ -0,0 +1,118 @@
+//! Unread counts per conversation, from read markers. A post is unread
+//! for an agent when its copy addressed to that agent was created after
+//! the agent's marker for the post's scope (or there is no marker yet).
+//! Cancelled, archived and self-sent messages never count.
+
+use super::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// Unread posts in one conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnreadScope {
+    /// Read-marker scope: `channel:<name>`, `channel:session:<id>`, or
+    /// `channel:dm-<peer>` for direct messages.
+    pub scope: String,
+    /// `channel`, `session` or `dm`.
+    pub kind: String,
+    pub unread: usize,
+    /// Oldest unread message, or oldest unread mention when every unread
+    /// mention is in a post addressed to someone else.
+    pub first_unread_id: Option<String>,
+    pub mentions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnreadSummary {
+    pub agent_id: String,
+    /// Scopes with anything unread, most unread first.
+    pub scopes: Vec<UnreadScope>,
+    pub unread: usize,
+    pub mentions: usize,
+}
+
+impl UnreadSummary {
+    /// Unread totals per kind, e.g. for a `channel` tab badge.
+    pub fn unread_of_kind(&self, kind: &str) -> usize {
+        self.scopes
+            .iter()
+            .filter(|s| s.kind == kind)
+            .map(|s| s.unread)
+            .sum()
+    }
+}
+
+fn scope_kind(scope: &str) -> &'static str {
+    match scope.strip_prefix("channel:") {
+        Some(rest) if rest.starts_with("session:") => "session",
+        Some(rest) if rest.starts_with("dm-") => "dm",
+        _ => "channel",
+    }
+}
+
+fn unread_entry(scopes: &mut BTreeMap<String, UnreadScope>, scope: String) -> &mut UnreadScope {
+    scopes.entry(scope.clone()).or_insert_with(|| UnreadScope {
+        kind: scope_kind(&scope).to_string(),
+        scope,
+        unread: 0,
+        first_unread_id: None,
+        mentions: 0,
+    })
+}
+
+impl HubStore {
+    /// What `agent` has not read yet across channels, work sessions and
+    /// direct messages, with unread @mention counts.
+    pub fn unread_summary(&self, agent: &str) -> Result<UnreadSummary, HubError> {
+        let markers: HashMap<String, String> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT scope, last_read_at FROM read_markers WHERE agent_id = ?1")?;
+            let rows = stmt.query_map(params![agent], |r| Ok((r.get(0)?, r.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let mut scopes: BTreeMap<String, UnreadScope> = BTreeMap::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT id, from_agent, subject, created_at FROM messages
+             WHERE to_agent = ?1 AND from_agent != ?1
+               AND archived_at IS NULL AND status != 'cancelled'
+             ORDER BY created_at ASC, id ASC",
+        )?;
+        let rows = stmt.query_map(params![agent], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, Option<String>>(2)?,
+                r.get::<_, String>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, from_agent, subject, created_at) = row?;
+            let scope = conversation_scope(subject.as_deref())
+                .unwrap_or_else(|| format!("channel:dm-{from_agent}"));
+            if markers
+                .get(&scope)
+                .is_some_and(|read_at| created_at <= *read_at)
+            {
+                continue;
+            }
+            let scope = unread_entry(&mut scopes, scope);
+            scope.unread += 1;
+            scope.first_unread_id.get_or_insert(id);
+        }
+        // Oldest first, so a mention-only scope points at its first mention.
+        for mention in self.list_mentions(agent, true, 500)?.into_iter().rev() {
+            let scope = unread_entry(&mut scopes, mention.scope);
+            scope.mentions += 1;
+            scope.first_unread_id.get_or_insert(mention.message_id);
+        }
+
+        let mut scopes: Vec<UnreadScope> = scopes.into_values().collect();
+        scopes.sort_by(|a, b| b.unread.cmp(&a.unread).then_with(|| a.scope.cmp(&b.scope)));
+        Ok(UnreadSummary {
+            agent_id: agent.to_string(),
+            unread: scopes.iter().map(|s| s.unread).sum(),
+            mentions: scopes.iter().map(|s| s.mentions).sum(),
+            scopes,
+        })
+    }
+}
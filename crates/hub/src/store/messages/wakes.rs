@@ -129,14 +129,60 @@ impl HubStore {
                 let _ = fs::remove_file(&path);
             }
         }
+        if status != WakeStatus::Pending {
+            if let Some(wake) = self.get_wake(id)? {
+                let workspace = match &wake.message_id {
+                    Some(message_id) => self
+                        .get_message(message_id)?
+                        .and_then(|message| message.workspace_path),
+                    None => None,
+                };
+                self.run_hooks(HookEvent::WakeResolved, workspace.as_deref(), &wake)?;
+            }
+        }
         Ok(())
     }
 
+    pub fn get_wake(&self, id: &str) -> Result<Option<WakeRecord>, HubError> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT id, target_agent, message_id, reason, status, requires_human_gate,
+                        created_at
+                 FROM wake_requests WHERE id = ?1",
+                params![id],
+                |r| {
+                    Ok(WakeRecord {
+                        id: r.get(0)?,
+                        target_agent: r.get(1)?,
+                        message_id: r.get(2)?,
+                        reason: r.get(3)?,
+                        status: r.get(4)?,
+                        requires_human_gate: r.get::<_, i64>(5)? != 0,
+                        created_at: r.get(6)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// The newest 100 wakes; see [`Self::list_wakes_page`] for older ones.
     pub fn list_wakes(
         &self,
         target_agent: Option<&str>,
         pending_only: bool,
     ) -> Result<Vec<WakeRecord>, HubError> {
+        Ok(self
+            .list_wakes_page(target_agent, pending_only, &PageRequest::latest(100))?
+            .items)
+    }
+
+    pub fn list_wakes_page(
+        &self,
+        target_agent: Option<&str>,
+        pending_only: bool,
+        page: &PageRequest,
+    ) -> Result<Page<WakeRecord>, HubError> {
         let mut sql = String::from(
             r#"
             SELECT id, target_agent, message_id, reason, status, requires_human_gate, created_at
@@ -151,42 +197,25 @@ impl HubStore {
         if pending_only {
             sql.push_str(" AND status = 'pending'");
         }
-        sql.push_str(" ORDER BY created_at DESC LIMIT 100");
+        let keyset = push_keyset(&mut sql, &mut params_vec, page, "created_at", "id")?;
 
         let mut stmt = self.conn.prepare(&sql)?;
         let params_refs: Vec<&dyn rusqlite::types::ToSql> =
             params_vec.iter().map(|p| p.as_ref()).collect();
-        let rows = stmt.query_map(params_refs.as_slice(), |r| {
-            Ok(WakeRecord {
-                id: r.get(0)?,
-                target_agent: r.get(1)?,
-                message_id: r.get(2)?,
-                reason: r.get(3)?,
-                status: r.get(4)?,
-                requires_human_gate: r.get::<_, i64>(5)? != 0,
-                created_at: r.get(6)?,
-            })
-        })?;
-        Ok(rows.collect::<Result<Vec<_>, _>>()?)
-    }
-
-    /// Append to a private journal file (never written into shared SQLite tables).
-    pub fn append_private_journal(&self, agent_id: &str, entry: &str) -> Result<PathBuf, HubError> {
-        if entry.trim().is_empty() {
-            return Err(HubError::Invalid("journal entry must not be empty".into()));
-        }
-        let dir = self.data_dir.join("journals").join(agent_id);
-        fs::create_dir_all(&dir)?;
-        let path = dir.join("journal.md");
-        let stamp = Utc::now().to_rfc3339();
-        let block = format!("\n## {stamp}\n\n{entry}\n");
-        use std::io::Write;
-        let mut f = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&path)?;
-        f.write_all(block.as_bytes())?;
-        Ok(path)
+        let rows = stmt
+            .query_map(params_refs.as_slice(), |r| {
+                Ok(WakeRecord {
+                    id: r.get(0)?,
+                    target_agent: r.get(1)?,
+                    message_id: r.get(2)?,
+                    reason: r.get(3)?,
+                    status: r.get(4)?,
+                    requires_human_gate: r.get::<_, i64>(5)? != 0,
+                    created_at: r.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(finish_page(rows, keyset, |w| (&w.created_at, &w.id)))
     }
 
     /// Permanently delete memories already marked stale (M5 retention).
@@ -240,7 +269,7 @@ impl HubStore {
             return Err(HubError::Invalid("message body must not be empty".into()));
         }
         let n = self.conn.execute(
-            "UPDATE messages SET body = ?1 WHERE id = ?2",
+            "UPDATE messages SET body = hub_seal(?1) WHERE id = ?2",
             params![body, id],
         )?;
         if n == 0 {
@@ -266,7 +295,7 @@ impl HubStore {
     /// fan-out, one row per recipient), otherwise the legacy grouping by
     /// `(from_agent, body, subject, created-at-to-the-second)` that the
     /// desktop chat also uses to collapse duplicate renders.
-    fn broadcast_group_ids(&self, message_id: &str) -> Result<Vec<String>, HubError> {
+    pub(super) fn broadcast_group_ids(&self, message_id: &str) -> Result<Vec<String>, HubError> {
         let anchor = self
             .get_message(message_id)?
             .ok_or_else(|| HubError::NotFound(message_id.into()))?;
@@ -291,7 +320,7 @@ impl HubStore {
         let mut stmt = self.conn.prepare(
             r#"
             SELECT id FROM messages
-            WHERE from_agent = ?1 AND body = ?2
+            WHERE from_agent = ?1 AND hub_plain(body) = ?2
               AND subject IS ?3
               AND substr(created_at, 1, 19) = ?4
             "#,
@@ -18,18 +18,65 @@ pub use types::*;
 
 mod agents;
 mod attachments;
+pub use attachments::{
+    AttachmentGcReport, AttachmentSearchHit, AttachmentUsage, ATTACHMENT_GC_GRACE_HOURS,
+    MAX_INDEXED_TEXT_BYTES,
+};
+pub(crate) use attachments::snippet;
+mod backend;
+pub use backend::{
+    HubBackend, InMemoryHub, MemoryBackend, MessageBackend, RoleBackend, TaskBackend,
+};
+mod backup;
+pub use backup::{
+    list_hub_backups, BackupFile, BackupManifest, BackupVerification, HubBackup, RestoreOutcome,
+    HUB_BACKUP_DIR, HUB_BACKUP_FORMAT_VERSION,
+};
+mod encryption;
+pub use encryption::{generate_hub_key, EncryptionChange, EncryptionStatus};
 mod exports;
+pub use exports::{
+    HubExportHeader, HubExportSummary, HubImportReport, ImportKindReport, RemappedId,
+    HUB_EXPORT_FORMAT, HUB_EXPORT_FORMAT_VERSION,
+};
+mod hooks;
+pub use hooks::{HookConfig, HookEvent, HookRun, HookScript, HUB_HOOKS_DIR, WORKSPACE_HOOKS_DIR};
 mod messages;
+pub use messages::{
+    conversation_scope, parse_mentions, ArchiveOutcome, DeliveryReceipt, DeliveryState,
+    EscalationConfig, EscalationRecord, EscalationStep, MentionConfig, MentionRecord,
+    MessageSearch, MessageSearchHit, QuestionAnswer, QuestionRecord, QuestionState,
+    ReceiptSummary, ThreadSummary, UnreadScope, UnreadSummary,
+};
+pub(crate) use messages::{message_columns, MESSAGE_COLUMNS};
 mod models;
+mod pagination;
+pub use pagination::*;
 pub use models::*;
 mod policies;
+mod redaction;
+pub use redaction::{
+    EntropyRule, Redaction, RedactionConfig, RedactionDetector, Redactor, SecretFinding,
+    SecretScanReport, BUILTIN_DETECTORS, ENTROPY_DETECTOR,
+};
+mod retention;
+pub use retention::{
+    RetentionExport, RetentionReport, WorkspaceRetention, RETENTION_ARCHIVE_DIR,
+    RETENTION_ARCHIVE_FORMAT,
+};
 mod roles;
 mod tasks;
 #[cfg(test)]
 mod tests;
+mod webhooks;
+pub use webhooks::{
+    webhook_signature, WebhookAttempt, WebhookDelivery, WebhookDeliveryStatus,
+    WEBHOOK_BACKOFF_SECS, WEBHOOK_MAX_ATTEMPTS, WEBHOOK_SIGNATURE_HEADER,
+};
 pub struct HubStore {
     conn: Connection,
     data_dir: PathBuf,
+    cipher: std::sync::Arc<std::sync::Mutex<encryption::Cipher>>,
 }
 
 fn sha256_hex(bytes: &[u8]) -> String {
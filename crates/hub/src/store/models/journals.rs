@@ -0,0 +1,106 @@
+//! Private journals, stored as `AgentPrivate` memories so they are
+//! searchable by their owner instead of loose `journals/<agent>/journal.md`
+//! files outside the store.
+
+use super::super::*;
+
+/// Tag every journal memory carries, so an agent can list just its journal.
+pub const JOURNAL_TAG: &str = "journal";
+
+/// Suffix a legacy journal file is renamed with once imported, so the
+/// import runs once per file and the original text is never deleted.
+const MIGRATED_SUFFIX: &str = "migrated";
+
+/// Split a legacy `journal.md` into `(stamp, entry)` blocks, in the
+/// `\n## <rfc3339>\n\n<entry>\n` shape the old file writer appended.
+fn parse_journal_blocks(text: &str) -> Vec<(Option<String>, String)> {
+    let mut blocks = Vec::new();
+    for chunk in format!("\n{text}").split("\n## ").skip(1) {
+        let (stamp, entry) = chunk.split_once('\n').unwrap_or((chunk, ""));
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let stamp = chrono::DateTime::parse_from_rfc3339(stamp.trim())
+            .ok()
+            .map(|_| stamp.trim().to_string());
+        blocks.push((stamp, entry.to_string()));
+    }
+    blocks
+}
+
+impl HubStore {
+    /// Append a journal entry as an `AgentPrivate` episodic memory tagged
+    /// [`JOURNAL_TAG`] — readable only by `agent_id`.
+    pub fn append_private_journal(
+        &self,
+        agent_id: &str,
+        entry: &str,
+    ) -> Result<MemoryRecord, HubError> {
+        if entry.trim().is_empty() {
+            return Err(HubError::Invalid("journal entry must not be empty".into()));
+        }
+        self.write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::AgentPrivate,
+            Some(agent_id),
+            None,
+            None,
+            entry,
+            &[JOURNAL_TAG.to_string()],
+        )
+    }
+
+    /// Import every legacy `journals/<agent>/journal.md` into that agent's
+    /// private memories, keeping each entry's original timestamp, then
+    /// rename the file to `journal.md.migrated`. Runs on every `open`;
+    /// already-migrated files are skipped by name. Returns entries imported.
+    pub fn migrate_journals(&self) -> Result<usize, HubError> {
+        let root = self.data_dir.join("journals");
+        let Ok(dirs) = fs::read_dir(&root) else {
+            return Ok(0);
+        };
+        if self.encryption_locked() {
+            return Ok(0);
+        }
+        let mut imported = 0usize;
+        for dir in dirs {
+            let dir = dir?;
+            if !dir.file_type()?.is_dir() {
+                continue;
+            }
+            let agent_id = dir.file_name().to_string_lossy().to_string();
+            let path = dir.path().join("journal.md");
+            if !path.is_file() {
+                continue;
+            }
+            let text = fs::read_to_string(&path)?;
+            let tags_json = serde_json::to_string(&[JOURNAL_TAG]).unwrap_or_else(|_| "[]".into());
+            let tx = self.conn.unchecked_transaction()?;
+            for (stamp, entry) in parse_journal_blocks(&text) {
+                let created_at = stamp.unwrap_or_else(|| Utc::now().to_rfc3339());
+                tx.execute(
+                    r#"
+                    INSERT INTO memories(
+                        id, scope, workspace_path, tier, agent_id, title, body,
+                        tags_json, created_at, updated_at, stale, source_event_id, session_id
+                    ) VALUES (?1, ?2, NULL, ?3, ?4, NULL, hub_seal(?5), ?6, ?7, ?7, 0, NULL, NULL)
+                    "#,
+                    params![
+                        Uuid::new_v4().to_string(),
+                        MemoryScope::AgentPrivate.as_str(),
+                        MemoryTier::Episodic.as_str(),
+                        agent_id,
+                        entry,
+                        tags_json,
+                        created_at,
+                    ],
+                )?;
+                imported += 1;
+            }
+            tx.commit()?;
+            fs::rename(&path, path.with_extension(format!("md.{MIGRATED_SUFFIX}")))?;
+        }
+        Ok(imported)
+    }
+}
@@ -1,4 +1,47 @@
 use super::super::*;
+use super::memory_usage::sort_by_relevance;
+
+/// Reader identity that may see every `Session` memory regardless of
+/// membership — the operator who creates the work sessions in the first
+/// place. It still can't read another agent's `AgentPrivate` notes.
+pub(super) const OPERATOR_READER: &str = "human";
+
+/// How many of the most recently touched rows `list_memories_as` scores
+/// before keeping the top 200. Recency of last touch (creation or last
+/// use) carries half the relevance score, so a row outside this pool would
+/// need to beat a fresh, unused memory on usage and links alone.
+const RELEVANCE_CANDIDATE_POOL: i64 = 1000;
+
+/// Append the scope read rules for `reader` to a `WHERE` clause. With no
+/// reader only the shared scopes (global, workspace) are visible.
+pub(super) fn push_visibility(
+    sql: &mut String,
+    params_vec: &mut Vec<Box<dyn rusqlite::types::ToSql>>,
+    reader: Option<&str>,
+) {
+    let Some(reader) = reader else {
+        sql.push_str(" AND scope IN ('global', 'workspace')");
+        return;
+    };
+    sql.push_str(
+        r#"
+        AND (
+            scope IN ('global', 'workspace')
+            OR (scope = 'agent_private' AND agent_id = ?)
+            OR (scope = 'session' AND (
+                ? = ?
+                OR session_id IN (
+                    SELECT session_id FROM work_session_members WHERE agent_id = ?
+                )
+            ))
+        )"#,
+    );
+    params_vec.push(Box::new(reader.to_string()));
+    params_vec.push(Box::new(reader.to_string()));
+    params_vec.push(Box::new(OPERATOR_READER.to_string()));
+    params_vec.push(Box::new(reader.to_string()));
+}
+
 impl HubStore {
     #[allow(clippy::too_many_arguments)]
     pub fn write_memory(
@@ -34,16 +77,83 @@ impl HubStore {
         body: &str,
         tags: &[String],
         source_event_id: Option<&str>,
+    ) -> Result<MemoryRecord, HubError> {
+        if scope == MemoryScope::Session {
+            return Err(HubError::Invalid(
+                "session scope requires a work session; use write_session_memory".into(),
+            ));
+        }
+        self.insert_memory(
+            tier,
+            scope,
+            agent_id,
+            workspace_path,
+            None,
+            title,
+            body,
+            tags,
+            source_event_id,
+        )
+    }
+
+    /// Write a `Session`-scoped memory: shared with `session_id`'s members
+    /// (and the human), invisible to every other reader.
+    pub fn write_session_memory(
+        &self,
+        session_id: &str,
+        tier: MemoryTier,
+        agent_id: Option<&str>,
+        title: Option<&str>,
+        body: &str,
+        tags: &[String],
+    ) -> Result<MemoryRecord, HubError> {
+        if self.get_work_session(session_id)?.is_none() {
+            return Err(HubError::NotFound(session_id.to_string()));
+        }
+        self.insert_memory(
+            tier,
+            MemoryScope::Session,
+            agent_id,
+            None,
+            Some(session_id),
+            title,
+            body,
+            tags,
+            None,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn insert_memory(
+        &self,
+        tier: MemoryTier,
+        scope: MemoryScope,
+        agent_id: Option<&str>,
+        workspace_path: Option<&str>,
+        session_id: Option<&str>,
+        title: Option<&str>,
+        body: &str,
+        tags: &[String],
+        source_event_id: Option<&str>,
     ) -> Result<MemoryRecord, HubError> {
         if scope == MemoryScope::Workspace && workspace_path.is_none() {
             return Err(HubError::Invalid(
                 "workspace scope requires --workspace".into(),
             ));
         }
+        if scope == MemoryScope::AgentPrivate && agent_id.is_none_or(|a| a.trim().is_empty()) {
+            return Err(HubError::Invalid(
+                "agent_private scope requires --agent".into(),
+            ));
+        }
         if body.trim().is_empty() {
             return Err(HubError::Invalid("memory body must not be empty".into()));
         }
 
+        let title = title
+            .map(|t| self.redact_incoming("memory", agent_id, t))
+            .transpose()?;
+        let body = &self.redact_incoming("memory", agent_id, body)?;
         let id = Uuid::new_v4().to_string();
         let now = Utc::now().to_rfc3339();
         let tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".into());
@@ -52,8 +162,8 @@ impl HubStore {
             r#"
             INSERT INTO memories(
                 id, scope, workspace_path, tier, agent_id, title, body,
-                tags_json, created_at, updated_at, stale, source_event_id
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 0, ?11)
+                tags_json, created_at, updated_at, stale, source_event_id, session_id
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, hub_seal(?7), ?8, ?9, ?10, 0, ?11, ?12)
             "#,
             params![
                 id,
@@ -67,37 +177,34 @@ impl HubStore {
                 now,
                 now,
                 source_event_id,
+                session_id,
             ],
         )?;
 
-        self.get_memory(&id)?.ok_or_else(|| HubError::NotFound(id))
+        let memory = self
+            .get_memory(&id)?
+            .ok_or_else(|| HubError::NotFound(id))?;
+        self.run_hooks(
+            HookEvent::MemoryWritten,
+            memory.workspace_path.as_deref(),
+            &memory,
+        )?;
+        Ok(memory)
     }
 
+    /// Unscoped lookup for the store's own bookkeeping. Anything that
+    /// serves a reader goes through [`Self::get_memory_as`] or
+    /// [`Self::read_memory`] instead.
     pub fn get_memory(&self, id: &str) -> Result<Option<MemoryRecord>, HubError> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT id, scope, workspace_path, tier, agent_id, title, body,
-                   tags_json, created_at, updated_at, stale, source_event_id
+            SELECT id, scope, workspace_path, tier, agent_id, title, hub_plain(body),
+                   tags_json, created_at, updated_at, stale, source_event_id, session_id
             FROM memories WHERE id = ?1
             "#,
         )?;
         let row = stmt
-            .query_row(params![id], |r| {
-                Ok(MemoryRecord {
-                    id: r.get(0)?,
-                    scope: r.get(1)?,
-                    workspace_path: r.get(2)?,
-                    tier: r.get(3)?,
-                    agent_id: r.get(4)?,
-                    title: r.get(5)?,
-                    body: r.get(6)?,
-                    tags_json: r.get(7)?,
-                    created_at: r.get(8)?,
-                    updated_at: r.get(9)?,
-                    stale: r.get::<_, i64>(10)? != 0,
-                    source_event_id: r.get(11)?,
-                })
-            })
+            .query_row(params![id], Self::row_to_memory)
             .optional()?;
         Ok(row)
     }
@@ -114,11 +221,15 @@ impl HubStore {
         if body.trim().is_empty() {
             return Err(HubError::Invalid("memory body must not be empty".into()));
         }
+        let title = title
+            .map(|t| self.redact_incoming("memory", None, t))
+            .transpose()?;
+        let body = &self.redact_incoming("memory", None, body)?;
 
         if let Some(t) = tags {
             let tags_json = serde_json::to_string(t).unwrap_or_else(|_| "[]".into());
             let updated = self.conn.execute(
-                "UPDATE memories SET title = ?1, body = ?2, tags_json = ?3, updated_at = ?4 WHERE id = ?5",
+                "UPDATE memories SET title = ?1, body = hub_seal(?2), tags_json = ?3, updated_at = ?4 WHERE id = ?5",
                 params![title, body, tags_json, now, id],
             )?;
             if updated == 0 {
@@ -126,7 +237,7 @@ impl HubStore {
             }
         } else {
             let updated = self.conn.execute(
-                "UPDATE memories SET title = ?1, body = ?2, updated_at = ?3 WHERE id = ?4",
+                "UPDATE memories SET title = ?1, body = hub_seal(?2), updated_at = ?3 WHERE id = ?4",
                 params![title, body, now, id],
             )?;
             if updated == 0 {
@@ -134,26 +245,52 @@ impl HubStore {
             }
         }
 
-        self.get_memory(id)?
-            .ok_or_else(|| HubError::NotFound(id.to_string()))
+        let memory = self
+            .get_memory(id)?
+            .ok_or_else(|| HubError::NotFound(id.to_string()))?;
+        self.run_hooks(
+            HookEvent::MemoryWritten,
+            memory.workspace_path.as_deref(),
+            &memory,
+        )?;
+        Ok(memory)
     }
 
+    /// Shared-scope (global/workspace) memories only. Use
+    /// [`Self::list_memories_as`] to also see a reader's session and
+    /// private memories.
     pub fn list_memories(
         &self,
         scope: Option<MemoryScope>,
         tier: Option<MemoryTier>,
         workspace_path: Option<&str>,
         include_stale: bool,
+    ) -> Result<Vec<MemoryRecord>, HubError> {
+        self.list_memories_as(None, scope, tier, workspace_path, include_stale)
+    }
+
+    /// `list_memories` as seen by `reader`: shared scopes, plus the
+    /// reader's own `AgentPrivate` notes and the `Session` memories of
+    /// every work session it belongs to (all sessions for the human).
+    /// Ordered by relevance (see `MemoryRelevance`), which is filled in.
+    pub fn list_memories_as(
+        &self,
+        reader: Option<&str>,
+        scope: Option<MemoryScope>,
+        tier: Option<MemoryTier>,
+        workspace_path: Option<&str>,
+        include_stale: bool,
     ) -> Result<Vec<MemoryRecord>, HubError> {
         let mut sql = String::from(
             r#"
-            SELECT id, scope, workspace_path, tier, agent_id, title, body,
-                   tags_json, created_at, updated_at, stale, source_event_id
+            SELECT id, scope, workspace_path, tier, agent_id, title, hub_plain(body),
+                   tags_json, created_at, updated_at, stale, source_event_id, session_id
             FROM memories WHERE 1=1
             "#,
         );
         let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
 
+        push_visibility(&mut sql, &mut params_vec, reader);
         if !include_stale {
             sql.push_str(" AND stale = 0");
         }
@@ -169,62 +306,47 @@ impl HubStore {
             sql.push_str(" AND workspace_path = ?");
             params_vec.push(Box::new(ws.to_string()));
         }
-        sql.push_str(" ORDER BY created_at DESC LIMIT 200");
+        // Bounded candidate pool ordered by last touch, the dominant
+        // relevance term; `attach_relevance` then ranks only these rows.
+        sql.push_str(
+            r#"
+            ORDER BY MAX(created_at, COALESCE(
+                (SELECT MAX(u.created_at) FROM memory_usage u WHERE u.memory_id = memories.id),
+                created_at
+            )) DESC
+            LIMIT ?"#,
+        );
+        params_vec.push(Box::new(RELEVANCE_CANDIDATE_POOL));
 
         let mut stmt = self.conn.prepare(&sql)?;
         let params_refs: Vec<&dyn rusqlite::types::ToSql> =
             params_vec.iter().map(|p| p.as_ref()).collect();
-        let rows = stmt.query_map(params_refs.as_slice(), |r| {
-            Ok(MemoryRecord {
-                id: r.get(0)?,
-                scope: r.get(1)?,
-                workspace_path: r.get(2)?,
-                tier: r.get(3)?,
-                agent_id: r.get(4)?,
-                title: r.get(5)?,
-                body: r.get(6)?,
-                tags_json: r.get(7)?,
-                created_at: r.get(8)?,
-                updated_at: r.get(9)?,
-                stale: r.get::<_, i64>(10)? != 0,
-                source_event_id: r.get(11)?,
-            })
-        })?;
-        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        let mut memories = stmt
+            .query_map(params_refs.as_slice(), Self::row_to_memory)?
+            .collect::<Result<Vec<_>, _>>()?;
+        // Rank the candidate pool before capping, so the 200 rows returned
+        // are the most relevant ones, not just the newest.
+        self.attach_relevance(&mut memories)?;
+        sort_by_relevance(&mut memories);
+        memories.truncate(200);
+        Ok(memories)
     }
 
+    /// Search shared-scope memories; see [`Self::search_memories_as`].
     pub fn search_memories(&self, query: &str) -> Result<Vec<MemoryRecord>, HubError> {
-        let q = format!("%{}%", query.trim());
-        if query.trim().is_empty() {
-            return Err(HubError::Invalid("search query must not be empty".into()));
-        }
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, scope, workspace_path, tier, agent_id, title, body,
-                   tags_json, created_at, updated_at, stale, source_event_id
-            FROM memories
-            WHERE stale = 0 AND (body LIKE ?1 OR IFNULL(title, '') LIKE ?1 OR tags_json LIKE ?1)
-            ORDER BY created_at DESC
-            LIMIT 100
-            "#,
-        )?;
-        let rows = stmt.query_map(params![q], |r| {
-            Ok(MemoryRecord {
-                id: r.get(0)?,
-                scope: r.get(1)?,
-                workspace_path: r.get(2)?,
-                tier: r.get(3)?,
-                agent_id: r.get(4)?,
-                title: r.get(5)?,
-                body: r.get(6)?,
-                tags_json: r.get(7)?,
-                created_at: r.get(8)?,
-                updated_at: r.get(9)?,
-                stale: r.get::<_, i64>(10)? != 0,
-                source_event_id: r.get(11)?,
-            })
-        })?;
-        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+        self.search_memories_as(None, query)
+    }
+
+    /// `search_memories` under the same read rules as `list_memories_as`:
+    /// the newest 100 matches (see [`Self::search_memories_page_as`]).
+    pub fn search_memories_as(
+        &self,
+        reader: Option<&str>,
+        query: &str,
+    ) -> Result<Vec<MemoryRecord>, HubError> {
+        Ok(self
+            .search_memories_page_as(reader, query, &PageRequest::latest(100))?
+            .items)
     }
 
     pub fn mark_memory_stale(&self, id: &str, stale: bool) -> Result<(), HubError> {
@@ -293,8 +415,8 @@ impl HubStore {
             r#"
             INSERT INTO memories(
                 id, scope, workspace_path, tier, agent_id, title, body,
-                tags_json, created_at, updated_at, stale, source_event_id
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 0, ?11)
+                tags_json, created_at, updated_at, stale, source_event_id, session_id
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, hub_seal(?7), ?8, ?9, ?10, 0, ?11, ?12)
             "#,
             params![
                 new_id,
@@ -308,6 +430,7 @@ impl HubStore {
                 now,
                 now,
                 id,
+                src.session_id,
             ],
         )?;
         // Mark source stale so short-term lists stay lean; provenance remains queryable.
@@ -316,10 +439,14 @@ impl HubStore {
             .ok_or_else(|| HubError::NotFound(new_id))
     }
 
-    /// Compact short-term memories: keep the newest `keep_newest`, promote the rest to episodic.
+    /// Compact short-term memories: keep the newest `keep_newest`, promote the
+    /// rest to episodic. Usage-based suggestions live in
+    /// `compaction_candidates`.
     pub fn compact_short_term(&self, keep_newest: usize) -> Result<CompactReport, HubError> {
+        // Shared scopes only: session and private notes are their owners' to curate.
         let mut short = self.list_memories(None, Some(MemoryTier::ShortTerm), None, false)?;
-        // list is DESC by created_at; keep head, promote tail
+        // list is ordered by relevance; re-sort DESC by created_at, keep head, promote tail
+        short.sort_by(|a, b| b.created_at.cmp(&a.created_at));
         let mut promoted = 0usize;
         let mut skipped = 0usize;
         if short.len() <= keep_newest {
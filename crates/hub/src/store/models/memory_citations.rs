@@ -0,0 +1,201 @@
+//! Reverse index of `[Memory #id]` references: which messages cite which
+//! memory. `list_message_memories` resolves one message forward; the
+//! `memory_citations` table answers the other direction and backs the
+//! warnings for messages whose cited memory has since gone stale, been
+//! deleted, or been revised. Rows are keyed by the resolved memory id and
+//! carry no foreign key to `memories`, so a deleted memory's citations
+//! survive to be reported.
+
+use super::super::*;
+
+/// Meta key set once every pre-existing message has been indexed.
+const CITATION_INDEX_META_KEY: &str = "memory_citations_indexed";
+
+/// One message citing a memory, from `memory_citations`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryCitation {
+    pub message: MessageRecord,
+    /// The reference as written in the body (full id or short prefix).
+    pub reference: String,
+}
+
+/// What happened to a cited memory after the citing message was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationIssue {
+    Stale,
+    Deleted,
+    Revised,
+}
+
+impl CitationIssue {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stale => "stale",
+            Self::Deleted => "deleted",
+            Self::Revised => "revised",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, HubError> {
+        match s {
+            "stale" => Ok(Self::Stale),
+            "deleted" => Ok(Self::Deleted),
+            "revised" => Ok(Self::Revised),
+            other => Err(HubError::Invalid(format!(
+                "unknown citation issue: {other} (expected one of: stale, deleted, revised)"
+            ))),
+        }
+    }
+}
+
+/// A message that relied on a memory which no longer says what it said
+/// when the message was sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitationWarning {
+    pub message_id: String,
+    pub subject: Option<String>,
+    pub from_agent: String,
+    pub to_agent: String,
+    pub sent_at: String,
+    pub memory_id: String,
+    pub reference: String,
+    pub issue: CitationIssue,
+    /// The memory's `updated_at`; `None` once it has been deleted.
+    pub memory_updated_at: Option<String>,
+}
+
+impl HubStore {
+    /// Index the resolvable `[Memory #id]` references in one message. A
+    /// memory cited twice in the same body is indexed once, under the
+    /// first spelling.
+    pub(crate) fn index_message_citations(&self, message: &MessageRecord) -> Result<(), HubError> {
+        for reference in parse_memory_references(&message.body) {
+            if let Some(memory_id) = self.resolve_memory_reference(&reference)? {
+                self.conn.execute(
+                    r#"
+                    INSERT OR IGNORE INTO memory_citations(message_id, memory_id, reference, created_at)
+                    VALUES (?1, ?2, ?3, ?4)
+                    "#,
+                    params![message.id, memory_id, reference, message.created_at],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuild `memory_citations` from every stored message body. References
+    /// are resolved against the memories that exist now, so a message citing
+    /// an already-deleted memory is not indexed. Returns citations indexed.
+    pub fn rebuild_memory_citations(&self) -> Result<usize, HubError> {
+        let messages: Vec<MessageRecord> = {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {MESSAGE_COLUMNS} FROM messages WHERE hub_plain(body) LIKE '%[Memory #%'"
+            ))?;
+            let rows = stmt.query_map([], Self::row_to_message)?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM memory_citations", [])?;
+        for message in &messages {
+            self.index_message_citations(message)?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO meta(key, value) VALUES (?1, '1')",
+            params![CITATION_INDEX_META_KEY],
+        )?;
+        tx.commit()?;
+        Ok(self
+            .conn
+            .query_row("SELECT COUNT(*) FROM memory_citations", [], |r| {
+                r.get::<_, i64>(0)
+            })? as usize)
+    }
+
+    /// Backfill the index once for hubs that predate it; new messages are
+    /// indexed as they are sent.
+    pub(crate) fn ensure_memory_citation_index(&self) -> Result<(), HubError> {
+        if self.encryption_locked() {
+            return Ok(());
+        }
+        let built: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![CITATION_INDEX_META_KEY],
+                |r| r.get(0),
+            )
+            .optional()?;
+        if built.is_none() {
+            self.rebuild_memory_citations()?;
+        }
+        Ok(())
+    }
+
+    /// Messages citing `memory_id`, oldest first. Works for deleted
+    /// memories too — their citations are kept.
+    pub fn memory_citations(&self, memory_id: &str) -> Result<Vec<MemoryCitation>, HubError> {
+        let columns = message_columns("m");
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {columns}, c.reference
+             FROM memory_citations c JOIN messages m ON m.id = c.message_id
+             WHERE c.memory_id = ?1
+             ORDER BY m.created_at ASC, m.id ASC"
+        ))?;
+        let rows = stmt.query_map(params![memory_id], |r| {
+            Ok(MemoryCitation {
+                message: Self::row_to_message(r)?,
+                reference: r.get(15)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Citations whose memory went stale, was deleted, or was updated after
+    /// the message was sent, newest message first. With `agent`, only
+    /// messages that agent sent or received.
+    pub fn citation_warnings(&self, agent: Option<&str>) -> Result<Vec<CitationWarning>, HubError> {
+        let mut sql = String::from(
+            r#"
+            SELECT m.id, m.subject, m.from_agent, m.to_agent, m.created_at,
+                   c.memory_id, c.reference, mem.id IS NULL, mem.stale, mem.updated_at
+            FROM memory_citations c
+            JOIN messages m ON m.id = c.message_id
+            LEFT JOIN memories mem ON mem.id = c.memory_id
+            WHERE (mem.id IS NULL OR mem.stale = 1 OR mem.updated_at > m.created_at)
+            "#,
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        if let Some(agent) = agent {
+            sql.push_str(" AND (m.from_agent = ?1 OR m.to_agent = ?1)");
+            params_vec.push(Box::new(agent.to_string()));
+        }
+        sql.push_str(" ORDER BY m.created_at DESC, m.id ASC, c.memory_id ASC");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(params_ref.as_slice(), |r| {
+            let deleted = r.get::<_, i64>(7)? != 0;
+            let stale = r.get::<_, Option<i64>>(8)?.unwrap_or(0) != 0;
+            let issue = if deleted {
+                CitationIssue::Deleted
+            } else if stale {
+                CitationIssue::Stale
+            } else {
+                CitationIssue::Revised
+            };
+            Ok(CitationWarning {
+                message_id: r.get(0)?,
+                subject: r.get(1)?,
+                from_agent: r.get(2)?,
+                to_agent: r.get(3)?,
+                sent_at: r.get(4)?,
+                memory_id: r.get(5)?,
+                reference: r.get(6)?,
+                issue,
+                memory_updated_at: r.get(9)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
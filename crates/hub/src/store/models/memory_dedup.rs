@@ -0,0 +1,366 @@
+//! Near-duplicate memory detection and merge. Several agents writing an
+//! episodic memory about the same event produce rows that differ only in
+//! phrasing; `compact_short_term` promotes by age and never notices. This
+//! pass clusters them by word-shingle similarity and folds each cluster
+//! into one canonical memory.
+
+use super::super::*;
+use std::collections::HashSet;
+
+/// Similarity at or above which two memories are proposed as duplicates.
+/// Shingle Jaccard is harsh on reworded text (one changed word breaks up
+/// to `SHINGLE_WORDS` shingles), so this is lower than it looks — but still
+/// high enough that two memories merely on the same topic stay apart.
+pub const DEFAULT_DEDUP_THRESHOLD: f64 = 0.6;
+
+/// Words per shingle. Three keeps word order significant without making
+/// a single edit wipe out most of a short memory's shingles.
+const SHINGLE_WORDS: usize = 3;
+
+/// One memory folded into a proposal's canonical, with the score that put
+/// it there (always measured against the canonical, never transitively).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryDuplicate {
+    pub memory: MemoryRecord,
+    pub similarity: f64,
+}
+
+/// A proposed merge: keep `canonical`, fold `duplicates` into it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryDedupProposal {
+    pub canonical: MemoryRecord,
+    pub duplicates: Vec<MemoryDuplicate>,
+}
+
+/// What `merge_memories` actually changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryMergeOutcome {
+    pub canonical_id: String,
+    /// Duplicate ids, now marked stale.
+    pub merged: Vec<String>,
+    /// `memory_links` endpoints moved from a duplicate to the canonical.
+    pub links_repointed: usize,
+    /// Links dropped because re-pointing would have made them self-links.
+    pub links_dropped: usize,
+    /// `[Memory #id]` references rewritten in message and memory bodies.
+    pub references_rewritten: usize,
+}
+
+/// Result of `dedup_memories`: the proposals, plus one outcome per
+/// proposal when it was run with `apply`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryDedupReport {
+    pub examined: usize,
+    pub proposals: Vec<MemoryDedupProposal>,
+    pub applied: Vec<MemoryMergeOutcome>,
+}
+
+/// Lowercase, keep only alphanumeric words, collapse whitespace — so
+/// punctuation, casing, and markdown emphasis never make two otherwise
+/// identical memories look different.
+fn normalize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Word shingles over title + body. A text shorter than one shingle is a
+/// single shingle of itself, so one-line memories still compare.
+fn shingles(memory: &MemoryRecord) -> HashSet<String> {
+    let words = normalize(&format!(
+        "{} {}",
+        memory.title.as_deref().unwrap_or(""),
+        memory.body
+    ));
+    if words.len() < SHINGLE_WORDS {
+        return std::iter::once(words.join(" ")).collect();
+    }
+    words.windows(SHINGLE_WORDS).map(|w| w.join(" ")).collect()
+}
+
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Whether two memories live in the same place: scope, workspace, session
+/// and, for private notes, owner. Only such memories are compared or
+/// merged — a global note and a workspace note saying the same thing stay
+/// apart.
+fn same_home(a: &MemoryRecord, b: &MemoryRecord) -> bool {
+    a.scope == b.scope
+        && a.workspace_path == b.workspace_path
+        && a.session_id == b.session_id
+        && (a.scope != MemoryScope::AgentPrivate.as_str() || a.agent_id == b.agent_id)
+}
+
+/// Replace every `[Memory #<ref>]` that points at `from_id` with the same
+/// length prefix of `to_id`, so a short UI-style reference stays short.
+/// References that don't resolve to `from_id` are left untouched.
+pub(crate) fn rewrite_memory_references(body: &str, from_id: &str, to_id: &str) -> (String, usize) {
+    const OPEN: &str = "[Memory #";
+    let mut out = String::with_capacity(body.len());
+    let mut rewritten = 0usize;
+    let mut remaining = body;
+    while let Some(start) = remaining.find(OPEN) {
+        let after_start = &remaining[start + OPEN.len()..];
+        let Some(end) = after_start.find(']') else {
+            break;
+        };
+        let candidate = &after_start[..end];
+        out.push_str(&remaining[..start + OPEN.len()]);
+        if !candidate.is_empty() && from_id.starts_with(candidate) {
+            out.push_str(&to_id[..candidate.len().min(to_id.len())]);
+            rewritten += 1;
+        } else {
+            out.push_str(candidate);
+        }
+        out.push(']');
+        remaining = &after_start[end + 1..];
+    }
+    out.push_str(remaining);
+    (out, rewritten)
+}
+
+impl HubStore {
+    /// Propose near-duplicate merges among live memories. Memories are only
+    /// compared within the same scope and workspace (and the same session
+    /// or private owner) — a global note and a workspace note saying the
+    /// same thing are deliberately kept apart.
+    /// Clustering is greedy, oldest first: each memory joins the first
+    /// cluster whose canonical (its oldest member) scores at least
+    /// `threshold` against it, otherwise it starts a new cluster. That keeps
+    /// every duplicate directly similar to the memory it will be merged
+    /// into, rather than chaining A≈B≈C into one merge where A and C share
+    /// nothing. Only memories `reader` can see (the `list_memories_as`
+    /// rules) are examined. Read-only.
+    pub fn find_duplicate_memories(
+        &self,
+        reader: Option<&str>,
+        workspace_path: Option<&str>,
+        tier: Option<MemoryTier>,
+        threshold: f64,
+    ) -> Result<Vec<MemoryDedupProposal>, HubError> {
+        Ok(self.dedup_candidates(reader, workspace_path, tier, threshold)?.1)
+    }
+
+    fn dedup_candidates(
+        &self,
+        reader: Option<&str>,
+        workspace_path: Option<&str>,
+        tier: Option<MemoryTier>,
+        threshold: f64,
+    ) -> Result<(usize, Vec<MemoryDedupProposal>), HubError> {
+        if threshold.is_nan() || threshold <= 0.0 || threshold > 1.0 {
+            return Err(HubError::Invalid(format!(
+                "dedup threshold must be in (0, 1], got {threshold}"
+            )));
+        }
+        // `memory_graph` rather than `list_memories`: dedup must see every
+        // row, not the newest 200.
+        let mut memories = self
+            .memory_graph(
+                reader,
+                &MemoryGraphFilter {
+                    workspace_path: workspace_path.map(str::to_string),
+                    tier,
+                    ..MemoryGraphFilter::default()
+                },
+            )?
+            .nodes;
+        memories.sort_by(|a, b| (&a.created_at, &a.id).cmp(&(&b.created_at, &b.id)));
+        let examined = memories.len();
+
+        let mut clusters: Vec<(HashSet<String>, MemoryDedupProposal)> = Vec::new();
+        for memory in memories {
+            let memory_shingles = shingles(&memory);
+            let home = clusters
+                .iter_mut()
+                .find_map(|(canonical_shingles, proposal)| {
+                    if !same_home(&proposal.canonical, &memory) {
+                        return None;
+                    }
+                    let score = similarity(canonical_shingles, &memory_shingles);
+                    (score >= threshold).then_some((proposal, score))
+                });
+            match home {
+                Some((proposal, score)) => proposal.duplicates.push(MemoryDuplicate {
+                    memory,
+                    similarity: score,
+                }),
+                None => clusters.push((
+                    memory_shingles,
+                    MemoryDedupProposal {
+                        canonical: memory,
+                        duplicates: Vec::new(),
+                    },
+                )),
+            }
+        }
+
+        let proposals = clusters
+            .into_iter()
+            .map(|(_, proposal)| proposal)
+            .filter(|proposal| !proposal.duplicates.is_empty())
+            .collect();
+        Ok((examined, proposals))
+    }
+
+    /// Fold `duplicate_ids` into `canonical_id` in one transaction: every
+    /// `memory_links` endpoint on a duplicate moves to the canonical (links
+    /// that would become self-links are dropped), every `[Memory #id]`
+    /// reference to a duplicate in a message or memory body is rewritten to
+    /// the canonical, and the duplicates are marked stale — not deleted, so
+    /// provenance stays queryable and `memory purge-stale` remains the only
+    /// destructive step. Duplicates must share the canonical's scope,
+    /// workspace, session and (for private notes) owner, as
+    /// `find_duplicate_memories` proposes them. Each merge is recorded as
+    /// a `merge` audit event.
+    pub fn merge_memories(
+        &self,
+        canonical_id: &str,
+        duplicate_ids: &[String],
+    ) -> Result<MemoryMergeOutcome, HubError> {
+        let canonical = self
+            .get_memory(canonical_id)?
+            .ok_or_else(|| HubError::NotFound(canonical_id.to_string()))?;
+        if canonical.stale {
+            return Err(HubError::Invalid("cannot merge into a stale memory".into()));
+        }
+        for id in duplicate_ids {
+            if id == canonical_id {
+                return Err(HubError::Invalid(
+                    "a memory cannot be merged into itself".into(),
+                ));
+            }
+            let duplicate = self
+                .get_memory(id)?
+                .ok_or_else(|| HubError::NotFound(id.clone()))?;
+            if !same_home(&canonical, &duplicate) {
+                return Err(HubError::Invalid(format!(
+                    "memory {id} is in a different scope, workspace, session or owner than {canonical_id}"
+                )));
+            }
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let mut outcome = MemoryMergeOutcome {
+            canonical_id: canonical_id.to_string(),
+            merged: Vec::new(),
+            links_repointed: 0,
+            links_dropped: 0,
+            references_rewritten: 0,
+        };
+        let tx = self.conn.unchecked_transaction()?;
+        for id in duplicate_ids {
+            outcome.links_dropped += tx.execute(
+                r#"
+                DELETE FROM memory_links
+                WHERE (from_memory_id = ?1 AND to_memory_id = ?2)
+                   OR (from_memory_id = ?2 AND to_memory_id = ?1)
+                "#,
+                params![id, canonical_id],
+            )?;
+            outcome.links_repointed += tx.execute(
+                "UPDATE memory_links SET from_memory_id = ?1 WHERE from_memory_id = ?2",
+                params![canonical_id, id],
+            )?;
+            outcome.links_repointed += tx.execute(
+                "UPDATE memory_links SET to_memory_id = ?1 WHERE to_memory_id = ?2",
+                params![canonical_id, id],
+            )?;
+            // Usage history follows the memory, so the canonical keeps the
+            // duplicate's reads and citations in its relevance.
+            tx.execute(
+                "UPDATE memory_usage SET memory_id = ?1 WHERE memory_id = ?2",
+                params![canonical_id, id],
+            )?;
+            // Citations move too; a message that cited both keeps one row.
+            tx.execute(
+                "UPDATE OR IGNORE memory_citations SET memory_id = ?1 WHERE memory_id = ?2",
+                params![canonical_id, id],
+            )?;
+            tx.execute(
+                "DELETE FROM memory_citations WHERE memory_id = ?1",
+                params![id],
+            )?;
+
+            for table in ["messages", "memories"] {
+                let rows: Vec<(String, String)> = {
+                    let mut stmt = tx.prepare(&format!(
+                        "SELECT id, hub_plain(body) FROM {table} WHERE hub_plain(body) LIKE '%[Memory #%'"
+                    ))?;
+                    let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+                    rows.collect::<Result<Vec<_>, _>>()?
+                };
+                for (row_id, body) in rows {
+                    let (rewritten, count) = rewrite_memory_references(&body, id, canonical_id);
+                    if count > 0 {
+                        tx.execute(
+                            &format!("UPDATE {table} SET body = hub_seal(?1) WHERE id = ?2"),
+                            params![rewritten, row_id],
+                        )?;
+                        outcome.references_rewritten += count;
+                    }
+                }
+            }
+
+            tx.execute(
+                "UPDATE memories SET stale = 1, updated_at = ?1 WHERE id = ?2",
+                params![now, id],
+            )?;
+            outcome.merged.push(id.clone());
+        }
+        tx.commit()?;
+
+        let process = serde_json::json!({
+            "canonical": outcome.canonical_id,
+            "merged": outcome.merged,
+            "links_repointed": outcome.links_repointed,
+            "links_dropped": outcome.links_dropped,
+            "references_rewritten": outcome.references_rewritten,
+        });
+        self.record_audit_event(
+            &self.data_dir,
+            Path::new("hub/memories").join(canonical_id).as_path(),
+            "merge",
+            &process.to_string(),
+            None,
+        )?;
+        Ok(outcome)
+    }
+
+    /// `find_duplicate_memories`, and with `apply` also `merge_memories`
+    /// for every proposal — the `ca memory dedup [--apply]` entry point.
+    pub fn dedup_memories(
+        &self,
+        reader: Option<&str>,
+        workspace_path: Option<&str>,
+        tier: Option<MemoryTier>,
+        threshold: f64,
+        apply: bool,
+    ) -> Result<MemoryDedupReport, HubError> {
+        let (examined, proposals) = self.dedup_candidates(reader, workspace_path, tier, threshold)?;
+        let mut applied = Vec::new();
+        if apply {
+            for proposal in &proposals {
+                let ids: Vec<String> = proposal
+                    .duplicates
+                    .iter()
+                    .map(|d| d.memory.id.clone())
+                    .collect();
+                applied.push(self.merge_memories(&proposal.canonical.id, &ids)?);
+            }
+        }
+        Ok(MemoryDedupReport {
+            examined,
+            proposals,
+            applied,
+        })
+    }
+}
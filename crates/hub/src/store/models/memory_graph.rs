@@ -0,0 +1,455 @@
+//! Whole-graph views over `memory_links`: export (DOT / GraphML / JSON)
+//! and the graph queries `list_memory_links` / `related_memories` can't
+//! answer from one node at a time — shortest path, clusters, orphans, and
+//! the "contradicts" report.
+
+use super::super::*;
+use super::memories::push_visibility;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Relation token the contradiction report looks for. Compared
+/// case-insensitively, since `relation` is freeform (see
+/// `MemoryLinkRecord`) and nothing normalizes it on write.
+pub const CONTRADICTS_RELATION: &str = "contradicts";
+
+/// Serialization target for [`HubStore::export_memory_graph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphExportFormat {
+    Dot,
+    GraphMl,
+    Json,
+}
+
+impl GraphExportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::GraphMl => "graphml",
+            Self::Json => "json",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, HubError> {
+        match s {
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "graphml" => Ok(Self::GraphMl),
+            "json" => Ok(Self::Json),
+            other => Err(HubError::Invalid(format!(
+                "unknown graph format: {other} (expected one of: dot, graphml, json)"
+            ))),
+        }
+    }
+}
+
+/// Which slice of the memory graph a whole-graph query looks at. Node
+/// filters (`workspace_path`, `tier`, `include_stale`) decide which
+/// memories are in the graph; `relation` only filters edges. An edge is
+/// kept only when both of its endpoints survived the node filters, so an
+/// export never references a node it doesn't define.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryGraphFilter {
+    #[serde(default)]
+    pub workspace_path: Option<String>,
+    #[serde(default)]
+    pub tier: Option<MemoryTier>,
+    #[serde(default)]
+    pub relation: Option<String>,
+    #[serde(default)]
+    pub include_stale: bool,
+}
+
+/// A filtered snapshot of the memory graph. Unlike `list_memories`, this
+/// is not capped at 200 rows — exporting half a graph is worse than a slow
+/// export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryGraph {
+    pub nodes: Vec<MemoryRecord>,
+    pub links: Vec<MemoryLinkRecord>,
+}
+
+/// One `contradicts` edge whose endpoints are both still live (non-stale):
+/// two memories the team currently holds that disagree with each other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryContradiction {
+    pub link: MemoryLinkRecord,
+    pub from: MemoryRecord,
+    pub to: MemoryRecord,
+}
+
+impl MemoryGraph {
+    /// Undirected adjacency over `links`, matching `related_memories`'s
+    /// "direction is ignored for traversal" rule.
+    fn adjacency(&self) -> HashMap<&str, Vec<&str>> {
+        let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+        for link in &self.links {
+            adjacency
+                .entry(link.from_memory_id.as_str())
+                .or_default()
+                .push(link.to_memory_id.as_str());
+            adjacency
+                .entry(link.to_memory_id.as_str())
+                .or_default()
+                .push(link.from_memory_id.as_str());
+        }
+        adjacency
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::from("digraph memories {\n    node [shape=box];\n");
+        for node in &self.nodes {
+            out.push_str(&format!(
+                "    \"{}\" [label=\"{}\", tier=\"{}\", scope=\"{}\", agent=\"{}\"{}];\n",
+                dot_escape(&node.id),
+                dot_escape(&node_label(node)),
+                dot_escape(&node.tier),
+                dot_escape(&node.scope),
+                dot_escape(node.agent_id.as_deref().unwrap_or(UNATTRIBUTED_AUTHOR)),
+                if node.stale { ", style=dashed" } else { "" },
+            ));
+        }
+        for link in &self.links {
+            out.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\", created_by=\"{}\"];\n",
+                dot_escape(&link.from_memory_id),
+                dot_escape(&link.to_memory_id),
+                dot_escape(link.relation.as_deref().unwrap_or("")),
+                dot_escape(&link.created_by),
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_graphml(&self) -> String {
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n\
+             \x20 <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n\
+             \x20 <key id=\"tier\" for=\"node\" attr.name=\"tier\" attr.type=\"string\"/>\n\
+             \x20 <key id=\"scope\" for=\"node\" attr.name=\"scope\" attr.type=\"string\"/>\n\
+             \x20 <key id=\"workspace\" for=\"node\" attr.name=\"workspace\" attr.type=\"string\"/>\n\
+             \x20 <key id=\"agent\" for=\"node\" attr.name=\"agent\" attr.type=\"string\"/>\n\
+             \x20 <key id=\"stale\" for=\"node\" attr.name=\"stale\" attr.type=\"boolean\"/>\n\
+             \x20 <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>\n\
+             \x20 <key id=\"created_by\" for=\"edge\" attr.name=\"created_by\" attr.type=\"string\"/>\n\
+             \x20 <graph id=\"memories\" edgedefault=\"directed\">\n",
+        );
+        for node in &self.nodes {
+            out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&node.id)));
+            out.push_str(&format!(
+                "      <data key=\"label\">{}</data>\n",
+                xml_escape(&node_label(node))
+            ));
+            out.push_str(&format!(
+                "      <data key=\"tier\">{}</data>\n",
+                xml_escape(&node.tier)
+            ));
+            out.push_str(&format!(
+                "      <data key=\"scope\">{}</data>\n",
+                xml_escape(&node.scope)
+            ));
+            if let Some(workspace) = &node.workspace_path {
+                out.push_str(&format!(
+                    "      <data key=\"workspace\">{}</data>\n",
+                    xml_escape(workspace)
+                ));
+            }
+            out.push_str(&format!(
+                "      <data key=\"agent\">{}</data>\n",
+                xml_escape(node.agent_id.as_deref().unwrap_or(UNATTRIBUTED_AUTHOR))
+            ));
+            out.push_str(&format!(
+                "      <data key=\"stale\">{}</data>\n",
+                node.stale
+            ));
+            out.push_str("    </node>\n");
+        }
+        for link in &self.links {
+            out.push_str(&format!(
+                "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n",
+                xml_escape(&link.id),
+                xml_escape(&link.from_memory_id),
+                xml_escape(&link.to_memory_id)
+            ));
+            if let Some(relation) = &link.relation {
+                out.push_str(&format!(
+                    "      <data key=\"relation\">{}</data>\n",
+                    xml_escape(relation)
+                ));
+            }
+            out.push_str(&format!(
+                "      <data key=\"created_by\">{}</data>\n",
+                xml_escape(&link.created_by)
+            ));
+            out.push_str("    </edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+}
+
+/// Title if present, otherwise the first line of the body, truncated — a
+/// node label has to stay readable in a rendered graph.
+fn node_label(memory: &MemoryRecord) -> String {
+    let raw = memory
+        .title
+        .as_deref()
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| memory.body.lines().next().unwrap_or(""));
+    let mut label: String = raw.chars().take(60).collect();
+    if raw.chars().count() > 60 {
+        label.push('…');
+    }
+    label
+}
+
+fn dot_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+impl HubStore {
+    /// Load every memory and link matching `filter` in one pass, under the
+    /// same scope read rules as `list_memories_as`: memories `reader` can't
+    /// see are not nodes, so no edge to them survives either. See
+    /// [`MemoryGraphFilter`] for how node and edge filters combine.
+    pub fn memory_graph(
+        &self,
+        reader: Option<&str>,
+        filter: &MemoryGraphFilter,
+    ) -> Result<MemoryGraph, HubError> {
+        let mut sql = String::from(
+            r#"
+            SELECT id, scope, workspace_path, tier, agent_id, title, hub_plain(body),
+                   tags_json, created_at, updated_at, stale, source_event_id, session_id
+            FROM memories WHERE 1=1
+            "#,
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        push_visibility(&mut sql, &mut params_vec, reader);
+        if !filter.include_stale {
+            sql.push_str(" AND stale = 0");
+        }
+        if let Some(tier) = filter.tier {
+            sql.push_str(" AND tier = ?");
+            params_vec.push(Box::new(tier.as_str().to_string()));
+        }
+        if let Some(workspace) = &filter.workspace_path {
+            sql.push_str(" AND workspace_path = ?");
+            params_vec.push(Box::new(workspace.clone()));
+        }
+        sql.push_str(" ORDER BY created_at ASC, id ASC");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let nodes = stmt
+            .query_map(params_refs.as_slice(), Self::row_to_memory)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let node_ids: HashSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, from_memory_id, to_memory_id, relation, created_by, created_at
+            FROM memory_links
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )?;
+        let links = stmt
+            .query_map([], Self::row_to_memory_link)?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|link| {
+                node_ids.contains(link.from_memory_id.as_str())
+                    && node_ids.contains(link.to_memory_id.as_str())
+            })
+            .filter(|link| match &filter.relation {
+                Some(relation) => link
+                    .relation
+                    .as_deref()
+                    .is_some_and(|r| r.eq_ignore_ascii_case(relation)),
+                None => true,
+            })
+            .collect();
+        Ok(MemoryGraph { nodes, links })
+    }
+
+    /// Render the filtered graph as Graphviz DOT, GraphML, or JSON (the
+    /// serialized [`MemoryGraph`]). Stale nodes (only present with
+    /// `include_stale`) are drawn dashed in DOT and flagged in GraphML.
+    pub fn export_memory_graph(
+        &self,
+        reader: Option<&str>,
+        filter: &MemoryGraphFilter,
+        format: GraphExportFormat,
+    ) -> Result<String, HubError> {
+        let graph = self.memory_graph(reader, filter)?;
+        Ok(match format {
+            GraphExportFormat::Dot => graph.to_dot(),
+            GraphExportFormat::GraphMl => graph.to_graphml(),
+            GraphExportFormat::Json => serde_json::to_string_pretty(&graph)
+                .map_err(|e| HubError::Invalid(format!("graph serialize: {e}")))?,
+        })
+    }
+
+    /// Shortest undirected path from `from_memory_id` to `to_memory_id`
+    /// through non-stale memories `reader` can see, endpoints included.
+    /// `Ok(None)` means both memories exist but no path connects them; an
+    /// endpoint hidden from `reader` is `NotFound`, as if it didn't exist.
+    pub fn memory_path(
+        &self,
+        reader: Option<&str>,
+        from_memory_id: &str,
+        to_memory_id: &str,
+    ) -> Result<Option<Vec<MemoryRecord>>, HubError> {
+        for id in [from_memory_id, to_memory_id] {
+            if self.get_memory_as(id, reader)?.is_none() {
+                return Err(HubError::NotFound(id.to_string()));
+            }
+        }
+        let graph = self.memory_graph(reader, &MemoryGraphFilter::default())?;
+        let by_id: HashMap<&str, &MemoryRecord> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        if !by_id.contains_key(from_memory_id) || !by_id.contains_key(to_memory_id) {
+            // One endpoint is stale, so it can't be on a live path.
+            return Ok(None);
+        }
+        let adjacency = graph.adjacency();
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut visited: HashSet<&str> = HashSet::from([from_memory_id]);
+        let mut queue = VecDeque::from([from_memory_id]);
+        while let Some(current) = queue.pop_front() {
+            if current == to_memory_id {
+                let mut path = vec![current];
+                let mut cursor = current;
+                while let Some(prev) = previous.get(cursor) {
+                    path.push(prev);
+                    cursor = prev;
+                }
+                path.reverse();
+                return Ok(Some(path.into_iter().map(|id| by_id[id].clone()).collect()));
+            }
+            for next in adjacency.get(current).into_iter().flatten() {
+                if visited.insert(next) {
+                    previous.insert(next, current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Connected components (ignoring edge direction) with at least two
+    /// memories, largest first. Singletons are what
+    /// [`Self::orphaned_memories`] reports.
+    pub fn memory_clusters(
+        &self,
+        reader: Option<&str>,
+        filter: &MemoryGraphFilter,
+    ) -> Result<Vec<Vec<MemoryRecord>>, HubError> {
+        let graph = self.memory_graph(reader, filter)?;
+        let adjacency = graph.adjacency();
+        let mut component_of: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut components: Vec<Vec<&str>> = Vec::new();
+        for node in &graph.nodes {
+            if component_of.contains_key(node.id.as_str()) {
+                continue;
+            }
+            let index = components.len();
+            let mut members = Vec::new();
+            let mut queue = VecDeque::from([node.id.as_str()]);
+            component_of.insert(node.id.as_str(), index);
+            while let Some(current) = queue.pop_front() {
+                members.push(current);
+                for next in adjacency.get(current).into_iter().flatten() {
+                    if !component_of.contains_key(next) {
+                        component_of.insert(next, index);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            components.push(members);
+        }
+        let by_id: HashMap<&str, &MemoryRecord> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        let mut clusters: Vec<Vec<MemoryRecord>> = components
+            .into_iter()
+            .filter(|members| members.len() >= 2)
+            .map(|members| members.into_iter().map(|id| by_id[id].clone()).collect())
+            .collect();
+        clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.len()));
+        Ok(clusters)
+    }
+
+    /// Memories in the filtered graph with no incident link at all.
+    /// `filter.relation` is ignored: a memory linked only by some other
+    /// relation is still connected, so orphan status is computed from the
+    /// full link set between the filtered nodes.
+    pub fn orphaned_memories(
+        &self,
+        reader: Option<&str>,
+        filter: &MemoryGraphFilter,
+    ) -> Result<Vec<MemoryRecord>, HubError> {
+        let graph = self.memory_graph(reader, &MemoryGraphFilter {
+            relation: None,
+            ..filter.clone()
+        })?;
+        let linked: HashSet<&str> = graph
+            .links
+            .iter()
+            .flat_map(|link| [link.from_memory_id.as_str(), link.to_memory_id.as_str()])
+            .collect();
+        Ok(graph
+            .nodes
+            .iter()
+            .filter(|node| !linked.contains(node.id.as_str()))
+            .cloned()
+            .collect())
+    }
+
+    /// Every [`CONTRADICTS_RELATION`] edge whose endpoints are both
+    /// non-stale. `filter.relation` and `filter.include_stale` are ignored:
+    /// the report is defined by exactly that relation between live memories.
+    pub fn contradiction_report(
+        &self,
+        reader: Option<&str>,
+        filter: &MemoryGraphFilter,
+    ) -> Result<Vec<MemoryContradiction>, HubError> {
+        let graph = self.memory_graph(reader, &MemoryGraphFilter {
+            relation: Some(CONTRADICTS_RELATION.to_string()),
+            include_stale: false,
+            ..filter.clone()
+        })?;
+        let by_id: HashMap<&str, &MemoryRecord> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        Ok(graph
+            .links
+            .iter()
+            .map(|link| MemoryContradiction {
+                link: link.clone(),
+                from: by_id[link.from_memory_id.as_str()].clone(),
+                to: by_id[link.to_memory_id.as_str()].clone(),
+            })
+            .collect())
+    }
+}
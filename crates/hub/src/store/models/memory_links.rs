@@ -1,4 +1,5 @@
 use super::super::*;
+use super::memories::push_visibility;
 use crate::settings::LinkSuggestionMode;
 use std::collections::{BTreeMap, HashSet};
 
@@ -171,18 +172,27 @@ impl HubStore {
     /// traversal purposes. Stale memories are excluded, matching
     /// `list_memories`'s default. Cycles can't cause infinite recursion:
     /// `UNION` (not `UNION ALL`) dedupes visited ids as part of the walk.
+    /// The walk only starts from and steps onto memories `reader` can see
+    /// (the `list_memories_as` rules), so a hidden memory is neither
+    /// returned nor used as a bridge to reach anything else.
     pub fn related_memories(
         &self,
+        reader: Option<&str>,
         memory_id: &str,
         depth: u8,
     ) -> Result<Vec<MemoryRecord>, HubError> {
         if depth == 0 {
             return Ok(Vec::new());
         }
-        let mut stmt = self.conn.prepare(
+        let mut sql =
+            String::from("WITH RECURSIVE visible(id) AS (SELECT id FROM memories WHERE 1=1");
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        push_visibility(&mut sql, &mut params_vec, reader);
+        sql.push_str(
             r#"
-            WITH RECURSIVE walk(memory_id, hops) AS (
-                SELECT ?1, 0
+            ),
+            walk(memory_id, hops) AS (
+                SELECT id, 0 FROM visible WHERE id = ?
                 UNION
                 SELECT
                     CASE WHEN l.from_memory_id = w.memory_id
@@ -191,18 +201,28 @@ impl HubStore {
                 FROM memory_links l
                 JOIN walk w
                   ON l.from_memory_id = w.memory_id OR l.to_memory_id = w.memory_id
-                WHERE w.hops < ?2
+                WHERE w.hops < ?
+                  AND (CASE WHEN l.from_memory_id = w.memory_id
+                            THEN l.to_memory_id ELSE l.from_memory_id END)
+                      IN (SELECT id FROM visible)
             )
             SELECT DISTINCT
-                m.id, m.scope, m.workspace_path, m.tier, m.agent_id, m.title, m.body,
-                m.tags_json, m.created_at, m.updated_at, m.stale, m.source_event_id
+                m.id, m.scope, m.workspace_path, m.tier, m.agent_id, m.title, hub_plain(m.body),
+                m.tags_json, m.created_at, m.updated_at, m.stale, m.source_event_id,
+                m.session_id
             FROM memories m
             JOIN walk w ON w.memory_id = m.id
-            WHERE w.memory_id != ?1 AND m.stale = 0
+            WHERE w.memory_id != ? AND m.stale = 0
             ORDER BY m.created_at DESC
             "#,
-        )?;
-        let rows = stmt.query_map(params![memory_id, depth as i64], Self::row_to_memory)?;
+        );
+        params_vec.push(Box::new(memory_id.to_string()));
+        params_vec.push(Box::new(depth as i64));
+        params_vec.push(Box::new(memory_id.to_string()));
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(params_refs.as_slice(), Self::row_to_memory)?;
         Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
 
@@ -349,7 +369,7 @@ impl HubStore {
         Ok(suggestions)
     }
 
-    fn row_to_memory_link(r: &rusqlite::Row) -> rusqlite::Result<MemoryLinkRecord> {
+    pub(super) fn row_to_memory_link(r: &rusqlite::Row) -> rusqlite::Result<MemoryLinkRecord> {
         Ok(MemoryLinkRecord {
             id: r.get(0)?,
             from_memory_id: r.get(1)?,
@@ -360,7 +380,7 @@ impl HubStore {
         })
     }
 
-    fn row_to_memory(r: &rusqlite::Row) -> rusqlite::Result<MemoryRecord> {
+    pub(super) fn row_to_memory(r: &rusqlite::Row) -> rusqlite::Result<MemoryRecord> {
         Ok(MemoryRecord {
             id: r.get(0)?,
             scope: r.get(1)?,
@@ -374,6 +394,8 @@ impl HubStore {
             updated_at: r.get(9)?,
             stale: r.get::<_, i64>(10)? != 0,
             source_event_id: r.get(11)?,
+            session_id: r.get(12)?,
+            relevance: None,
         })
     }
 }
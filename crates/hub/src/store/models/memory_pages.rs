@@ -0,0 +1,84 @@
+//! Chronological, cursor-paged memory listing and search. Unlike
+//! `list_memories_as`, which ranks by relevance and keeps the top 200,
+//! these walk every visible memory newest first (see [`PageRequest`]).
+
+use super::super::*;
+use super::memories::push_visibility;
+
+const MEMORY_COLUMNS: &str = "SELECT id, scope, workspace_path, tier, agent_id, title, hub_plain(body),
+       tags_json, created_at, updated_at, stale, source_event_id, session_id
+FROM memories WHERE 1=1";
+
+impl HubStore {
+    /// One page of the memories `reader` may see, filtered like
+    /// `list_memories_as`, with relevance filled in.
+    pub fn list_memories_page_as(
+        &self,
+        reader: Option<&str>,
+        scope: Option<MemoryScope>,
+        tier: Option<MemoryTier>,
+        workspace_path: Option<&str>,
+        include_stale: bool,
+        page: &PageRequest,
+    ) -> Result<Page<MemoryRecord>, HubError> {
+        let mut sql = String::from(MEMORY_COLUMNS);
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        push_visibility(&mut sql, &mut params_vec, reader);
+        if !include_stale {
+            sql.push_str(" AND stale = 0");
+        }
+        if let Some(s) = scope {
+            sql.push_str(" AND scope = ?");
+            params_vec.push(Box::new(s.as_str().to_string()));
+        }
+        if let Some(t) = tier {
+            sql.push_str(" AND tier = ?");
+            params_vec.push(Box::new(t.as_str().to_string()));
+        }
+        if let Some(ws) = workspace_path {
+            sql.push_str(" AND workspace_path = ?");
+            params_vec.push(Box::new(ws.to_string()));
+        }
+        self.memory_page(sql, params_vec, page)
+    }
+
+    /// One page of non-stale memories whose body, title or tags contain
+    /// `query`.
+    pub fn search_memories_page_as(
+        &self,
+        reader: Option<&str>,
+        query: &str,
+        page: &PageRequest,
+    ) -> Result<Page<MemoryRecord>, HubError> {
+        if query.trim().is_empty() {
+            return Err(HubError::Invalid("search query must not be empty".into()));
+        }
+        let q = format!("%{}%", query.trim());
+        let mut sql = String::from(MEMORY_COLUMNS);
+        sql.push_str(
+            " AND stale = 0 AND (hub_plain(body) LIKE ? OR IFNULL(title, '') LIKE ? OR tags_json LIKE ?)",
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> =
+            vec![Box::new(q.clone()), Box::new(q.clone()), Box::new(q)];
+        push_visibility(&mut sql, &mut params_vec, reader);
+        self.memory_page(sql, params_vec, page)
+    }
+
+    fn memory_page(
+        &self,
+        mut sql: String,
+        mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>>,
+        page: &PageRequest,
+    ) -> Result<Page<MemoryRecord>, HubError> {
+        let keyset = push_keyset(&mut sql, &mut params_vec, page, "created_at", "id")?;
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt
+            .query_map(params_refs.as_slice(), Self::row_to_memory)?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut page = finish_page(rows, keyset, |m| (&m.created_at, &m.id));
+        self.attach_relevance(&mut page.items)?;
+        Ok(page)
+    }
+}
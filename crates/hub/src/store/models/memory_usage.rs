@@ -0,0 +1,348 @@
+//! Memory usage signals and the relevance score built from them. A memory
+//! is "used" when someone reads it, cites it in a message
+//! (`[Memory #id]`, see `parse_memory_references`), or has it dispatched
+//! to a harness inside a task/wake/handoff message. Relevance combines how
+//! recently it was touched, how often it was used, and how connected it is
+//! in `memory_links`; it orders `list_memories` and drives the
+//! promote/stale suggestions in `compaction_candidates`.
+
+use super::super::*;
+use super::memories::OPERATOR_READER;
+use std::collections::HashMap;
+
+/// Half-life of the recency term: a memory nobody has touched for this
+/// long has half the recency of one touched just now.
+const RELEVANCE_HALF_LIFE_DAYS: f64 = 14.0;
+
+const RECENCY_WEIGHT: f64 = 0.5;
+const USAGE_WEIGHT: f64 = 0.3;
+const LINK_WEIGHT: f64 = 0.2;
+
+/// A read is a weaker signal than a citation or dispatch — it says someone
+/// looked, not that the memory changed what anyone did.
+const READ_USE_WEIGHT: f64 = 0.25;
+
+/// Short-term memories scoring below this with no citations or dispatches
+/// are suggested for staleness: untouched for roughly five weeks and
+/// unlinked.
+pub const STALE_CANDIDATE_BELOW: f64 = 0.15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryUsageKind {
+    Read,
+    Cited,
+    Dispatched,
+}
+
+impl MemoryUsageKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Cited => "cited",
+            Self::Dispatched => "dispatched",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, HubError> {
+        match s {
+            "read" => Ok(Self::Read),
+            "cited" => Ok(Self::Cited),
+            "dispatched" => Ok(Self::Dispatched),
+            other => Err(HubError::Invalid(format!(
+                "unknown memory usage kind: {other} (expected one of: read, cited, dispatched)"
+            ))),
+        }
+    }
+}
+
+/// Suggestions from `compaction_candidates`. Nothing is changed; the
+/// caller decides whether to `promote_memory` / `mark_memory_stale`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionCandidates {
+    /// Short-term memories that were cited or dispatched — already worth
+    /// more than a scratch note.
+    pub promote: Vec<MemoryRecord>,
+    /// Short-term memories nobody used that scored below
+    /// [`STALE_CANDIDATE_BELOW`].
+    pub stale: Vec<MemoryRecord>,
+}
+
+#[derive(Default)]
+struct UsageTotals {
+    reads: i64,
+    citations: i64,
+    dispatches: i64,
+    last_used_at: Option<String>,
+}
+
+fn days_since(stamp: &str, now: chrono::DateTime<Utc>) -> f64 {
+    chrono::DateTime::parse_from_rfc3339(stamp)
+        .map(|t| (now - t.with_timezone(&Utc)).num_seconds().max(0) as f64 / 86_400.0)
+        .unwrap_or(0.0)
+}
+
+fn relevance_score(memory: &MemoryRecord, totals: &UsageTotals, degree: i64) -> f64 {
+    let now = Utc::now();
+    let last_touch = match &totals.last_used_at {
+        Some(used) if used.as_str() > memory.created_at.as_str() => used.as_str(),
+        _ => memory.created_at.as_str(),
+    };
+    let recency = 0.5f64.powf(days_since(last_touch, now) / RELEVANCE_HALF_LIFE_DAYS);
+    let uses =
+        (totals.citations + totals.dispatches) as f64 + READ_USE_WEIGHT * totals.reads as f64;
+    let usage = 1.0 - 1.0 / (1.0 + uses);
+    let links = 1.0 - 1.0 / (1.0 + degree as f64);
+    RECENCY_WEIGHT * recency + USAGE_WEIGHT * usage + LINK_WEIGHT * links
+}
+
+impl HubStore {
+    /// Record one use of a memory. `actor` is who read/cited/dispatched it;
+    /// `message_id` is the carrying message for citations and dispatches.
+    pub fn record_memory_usage(
+        &self,
+        memory_id: &str,
+        kind: MemoryUsageKind,
+        actor: Option<&str>,
+        message_id: Option<&str>,
+    ) -> Result<(), HubError> {
+        if self.get_memory(memory_id)?.is_none() {
+            return Err(HubError::NotFound(memory_id.to_string()));
+        }
+        self.conn.execute(
+            r#"
+            INSERT INTO memory_usage(id, memory_id, kind, actor, message_id, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                Uuid::new_v4().to_string(),
+                memory_id,
+                kind.as_str(),
+                actor,
+                message_id,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// `get_memory` for a reader, recording the read. Session and private
+    /// memories the reader can't see come back as `None`, exactly as if
+    /// they didn't exist.
+    pub fn read_memory(
+        &self,
+        id: &str,
+        reader: Option<&str>,
+    ) -> Result<Option<MemoryRecord>, HubError> {
+        let Some(mut memory) = self.get_memory_as(id, reader)? else {
+            return Ok(None);
+        };
+        self.record_memory_usage(id, MemoryUsageKind::Read, reader, None)?;
+        self.attach_relevance(std::slice::from_mut(&mut memory))?;
+        Ok(Some(memory))
+    }
+
+    /// `get_memory` under the scope read rules, without recording a read:
+    /// a memory `reader` can't see is `None`.
+    pub fn get_memory_as(
+        &self,
+        id: &str,
+        reader: Option<&str>,
+    ) -> Result<Option<MemoryRecord>, HubError> {
+        let Some(memory) = self.get_memory(id)? else {
+            return Ok(None);
+        };
+        let visible = match MemoryScope::parse(&memory.scope)? {
+            MemoryScope::Global | MemoryScope::Workspace => true,
+            MemoryScope::AgentPrivate => reader.is_some() && memory.agent_id.as_deref() == reader,
+            MemoryScope::Session => match (reader, memory.session_id.as_deref()) {
+                (Some(OPERATOR_READER), _) => true,
+                (Some(reader), Some(session)) => self.is_session_member(session, reader)?,
+                _ => false,
+            },
+        };
+        Ok(visible.then_some(memory))
+    }
+
+    /// Resolve a `[Memory #<ref>]` reference (full id or unique prefix),
+    /// the same way `list_message_memories` does.
+    pub(crate) fn resolve_memory_reference(
+        &self,
+        reference: &str,
+    ) -> Result<Option<String>, HubError> {
+        if self.get_memory(reference)?.is_some() {
+            return Ok(Some(reference.to_string()));
+        }
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id FROM memories WHERE id LIKE ?1 ORDER BY id ASC LIMIT 2")?;
+        let matches = stmt
+            .query_map(params![format!("{reference}%")], |r| r.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(if matches.len() == 1 {
+            matches.into_iter().next()
+        } else {
+            None
+        })
+    }
+
+    /// Called for every stored message: each resolvable `[Memory #id]` in
+    /// the body counts as a citation, or as a dispatch when the message is
+    /// task/wake/handoff traffic that a harness will act on. A broadcast
+    /// counts once per recipient — each of them received the reference.
+    /// The references also go into the `memory_citations` index.
+    pub(crate) fn record_message_memory_usage(
+        &self,
+        message: &MessageRecord,
+    ) -> Result<(), HubError> {
+        let dispatched = message.task_id.is_some()
+            || matches!(
+                MessageKind::parse(&message.kind),
+                Ok(MessageKind::Wake | MessageKind::Handoff)
+            );
+        let kind = if dispatched {
+            MemoryUsageKind::Dispatched
+        } else {
+            MemoryUsageKind::Cited
+        };
+        for reference in parse_memory_references(&message.body) {
+            if let Some(memory_id) = self.resolve_memory_reference(&reference)? {
+                self.record_memory_usage(
+                    &memory_id,
+                    kind,
+                    Some(&message.from_agent),
+                    Some(&message.id),
+                )?;
+            }
+        }
+        self.index_message_citations(message)
+    }
+
+    /// Fill in `relevance` on each memory in place. Usage and link degree
+    /// are aggregated for these ids only, not the whole tables.
+    pub(crate) fn attach_relevance(&self, memories: &mut [MemoryRecord]) -> Result<(), HubError> {
+        if memories.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<&str> = memories.iter().map(|m| m.id.as_str()).collect();
+        let ids_json = serde_json::to_string(&ids).unwrap_or_else(|_| "[]".into());
+        let mut totals: HashMap<String, UsageTotals> = HashMap::new();
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT memory_id, kind, COUNT(*), MAX(created_at)
+            FROM memory_usage
+            WHERE memory_id IN (SELECT value FROM json_each(?1))
+            GROUP BY memory_id, kind
+            "#,
+        )?;
+        let rows = stmt.query_map(params![ids_json], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, i64>(2)?,
+                r.get::<_, String>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (memory_id, kind, count, last) = row?;
+            let entry = totals.entry(memory_id).or_default();
+            match MemoryUsageKind::parse(&kind) {
+                Ok(MemoryUsageKind::Read) => entry.reads += count,
+                Ok(MemoryUsageKind::Cited) => entry.citations += count,
+                Ok(MemoryUsageKind::Dispatched) => entry.dispatches += count,
+                Err(_) => continue,
+            }
+            if entry.last_used_at.as_deref() < Some(last.as_str()) {
+                entry.last_used_at = Some(last);
+            }
+        }
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT memory_id, COUNT(*) FROM (
+                SELECT from_memory_id AS memory_id FROM memory_links
+                WHERE from_memory_id IN (SELECT value FROM json_each(?1))
+                UNION ALL
+                SELECT to_memory_id AS memory_id FROM memory_links
+                WHERE to_memory_id IN (SELECT value FROM json_each(?1))
+            ) GROUP BY memory_id
+            "#,
+        )?;
+        let degrees: HashMap<String, i64> = stmt
+            .query_map(params![ids_json], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)))?
+            .collect::<Result<_, _>>()?;
+
+        let empty = UsageTotals::default();
+        for memory in memories.iter_mut() {
+            let usage = totals.get(&memory.id).unwrap_or(&empty);
+            let degree = degrees.get(&memory.id).copied().unwrap_or(0);
+            memory.relevance = Some(MemoryRelevance {
+                score: relevance_score(memory, usage, degree),
+                reads: usage.reads,
+                citations: usage.citations,
+                dispatches: usage.dispatches,
+                link_degree: degree,
+                last_used_at: usage.last_used_at.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Short-term, shared-scope memories worth promoting or retiring, by
+    /// usage and relevance (see [`CompactionCandidates`]). Read-only —
+    /// `compact_short_term` is the step that acts.
+    pub fn compaction_candidates(&self) -> Result<CompactionCandidates, HubError> {
+        let mut short = self
+            .memory_graph(
+                None,
+                &MemoryGraphFilter {
+                    tier: Some(MemoryTier::ShortTerm),
+                    ..MemoryGraphFilter::default()
+                },
+            )?
+            .nodes;
+        self.attach_relevance(&mut short)?;
+        sort_by_relevance(&mut short);
+
+        let mut candidates = CompactionCandidates {
+            promote: Vec::new(),
+            stale: Vec::new(),
+        };
+        for memory in short {
+            let Some(relevance) = &memory.relevance else {
+                continue;
+            };
+            if relevance.citations + relevance.dispatches > 0 {
+                candidates.promote.push(memory);
+            } else if relevance.score < STALE_CANDIDATE_BELOW {
+                candidates.stale.push(memory);
+            }
+        }
+        Ok(candidates)
+    }
+}
+
+/// Relevance of a memory nobody has used and nothing links to: the
+/// recency term alone. All a backend without usage or link tables
+/// (`InMemoryHub`) can score, so both backends rank by the same rule.
+pub(crate) fn unused_relevance(memory: &MemoryRecord) -> MemoryRelevance {
+    MemoryRelevance {
+        score: relevance_score(memory, &UsageTotals::default(), 0),
+        reads: 0,
+        citations: 0,
+        dispatches: 0,
+        link_degree: 0,
+        last_used_at: None,
+    }
+}
+
+/// Most relevant first; newest first among equals.
+pub(crate) fn sort_by_relevance(memories: &mut [MemoryRecord]) {
+    let score = |m: &MemoryRecord| m.relevance.as_ref().map_or(0.0, |r| r.score);
+    memories.sort_by(|a, b| {
+        score(b)
+            .partial_cmp(&score(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.created_at.cmp(&a.created_at))
+    });
+}
@@ -1,8 +1,27 @@
 use super::*;
 
+mod journals;
 mod memories;
+mod memory_citations;
+mod memory_dedup;
+mod memory_graph;
 mod memory_links;
+mod memory_pages;
+mod memory_usage;
+pub use journals::JOURNAL_TAG;
+pub use memory_citations::{CitationIssue, CitationWarning, MemoryCitation};
+pub(crate) use memory_dedup::rewrite_memory_references;
+pub use memory_dedup::{
+    MemoryDedupProposal, MemoryDedupReport, MemoryDuplicate, MemoryMergeOutcome,
+    DEFAULT_DEDUP_THRESHOLD,
+};
+pub use memory_graph::{
+    GraphExportFormat, MemoryContradiction, MemoryGraph, MemoryGraphFilter, CONTRADICTS_RELATION,
+};
 pub use memory_links::{LinkSuggestion, UNATTRIBUTED_AUTHOR};
+pub use memory_usage::{CompactionCandidates, MemoryUsageKind, STALE_CANDIDATE_BELOW};
+pub(crate) use memory_usage::{sort_by_relevance, unused_relevance};
+
 /// One step in a multi-agent workflow (C5).
 ///
 /// Consecutive steps that share the same non-empty `parallel_group` form a
@@ -20,6 +39,10 @@ pub struct WorkflowStep {
     /// When set, adjacent steps with the same group run as one parallel stage.
     #[serde(default)]
     pub parallel_group: Option<String>,
+    /// Structured instructions sent as the handoff's payload, checked
+    /// against the agent's `input_schema`.
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
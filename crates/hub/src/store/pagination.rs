@@ -0,0 +1,172 @@
+//! Keyset pagination shared by the `*_page` list APIs. Pages run newest
+//! first on a `(created_at, id)` key, so a cursor stays valid however many
+//! rows are written meanwhile. A cursor is `<created_at>|<id>`; a bare
+//! RFC 3339 timestamp (or a prefix like `2026-10-01`) also works and
+//! compares by time alone.
+
+use super::*;
+
+/// Rows per page when the request names no limit.
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+
+/// Upper bound on rows per page.
+pub const MAX_PAGE_LIMIT: usize = 500;
+
+/// Which slice of a list to return. `before` pages toward older rows,
+/// `after` toward newer ones; both together bound a range.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageRequest {
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl PageRequest {
+    /// The newest `limit` rows.
+    pub fn latest(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::default()
+        }
+    }
+
+    fn effective_limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+}
+
+/// One page of rows, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// More rows exist past this page in the direction it was read: older
+    /// for `before` (and for no cursor), newer for `after` alone.
+    pub has_more: bool,
+    /// Cursor of the oldest item; pass as `before` for the next older page.
+    pub before: Option<String>,
+    /// Cursor of the newest item; pass as `after` for the next newer page.
+    pub after: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            has_more: self.has_more,
+            before: self.before,
+            after: self.after,
+        }
+    }
+}
+
+/// Cursor for a row keyed by `created_at` and `id`.
+pub fn page_cursor(created_at: &str, id: &str) -> String {
+    format!("{created_at}|{id}")
+}
+
+fn parse_cursor(raw: &str) -> Result<(String, String), HubError> {
+    let (time, id) = raw.trim().split_once('|').unwrap_or((raw.trim(), ""));
+    if time.is_empty() {
+        return Err(HubError::Invalid(format!(
+            "invalid page cursor: {raw:?} (expected <created_at>|<id> or a timestamp)"
+        )));
+    }
+    Ok((time.to_string(), id.to_string()))
+}
+
+/// How [`push_keyset`] ordered the query, for [`finish_page`].
+pub(crate) struct Keyset {
+    ascending: bool,
+    limit: usize,
+}
+
+/// Append the cursor bounds, `ORDER BY` and `LIMIT` for `page` to a query
+/// whose `WHERE` clause is already open. One extra row is fetched to learn
+/// whether more exist.
+pub(crate) fn push_keyset(
+    sql: &mut String,
+    params_vec: &mut Vec<Box<dyn rusqlite::types::ToSql>>,
+    page: &PageRequest,
+    time_col: &str,
+    id_col: &str,
+) -> Result<Keyset, HubError> {
+    if let Some(before) = &page.before {
+        let (time, id) = parse_cursor(before)?;
+        sql.push_str(&format!(
+            " AND ({time_col} < ? OR ({time_col} = ? AND {id_col} < ?))"
+        ));
+        params_vec.push(Box::new(time.clone()));
+        params_vec.push(Box::new(time));
+        params_vec.push(Box::new(id));
+    }
+    if let Some(after) = &page.after {
+        let (time, id) = parse_cursor(after)?;
+        sql.push_str(&format!(
+            " AND ({time_col} > ? OR ({time_col} = ? AND {id_col} > ?))"
+        ));
+        params_vec.push(Box::new(time.clone()));
+        params_vec.push(Box::new(time));
+        params_vec.push(Box::new(id));
+    }
+    // `after` alone reads upward from the cursor so the page is the rows
+    // nearest to it, not the newest overall.
+    let ascending = page.after.is_some() && page.before.is_none();
+    let direction = if ascending { "ASC" } else { "DESC" };
+    let limit = page.effective_limit();
+    sql.push_str(&format!(
+        " ORDER BY {time_col} {direction}, {id_col} {direction} LIMIT {}",
+        limit + 1
+    ));
+    Ok(Keyset { ascending, limit })
+}
+
+/// Trim the look-ahead row, restore newest-first order, and set cursors
+/// from `key` (the row's `(created_at, id)`).
+pub(crate) fn finish_page<T>(
+    mut rows: Vec<T>,
+    keyset: Keyset,
+    key: impl Fn(&T) -> (&str, &str),
+) -> Page<T> {
+    let has_more = rows.len() > keyset.limit;
+    rows.truncate(keyset.limit);
+    if keyset.ascending {
+        rows.reverse();
+    }
+    let cursor = |row: &T| {
+        let (time, id) = key(row);
+        page_cursor(time, id)
+    };
+    Page {
+        before: rows.last().map(cursor),
+        after: rows.first().map(cursor),
+        has_more,
+        items: rows,
+    }
+}
+
+/// [`push_keyset`] plus [`finish_page`] for rows already in memory, with
+/// the same cursor semantics as the SQL version.
+pub(crate) fn page_rows<T>(
+    mut rows: Vec<T>,
+    page: &PageRequest,
+    key: impl Fn(&T) -> (&str, &str),
+) -> Result<Page<T>, HubError> {
+    let before = page.before.as_deref().map(parse_cursor).transpose()?;
+    let after = page.after.as_deref().map(parse_cursor).transpose()?;
+    rows.retain(|row| {
+        let (time, id) = key(row);
+        let older = |(t, i): &(String, String)| time < t.as_str() || (time == t && id < i.as_str());
+        let newer = |(t, i): &(String, String)| time > t.as_str() || (time == t && id > i.as_str());
+        before.as_ref().is_none_or(older) && after.as_ref().is_none_or(newer)
+    });
+    let ascending = page.after.is_some() && page.before.is_none();
+    rows.sort_by(|a, b| key(a).cmp(&key(b)));
+    if !ascending {
+        rows.reverse();
+    }
+    let limit = page.effective_limit();
+    rows.truncate(limit + 1);
+    Ok(finish_page(rows, Keyset { ascending, limit }, key))
+}
@@ -6,7 +6,6 @@ impl HubStore {
     pub fn open(data_dir: impl AsRef<Path>) -> Result<Self, HubError> {
         let data_dir = data_dir.as_ref().to_path_buf();
         fs::create_dir_all(&data_dir)?;
-        fs::create_dir_all(data_dir.join("journals"))?;
         fs::create_dir_all(data_dir.join("markdown"))?;
         fs::create_dir_all(data_dir.join("wake"))?;
         fs::create_dir_all(data_dir.join("attachments"))?;
@@ -14,8 +13,15 @@ impl HubStore {
         let db_path = data_dir.join("hub.db");
         let conn = Connection::open(&db_path)?;
         conn.execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")?;
-        let store = Self { conn, data_dir };
+        let store = Self::from_connection(conn, data_dir)?;
         store.migrate()?;
+        store.ensure_message_extensions()?;
+        store.migrate_journals()?;
+        store.ensure_memory_citation_index()?;
+        store.ensure_content_addressed_attachments()?;
+        store.ensure_attachment_text_index()?;
+        store.ensure_webhook_tables()?;
+        store.ensure_hook_table()?;
         Ok(store)
     }
 
@@ -34,7 +40,7 @@ impl HubStore {
             &db_path,
             rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
         )?;
-        Ok(Self { conn, data_dir })
+        Self::from_connection(conn, data_dir)
     }
 
     pub fn data_dir(&self) -> &Path {
@@ -71,7 +77,8 @@ impl HubStore {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
                 stale INTEGER NOT NULL DEFAULT 0,
-                source_event_id TEXT
+                source_event_id TEXT,
+                session_id TEXT
             );
 
             CREATE INDEX IF NOT EXISTS idx_memories_scope_tier
@@ -96,6 +103,31 @@ impl HubStore {
             CREATE INDEX IF NOT EXISTS idx_memory_links_to
                 ON memory_links(to_memory_id);
 
+            CREATE TABLE IF NOT EXISTS memory_usage (
+                id TEXT PRIMARY KEY NOT NULL,
+                memory_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                actor TEXT,
+                message_id TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY(memory_id) REFERENCES memories(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_memory_usage_memory
+                ON memory_usage(memory_id, kind);
+
+            CREATE TABLE IF NOT EXISTS memory_citations (
+                message_id TEXT NOT NULL,
+                memory_id TEXT NOT NULL,
+                reference TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY(message_id, memory_id),
+                FOREIGN KEY(message_id) REFERENCES messages(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_memory_citations_memory
+                ON memory_citations(memory_id);
+
             CREATE TABLE IF NOT EXISTS messages (
                 id TEXT PRIMARY KEY NOT NULL,
                 from_agent TEXT NOT NULL,
@@ -261,7 +293,16 @@ impl HubStore {
                 mime TEXT NOT NULL,
                 byte_size INTEGER NOT NULL,
                 relative_path TEXT NOT NULL,
-                created_at TEXT NOT NULL
+                created_at TEXT NOT NULL,
+                sha256 TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS attachment_texts (
+                sha256 TEXT PRIMARY KEY NOT NULL,
+                text_mime TEXT,
+                body TEXT,
+                truncated INTEGER NOT NULL DEFAULT 0,
+                indexed_at TEXT NOT NULL
             );
 
             CREATE TABLE IF NOT EXISTS roles (
@@ -356,6 +397,8 @@ impl HubStore {
             "ALTER TABLE harness_session_registrations ADD COLUMN managed_pid INTEGER",
             "ALTER TABLE harness_session_registrations ADD COLUMN writer_owner TEXT",
             "ALTER TABLE harness_session_registrations ADD COLUMN writer_acquired_at TEXT",
+            "ALTER TABLE memories ADD COLUMN session_id TEXT",
+            "ALTER TABLE attachments ADD COLUMN sha256 TEXT",
         ] {
             let _ = self.conn.execute(ddl, []);
         }
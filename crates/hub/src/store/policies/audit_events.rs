@@ -55,7 +55,7 @@ impl HubStore {
             ],
         )?;
         tx.commit()?;
-        Ok(AuditEvent {
+        let event = AuditEvent {
             id,
             root_path,
             path,
@@ -66,7 +66,9 @@ impl HubStore {
             previous_hash,
             event_hash,
             status: "pending".into(),
-        })
+        };
+        self.run_hooks(HookEvent::AuditEvent, Some(&event.root_path), &event)?;
+        Ok(event)
     }
 
     pub fn list_audit_events(&self, pending_only: bool) -> Result<Vec<AuditEvent>, HubError> {
@@ -80,6 +82,30 @@ impl HubStore {
         Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
 
+    /// Audit events by `observed_at`, newest first. [`Self::list_audit_events`]
+    /// stays the full chain in insertion order for verification.
+    pub fn list_audit_events_page(
+        &self,
+        pending_only: bool,
+        page: &PageRequest,
+    ) -> Result<Page<AuditEvent>, HubError> {
+        let mut sql = String::from(
+            "SELECT id, root_path, path, operation, observed_at, process_json, content_hash, previous_hash, event_hash, status FROM audit_events WHERE 1=1",
+        );
+        if pending_only {
+            sql.push_str(" AND status = 'pending'");
+        }
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        let keyset = push_keyset(&mut sql, &mut params_vec, page, "observed_at", "id")?;
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt
+            .query_map(params_refs.as_slice(), audit_event_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(finish_page(rows, keyset, |e| (&e.observed_at, &e.id)))
+    }
+
     pub fn set_audit_status(&self, id: &str, status: &str) -> Result<(), HubError> {
         if !matches!(status, "approved" | "quarantined" | "pending") {
             return Err(HubError::Invalid(format!("unknown audit status: {status}")));
@@ -283,11 +283,13 @@ impl HubStore {
             task_id,
         )?;
 
-        Ok(BudgetPauseOutcome {
+        let outcome = BudgetPauseOutcome {
             status,
             summary_path,
             handoff_message_id: message.id,
-        })
+        };
+        self.emit_webhook_event(crate::WebhookEvent::BudgetPaused, &outcome)?;
+        Ok(outcome)
     }
 
     /// Persist a cancellation/shutdown handoff so interrupted work is not lost.
@@ -0,0 +1,379 @@
+//! Secret redaction for content agents push into the hub. Harness captures,
+//! channel replies and memory writes pass through [`HubStore::redact_incoming`]
+//! before they are stored: every detector match is replaced with a
+//! `[REDACTED:<detector>]` placeholder, and a `redact` audit event records
+//! which detectors fired and how often, never the secret itself.
+//!
+//! Detectors are the [`BUILTIN_DETECTORS`] regexes, any custom regexes and
+//! an entropy check for long random-looking tokens. All of them are set in
+//! the `redaction_config` meta row. A regex with a `secret` named group
+//! replaces only that group, so `Bearer <token>` keeps its `Bearer`.
+//! `ca hub scan` runs the same detectors over rows already stored.
+
+use super::encryption::is_sealed_column;
+use super::*;
+use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+
+const CONFIG_META: &str = "redaction_config";
+
+/// Name of the entropy detector, for `disabled_detectors`.
+pub const ENTROPY_DETECTOR: &str = "high_entropy";
+
+/// Built-in `(name, regex)` detectors, always on unless disabled by name.
+pub const BUILTIN_DETECTORS: [(&str, &str); 8] = [
+    ("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    (
+        "aws_secret_key",
+        r#"(?i)\baws_secret_access_key["']?\s*[:=]\s*["']?(?P<secret>[A-Za-z0-9/+=]{40})"#,
+    ),
+    (
+        "github_token",
+        r"\b(?:gh[pousr]_[A-Za-z0-9]{36,255}|github_pat_[A-Za-z0-9_]{22,255})\b",
+    ),
+    (
+        "bearer_token",
+        r"(?i)\bbearer\s+(?P<secret>[A-Za-z0-9\-._~+/]{16,}=*)",
+    ),
+    (
+        "private_key",
+        r"(?s)-----BEGIN [A-Z ]*PRIVATE KEY-----.*?-----END [A-Z ]*PRIVATE KEY-----",
+    ),
+    (
+        "provider_api_key",
+        r"\bsk-(?:ant-|proj-)?[A-Za-z0-9_\-]{20,}",
+    ),
+    (
+        "jwt",
+        r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+    ),
+    (
+        "env_secret",
+        r#"\b[A-Z][A-Z0-9_]*(?:SECRET|TOKEN|PASSWORD|PASSWD|API_KEY|APIKEY|ACCESS_KEY)[A-Z0-9_]*=["']?(?P<secret>[^\s"']{8,})"#,
+    ),
+];
+
+/// `(table, key column, text column)` rows `ca hub scan` looks at.
+const SCANNED_COLUMNS: [(&str, &str, &str); 6] = [
+    ("messages", "id", "body"),
+    ("memories", "id", "title"),
+    ("memories", "id", "body"),
+    ("harness_captures", "id", "body"),
+    ("pending_gate_approvals", "id", "body"),
+    ("attachment_texts", "sha256", "body"),
+];
+
+/// A user-defined regex detector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactionDetector {
+    pub name: String,
+    pub pattern: String,
+}
+
+/// Flags tokens of at least `min_length` characters that mix letters and
+/// digits and whose Shannon entropy is at least `min_bits_per_char`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntropyRule {
+    pub min_length: usize,
+    pub min_bits_per_char: f64,
+}
+
+impl Default for EntropyRule {
+    fn default() -> Self {
+        Self {
+            min_length: 32,
+            min_bits_per_char: 4.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Built-in detectors (or [`ENTROPY_DETECTOR`]) turned off.
+    pub disabled_detectors: Vec<String>,
+    pub custom_detectors: Vec<RedactionDetector>,
+    pub entropy: EntropyRule,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            disabled_detectors: Vec::new(),
+            custom_detectors: Vec::new(),
+            entropy: EntropyRule::default(),
+        }
+    }
+}
+
+/// Redacted text plus how many matches each detector replaced.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Redaction {
+    pub text: String,
+    pub detectors: BTreeMap<String, usize>,
+}
+
+impl Redaction {
+    pub fn is_clean(&self) -> bool {
+        self.detectors.is_empty()
+    }
+}
+
+/// Compiled detectors for one [`RedactionConfig`].
+pub struct Redactor {
+    regexes: Vec<(String, Regex)>,
+    entropy: Option<EntropyRule>,
+}
+
+fn compile(name: &str, pattern: &str) -> Result<Regex, HubError> {
+    Regex::new(pattern).map_err(|e| HubError::Invalid(format!("redaction detector {name}: {e}")))
+}
+
+fn shannon_bits_per_char(token: &str) -> f64 {
+    let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+    for c in token.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let len = token.chars().count() as f64;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+impl Redactor {
+    pub fn new(config: &RedactionConfig) -> Result<Self, HubError> {
+        let enabled =
+            |name: &str| config.enabled && !config.disabled_detectors.iter().any(|d| d == name);
+        let mut regexes = Vec::new();
+        for (name, pattern) in BUILTIN_DETECTORS {
+            if enabled(name) {
+                regexes.push((name.to_string(), compile(name, pattern)?));
+            }
+        }
+        for detector in &config.custom_detectors {
+            if enabled(&detector.name) {
+                regexes.push((
+                    detector.name.clone(),
+                    compile(&detector.name, &detector.pattern)?,
+                ));
+            }
+        }
+        Ok(Self {
+            regexes,
+            entropy: enabled(ENTROPY_DETECTOR).then(|| config.entropy.clone()),
+        })
+    }
+
+    pub fn redact(&self, text: &str) -> Redaction {
+        let mut out = text.to_string();
+        let mut detectors = BTreeMap::new();
+        for (name, regex) in &self.regexes {
+            let placeholder = format!("[REDACTED:{name}]");
+            let mut hits = 0;
+            let replaced = regex.replace_all(&out, |caps: &Captures| {
+                let whole = caps.get(0).expect("group 0 always matches");
+                let secret = caps.name("secret").unwrap_or(whole);
+                // An earlier detector's placeholder is not a secret.
+                if secret.as_str().starts_with("[REDACTED:") {
+                    return whole.as_str().to_string();
+                }
+                hits += 1;
+                format!(
+                    "{}{placeholder}{}",
+                    &whole.as_str()[..secret.start() - whole.start()],
+                    &whole.as_str()[secret.end() - whole.start()..]
+                )
+            });
+            if hits > 0 {
+                out = replaced.into_owned();
+                detectors.insert(name.clone(), hits);
+            }
+        }
+        if let Some(rule) = &self.entropy {
+            let (text, hits) = redact_high_entropy(&out, rule);
+            if hits > 0 {
+                out = text;
+                detectors.insert(ENTROPY_DETECTOR.to_string(), hits);
+            }
+        }
+        Redaction {
+            text: out,
+            detectors,
+        }
+    }
+}
+
+fn redact_high_entropy(text: &str, rule: &EntropyRule) -> (String, usize) {
+    let is_token_char = |c: char| c.is_ascii_alphanumeric() || "+/=_-".contains(c);
+    let mut out = String::with_capacity(text.len());
+    let mut hits = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find(is_token_char) {
+        out.push_str(&rest[..start]);
+        let token_rest = &rest[start..];
+        let end = token_rest
+            .find(|c| !is_token_char(c))
+            .unwrap_or(token_rest.len());
+        let token = &token_rest[..end];
+        let suspicious = token.len() >= rule.min_length
+            && token.chars().any(|c| c.is_ascii_digit())
+            && token.chars().any(|c| c.is_ascii_alphabetic())
+            && shannon_bits_per_char(token) >= rule.min_bits_per_char;
+        if suspicious {
+            out.push_str(&format!("[REDACTED:{ENTROPY_DETECTOR}]"));
+            hits += 1;
+        } else {
+            out.push_str(token);
+        }
+        rest = &token_rest[end..];
+    }
+    out.push_str(rest);
+    (out, hits)
+}
+
+/// One row and column where `ca hub scan` found secrets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretFinding {
+    pub table: String,
+    pub id: String,
+    pub column: String,
+    pub detectors: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretScanReport {
+    pub scanned: usize,
+    pub findings: Vec<SecretFinding>,
+    /// Values rewritten with placeholders; 0 unless redaction was asked for.
+    pub redacted: usize,
+}
+
+impl HubStore {
+    pub fn redaction_config(&self) -> Result<RedactionConfig, HubError> {
+        let raw: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![CONFIG_META],
+                |r| r.get(0),
+            )
+            .optional()?;
+        match raw {
+            Some(s) => serde_json::from_str(&s)
+                .map_err(|e| HubError::Invalid(format!("{CONFIG_META} JSON corrupt: {e}"))),
+            None => Ok(RedactionConfig::default()),
+        }
+    }
+
+    /// Store `config` after checking every custom pattern compiles.
+    pub fn set_redaction_config(&self, config: &RedactionConfig) -> Result<(), HubError> {
+        Redactor::new(config)?;
+        let json = serde_json::to_string(config)
+            .map_err(|e| HubError::Invalid(format!("{CONFIG_META} serialize: {e}")))?;
+        self.conn.execute(
+            r#"
+            INSERT INTO meta(key, value) VALUES (?1, ?2)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value
+            "#,
+            params![CONFIG_META, json],
+        )?;
+        Ok(())
+    }
+
+    /// Redact `text` arriving from `source` (e.g. `harness_capture`) on
+    /// behalf of `agent`, recording a `redact` audit event when anything
+    /// matched. Returns the text to store.
+    pub fn redact_incoming(
+        &self,
+        source: &str,
+        agent: Option<&str>,
+        text: &str,
+    ) -> Result<String, HubError> {
+        let redaction = Redactor::new(&self.redaction_config()?)?.redact(text);
+        if !redaction.is_clean() {
+            self.record_redaction(source, agent, None, &redaction.detectors)?;
+        }
+        Ok(redaction.text)
+    }
+
+    fn record_redaction(
+        &self,
+        source: &str,
+        agent: Option<&str>,
+        row: Option<(&str, &str)>,
+        detectors: &BTreeMap<String, usize>,
+    ) -> Result<(), HubError> {
+        let process = serde_json::json!({
+            "source": source,
+            "agent": agent,
+            "row": row.map(|(table, id)| format!("{table}/{id}")),
+            "detectors": detectors,
+        });
+        self.record_audit_event(
+            &self.data_dir,
+            Path::new("hub").join(source).as_path(),
+            "redact",
+            &process.to_string(),
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Look for secrets in rows already stored. With `redact`, matching
+    /// values are rewritten with placeholders (one audit event each);
+    /// attachment files themselves are left alone, only their search text
+    /// is redacted.
+    pub fn scan_for_secrets(&self, redact: bool) -> Result<SecretScanReport, HubError> {
+        let redactor = Redactor::new(&self.redaction_config()?)?;
+        let mut report = SecretScanReport {
+            scanned: 0,
+            findings: Vec::new(),
+            redacted: 0,
+        };
+        for (table, key, column) in SCANNED_COLUMNS {
+            let plain = match is_sealed_column(table, column) {
+                true => format!("hub_plain({column})"),
+                false => column.to_string(),
+            };
+            let rows: Vec<(String, String)> = {
+                let mut stmt = self.conn.prepare(&format!(
+                    "SELECT {key}, {plain} FROM {table} WHERE {column} IS NOT NULL"
+                ))?;
+                let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+                rows.collect::<Result<_, _>>()?
+            };
+            for (id, text) in rows {
+                report.scanned += 1;
+                let redaction = redactor.redact(&text);
+                if redaction.is_clean() {
+                    continue;
+                }
+                if redact {
+                    let value = match is_sealed_column(table, column) {
+                        true => "hub_seal(?1)",
+                        false => "?1",
+                    };
+                    self.conn.execute(
+                        &format!("UPDATE {table} SET {column} = {value} WHERE {key} = ?2"),
+                        params![redaction.text, id],
+                    )?;
+                    self.record_redaction("scan", None, Some((table, &id)), &redaction.detectors)?;
+                    report.redacted += 1;
+                }
+                report.findings.push(SecretFinding {
+                    table: table.to_string(),
+                    id,
+                    column: column.to_string(),
+                    detectors: redaction.detectors,
+                });
+            }
+        }
+        Ok(report)
+    }
+}
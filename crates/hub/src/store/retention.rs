@@ -0,0 +1,258 @@
+//! Retention sweeps for `orchestration.retention_days`. Each row is held to
+//! the effective value for its workspace: messages and short-term memories
+//! carry their own, while harness captures, tagged-send outcomes and
+//! resolved wakes take the workspace of the message they belong to (the
+//! global value when there is none). Expired rows are written to a JSON
+//! Lines archive under `<hub_home>/retention-archive/` before they are
+//! deleted. Audit events are never swept so the hash chain stays
+//! verifiable; pending messages and wakes, open questions, and
+//! episodic/semantic memories are kept too.
+
+use super::backup::backup_stamp;
+use super::exports::sql_to_json;
+use super::*;
+use crate::settings::SettingsStore;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufWriter, Write};
+
+/// Directory under the hub home that holds retention archives.
+pub const RETENTION_ARCHIVE_DIR: &str = "retention-archive";
+
+/// `format` field of an archive's header line.
+pub const RETENTION_ARCHIVE_FORMAT: &str = "coding-assistants-retention-archive";
+
+/// A table the sweeper prunes. `from` aliases it as `t` (joining the owning
+/// message as `m` where the workspace comes from there) and opens the
+/// `WHERE` clause with the rows that are ever eligible.
+struct SweptTable {
+    table: &'static str,
+    from: &'static str,
+    workspace: &'static str,
+    time: &'static str,
+}
+
+/// Dependents first, so an archive reads in the order rows were removed.
+const SWEPT_TABLES: [SweptTable; 5] = [
+    SweptTable {
+        table: "harness_captures",
+        from: "harness_captures t LEFT JOIN messages m ON m.id = t.message_id WHERE 1=1",
+        workspace: "m.workspace_path",
+        time: "t.created_at",
+    },
+    SweptTable {
+        table: "tagged_send_outcomes",
+        from: "tagged_send_outcomes t LEFT JOIN messages m ON m.id = t.message_id WHERE 1=1",
+        workspace: "m.workspace_path",
+        time: "t.created_at",
+    },
+    SweptTable {
+        table: "wake_requests",
+        from: "wake_requests t LEFT JOIN messages m ON m.id = t.message_id
+               WHERE t.status != 'pending'",
+        workspace: "m.workspace_path",
+        time: "t.created_at",
+    },
+    SweptTable {
+        table: "memories",
+        from: "memories t WHERE t.tier = 'short_term'",
+        workspace: "t.workspace_path",
+        time: "t.updated_at",
+    },
+    SweptTable {
+        table: "messages",
+        from: "messages t WHERE t.status != 'pending'
+               AND NOT (t.kind = 'question' AND t.status != 'cancelled' AND NOT EXISTS (
+                   SELECT 1 FROM message_questions q WHERE q.message_id = t.id
+                     AND (q.answer_id IS NOT NULL
+                          OR julianday(q.deadline) <= julianday('now'))))",
+        workspace: "t.workspace_path",
+        time: "t.created_at",
+    },
+];
+
+/// Full-hub export written before a sweep removes anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionExport {
+    Markdown,
+    Jsonl,
+}
+
+impl RetentionExport {
+    pub fn parse(raw: &str) -> Result<Self, HubError> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "jsonl" => Ok(Self::Jsonl),
+            other => Err(HubError::Invalid(format!(
+                "unknown retention export: {other} (expected markdown or jsonl)"
+            ))),
+        }
+    }
+}
+
+/// The retention applied to one workspace (`None` is rows without one).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceRetention {
+    pub workspace: Option<String>,
+    pub retention_days: u32,
+    /// Rows last written before this instant are expired.
+    pub cutoff: String,
+}
+
+/// Result of [`HubStore::sweep_retention`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub swept_at: String,
+    /// Workspaces with rows under a retention limit; unlimited ones are
+    /// left out.
+    pub workspaces: Vec<WorkspaceRetention>,
+    /// Expired rows per table, removed unless `dry_run`.
+    pub expired: BTreeMap<String, usize>,
+    /// Archive of the removed rows; `None` on a dry run or when nothing
+    /// expired.
+    pub archive: Option<PathBuf>,
+    /// Export written before the sweep, when one was requested.
+    pub export: Option<PathBuf>,
+}
+
+impl RetentionReport {
+    pub fn total_expired(&self) -> usize {
+        self.expired.values().sum()
+    }
+}
+
+impl HubStore {
+    pub fn retention_archive_dir(&self) -> PathBuf {
+        self.data_dir.join(RETENTION_ARCHIVE_DIR)
+    }
+
+    /// Delete rows older than the effective `retention_days` for their
+    /// workspace, archiving them first. With `dry_run` nothing is written
+    /// and the report only counts. `export` writes a full Markdown or JSONL
+    /// export of the hub before anything is removed.
+    pub fn sweep_retention(
+        &self,
+        settings: &SettingsStore,
+        dry_run: bool,
+        export: Option<RetentionExport>,
+    ) -> Result<RetentionReport, HubError> {
+        let now = Utc::now();
+        let mut cutoffs: HashMap<Option<String>, Option<WorkspaceRetention>> = HashMap::new();
+        let mut expired: Vec<(&'static str, Vec<String>)> = Vec::new();
+        for swept in &SWEPT_TABLES {
+            let workspaces: Vec<Option<String>> = {
+                let mut stmt = self.conn.prepare(&format!(
+                    "SELECT DISTINCT {} FROM {}",
+                    swept.workspace, swept.from
+                ))?;
+                let rows = stmt.query_map([], |r| r.get(0))?;
+                rows.collect::<Result<_, _>>()?
+            };
+            let mut ids = Vec::new();
+            for workspace in workspaces {
+                let retention = cutoffs.entry(workspace.clone()).or_insert_with(|| {
+                    let days = settings
+                        .effective(workspace.as_deref())
+                        .orchestration
+                        .retention_days?;
+                    Some(WorkspaceRetention {
+                        workspace: workspace.clone(),
+                        retention_days: days,
+                        cutoff: (now - chrono::Duration::days(i64::from(days))).to_rfc3339(),
+                    })
+                });
+                let Some(retention) = retention else {
+                    continue;
+                };
+                let mut stmt = self.conn.prepare(&format!(
+                    "SELECT t.id FROM {} AND {} IS ?1 AND {} < ?2",
+                    swept.from, swept.workspace, swept.time
+                ))?;
+                let rows = stmt.query_map(params![workspace, retention.cutoff], |r| r.get(0))?;
+                ids.extend(rows.collect::<Result<Vec<String>, _>>()?);
+            }
+            expired.push((swept.table, ids));
+        }
+
+        let mut workspaces: Vec<WorkspaceRetention> = cutoffs.into_values().flatten().collect();
+        workspaces.sort_by(|a, b| a.workspace.cmp(&b.workspace));
+        let mut report = RetentionReport {
+            dry_run,
+            swept_at: now.to_rfc3339(),
+            workspaces,
+            expired: expired
+                .iter()
+                .map(|(table, ids)| (table.to_string(), ids.len()))
+                .collect(),
+            archive: None,
+            export: None,
+        };
+        if dry_run {
+            return Ok(report);
+        }
+
+        let stamp = backup_stamp();
+        let dir = self.retention_archive_dir();
+        if let Some(export) = export {
+            fs::create_dir_all(&dir)?;
+            report.export = Some(match export {
+                RetentionExport::Markdown => self.export_markdown(None)?,
+                RetentionExport::Jsonl => {
+                    let path = dir.join(format!("hub-export-{stamp}.jsonl"));
+                    let mut out = BufWriter::new(fs::File::create(&path)?);
+                    self.export_jsonl(&mut out)?;
+                    out.flush()?;
+                    path
+                }
+            });
+        }
+        if report.total_expired() == 0 {
+            return Ok(report);
+        }
+
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("retention-{stamp}.jsonl"));
+        let mut out = BufWriter::new(fs::File::create(&path)?);
+        let header = serde_json::json!({
+            "format": RETENTION_ARCHIVE_FORMAT,
+            "version": 1,
+            "swept_at": report.swept_at,
+            "schema_version": SCHEMA_VERSION,
+        });
+        writeln!(out, "{header}")?;
+        for (table, ids) in &expired {
+            for id in ids {
+                self.archive_row(&mut out, table, id)?;
+            }
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        for (table, ids) in &expired {
+            for id in ids {
+                tx.execute(&format!("DELETE FROM {table} WHERE id = ?1"), params![id])?;
+            }
+        }
+        tx.commit()?;
+        report.archive = Some(path);
+        Ok(report)
+    }
+
+    fn archive_row(&self, out: &mut impl Write, table: &str, id: &str) -> Result<(), HubError> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT * FROM {table} WHERE id = ?1"))?;
+        let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+        let mut rows = stmt.query(params![id])?;
+        if let Some(row) = rows.next()? {
+            let mut record = serde_json::Map::new();
+            for (i, column) in columns.iter().enumerate() {
+                record.insert(column.clone(), sql_to_json(row.get_ref(i)?)?);
+            }
+            let line = serde_json::json!({ "table": table, "record": record });
+            writeln!(out, "{line}")?;
+        }
+        Ok(())
+    }
+}
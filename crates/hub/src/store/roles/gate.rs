@@ -32,7 +32,7 @@ fn row_to_pending(row: &rusqlite::Row) -> rusqlite::Result<PendingGateApproval>
     })
 }
 
-const PENDING_COLUMNS: &str = "id, subject, from_agent, to_agents_json, is_task, is_wake, body, \
+const PENDING_COLUMNS: &str = "id, subject, from_agent, to_agents_json, is_task, is_wake, hub_plain(body), \
      workspace_path, task_id, session_id, reason, status, created_at, resolved_at";
 
 impl HubStore {
@@ -209,7 +209,7 @@ impl HubStore {
             INSERT INTO pending_gate_approvals(
                 id, subject, from_agent, to_agents_json, is_task, is_wake, body,
                 workspace_path, task_id, session_id, reason, status, created_at, resolved_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, 'pending', ?12, NULL)
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, hub_seal(?7), ?8, ?9, ?10, ?11, 'pending', ?12, NULL)
             "#,
             params![
                 id,
@@ -226,8 +226,11 @@ impl HubStore {
                 now,
             ],
         )?;
-        self.get_pending_gate_approval(&id)?
-            .ok_or_else(|| HubError::NotFound(id))
+        let approval = self
+            .get_pending_gate_approval(&id)?
+            .ok_or_else(|| HubError::NotFound(id))?;
+        self.emit_webhook_event(crate::WebhookEvent::GateApprovalPending, &approval)?;
+        Ok(approval)
     }
 
     pub fn get_pending_gate_approval(
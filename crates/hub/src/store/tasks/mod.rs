@@ -159,6 +159,38 @@ impl HubStore {
         Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
 
+    /// Tasks by creation time, newest first. Unlike [`Self::list_tasks`]
+    /// (ordered by last update), positions here do not move as tasks
+    /// progress, so cursors stay stable.
+    pub fn list_tasks_page(
+        &self,
+        status: Option<TaskStatus>,
+        page: &PageRequest,
+    ) -> Result<Page<TaskRecord>, HubError> {
+        let mut sql = String::from(
+            r#"
+            SELECT id, title, workspace_path, status, step_index, steps_json,
+                   created_at, updated_at, last_message_id,
+                   attempts_json, open_agents_json, pending_agents_json, max_parallel,
+                   require_human_approval
+            FROM tasks WHERE 1=1
+            "#,
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        if let Some(st) = status {
+            sql.push_str(" AND status = ?");
+            params_vec.push(Box::new(st.as_str().to_string()));
+        }
+        let keyset = push_keyset(&mut sql, &mut params_vec, page, "created_at", "id")?;
+        let mut stmt = self.conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt
+            .query_map(params_refs.as_slice(), Self::map_task_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(finish_page(rows, keyset, |t| (&t.created_at, &t.id)))
+    }
+
     pub(super) fn dispatch_step(
         &self,
         task_id: &str,
@@ -174,11 +206,12 @@ impl HubStore {
             step.instruction.clone()
         };
         let subject = Some(format!("[{}] {}", stage_label, task.title));
-        let msg = self.send_message(
+        let msg = self.send_message_with_payload(
             from_agent,
             &step.agent,
             MessageKind::Handoff,
             &body,
+            step.payload.as_ref(),
             subject.as_deref(),
             task.workspace_path.as_deref(),
             Some(task_id),
@@ -225,6 +258,11 @@ impl HubStore {
                 id,
             ],
         )?;
+        if status == TaskStatus::Failed.as_str() {
+            if let Some(task) = self.get_task(id)? {
+                self.emit_webhook_event(crate::WebhookEvent::TaskFailed, &task)?;
+            }
+        }
         Ok(())
     }
 
@@ -264,11 +302,30 @@ impl HubStore {
             return Err(HubError::Invalid("task has no steps".into()));
         }
 
+        // `None` completes the task.
         let next_stage = if status == TaskStatus::Pending {
-            0i64
+            Some(0i64)
         } else {
-            let ni = task.step_index + 1;
-            if ni >= stages.len() as i64 {
+            Some(task.step_index + 1).filter(|ni| *ni < stages.len() as i64)
+        };
+        let from_agent = from_agent.unwrap_or("human");
+        let from_stage = (status != TaskStatus::Pending).then_some(task.step_index);
+        let workspace = task.workspace_path.clone();
+        self.run_hooks(
+            HookEvent::PreTaskAdvance,
+            workspace.as_deref(),
+            &serde_json::json!({
+                "task": task,
+                "from_stage": from_stage,
+                "to_stage": next_stage,
+                "from_agent": from_agent,
+                "note": note,
+            }),
+        )?;
+
+        let advanced = match next_stage {
+            Some(stage) => self.activate_stage(id, &task, stage, from_agent, note)?,
+            None => {
                 self.persist_task_runtime(
                     id,
                     TaskStatus::Done.as_str(),
@@ -278,13 +335,21 @@ impl HubStore {
                     &[],
                     &[],
                 )?;
-                return self
-                    .get_task(id)?
-                    .ok_or_else(|| HubError::NotFound(id.into()));
+                self.get_task(id)?
+                    .ok_or_else(|| HubError::NotFound(id.into()))?
             }
-            ni
         };
-
-        self.activate_stage(id, &task, next_stage, from_agent.unwrap_or("human"), note)
+        self.run_hooks(
+            HookEvent::PostTaskAdvance,
+            workspace.as_deref(),
+            &serde_json::json!({
+                "task": advanced,
+                "from_stage": from_stage,
+                "to_stage": next_stage,
+                "from_agent": from_agent,
+                "note": note,
+            }),
+        )?;
+        Ok(advanced)
     }
 }
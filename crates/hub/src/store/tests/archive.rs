@@ -0,0 +1,137 @@
+//! Message, channel, and work-session archiving tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn post(store: &HubStore, subject: &str, body: &str) -> MessageRecord {
+    store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Message,
+            body,
+            Some(subject),
+            None,
+            None,
+        )
+        .unwrap()
+}
+
+#[test]
+fn archiving_needs_the_role_permission_and_hides_messages() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let message = post(&store, "channel:general", "old news");
+
+    let err = store.archive_message("codex", &message.id).unwrap_err();
+    assert!(
+        matches!(&err, HubError::Invalid(msg) if msg.contains("can_archive_messages")),
+        "{err}"
+    );
+
+    let outcome = store.archive_message("human", &message.id).unwrap();
+    assert_eq!((outcome.archived, outcome.messages), (true, 1));
+    assert!(store.list_messages(None, None).unwrap().is_empty());
+    assert!(store
+        .list_channel_messages("general", 10)
+        .unwrap()
+        .is_empty());
+    assert!(store.poll_messages("claude", false).unwrap().is_empty());
+    let archived = store
+        .list_archived_messages_page(&PageRequest::default())
+        .unwrap();
+    assert_eq!(archived.items[0].id, message.id);
+    assert!(archived.items[0].archived_at.is_some());
+    assert_eq!(
+        store.get_message(&message.id).unwrap().unwrap().status,
+        "pending",
+        "archiving keeps the status"
+    );
+
+    store.unarchive_message("human", &message.id).unwrap();
+    assert_eq!(store.list_messages(None, None).unwrap().len(), 1);
+}
+
+#[test]
+fn unarchiving_a_channel_restores_only_what_it_archived() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let archivist = store
+        .upsert_role(
+            "archivist",
+            "Archivist",
+            None,
+            None,
+            true,
+            false,
+            false,
+            &[],
+        )
+        .unwrap();
+    store.assign_agent_role("codex", &archivist.id).unwrap();
+    store.create_channel("release", None).unwrap();
+    let kept_archived = post(&store, "channel:release", "draft");
+    let first = post(&store, "channel:release", "ship it");
+    post(&store, "channel:general", "elsewhere");
+    store.archive_message("codex", &kept_archived.id).unwrap();
+
+    let outcome = store.archive_channel("codex", "#release").unwrap();
+    assert_eq!(outcome.messages, 1);
+    assert!(store
+        .list_channels()
+        .unwrap()
+        .iter()
+        .all(|c| c.id != "release"));
+    assert_eq!(store.list_archived_channels().unwrap()[0].id, "release");
+    assert!(matches!(
+        store.archive_channel("codex", "release"),
+        Err(HubError::Invalid(_))
+    ));
+    assert!(matches!(
+        store.archive_channel("codex", "general"),
+        Err(HubError::Invalid(_))
+    ));
+
+    let outcome = store.unarchive_channel("codex", "release").unwrap();
+    assert_eq!(outcome.messages, 1);
+    let visible = store.list_channel_messages("release", 10).unwrap();
+    assert_eq!(
+        visible.iter().map(|m| &m.id).collect::<Vec<_>>(),
+        vec![&first.id]
+    );
+}
+
+#[test]
+fn work_sessions_archive_with_their_chat() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let session = store.create_work_session("Sprint 12").unwrap();
+    post(
+        &store,
+        &format!("channel:session:{}", session.id),
+        "kickoff",
+    );
+
+    let outcome = store.archive_work_session("human", &session.id).unwrap();
+    assert_eq!(outcome.messages, 1);
+    assert!(store.list_work_sessions().unwrap().is_empty());
+    assert_eq!(
+        store.list_archived_work_sessions().unwrap()[0].id,
+        session.id
+    );
+    assert!(store
+        .list_channel_messages(&format!("session:{}", session.id), 10)
+        .unwrap()
+        .is_empty());
+
+    store.unarchive_work_session("human", &session.id).unwrap();
+    assert_eq!(store.list_work_sessions().unwrap().len(), 1);
+    assert!(matches!(
+        store.unarchive_work_session("human", &session.id),
+        Err(HubError::Invalid(_))
+    ));
+    assert!(matches!(
+        store.archive_work_session("human", "missing"),
+        Err(HubError::NotFound(_))
+    ));
+}
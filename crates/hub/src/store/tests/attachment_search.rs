@@ -0,0 +1,92 @@
+//! Attachment text extraction and search tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+#[test]
+fn search_finds_text_attachments_and_the_messages_embedding_them() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let log = store
+        .save_attachment(
+            "build.log",
+            "application/octet-stream",
+            b"step 3/9\nerror: Connection refused (os error 111)\nretrying",
+        )
+        .unwrap();
+    let message = store
+        .send_message(
+            "codex",
+            "claude",
+            MessageKind::Message,
+            &format!("CI failed, see [attachment:{}:build.log]", log.id),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+    let hits = store.search_attachments("connection REFUSED", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+    let hit = &hits[0];
+    assert_eq!(hit.attachment.id, log.id);
+    assert_eq!(hit.text_mime.as_deref(), Some("text/plain"));
+    assert!(
+        hit.snippet.contains("Connection refused"),
+        "{}",
+        hit.snippet
+    );
+    assert!(!hit.snippet.contains('\n'));
+    assert_eq!(hit.messages.len(), 1);
+    assert_eq!(hit.messages[0].id, message.id);
+
+    // Filename matches work for binaries, whose bytes are never indexed.
+    store
+        .save_attachment("refused.png", "text/plain", b"\x89PNG refused pixels")
+        .unwrap();
+    let hits = store.search_attachments("pixels", 10).unwrap();
+    assert!(hits.is_empty());
+    let hits = store.search_attachments("refused.png", 10).unwrap();
+    assert_eq!(hits[0].text_mime, None);
+    assert!(matches!(
+        store.search_attachments("  ", 10),
+        Err(HubError::Invalid(_))
+    ));
+}
+
+#[test]
+fn long_attachments_are_indexed_by_their_head() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let mut data = b"HEADMARKER ".to_vec();
+    data.resize(MAX_INDEXED_TEXT_BYTES + 10, b'x');
+    data.extend_from_slice(b" TAILMARKER");
+    store.save_attachment("dump.md", "", &data).unwrap();
+
+    let hits = store.search_attachments("headmarker", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert!(hits[0].truncated);
+    assert_eq!(hits[0].text_mime.as_deref(), Some("text/markdown"));
+    assert!(store
+        .search_attachments("tailmarker", 10)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn attachments_without_an_index_row_are_indexed_on_open() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    store
+        .save_attachment("notes.txt", "text/plain", b"rotate the signing key")
+        .unwrap();
+    store
+        .conn
+        .execute("DELETE FROM attachment_texts", [])
+        .unwrap();
+    assert!(store.search_attachments("signing", 10).unwrap().is_empty());
+    drop(store);
+
+    let store = HubStore::open(dir.path()).unwrap();
+    assert_eq!(store.search_attachments("signing", 10).unwrap().len(), 1);
+}
@@ -0,0 +1,185 @@
+//! Content-addressed attachment storage, garbage collection, and quota tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+/// Move an attachment's `created_at` past the gc grace window.
+fn backdate(store: &HubStore, id: &str) {
+    let old = (Utc::now() - chrono::Duration::hours(ATTACHMENT_GC_GRACE_HOURS + 1)).to_rfc3339();
+    store
+        .conn
+        .execute(
+            "UPDATE attachments SET created_at = ?1 WHERE id = ?2",
+            params![old, id],
+        )
+        .unwrap();
+}
+
+#[test]
+fn identical_bytes_share_one_blob() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let first = store
+        .save_attachment("shot.png", "image/png", b"same pixels")
+        .unwrap();
+    let second = store
+        .save_attachment("shot (1).png", "image/png", b"same pixels")
+        .unwrap();
+    assert_ne!(first.id, second.id);
+    assert_eq!(first.absolute_path, second.absolute_path);
+    assert_eq!(
+        first.sha256.as_deref(),
+        Some(sha256_hex(b"same pixels").as_str())
+    );
+
+    let usage = store.attachment_usage().unwrap();
+    assert_eq!((usage.attachments, usage.blobs), (2, 1));
+    assert_eq!(usage.stored_bytes, 11);
+    assert_eq!(usage.logical_bytes, 22);
+}
+
+#[test]
+fn gc_keeps_referenced_and_recent_attachments() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let in_message = store
+        .save_attachment("log.txt", "text/plain", b"shared bytes")
+        .unwrap();
+    let orphan_sharing_blob = store
+        .save_attachment("log-copy.txt", "text/plain", b"shared bytes")
+        .unwrap();
+    let orphan = store
+        .save_attachment("old.txt", "text/plain", b"nobody links me")
+        .unwrap();
+    let pending = store
+        .save_attachment("draft.png", "image/png", b"still in the composer")
+        .unwrap();
+    let avatar = store
+        .set_agent_avatar("claude", "me.png", "image/png", b"avatar bytes")
+        .unwrap()
+        .avatar_attachment_id
+        .unwrap();
+    store
+        .send_message(
+            "codex",
+            "claude",
+            MessageKind::Message,
+            &format!("see [attachment:{}:log.txt]", in_message.id),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let in_task = store
+        .save_attachment("spec.md", "text/markdown", b"# Spec")
+        .unwrap();
+    store
+        .create_task(
+            "Implement the spec",
+            None,
+            &[WorkflowStep {
+                agent: "codex".into(),
+                role: None,
+                instruction: format!("Follow attachment://{}", in_task.id),
+                max_retries: 0,
+                parallel_group: None,
+                payload: None,
+            }],
+        )
+        .unwrap();
+    for id in [
+        &in_message.id,
+        &orphan_sharing_blob.id,
+        &orphan.id,
+        &avatar,
+        &in_task.id,
+    ] {
+        backdate(&store, id);
+    }
+
+    let counts = store.attachment_reference_counts().unwrap();
+    assert_eq!(counts[&in_message.id], 1);
+    assert_eq!(counts[&avatar], 1);
+    assert_eq!(counts[&in_task.id], 1);
+    assert_eq!(counts[&orphan.id], 0);
+
+    let dry = store.gc_attachments(true).unwrap();
+    let removed: Vec<&str> = dry.removed.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(removed.len(), 2);
+    assert!(removed.contains(&orphan.id.as_str()));
+    assert!(removed.contains(&orphan_sharing_blob.id.as_str()));
+    assert_eq!(dry.removed_files.len(), 1, "the shared blob stays");
+    assert_eq!(dry.reclaimed_bytes, 15);
+    assert_eq!(dry.kept_recent, 1);
+    assert!(store.get_attachment_record(&orphan.id).unwrap().is_some());
+    assert!(Path::new(&orphan.absolute_path).is_file());
+
+    let report = store.gc_attachments(false).unwrap();
+    assert_eq!(report.removed.len(), 2);
+    assert!(store.get_attachment_record(&orphan.id).unwrap().is_none());
+    assert!(!Path::new(&orphan.absolute_path).exists());
+    assert!(Path::new(&in_message.absolute_path).is_file());
+    assert!(store.read_attachment(&pending.id).unwrap().is_some());
+
+    // Clearing the avatar drops its only reference.
+    store.clear_agent_avatar("claude").unwrap();
+    let report = store.gc_attachments(false).unwrap();
+    assert_eq!(report.removed[0].id, avatar);
+}
+
+#[test]
+fn quota_refuses_new_bytes_but_not_duplicates() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    assert!(matches!(
+        store.set_attachment_quota(Some(0)),
+        Err(HubError::Invalid(_))
+    ));
+    store.set_attachment_quota(Some(12)).unwrap();
+    assert_eq!(store.attachment_quota().unwrap(), Some(12));
+
+    store
+        .save_attachment("a.bin", "application/octet-stream", b"eight by")
+        .unwrap();
+    store
+        .save_attachment("b.bin", "application/octet-stream", b"eight by")
+        .unwrap();
+    let err = store
+        .save_attachment("c.bin", "application/octet-stream", b"five!")
+        .unwrap_err();
+    assert!(
+        matches!(&err, HubError::Invalid(msg) if msg.contains("quota exceeded") && msg.contains("13 of 12")),
+        "{err}"
+    );
+
+    store.set_attachment_quota(None).unwrap();
+    store
+        .save_attachment("c.bin", "application/octet-stream", b"five!")
+        .unwrap();
+}
+
+#[test]
+fn legacy_per_upload_files_move_into_blobs_on_open() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let legacy = store.attachments_dir().join("legacy-id__notes.txt");
+    fs::write(&legacy, b"old layout").unwrap();
+    store
+        .conn
+        .execute(
+            "INSERT INTO attachments (id, filename, mime, byte_size, relative_path, created_at)
+             VALUES ('legacy-id', 'notes.txt', 'text/plain', 10, 'legacy-id__notes.txt', ?1)",
+            params![Utc::now().to_rfc3339()],
+        )
+        .unwrap();
+    drop(store);
+
+    let store = HubStore::open(dir.path()).unwrap();
+    let (record, data) = store.read_attachment("legacy-id").unwrap().unwrap();
+    assert_eq!(data, b"old layout");
+    assert_eq!(
+        record.sha256.as_deref(),
+        Some(sha256_hex(b"old layout").as_str())
+    );
+    assert!(!legacy.exists());
+}
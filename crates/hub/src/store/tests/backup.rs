@@ -0,0 +1,176 @@
+//! Online backup, rotation, verification, and restore tests.
+
+use super::super::*;
+use crate::settings::MAX_BACKUP_RETENTION;
+use tempfile::tempdir;
+
+fn seed(store: &HubStore) -> (MemoryRecord, AttachmentRecord) {
+    let memory = store
+        .write_memory(
+            MemoryTier::Semantic,
+            MemoryScope::Global,
+            Some("claude"),
+            None,
+            None,
+            "Backups keep three generations by default.",
+            &[],
+        )
+        .unwrap();
+    let attachment = store
+        .save_attachment("diagram.png", "image/png", b"\x89PNG fake bytes")
+        .unwrap();
+    store
+        .record_audit_event(
+            store.data_dir(),
+            Path::new("notes.md"),
+            "modified",
+            "{}",
+            None,
+        )
+        .unwrap();
+    (memory, attachment)
+}
+
+#[test]
+fn backup_restores_database_and_attachments_after_verification() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("hub");
+    let store = HubStore::open(&home).unwrap();
+    let (memory, attachment) = seed(&store);
+
+    let backup = store.backup(None, 3).unwrap();
+    assert!(backup.path.starts_with(home.join(HUB_BACKUP_DIR)));
+    assert_eq!(backup.manifest.files[0].path, "hub.db");
+    let relative = Path::new(&attachment.absolute_path)
+        .strip_prefix(&home)
+        .unwrap()
+        .to_string_lossy()
+        .replace('\\', "/");
+    assert!(backup.manifest.files.iter().any(|f| f.path == relative));
+    let verified = HubStore::verify_backup(&backup.path).unwrap();
+    assert_eq!(verified.audit_events, 1);
+
+    // Damage the live hub after the backup, then restore.
+    store.delete_memory(&memory.id).unwrap();
+    fs::remove_file(&attachment.absolute_path).unwrap();
+    drop(store);
+
+    let outcome = HubStore::restore_backup(&home, &backup.path).unwrap();
+    assert!(outcome.previous.join("hub.db").is_file());
+    let store = HubStore::open(&home).unwrap();
+    assert!(store.get_memory(&memory.id).unwrap().is_some());
+    assert!(Path::new(&attachment.absolute_path).is_file());
+    assert_eq!(store.verify_audit_chain().unwrap(), 1);
+}
+
+#[test]
+fn tampered_backups_are_refused_before_anything_is_moved() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("hub");
+    let store = HubStore::open(&home).unwrap();
+    let (memory, attachment) = seed(&store);
+    let backup = store.backup(Some(&dir.path().join("out")), 1).unwrap();
+    drop(store);
+
+    let relative = Path::new(&attachment.absolute_path)
+        .strip_prefix(&home)
+        .unwrap()
+        .to_path_buf();
+    fs::write(backup.path.join(&relative), b"tampered").unwrap();
+    assert!(matches!(
+        HubStore::verify_backup(&backup.path),
+        Err(HubError::Invalid(_))
+    ));
+    assert!(HubStore::restore_backup(&home, &backup.path).is_err());
+
+    let store = HubStore::open(&home).unwrap();
+    assert!(store.get_memory(&memory.id).unwrap().is_some());
+    assert!(!home.join(HUB_BACKUP_DIR).exists(), "nothing set aside");
+}
+
+#[test]
+fn manifest_paths_outside_the_backup_are_refused() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("hub");
+    let store = HubStore::open(&home).unwrap();
+    seed(&store);
+    let backup = store.backup(Some(&dir.path().join("out")), 1).unwrap();
+    drop(store);
+
+    // A correctly hashed absolute entry: only the path check stands
+    // between it and a restore that reads (and copies) outside the backup.
+    let outside = dir.path().join("outside.txt");
+    fs::write(&outside, b"not part of any backup").unwrap();
+    let manifest_path = backup.path.join("manifest.json");
+    let mut manifest: BackupManifest =
+        serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    manifest.files.push(BackupFile {
+        path: outside.to_string_lossy().into_owned(),
+        sha256: sha256_hex(b"not part of any backup"),
+        bytes: 22,
+    });
+    fs::write(&manifest_path, serde_json::to_string(&manifest).unwrap()).unwrap();
+
+    assert!(matches!(
+        HubStore::verify_backup(&backup.path),
+        Err(HubError::Invalid(message)) if message.contains("escapes the backup")
+    ));
+    assert!(HubStore::restore_backup(&home, &backup.path).is_err());
+    assert_eq!(fs::read(&outside).unwrap(), b"not part of any backup");
+    assert!(!home.join(HUB_BACKUP_DIR).exists(), "nothing set aside");
+}
+
+#[test]
+fn a_restore_that_fails_midway_puts_the_live_hub_back() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("hub");
+    let store = HubStore::open(&home).unwrap();
+    let (memory, attachment) = seed(&store);
+    let backup = store.backup(Some(&dir.path().join("out")), 1).unwrap();
+    store.delete_memory(&memory.id).unwrap();
+    drop(store);
+
+    // A dangling `wake` symlink is not set aside, so placing the staged
+    // `wake` directory fails after hub.db and attachments have moved.
+    let _ = fs::remove_dir_all(home.join("wake"));
+    std::os::unix::fs::symlink(home.join("missing"), home.join("wake")).unwrap();
+    assert!(HubStore::restore_backup(&home, &backup.path).is_err());
+    fs::remove_file(home.join("wake")).unwrap();
+
+    let store = HubStore::open(&home).unwrap();
+    assert!(
+        store.get_memory(&memory.id).unwrap().is_none(),
+        "the live database is back, not the backup"
+    );
+    assert!(Path::new(&attachment.absolute_path).is_file());
+    let leftovers = fs::read_dir(&home)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().starts_with(".restore-"))
+        .count();
+    assert_eq!(leftovers, 0, "staging is cleaned up");
+    assert!(fs::read_dir(home.join(HUB_BACKUP_DIR))
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(true));
+}
+
+#[test]
+fn backups_rotate_to_the_retention_count() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let mut made = Vec::new();
+    for _ in 0..3 {
+        made.push(store.backup(None, 2).unwrap());
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+    assert_eq!(made[2].pruned, vec![made[0].path.clone()]);
+    let kept = list_hub_backups(&store.backups_dir()).unwrap();
+    assert_eq!(kept, vec![made[2].path.clone(), made[1].path.clone()]);
+
+    assert!(matches!(
+        store.backup(None, MAX_BACKUP_RETENTION + 1),
+        Err(HubError::Invalid(_))
+    ));
+    assert!(matches!(store.backup(None, 0), Err(HubError::Invalid(_))));
+}
@@ -1,6 +1,18 @@
 use super::super::*;
+use super::for_each_backend;
 use tempfile::tempdir;
 
+fn step(agent: &str) -> WorkflowStep {
+    WorkflowStep {
+        agent: agent.into(),
+        role: None,
+        instruction: "review the diff".into(),
+        max_retries: 0,
+        parallel_group: None,
+        payload: None,
+    }
+}
+
 #[test]
 fn team_broadcast_uses_enrolled_roster_and_includes_human() {
     let dir = tempdir().unwrap();
@@ -260,6 +272,7 @@ fn c5_sequential_task_advance_plan_code_review() {
             instruction: "Plan the dual-mode pathing fix.".into(),
             max_retries: 0,
             parallel_group: None,
+            payload: None,
         },
         WorkflowStep {
             agent: "claude".into(),
@@ -267,6 +280,7 @@ fn c5_sequential_task_advance_plan_code_review() {
             instruction: "Implement the plan.".into(),
             max_retries: 0,
             parallel_group: None,
+            payload: None,
         },
         WorkflowStep {
             agent: "gemini".into(),
@@ -274,6 +288,7 @@ fn c5_sequential_task_advance_plan_code_review() {
             instruction: "Review the implementation.".into(),
             max_retries: 0,
             parallel_group: None,
+            payload: None,
         },
     ];
     let task = store
@@ -319,6 +334,7 @@ fn c5_bounded_parallel_and_retry() {
             instruction: "Plan".into(),
             max_retries: 0,
             parallel_group: None,
+            payload: None,
         },
         WorkflowStep {
             agent: "dev_a".into(),
@@ -326,6 +342,7 @@ fn c5_bounded_parallel_and_retry() {
             instruction: "Code path A".into(),
             max_retries: 1,
             parallel_group: Some("impl".into()),
+            payload: None,
         },
         WorkflowStep {
             agent: "dev_b".into(),
@@ -333,6 +350,7 @@ fn c5_bounded_parallel_and_retry() {
             instruction: "Code path B".into(),
             max_retries: 1,
             parallel_group: Some("impl".into()),
+            payload: None,
         },
         WorkflowStep {
             agent: "dev_c".into(),
@@ -340,6 +358,7 @@ fn c5_bounded_parallel_and_retry() {
             instruction: "Code path C".into(),
             max_retries: 1,
             parallel_group: Some("impl".into()),
+            payload: None,
         },
         WorkflowStep {
             agent: "reviewer".into(),
@@ -347,6 +366,7 @@ fn c5_bounded_parallel_and_retry() {
             instruction: "Review all".into(),
             max_retries: 0,
             parallel_group: None,
+            payload: None,
         },
     ];
     // max_parallel=2 → wake two of three implementers first
@@ -413,3 +433,120 @@ fn c5_bounded_parallel_and_retry() {
         .unwrap();
     assert_eq!(finished.status, "done");
 }
+
+#[test]
+fn messages_are_sent_paged_and_polled() {
+    for_each_backend(|hub| {
+        let first = hub
+            .send_message(
+                "human",
+                "claude",
+                MessageKind::Message,
+                "one",
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        let second = hub
+            .send_message(
+                "codex",
+                "claude",
+                MessageKind::Handoff,
+                "two",
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        hub.send_message(
+            "human",
+            "codex",
+            MessageKind::Message,
+            "other",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            hub.send_message(
+                "human",
+                "claude",
+                MessageKind::Message,
+                " ",
+                None,
+                None,
+                None
+            ),
+            Err(HubError::Invalid(_))
+        ));
+        assert_eq!(hub.get_message(&first.id).unwrap().unwrap().body, "one");
+
+        let page = hub
+            .list_messages_page(Some("claude"), None, &PageRequest::latest(1))
+            .unwrap();
+        assert_eq!(page.items[0].id, second.id);
+        assert!(page.has_more);
+        let older = PageRequest {
+            before: page.before,
+            ..PageRequest::latest(1)
+        };
+        let page = hub
+            .list_messages_page(Some("claude"), None, &older)
+            .unwrap();
+        assert_eq!(page.items[0].id, first.id);
+        assert!(!page.has_more);
+
+        let polled = hub.poll_messages("claude", true).unwrap();
+        assert_eq!(polled.len(), 2);
+        assert!(polled
+            .iter()
+            .all(|m| m.status == "acked" && m.acked_at.is_some()));
+        assert!(hub.poll_messages("claude", false).unwrap().is_empty());
+
+        let done = hub
+            .set_message_status(&first.id, MessageStatus::Done)
+            .unwrap();
+        assert_eq!(done.status, "done");
+        assert!(matches!(
+            hub.set_message_status("missing", MessageStatus::Done),
+            Err(HubError::NotFound(_))
+        ));
+        assert!(hub.ack_message("missing").unwrap().is_none());
+    });
+}
+
+#[test]
+fn tasks_are_created_listed_and_cancelled() {
+    for_each_backend(|hub| {
+        let task = hub
+            .create_task("Review", Some("/repo"), &[step("codex")])
+            .unwrap();
+        assert_eq!((task.status.as_str(), task.step_index), ("pending", 0));
+        assert!(matches!(
+            hub.create_task("Empty", None, &[]),
+            Err(HubError::Invalid(_))
+        ));
+        assert!(matches!(
+            hub.create_task("No agent", None, &[step(" ")]),
+            Err(HubError::Invalid(_))
+        ));
+        assert_eq!(
+            hub.list_tasks(Some(TaskStatus::Pending)).unwrap()[0].id,
+            task.id
+        );
+
+        let cancelled = hub.cancel_task(&task.id).unwrap();
+        assert_eq!(cancelled.status, "cancelled");
+        assert!(hub
+            .list_tasks(Some(TaskStatus::Pending))
+            .unwrap()
+            .is_empty());
+        assert_eq!(hub.get_task(&task.id).unwrap().unwrap().status, "cancelled");
+        assert!(matches!(
+            hub.cancel_task("missing"),
+            Err(HubError::NotFound(_))
+        ));
+    });
+}
@@ -0,0 +1,279 @@
+//! Encryption-at-rest tests.
+
+use super::super::*;
+use crate::settings::SecretReference;
+use tempfile::tempdir;
+
+fn key_file(dir: &Path, name: &str) -> SecretReference {
+    let path = dir.join(name);
+    fs::write(&path, format!("{}\n", generate_hub_key())).unwrap();
+    SecretReference::KeyFile {
+        path: path.to_string_lossy().to_string(),
+    }
+}
+
+fn raw_body(store: &HubStore, table: &str, id: &str) -> String {
+    store
+        .conn
+        .query_row(
+            &format!("SELECT body FROM {table} WHERE id = ?1"),
+            params![id],
+            |r| r.get(0),
+        )
+        .unwrap()
+}
+
+#[test]
+fn enabling_seals_existing_and_new_content_and_rotation_keeps_it_readable() {
+    let dir = tempdir().unwrap();
+    let keys = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let before = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "the deploy token lives in vault",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let journal = store
+        .append_private_journal("claude", "private worry about the migration")
+        .unwrap();
+    let attachment = store
+        .save_attachment("notes.txt", "text/plain", b"secret design notes")
+        .unwrap();
+
+    let first = key_file(keys.path(), "hub.key");
+    let change = store.enable_encryption(&first).unwrap();
+    assert!(change.enabled);
+    assert_eq!((change.values, change.attachments), (3, 1));
+    assert!(raw_body(&store, "messages", &before.id).starts_with("hubenc1:"));
+    assert!(!raw_body(&store, "memories", &journal.id).contains("migration"));
+    let on_disk = fs::read(&attachment.absolute_path).unwrap();
+    assert!(!on_disk.windows(6).any(|w| w == b"secret"));
+
+    let after = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "ping",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(after.body, "ping");
+    assert!(raw_body(&store, "messages", &after.id).starts_with("hubenc1:"));
+    assert_eq!(
+        store.get_memory(&journal.id).unwrap().unwrap().body,
+        "private worry about the migration"
+    );
+    assert_eq!(
+        store.search_memories_as(Some("claude"), "worry").unwrap()[0].id,
+        journal.id
+    );
+    let again = store
+        .save_attachment("copy.txt", "text/plain", b"secret design notes")
+        .unwrap();
+    assert_eq!(
+        again.absolute_path, attachment.absolute_path,
+        "dedup by plaintext hash"
+    );
+    assert_eq!(
+        store.search_attachments("design", 5).unwrap()[0].snippet,
+        "secret design notes"
+    );
+
+    let second = key_file(keys.path(), "hub-2.key");
+    let old_id = store.encryption_status().unwrap().key_id;
+    store.rotate_encryption_key(&second).unwrap();
+    assert!(matches!(
+        store.rotate_encryption_key(&second),
+        Err(HubError::Invalid(_))
+    ));
+    drop(store);
+    fs::remove_file(keys.path().join("hub.key")).unwrap();
+
+    let store = HubStore::open(dir.path()).unwrap();
+    let status = store.encryption_status().unwrap();
+    assert!(status.enabled && status.unlocked);
+    assert_ne!(status.key_id, old_id);
+    assert_eq!(
+        store.get_message(&before.id).unwrap().unwrap().body,
+        "the deploy token lives in vault"
+    );
+    let (_, bytes) = store.read_attachment(&attachment.id).unwrap().unwrap();
+    assert_eq!(bytes, b"secret design notes");
+
+    store.disable_encryption().unwrap();
+    assert_eq!(
+        raw_body(&store, "messages", &before.id),
+        "the deploy token lives in vault"
+    );
+    assert_eq!(
+        fs::read(&attachment.absolute_path).unwrap(),
+        b"secret design notes"
+    );
+}
+
+#[test]
+fn a_missing_key_opens_the_hub_but_refuses_sealed_reads_and_writes() {
+    let dir = tempdir().unwrap();
+    let var = "CA_HUB_TEST_KEY_MISSING";
+    std::env::set_var(var, generate_hub_key());
+    let store = HubStore::open(dir.path()).unwrap();
+    let message = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "hello",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    store
+        .enable_encryption(&SecretReference::EnvVar { name: var.into() })
+        .unwrap();
+    drop(store);
+    std::env::remove_var(var);
+
+    let store = HubStore::open(dir.path()).unwrap();
+    let status = store.encryption_status().unwrap();
+    assert!(status.enabled && !status.unlocked);
+    assert!(status.error.unwrap().contains(var));
+    let err = store.get_message(&message.id).unwrap_err().to_string();
+    assert!(err.contains(var), "{err}");
+    assert!(store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "leak?",
+            None,
+            None,
+            None
+        )
+        .is_err());
+    assert!(store.list_agents().is_ok(), "unsealed tables still work");
+    assert!(store.disable_encryption().is_err());
+
+    std::env::set_var(var, generate_hub_key());
+    let store = HubStore::open(dir.path()).unwrap();
+    let error = store.encryption_status().unwrap().error.unwrap();
+    assert!(error.contains("not this hub's key"), "{error}");
+    std::env::remove_var(var);
+}
+
+#[test]
+fn a_rotation_that_fails_after_swapping_blobs_puts_the_originals_back() {
+    let dir = tempdir().unwrap();
+    let keys = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let attachment = store
+        .save_attachment("notes.txt", "text/plain", b"secret design notes")
+        .unwrap();
+    let message = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "hello",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    store
+        .enable_encryption(&key_file(keys.path(), "hub.key"))
+        .unwrap();
+    let sealed_blob = fs::read(&attachment.absolute_path).unwrap();
+
+    // A value that can't be opened fails the database pass, which now runs
+    // after the blobs have been swapped in under the new key.
+    store
+        .conn
+        .execute(
+            "UPDATE messages SET body = 'hubenc1:AAAA' WHERE id = ?1",
+            params![message.id],
+        )
+        .unwrap();
+    assert!(store
+        .rotate_encryption_key(&key_file(keys.path(), "hub-2.key"))
+        .is_err());
+
+    assert_eq!(fs::read(&attachment.absolute_path).unwrap(), sealed_blob);
+    let (_, bytes) = store.read_attachment(&attachment.id).unwrap().unwrap();
+    assert_eq!(bytes, b"secret design notes");
+    let blob_dir = Path::new(&attachment.absolute_path).parent().unwrap();
+    assert_eq!(
+        fs::read_dir(blob_dir).unwrap().count(),
+        1,
+        "no .tmp or .bak left"
+    );
+}
+
+#[test]
+fn plaintext_that_looks_sealed_round_trips_with_encryption_off_and_on() {
+    let dir = tempdir().unwrap();
+    let keys = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let mut bodies = Vec::new();
+    for body in ["hubenc1:hello there", "hubraw1:already marked"] {
+        let message = store
+            .send_message(
+                "human",
+                "codex",
+                MessageKind::Message,
+                body,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(message.body, body);
+        bodies.push(body);
+    }
+    let memory = store
+        .write_memory(
+            MemoryTier::Semantic,
+            MemoryScope::Global,
+            None,
+            None,
+            None,
+            "hubenc1:not a ciphertext",
+            &[],
+        )
+        .unwrap();
+    let blob = b"HUBENC1\0looks sealed".to_vec();
+    let attachment = store
+        .save_attachment("odd.bin", "application/octet-stream", &blob)
+        .unwrap();
+
+    let check = |store: &HubStore| {
+        let inbox = store.list_messages(Some("codex"), None).unwrap();
+        let mut read: Vec<_> = inbox.iter().map(|m| m.body.as_str()).collect();
+        read.sort();
+        assert_eq!(read, bodies);
+        assert_eq!(
+            store.get_memory(&memory.id).unwrap().unwrap().body,
+            "hubenc1:not a ciphertext"
+        );
+        assert_eq!(
+            store.read_attachment(&attachment.id).unwrap().unwrap().1,
+            blob
+        );
+    };
+    check(&store);
+    store
+        .enable_encryption(&key_file(keys.path(), "hub.key"))
+        .unwrap();
+    check(&store);
+    store.disable_encryption().unwrap();
+    check(&store);
+}
@@ -0,0 +1,211 @@
+//! Escalation tests.
+
+use super::super::*;
+use chrono::{DateTime, Duration};
+use std::collections::BTreeMap;
+use std::os::unix::fs::PermissionsExt;
+use tempfile::tempdir;
+
+fn handoff_sla(store: &HubStore, minutes: u64) {
+    store
+        .set_escalation_config(&EscalationConfig {
+            ack_within_minutes: BTreeMap::from([("handoff".to_string(), minutes)]),
+        })
+        .unwrap();
+}
+
+#[test]
+fn unacked_handoffs_escalate_one_step_per_missed_deadline() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    handoff_sla(&store, 30);
+    let handoff = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Handoff,
+            "implement the parser",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let ordinary = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "fyi",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let sent = DateTime::parse_from_rfc3339(&handoff.created_at)
+        .unwrap()
+        .with_timezone(&Utc);
+    let at = |minutes| sent + Duration::minutes(minutes);
+
+    assert!(store.run_escalations(at(10)).unwrap().is_empty());
+    let first = store.run_escalations(at(31)).unwrap();
+    assert_eq!(first.len(), 1, "only the handoff kind has a deadline");
+    assert_eq!(first[0].message_id, handoff.id);
+    assert_eq!((first[0].level, first[0].step), (1, EscalationStep::Rewake));
+    assert_eq!(first[0].notified, vec!["claude".to_string()]);
+    assert!(first[0].wake_id.is_some() || first[0].error.is_some());
+    let notice = store.get_message(&first[0].notice_ids[0]).unwrap().unwrap();
+    assert_eq!(notice.from_agent, "system");
+    assert_eq!(notice.kind, "system");
+    assert_eq!(notice.reply_to.as_deref(), Some(handoff.id.as_str()));
+    assert!(notice.body.contains(&handoff.id));
+    assert!(
+        store.run_escalations(at(45)).unwrap().is_empty(),
+        "the next step waits for the next deadline"
+    );
+
+    let second = store.run_escalations(at(61)).unwrap();
+    assert_eq!(second[0].step, EscalationStep::Allocators);
+    assert!(second[0].notified.contains(&"grok".to_string()));
+    assert!(!second[0].notified.contains(&"claude".to_string()));
+    let third = store.run_escalations(at(91)).unwrap();
+    assert_eq!(third[0].step, EscalationStep::Human);
+    assert_eq!(third[0].notified, vec!["human".to_string()]);
+    assert!(store.run_escalations(at(500)).unwrap().is_empty());
+
+    let recorded = store.list_escalations(Some(&handoff.id)).unwrap();
+    assert_eq!(recorded.len(), 3);
+    assert!(store
+        .list_escalations(Some(&ordinary.id))
+        .unwrap()
+        .is_empty());
+    let thread = store.list_thread(&handoff.id).unwrap();
+    assert_eq!(thread.len(), 1 + 1 + second[0].notified.len() + 1);
+}
+
+#[test]
+fn acked_messages_do_not_escalate_and_deadlines_are_validated() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    handoff_sla(&store, 5);
+    let handoff = store
+        .send_message(
+            "grok",
+            "claude",
+            MessageKind::Handoff,
+            "review",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    store
+        .set_message_status(&handoff.id, MessageStatus::Acked)
+        .unwrap();
+    assert!(store
+        .run_escalations(Utc::now() + Duration::hours(1))
+        .unwrap()
+        .is_empty());
+
+    for (kind, minutes) in [("handoff", 0), ("memo", 30)] {
+        let error = store
+            .set_escalation_config(&EscalationConfig {
+                ack_within_minutes: BTreeMap::from([(kind.to_string(), minutes)]),
+            })
+            .unwrap_err();
+        assert!(matches!(error, HubError::Invalid(_)), "{kind}={minutes}");
+    }
+    assert_eq!(
+        store.escalation_config().unwrap().ack_within_minutes["handoff"],
+        5
+    );
+}
+
+#[test]
+fn a_claimed_level_is_never_taken_twice() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let handoff = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Handoff,
+            "implement the parser",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let messages = || store.list_messages(None, None).unwrap().len();
+
+    let first = store
+        .escalate(&handoff, 1, EscalationStep::Human, 30)
+        .unwrap()
+        .expect("the first pass claims level 1");
+    assert_eq!(first.notified, vec!["human".to_string()]);
+    let before = messages();
+    assert!(
+        store
+            .escalate(&handoff, 1, EscalationStep::Human, 30)
+            .unwrap()
+            .is_none(),
+        "a racing pass finds the level claimed"
+    );
+    assert_eq!(messages(), before, "and notifies no one");
+
+    store.ack_message(&handoff.id).unwrap();
+    assert!(store
+        .escalate(&handoff, 2, EscalationStep::Human, 30)
+        .unwrap()
+        .is_none());
+    assert_eq!(messages(), before);
+    assert_eq!(store.list_escalations(Some(&handoff.id)).unwrap().len(), 1);
+}
+
+#[test]
+fn a_failed_notice_is_recorded_without_stopping_the_pass() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    handoff_sla(&store, 30);
+    let send = |body| {
+        store
+            .send_message(
+                "human",
+                "claude",
+                MessageKind::Handoff,
+                body,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+    };
+    let blocked = send("first handoff");
+    let open = send("second handoff");
+    let hook = store.hooks_dir().join("pre-send");
+    fs::create_dir_all(store.hooks_dir()).unwrap();
+    fs::write(
+        &hook,
+        format!(
+            "#!/bin/sh\ncase \"$(cat)\" in *{}*) echo \"paused\"; exit 1;; esac\n",
+            blocked.id
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let taken = store
+        .run_escalations(Utc::now() + Duration::minutes(31))
+        .unwrap();
+    assert_eq!(taken.len(), 2);
+    let failed = taken.iter().find(|r| r.message_id == blocked.id).unwrap();
+    assert!(failed.notice_ids.is_empty());
+    assert!(failed
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("notice to claude failed"));
+    let sent = taken.iter().find(|r| r.message_id == open.id).unwrap();
+    assert_eq!(sent.notice_ids.len(), 1);
+    let recorded = store.list_escalations(Some(&blocked.id)).unwrap();
+    assert_eq!(recorded[0].error, failed.error, "the failure is kept");
+}
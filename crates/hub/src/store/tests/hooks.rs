@@ -0,0 +1,203 @@
+//! Hook script tests.
+
+use super::super::*;
+use std::os::unix::fs::PermissionsExt;
+use tempfile::tempdir;
+
+fn install_hook(dir: &Path, event: &str, script: &str) {
+    fs::create_dir_all(dir).unwrap();
+    let path = dir.join(event);
+    fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn step(agent: &str) -> WorkflowStep {
+    WorkflowStep {
+        agent: agent.into(),
+        role: None,
+        instruction: "ship it".into(),
+        max_retries: 0,
+        parallel_group: None,
+        payload: None,
+    }
+}
+
+#[test]
+fn pre_send_vetoes_with_the_hooks_reason_and_post_send_gets_the_message() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    install_hook(
+        &store.hooks_dir(),
+        "pre-send",
+        r#"case "$(cat)" in *"rm -rf"*) echo "no destructive commands"; exit 1;; esac"#,
+    );
+    install_hook(
+        &store.hooks_dir(),
+        "post-send",
+        r#"echo "$1" > "$CA_HOME/argv"; cat > "$CA_HOME/post-send.json""#,
+    );
+    fs::write(
+        store.hooks_dir().join("post-task-advance"),
+        "not executable",
+    )
+    .unwrap();
+
+    let vetoed = store
+        .send_message(
+            "grok",
+            "codex",
+            MessageKind::Message,
+            "please rm -rf /",
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+    assert!(
+        vetoed.to_string().contains("no destructive commands"),
+        "{vetoed}"
+    );
+    assert!(store.list_messages(None, None).unwrap().is_empty());
+
+    let message = store
+        .send_message(
+            "grok",
+            "codex",
+            MessageKind::Message,
+            "hello",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let event: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("post-send.json")).unwrap())
+            .unwrap();
+    assert_eq!(event["event"], "post-send");
+    assert_eq!(event["data"]["id"], message.id.as_str());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("argv")).unwrap().trim(),
+        "post-send"
+    );
+
+    let runs = store.list_hook_runs(Some(HookEvent::PreSend), 10).unwrap();
+    let outcomes: Vec<_> = runs.iter().map(|r| (r.exit_code, r.vetoed)).collect();
+    assert_eq!(outcomes, vec![(Some(0), false), (Some(1), true)]);
+    assert_eq!(runs[1].output.trim(), "no destructive commands");
+    let installed: Vec<_> = store
+        .list_hooks(None)
+        .unwrap()
+        .into_iter()
+        .map(|h| h.event)
+        .collect();
+    assert_eq!(installed, vec![HookEvent::PreSend, HookEvent::PostSend]);
+}
+
+#[test]
+fn workspace_pre_task_advance_can_hold_a_task_until_signed_off() {
+    let dir = tempdir().unwrap();
+    let workspace = tempdir().unwrap();
+    let ws = workspace.path().to_str().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    install_hook(
+        &workspace.path().join(WORKSPACE_HOOKS_DIR),
+        "pre-task-advance",
+        r#"[ -f signed-off ] || { echo "waiting for sign-off" >&2; exit 3; }"#,
+    );
+    let task = store
+        .create_task("Ship", Some(ws), &[step("codex"), step("claude")])
+        .unwrap();
+    assert!(
+        store.list_hooks(Some(ws)).unwrap().is_empty(),
+        "workspace hooks are off until enabled and trusted"
+    );
+    store
+        .set_hook_config(&HookConfig {
+            workspace_hooks: true,
+            ..HookConfig::default()
+        })
+        .unwrap();
+    assert!(
+        store.list_hooks(Some(ws)).unwrap().is_empty(),
+        "an untrusted workspace's hooks never run"
+    );
+    store.trust_hook_workspace(ws, true).unwrap();
+    assert_eq!(store.list_hooks(Some(ws)).unwrap().len(), 1);
+
+    let held = store.advance_task(&task.id, None, None).unwrap_err();
+    assert!(held.to_string().contains("waiting for sign-off"), "{held}");
+    assert_eq!(store.get_task(&task.id).unwrap().unwrap().status, "pending");
+
+    fs::write(workspace.path().join("signed-off"), "").unwrap();
+    let running = store.advance_task(&task.id, None, None).unwrap();
+    assert_eq!(
+        (running.status.as_str(), running.step_index),
+        ("running", 0)
+    );
+
+    fs::remove_file(workspace.path().join("signed-off")).unwrap();
+    store.trust_hook_workspace(ws, false).unwrap();
+    assert!(store.list_hooks(Some(ws)).unwrap().is_empty());
+    let advanced = store.advance_task(&task.id, None, None).unwrap();
+    assert_eq!(advanced.step_index, 1);
+}
+
+#[test]
+fn observer_hooks_never_undo_the_event_and_slow_pre_hooks_time_out() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    install_hook(&store.hooks_dir(), "memory-written", "exit 1");
+    install_hook(
+        &store.hooks_dir(),
+        "wake-resolved",
+        r#"cat > "$CA_HOME/wake.json""#,
+    );
+    let memory = store
+        .write_memory(
+            MemoryTier::Semantic,
+            MemoryScope::Global,
+            None,
+            None,
+            None,
+            "deploys go through staging",
+            &[],
+        )
+        .unwrap();
+    assert!(store.get_memory(&memory.id).unwrap().is_some());
+    let run = &store
+        .list_hook_runs(Some(HookEvent::MemoryWritten), 1)
+        .unwrap()[0];
+    assert_eq!((run.exit_code, run.vetoed), (Some(1), false));
+
+    let wake = store
+        .request_wake("codex", Some("review"), None, false)
+        .unwrap();
+    store
+        .set_wake_status(&wake.id, WakeStatus::Delivered)
+        .unwrap();
+    let event: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("wake.json")).unwrap()).unwrap();
+    assert_eq!(event["data"]["id"], wake.id.as_str());
+    assert_eq!(event["data"]["status"], "delivered");
+
+    install_hook(&store.hooks_dir(), "pre-send", "sleep 5");
+    store
+        .set_hook_config(&HookConfig {
+            timeout_secs: 1,
+            ..HookConfig::default()
+        })
+        .unwrap();
+    let error = store
+        .send_message(
+            "grok",
+            "codex",
+            MessageKind::Message,
+            "hi",
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+    assert!(error.to_string().ends_with("vetoed: timed out"), "{error}");
+    assert!(store.list_hook_runs(Some(HookEvent::PreSend), 1).unwrap()[0].timed_out);
+}
@@ -0,0 +1,274 @@
+//! JSON Lines hub export/import tests.
+
+use super::super::*;
+use std::collections::HashSet;
+use std::io::Cursor;
+use tempfile::tempdir;
+
+struct Seeded {
+    memory: MemoryRecord,
+    message: MessageRecord,
+    task: TaskRecord,
+}
+
+fn seed(store: &HubStore) -> Seeded {
+    store.upsert_agent("reviewer", "Reviewer").unwrap();
+    let session = store.create_work_session("release").unwrap();
+    store
+        .add_work_session_member(&session.id, "reviewer")
+        .unwrap();
+    let memory = store
+        .write_memory(
+            MemoryTier::Semantic,
+            MemoryScope::Global,
+            Some("claude"),
+            None,
+            None,
+            "Release notes live in docs/CHANGELOG.md.",
+            &[],
+        )
+        .unwrap();
+    let other = store
+        .write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::Global,
+            Some("claude"),
+            None,
+            None,
+            "Drafted the 0.4 notes.",
+            &[],
+        )
+        .unwrap();
+    store
+        .link_memories(&other.id, &memory.id, Some("extends"), "claude")
+        .unwrap();
+    let task = store
+        .create_task(
+            "Ship 0.4",
+            None,
+            &[WorkflowStep {
+                agent: "reviewer".into(),
+                role: None,
+                instruction: "Review the notes".into(),
+                max_retries: 0,
+                parallel_group: None,
+                payload: None,
+            }],
+        )
+        .unwrap();
+    let message = store
+        .send_message(
+            "claude",
+            "reviewer",
+            MessageKind::Message,
+            &format!("Per [Memory #{}] the notes are ready.", &memory.id[..8]),
+            None,
+            None,
+            Some(&task.id),
+        )
+        .unwrap();
+    store
+        .request_wake("reviewer", Some("review"), Some(&message.id), false)
+        .unwrap();
+    store.set_agent_budget("reviewer", 10.0).unwrap();
+    Seeded {
+        memory,
+        message,
+        task,
+    }
+}
+
+fn export(store: &HubStore) -> Vec<u8> {
+    let mut out = Vec::new();
+    store.export_jsonl(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn export_round_trips_into_a_fresh_hub_preserving_ids_and_timestamps() {
+    let source_dir = tempdir().unwrap();
+    let source = HubStore::open(source_dir.path()).unwrap();
+    let seeded = seed(&source);
+    let mut bytes = Vec::new();
+    let summary = source.export_jsonl(&mut bytes).unwrap();
+    assert_eq!(summary.counts["memory"], 2);
+    assert_eq!(summary.counts["memory_link"], 1);
+    let first: HubExportHeader =
+        serde_json::from_str(String::from_utf8_lossy(&bytes).lines().next().unwrap()).unwrap();
+    assert_eq!(first.format, HUB_EXPORT_FORMAT);
+
+    let target_dir = tempdir().unwrap();
+    let target = HubStore::open(target_dir.path()).unwrap();
+    let report = target.import_jsonl(Cursor::new(&bytes), false).unwrap();
+    assert!(report.remapped_ids.is_empty(), "{report:#?}");
+    assert_eq!(report.kinds["memory"].inserted, 2);
+    assert_eq!(report.kinds["message"].inserted, 1);
+    assert_eq!(report.kinds["wake"].inserted, 1);
+    assert_eq!(report.kinds["budget"].inserted, 1);
+    assert!(report.kinds["agent"].skipped >= 8, "seeded agents merge");
+
+    let memory = target.get_memory(&seeded.memory.id).unwrap().unwrap();
+    assert_eq!(memory.created_at, seeded.memory.created_at);
+    let message = target.get_message(&seeded.message.id).unwrap().unwrap();
+    assert_eq!(message.created_at, seeded.message.created_at);
+    assert_eq!(message.task_id.as_deref(), Some(seeded.task.id.as_str()));
+    assert_eq!(target.memory_citations(&memory.id).unwrap().len(), 1);
+    assert!(target.get_budget("reviewer").unwrap().is_some());
+    assert_eq!(target.list_work_sessions().unwrap().len(), 1);
+}
+
+#[test]
+fn importing_into_a_hub_with_the_same_ids_remaps_and_rewrites_references() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let seeded = seed(&store);
+    let bytes = export(&store);
+
+    let report = store.import_jsonl(Cursor::new(&bytes), false).unwrap();
+    assert_eq!(report.kinds["memory"].remapped, 2);
+    assert_eq!(report.kinds["message"].remapped, 1);
+    let new_memory = &report
+        .remapped_ids
+        .iter()
+        .find(|r| r.kind == "memory" && r.from == seeded.memory.id)
+        .unwrap()
+        .to;
+    let new_task = &report
+        .remapped_ids
+        .iter()
+        .find(|r| r.kind == "task")
+        .unwrap()
+        .to;
+    let new_message = &report
+        .remapped_ids
+        .iter()
+        .find(|r| r.kind == "message")
+        .unwrap()
+        .to;
+
+    let copy = store.get_message(new_message).unwrap().unwrap();
+    assert_eq!(copy.task_id.as_deref(), Some(new_task.as_str()));
+    assert!(copy
+        .body
+        .contains(&format!("[Memory #{}]", &new_memory[..8])));
+    assert_eq!(
+        store.list_memory_links(new_memory).unwrap().len(),
+        1,
+        "the copied link joins the copied memories"
+    );
+    assert_eq!(store.list_wakes(Some("reviewer"), false).unwrap().len(), 2);
+}
+
+#[test]
+fn dry_run_reports_without_writing_and_bad_input_is_rejected() {
+    let source_dir = tempdir().unwrap();
+    let source = HubStore::open(source_dir.path()).unwrap();
+    seed(&source);
+    let bytes = export(&source);
+
+    let target_dir = tempdir().unwrap();
+    let target = HubStore::open(target_dir.path()).unwrap();
+    let report = target.import_jsonl(Cursor::new(&bytes), true).unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.kinds["memory"].inserted, 2);
+    assert!(target
+        .list_memories(None, None, None, true)
+        .unwrap()
+        .is_empty());
+
+    let wrong_version = format!(
+        "{{\"format\":\"{HUB_EXPORT_FORMAT}\",\"version\":99,\"exported_at\":\"x\",\"schema_version\":1}}\n"
+    );
+    assert!(matches!(
+        target.import_jsonl(Cursor::new(wrong_version), false),
+        Err(HubError::Invalid(_))
+    ));
+    let unknown_kind = format!(
+        "{}\n{{\"kind\":\"spaceship\",\"record\":{{}}}}\n",
+        String::from_utf8_lossy(&bytes).lines().next().unwrap()
+    );
+    assert!(matches!(
+        target.import_jsonl(Cursor::new(unknown_kind), false),
+        Err(HubError::Invalid(_))
+    ));
+}
+
+#[test]
+fn remapped_promotions_keep_pointing_at_their_remapped_source() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let note = store
+        .write_memory(
+            MemoryTier::ShortTerm,
+            MemoryScope::Global,
+            Some("claude"),
+            None,
+            None,
+            "Flaky test traced to a shared temp dir.",
+            &[],
+        )
+        .unwrap();
+    let promoted = store.promote_memory(&note.id, MemoryTier::Episodic).unwrap();
+    assert_eq!(promoted.source_event_id.as_deref(), Some(note.id.as_str()));
+
+    let report = store.import_jsonl(Cursor::new(export(&store)), false).unwrap();
+    let remapped = |id: &str| {
+        report
+            .remapped_ids
+            .iter()
+            .find(|r| r.kind == "memory" && r.from == id)
+            .unwrap()
+            .to
+            .clone()
+    };
+    let copy = store.get_memory(&remapped(&promoted.id)).unwrap().unwrap();
+    assert_eq!(copy.source_event_id, Some(remapped(&note.id)));
+}
+
+#[test]
+fn an_id_repeated_in_the_file_gets_its_own_id_per_record() {
+    let source_dir = tempdir().unwrap();
+    let source = HubStore::open(source_dir.path()).unwrap();
+    let seeded = seed(&source);
+    let text = String::from_utf8(export(&source)).unwrap();
+    let mut doubled = String::new();
+    for line in text.lines() {
+        doubled.push_str(line);
+        doubled.push('\n');
+        if line.contains("\"kind\":\"message\"") {
+            doubled.push_str(line);
+            doubled.push('\n');
+        }
+    }
+
+    let target_dir = tempdir().unwrap();
+    let target = HubStore::open(target_dir.path()).unwrap();
+    let report = target
+        .import_jsonl(Cursor::new(doubled.as_bytes()), false)
+        .unwrap();
+    assert_eq!(report.kinds["message"].inserted, 2);
+    let copies: Vec<_> = report
+        .remapped_ids
+        .iter()
+        .filter(|r| r.kind == "message")
+        .collect();
+    assert_eq!(copies.len(), 1, "only the repeat is remapped");
+    assert_ne!(copies[0].to, seeded.message.id);
+    assert!(target.get_message(&seeded.message.id).unwrap().is_some());
+    assert!(target.get_message(&copies[0].to).unwrap().is_some());
+
+    // Back into the source hub, every occurrence collides and each gets
+    // a distinct fresh id.
+    let report = source
+        .import_jsonl(Cursor::new(doubled.as_bytes()), false)
+        .unwrap();
+    assert_eq!(report.kinds["message"].inserted, 2);
+    let fresh: HashSet<_> = report
+        .remapped_ids
+        .iter()
+        .filter(|r| r.kind == "message")
+        .map(|r| r.to.clone())
+        .collect();
+    assert_eq!(fresh.len(), 2);
+    assert!(!fresh.contains(&seeded.message.id));
+}
@@ -0,0 +1,162 @@
+//! Memory citation index and citation-warning tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn note(store: &HubStore, body: &str) -> MemoryRecord {
+    store
+        .write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::Global,
+            Some("claude"),
+            None,
+            None,
+            body,
+            &[],
+        )
+        .unwrap()
+}
+
+fn cite(store: &HubStore, to: &str, body: &str) -> MessageRecord {
+    store
+        .send_message("claude", to, MessageKind::Message, body, None, None, None)
+        .unwrap()
+}
+
+#[test]
+fn citations_are_indexed_on_send_and_backfilled_for_older_messages() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let memory = note(&store, "Staging runs on port 8443.");
+    let short = &memory.id[..8];
+    let first = cite(
+        &store,
+        "codex",
+        &format!("Use [Memory #{short}] (also [Memory #{}]).", memory.id),
+    );
+    let second = cite(&store, "grok", &format!("[Memory #{}]", memory.id));
+    cite(&store, "grok", "No references here. [Memory #nope]");
+
+    let citations = store.memory_citations(&memory.id).unwrap();
+    let ids: Vec<&str> = citations.iter().map(|c| c.message.id.as_str()).collect();
+    assert_eq!(ids, vec![first.id.as_str(), second.id.as_str()]);
+    assert_eq!(citations[0].reference, short, "first spelling is kept");
+
+    // A hub from before the index: drop the rows and the marker, reopen.
+    store
+        .conn
+        .execute_batch(
+            "DELETE FROM memory_citations; DELETE FROM meta WHERE key = 'memory_citations_indexed';",
+        )
+        .unwrap();
+    drop(store);
+    let store = HubStore::open(dir.path()).unwrap();
+    assert_eq!(store.memory_citations(&memory.id).unwrap().len(), 2);
+    assert_eq!(store.rebuild_memory_citations().unwrap(), 2);
+}
+
+#[test]
+fn warnings_cover_stale_deleted_and_revised_citations() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let stale = note(&store, "The release branch is release/0.4.");
+    let deleted = note(&store, "Ping ops in #deploys before merging.");
+    let revised = note(&store, "Feature freeze is on the 12th.");
+    let steady = note(&store, "CI runs on every push.");
+
+    let to_codex = cite(
+        &store,
+        "codex",
+        &format!(
+            "[Memory #{}] [Memory #{}] [Memory #{}]",
+            stale.id, deleted.id, steady.id
+        ),
+    );
+    let to_grok = cite(&store, "grok", &format!("[Memory #{}]", revised.id));
+    assert!(store.citation_warnings(None).unwrap().is_empty());
+
+    store.mark_memory_stale(&stale.id, true).unwrap();
+    store.delete_memory(&deleted.id).unwrap();
+    store
+        .update_memory(&revised.id, None, "Feature freeze moved to the 19th.", None)
+        .unwrap();
+
+    let warnings = store.citation_warnings(None).unwrap();
+    let mut seen: Vec<(&str, &str, CitationIssue)> = warnings
+        .iter()
+        .map(|w| (w.message_id.as_str(), w.memory_id.as_str(), w.issue))
+        .collect();
+    seen.sort_by(|a, b| a.1.cmp(b.1));
+    let mut expected = vec![
+        (
+            to_codex.id.as_str(),
+            stale.id.as_str(),
+            CitationIssue::Stale,
+        ),
+        (
+            to_codex.id.as_str(),
+            deleted.id.as_str(),
+            CitationIssue::Deleted,
+        ),
+        (
+            to_grok.id.as_str(),
+            revised.id.as_str(),
+            CitationIssue::Revised,
+        ),
+    ];
+    expected.sort_by(|a, b| a.1.cmp(b.1));
+    assert_eq!(seen, expected);
+    assert!(warnings
+        .iter()
+        .find(|w| w.issue == CitationIssue::Deleted)
+        .unwrap()
+        .memory_updated_at
+        .is_none());
+    assert_eq!(
+        store.memory_citations(&deleted.id).unwrap().len(),
+        1,
+        "citations outlive the memory"
+    );
+
+    let grok = store.citation_warnings(Some("grok")).unwrap();
+    assert_eq!(grok.len(), 1);
+    assert_eq!(grok[0].memory_id, revised.id);
+    assert_eq!(
+        CitationIssue::parse("revised").unwrap(),
+        CitationIssue::Revised
+    );
+}
+
+#[test]
+fn merge_moves_citations_to_the_canonical_memory() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let canonical = note(&store, "Release 0.4 ships on Friday.");
+    let duplicate = note(&store, "release 0.4 ships on friday!");
+    let both = cite(
+        &store,
+        "codex",
+        &format!("[Memory #{}] [Memory #{}]", canonical.id, duplicate.id),
+    );
+    let dup_only = cite(&store, "grok", &format!("[Memory #{}]", duplicate.id));
+
+    store
+        .merge_memories(&canonical.id, std::slice::from_ref(&duplicate.id))
+        .unwrap();
+
+    assert!(store.memory_citations(&duplicate.id).unwrap().is_empty());
+    let ids: Vec<String> = store
+        .memory_citations(&canonical.id)
+        .unwrap()
+        .into_iter()
+        .map(|c| c.message.id)
+        .collect();
+    assert_eq!(ids, vec![both.id, dup_only.id]);
+    assert!(
+        store.citation_warnings(None).unwrap().is_empty(),
+        "a merged duplicate is not a stale citation"
+    );
+}
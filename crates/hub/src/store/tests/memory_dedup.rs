@@ -0,0 +1,169 @@
+//! Near-duplicate memory detection and merge tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn episode(store: &HubStore, agent: &str, workspace: Option<&str>, body: &str) -> MemoryRecord {
+    let scope = if workspace.is_some() {
+        MemoryScope::Workspace
+    } else {
+        MemoryScope::Global
+    };
+    store
+        .write_memory(
+            MemoryTier::Episodic,
+            scope,
+            Some(agent),
+            workspace,
+            None,
+            body,
+            &[],
+        )
+        .unwrap()
+}
+
+#[test]
+fn dedup_clusters_near_duplicates_within_scope_and_workspace_only() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let body = "The checkout deploy failed at 14:02 because the payments migration \
+                timed out; rolled back and re-ran it with a longer lock timeout.";
+    let first = episode(&store, "claude", Some("/repo"), body);
+    let reworded = episode(
+        &store,
+        "codex",
+        Some("/repo"),
+        &format!("{} Deploy is green now.", body.to_uppercase()),
+    );
+    let elsewhere = episode(&store, "grok", Some("/other"), body);
+    let unrelated = episode(
+        &store,
+        "grok",
+        Some("/repo"),
+        "Agreed to keep the settings file in TOML rather than JSON.",
+    );
+
+    let proposals = store
+        .find_duplicate_memories(None, None, None, DEFAULT_DEDUP_THRESHOLD)
+        .unwrap();
+    assert_eq!(proposals.len(), 1, "{proposals:#?}");
+    assert_eq!(
+        proposals[0].canonical.id, first.id,
+        "oldest member is canonical"
+    );
+    assert_eq!(proposals[0].duplicates.len(), 1);
+    assert_eq!(proposals[0].duplicates[0].memory.id, reworded.id);
+    assert!(proposals[0].duplicates[0].similarity >= DEFAULT_DEDUP_THRESHOLD);
+
+    let ids: Vec<&str> = [&elsewhere, &unrelated].map(|m| m.id.as_str()).to_vec();
+    assert!(proposals
+        .iter()
+        .flat_map(|p| p.duplicates.iter())
+        .all(|d| !ids.contains(&d.memory.id.as_str())));
+
+    assert!(matches!(
+        store.find_duplicate_memories(None, None, None, 0.0),
+        Err(HubError::Invalid(_))
+    ));
+}
+
+#[test]
+fn merge_repoints_links_and_references_and_stales_the_duplicates() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let canonical = episode(&store, "claude", None, "Release 0.4 ships on Friday.");
+    let duplicate = episode(&store, "codex", None, "release 0.4 ships on friday!");
+    let other = episode(
+        &store,
+        "grok",
+        None,
+        "Release notes live in docs/CHANGELOG.md.",
+    );
+
+    let kept = store
+        .link_memories(&duplicate.id, &other.id, Some("extends"), "human")
+        .unwrap();
+    store
+        .link_memories(&canonical.id, &duplicate.id, None, "human")
+        .unwrap();
+    let short = &duplicate.id[..8];
+    let message = store
+        .send_message(
+            "claude",
+            "codex",
+            MessageKind::Message,
+            &format!("See [Memory #{short}] and [Memory #{}].", duplicate.id),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+    let report = store
+        .dedup_memories(None, None, None, DEFAULT_DEDUP_THRESHOLD, true)
+        .unwrap();
+    assert_eq!(report.examined, 3);
+    assert_eq!(report.applied.len(), 1);
+    let outcome = &report.applied[0];
+    assert_eq!(outcome.canonical_id, canonical.id);
+    assert_eq!(outcome.merged, vec![duplicate.id.clone()]);
+    assert_eq!(outcome.links_dropped, 1, "canonical <-> duplicate edge");
+    assert_eq!(outcome.links_repointed, 1);
+    assert_eq!(outcome.references_rewritten, 2);
+
+    let moved = store.get_memory_link(&kept.id).unwrap().unwrap();
+    assert_eq!(moved.from_memory_id, canonical.id);
+    assert_eq!(moved.relation.as_deref(), Some("extends"));
+
+    let body = store.get_message(&message.id).unwrap().unwrap().body;
+    assert_eq!(
+        body,
+        format!(
+            "See [Memory #{}] and [Memory #{}].",
+            &canonical.id[..8],
+            canonical.id
+        )
+    );
+    let resolved: Vec<String> = store
+        .list_message_memories(&message.id)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    assert_eq!(resolved, vec![canonical.id.clone(), canonical.id.clone()]);
+
+    assert!(store.get_memory(&duplicate.id).unwrap().unwrap().stale);
+    assert!(store
+        .find_duplicate_memories(None, None, None, DEFAULT_DEDUP_THRESHOLD)
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        store.merge_memories(&duplicate.id, std::slice::from_ref(&other.id)),
+        Err(HubError::Invalid(_))
+    ));
+
+    let merges: Vec<AuditEvent> = store
+        .list_audit_events(false)
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.operation == "merge")
+        .collect();
+    assert_eq!(merges.len(), 1);
+    assert!(merges[0].path.ends_with(&canonical.id));
+    assert!(merges[0].process_json.contains(&duplicate.id));
+
+    // Memories in another scope or workspace are never folded in.
+    let elsewhere = episode(
+        &store,
+        "codex",
+        Some("/work/app"),
+        "Release 0.4 ships on Friday.",
+    );
+    let error = store
+        .merge_memories(&canonical.id, std::slice::from_ref(&elsewhere.id))
+        .unwrap_err();
+    assert!(error.to_string().contains("different scope"), "{error}");
+    assert!(!store.get_memory(&elsewhere.id).unwrap().unwrap().stale);
+}
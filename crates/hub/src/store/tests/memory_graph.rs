@@ -0,0 +1,175 @@
+//! Whole-graph memory export and graph-query tests (M-links follow-up).
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn note(store: &HubStore, title: &str, workspace: Option<&str>) -> MemoryRecord {
+    let scope = if workspace.is_some() {
+        MemoryScope::Workspace
+    } else {
+        MemoryScope::Global
+    };
+    store
+        .write_memory(
+            MemoryTier::Episodic,
+            scope,
+            Some("claude"),
+            workspace,
+            Some(title),
+            &format!("{title} body"),
+            &[],
+        )
+        .unwrap()
+}
+
+#[test]
+fn graph_queries_find_paths_clusters_orphans_and_live_contradictions() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let a = note(&store, "a", None);
+    let b = note(&store, "b", None);
+    let c = note(&store, "c", None);
+    let d = note(&store, "d", None);
+    let lonely = note(&store, "lonely", None);
+    let stale = note(&store, "stale", None);
+
+    store
+        .link_memories(&a.id, &b.id, Some("extends"), "human")
+        .unwrap();
+    // Direction is ignored for traversal: c -> b still connects a to c.
+    store.link_memories(&c.id, &b.id, None, "human").unwrap();
+    store
+        .link_memories(&a.id, &c.id, Some("Contradicts"), "human")
+        .unwrap();
+    store
+        .link_memories(&d.id, &stale.id, Some("contradicts"), "human")
+        .unwrap();
+    store.mark_memory_stale(&stale.id, true).unwrap();
+
+    let path = store.memory_path(None, &a.id, &c.id).unwrap().unwrap();
+    assert_eq!(path.first().unwrap().id, a.id);
+    assert_eq!(path.last().unwrap().id, c.id);
+    assert_eq!(path.len(), 2, "the direct a-c edge is the shortest path");
+    assert!(store.memory_path(None, &a.id, &lonely.id).unwrap().is_none());
+    // A stale endpoint is never on a live path.
+    assert!(store.memory_path(None, &d.id, &stale.id).unwrap().is_none());
+    assert!(matches!(
+        store.memory_path(None, &a.id, "missing"),
+        Err(HubError::NotFound(_))
+    ));
+
+    let filter = MemoryGraphFilter::default();
+    let clusters = store.memory_clusters(None, &filter).unwrap();
+    assert_eq!(clusters.len(), 1);
+    let mut ids: Vec<&str> = clusters[0].iter().map(|m| m.id.as_str()).collect();
+    ids.sort_unstable();
+    let mut expected = vec![a.id.as_str(), b.id.as_str(), c.id.as_str()];
+    expected.sort_unstable();
+    assert_eq!(ids, expected);
+
+    // d's only edge points at a stale memory, so with stale rows filtered
+    // out it is just as orphaned as `lonely`.
+    let orphans: Vec<String> = store
+        .orphaned_memories(None, &filter)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    assert!(orphans.contains(&d.id));
+    assert!(orphans.contains(&lonely.id));
+    assert!(!orphans.contains(&a.id));
+
+    let contradictions = store.contradiction_report(None, &filter).unwrap();
+    assert_eq!(contradictions.len(), 1);
+    assert_eq!(contradictions[0].from.id, a.id);
+    assert_eq!(contradictions[0].to.id, c.id);
+}
+
+#[test]
+fn orphan_status_ignores_the_relation_filter() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let a = note(&store, "a", None);
+    let b = note(&store, "b", None);
+    let lonely = note(&store, "lonely", None);
+    store
+        .link_memories(&a.id, &b.id, Some("contradicts"), "human")
+        .unwrap();
+
+    // Asking about "extends" edges must not make a contradicts-only pair
+    // look unlinked.
+    let filter = MemoryGraphFilter {
+        relation: Some("extends".into()),
+        ..MemoryGraphFilter::default()
+    };
+    let orphans: Vec<String> = store
+        .orphaned_memories(None, &filter)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    assert_eq!(orphans, vec![lonely.id]);
+}
+
+#[test]
+fn graph_export_honours_filters_and_renders_each_format() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let a = note(&store, "alpha \"quoted\"", Some("/repo"));
+    let b = note(&store, "beta <tag>", Some("/repo"));
+    let other = note(&store, "elsewhere", Some("/other"));
+    store
+        .link_memories(&a.id, &b.id, Some("agrees"), "human")
+        .unwrap();
+    store
+        .link_memories(&b.id, &a.id, Some("contradicts"), "grok")
+        .unwrap();
+    store
+        .link_memories(&a.id, &other.id, None, "human")
+        .unwrap();
+
+    let repo_only = MemoryGraphFilter {
+        workspace_path: Some("/repo".into()),
+        ..MemoryGraphFilter::default()
+    };
+    let graph = store.memory_graph(None, &repo_only).unwrap();
+    assert_eq!(graph.nodes.len(), 2);
+    // The a -> other edge leaves the filtered node set, so it's dropped.
+    assert_eq!(graph.links.len(), 2);
+
+    let agrees_only = MemoryGraphFilter {
+        relation: Some("agrees".into()),
+        ..repo_only.clone()
+    };
+    assert_eq!(store.memory_graph(None, &agrees_only).unwrap().links.len(), 1);
+
+    let dot = store
+        .export_memory_graph(None, &repo_only, GraphExportFormat::Dot)
+        .unwrap();
+    assert!(dot.starts_with("digraph memories {"));
+    assert!(dot.contains(&format!("\"{}\" -> \"{}\"", a.id, b.id)));
+    assert!(dot.contains("alpha \\\"quoted\\\""));
+    assert!(!dot.contains(&other.id));
+
+    let graphml = store
+        .export_memory_graph(None, &repo_only, GraphExportFormat::GraphMl)
+        .unwrap();
+    assert!(graphml.contains("<graphml"));
+    assert!(graphml.contains("beta &lt;tag&gt;"));
+    assert!(graphml.contains("<data key=\"relation\">contradicts</data>"));
+
+    let json = store
+        .export_memory_graph(None, &repo_only, GraphExportFormat::Json)
+        .unwrap();
+    let parsed: MemoryGraph = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.nodes.len(), 2);
+
+    assert_eq!(
+        GraphExportFormat::parse("graphml").unwrap(),
+        GraphExportFormat::GraphMl
+    );
+    assert!(GraphExportFormat::parse("svg").is_err());
+}
@@ -0,0 +1,212 @@
+//! Session / agent-private memory scopes and the journal migration.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn ids(records: Vec<MemoryRecord>) -> Vec<String> {
+    records.into_iter().map(|m| m.id).collect()
+}
+
+#[test]
+fn session_and_private_memories_follow_reader_identity() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let session = store.create_work_session("release prep").unwrap();
+    store
+        .add_work_session_member(&session.id, "claude")
+        .unwrap();
+
+    let shared = store
+        .write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::Global,
+            Some("claude"),
+            None,
+            None,
+            "marker shared note",
+            &[],
+        )
+        .unwrap();
+    let in_session = store
+        .write_session_memory(
+            &session.id,
+            MemoryTier::ShortTerm,
+            Some("claude"),
+            None,
+            "marker session context",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(in_session.session_id.as_deref(), Some(session.id.as_str()));
+    let private = store
+        .write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::AgentPrivate,
+            Some("claude"),
+            None,
+            None,
+            "marker private scratch",
+            &[],
+        )
+        .unwrap();
+
+    let anonymous = ids(store.list_memories(None, None, None, false).unwrap());
+    assert_eq!(anonymous, vec![shared.id.clone()]);
+
+    let claude = ids(store.search_memories_as(Some("claude"), "marker").unwrap());
+    assert!(claude.contains(&shared.id));
+    assert!(claude.contains(&in_session.id));
+    assert!(claude.contains(&private.id));
+
+    let codex = ids(store
+        .list_memories_as(Some("codex"), None, None, None, false)
+        .unwrap());
+    assert_eq!(
+        codex,
+        vec![shared.id.clone()],
+        "not a member, not the owner"
+    );
+
+    let human = ids(store.search_memories_as(Some("human"), "marker").unwrap());
+    assert!(human.contains(&in_session.id));
+    assert!(!human.contains(&private.id));
+
+    assert!(matches!(
+        store.write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::Session,
+            Some("claude"),
+            None,
+            None,
+            "no session given",
+            &[],
+        ),
+        Err(HubError::Invalid(_))
+    ));
+    assert!(matches!(
+        store.write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::AgentPrivate,
+            None,
+            None,
+            None,
+            "no owner",
+            &[],
+        ),
+        Err(HubError::Invalid(_))
+    ));
+    assert!(matches!(
+        store.write_session_memory("missing", MemoryTier::Episodic, None, None, "x", &[]),
+        Err(HubError::NotFound(_))
+    ));
+}
+
+#[test]
+fn legacy_journal_files_become_private_memories_on_open() {
+    let dir = tempdir().unwrap();
+    let journal_dir = dir.path().join("journals").join("grok");
+    std::fs::create_dir_all(&journal_dir).unwrap();
+    std::fs::write(
+        journal_dir.join("journal.md"),
+        "\n## 2025-01-02T03:04:05+00:00\n\nfirst thought\n\
+         \n## 2025-01-03T03:04:05+00:00\n\nsecond thought\nwith two lines\n",
+    )
+    .unwrap();
+
+    let store = HubStore::open(dir.path()).unwrap();
+    assert!(!journal_dir.join("journal.md").exists());
+    assert!(journal_dir.join("journal.md.migrated").exists());
+
+    let journal = store
+        .list_memories_as(
+            Some("grok"),
+            Some(MemoryScope::AgentPrivate),
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+    assert_eq!(journal.len(), 2);
+    assert_eq!(journal[0].body, "second thought\nwith two lines");
+    assert_eq!(journal[1].created_at, "2025-01-02T03:04:05+00:00");
+    assert!(journal[0].tags_json.contains(JOURNAL_TAG));
+
+    // Reopening doesn't import twice; new entries land in the store.
+    drop(store);
+    let store = HubStore::open(dir.path()).unwrap();
+    let appended = store
+        .append_private_journal("grok", "third thought")
+        .unwrap();
+    assert_eq!(appended.scope, MemoryScope::AgentPrivate.as_str());
+    let found = ids(store.search_memories_as(Some("grok"), "thought").unwrap());
+    assert_eq!(found.len(), 3);
+    assert!(store.search_memories("thought").unwrap().is_empty());
+}
+
+#[test]
+fn graph_and_dedup_views_hide_other_agents_private_memories() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let write_private = |body: &str| {
+        store
+            .write_memory(
+                MemoryTier::Episodic,
+                MemoryScope::AgentPrivate,
+                Some("claude"),
+                None,
+                None,
+                body,
+                &[],
+            )
+            .unwrap()
+    };
+    let first = write_private("deploy key rotated on the staging box tonight");
+    let second = write_private("deploy key rotated on the staging box tonight again");
+    let shared = store
+        .write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::Global,
+            Some("claude"),
+            None,
+            None,
+            "staging is frozen",
+            &[],
+        )
+        .unwrap();
+    store
+        .link_memories(&first.id, &shared.id, None, "claude")
+        .unwrap();
+
+    let filter = MemoryGraphFilter::default();
+    let export = store
+        .export_memory_graph(Some("grok"), &filter, GraphExportFormat::Json)
+        .unwrap();
+    assert!(!export.contains(&first.id));
+    assert!(export.contains(&shared.id));
+    let owner = store
+        .export_memory_graph(Some("claude"), &filter, GraphExportFormat::Json)
+        .unwrap();
+    assert!(owner.contains(&first.id));
+
+    let proposals = store
+        .find_duplicate_memories(Some("grok"), None, None, DEFAULT_DEDUP_THRESHOLD)
+        .unwrap();
+    assert!(proposals.is_empty());
+    let proposals = store
+        .find_duplicate_memories(Some("claude"), None, None, DEFAULT_DEDUP_THRESHOLD)
+        .unwrap();
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].canonical.id, first.id);
+    assert_eq!(proposals[0].duplicates[0].memory.id, second.id);
+
+    assert!(store.get_memory_as(&first.id, Some("grok")).unwrap().is_none());
+    assert!(store.related_memories(Some("grok"), &shared.id, 1).unwrap().is_empty());
+    assert_eq!(
+        ids(store.related_memories(Some("claude"), &shared.id, 1).unwrap()),
+        vec![first.id.clone()]
+    );
+    assert!(matches!(
+        store.memory_path(Some("grok"), &shared.id, &first.id),
+        Err(HubError::NotFound(_))
+    ));
+}
@@ -0,0 +1,142 @@
+//! Memory usage tracking and relevance ranking tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn short_note(store: &HubStore, body: &str) -> MemoryRecord {
+    store
+        .write_memory(
+            MemoryTier::ShortTerm,
+            MemoryScope::Global,
+            Some("claude"),
+            None,
+            None,
+            body,
+            &[],
+        )
+        .unwrap()
+}
+
+#[test]
+fn citations_dispatches_and_reads_feed_relevance_ordering() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let cited = short_note(&store, "cited note");
+    let dispatched = short_note(&store, "dispatched note");
+    let untouched = short_note(&store, "untouched note");
+
+    store
+        .send_message(
+            "claude",
+            "codex",
+            MessageKind::Message,
+            &format!("per [Memory #{}]", &cited.id[..8]),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    store
+        .send_message(
+            "human",
+            "codex",
+            MessageKind::Handoff,
+            &format!("do it, see [Memory #{}]", dispatched.id),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let read = store.read_memory(&cited.id, Some("grok")).unwrap().unwrap();
+    let relevance = read.relevance.unwrap();
+    assert_eq!(relevance.citations, 1);
+    assert_eq!(relevance.reads, 1);
+    assert_eq!(relevance.dispatches, 0);
+    assert!(relevance.last_used_at.is_some());
+
+    let listed = store.list_memories(None, None, None, false).unwrap();
+    let order: Vec<&str> = listed.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(
+        order,
+        vec![
+            cited.id.as_str(),
+            dispatched.id.as_str(),
+            untouched.id.as_str()
+        ]
+    );
+    assert_eq!(listed[1].relevance.as_ref().unwrap().dispatches, 1);
+    assert!(
+        listed[0].relevance.as_ref().unwrap().score > listed[2].relevance.as_ref().unwrap().score
+    );
+
+    // Private memories stay unreadable to anyone but their owner.
+    let private = store
+        .write_memory(
+            MemoryTier::Episodic,
+            MemoryScope::AgentPrivate,
+            Some("grok"),
+            None,
+            None,
+            "mine",
+            &[],
+        )
+        .unwrap();
+    assert!(store
+        .read_memory(&private.id, Some("claude"))
+        .unwrap()
+        .is_none());
+    assert!(store
+        .read_memory(&private.id, Some("grok"))
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn compaction_candidates_split_used_and_forgotten_short_term_notes() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+
+    let used = short_note(&store, "used note");
+    let forgotten = short_note(&store, "forgotten note");
+    let fresh = short_note(&store, "fresh note");
+    store
+        .record_memory_usage(&used.id, MemoryUsageKind::Dispatched, Some("human"), None)
+        .unwrap();
+    let long_ago = (Utc::now() - chrono::Duration::days(90)).to_rfc3339();
+    store
+        .conn
+        .execute(
+            "UPDATE memories SET created_at = ?1 WHERE id = ?2",
+            params![long_ago, forgotten.id],
+        )
+        .unwrap();
+
+    let candidates = store.compaction_candidates().unwrap();
+    let ids = |v: &[MemoryRecord]| v.iter().map(|m| m.id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&candidates.promote), vec![used.id.clone()]);
+    assert_eq!(ids(&candidates.stale), vec![forgotten.id.clone()]);
+    assert!(!ids(&candidates.stale).contains(&fresh.id));
+
+    assert!(matches!(
+        store.record_memory_usage("missing", MemoryUsageKind::Read, None, None),
+        Err(HubError::NotFound(_))
+    ));
+
+    // Compaction keeps the newest notes whatever their relevance, so the
+    // used note (a promote candidate) is promoted rather than kept.
+    let yesterday = (Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+    store
+        .conn
+        .execute(
+            "UPDATE memories SET created_at = ?1 WHERE id = ?2",
+            params![yesterday, used.id],
+        )
+        .unwrap();
+    let report = store.compact_short_term(1).unwrap();
+    assert_eq!((report.kept, report.promoted), (1, 2));
+    let short = store
+        .list_memories(None, Some(MemoryTier::ShortTerm), None, false)
+        .unwrap();
+    assert_eq!(ids(&short), vec![fresh.id.clone()]);
+}
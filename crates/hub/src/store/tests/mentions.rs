@@ -0,0 +1,136 @@
+//! @mention parsing, resolution and notification tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn post(store: &HubStore, from: &str, to: &str, subject: &str, body: &str) -> MessageRecord {
+    store
+        .send_message(
+            from,
+            to,
+            MessageKind::Message,
+            body,
+            Some(subject),
+            None,
+            None,
+        )
+        .unwrap()
+}
+
+#[test]
+fn mentions_are_parsed_outside_words_and_scoped_by_subject() {
+    assert_eq!(
+        parse_mentions("@claude, can you and @Code-Reviewer. look? mail ops@example.com @claude"),
+        vec!["claude", "Code-Reviewer"]
+    );
+    assert!(parse_mentions("no one @ all").is_empty());
+    assert_eq!(
+        conversation_scope(Some("channel:general:thread:1:abc")).as_deref(),
+        Some("channel:general")
+    );
+    assert_eq!(
+        conversation_scope(Some("channel:session:s1:reply:abc")).as_deref(),
+        Some("channel:session:s1")
+    );
+    assert_eq!(conversation_scope(Some("team:abc")), None);
+    assert_eq!(conversation_scope(None), None);
+}
+
+#[test]
+fn channel_mentions_notify_agents_names_and_role_holders_once_per_post() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    store.upsert_agent("codex", "Codex").unwrap();
+    store
+        .upsert_role("reviewer", "Reviewer", None, None, false, false, false, &[])
+        .unwrap();
+    store.assign_agent_role("grok", "reviewer").unwrap();
+    store.set_team_member("claude", true).unwrap();
+    store.set_team_member("grok", true).unwrap();
+
+    let copies = store
+        .send_message_to_team(
+            "human",
+            MessageKind::Message,
+            "@claude @CODEX @reviewer please look",
+            Some("channel:general:abc"),
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(copies.len() >= 2);
+    for agent in ["claude", "codex", "grok"] {
+        let mentions = store.list_mentions(agent, false, 10).unwrap();
+        assert_eq!(mentions.len(), 1, "{agent} is notified once");
+        assert_eq!(mentions[0].scope, "channel:general");
+        assert_eq!(mentions[0].from_agent, "human");
+        assert!(mentions[0].wake_id.is_none());
+    }
+    assert_eq!(
+        store.list_mentions("grok", false, 10).unwrap()[0].token,
+        "reviewer"
+    );
+
+    post(
+        &store,
+        "claude",
+        "human",
+        "channel:general:def",
+        "thanks @claude",
+    );
+    post(
+        &store,
+        "human",
+        "claude",
+        "team:xyz",
+        "@claude not a channel",
+    );
+    assert_eq!(
+        store.list_mentions("claude", false, 10).unwrap().len(),
+        1,
+        "self-mentions and non-channel posts notify no one"
+    );
+
+    let later = chrono::Utc::now() + chrono::Duration::seconds(1);
+    store
+        .mark_read("claude", "channel:general", Some(&later.to_rfc3339()))
+        .unwrap();
+    assert!(store.list_mentions("claude", true, 10).unwrap().is_empty());
+    assert_eq!(store.list_mentions("codex", true, 10).unwrap().len(), 1);
+}
+
+#[test]
+fn mention_wakes_follow_wake_policy() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    store.upsert_agent("codex", "codex").unwrap();
+    store
+        .set_mention_config(&MentionConfig { wake: true })
+        .unwrap();
+
+    post(
+        &store,
+        "human",
+        "codex",
+        "channel:general",
+        "@codex wake up",
+    );
+    let mention = &store.list_mentions("codex", false, 10).unwrap()[0];
+    let wake_id = mention.wake_id.as_deref().expect("wake filed");
+    assert!(store
+        .list_wakes(Some("codex"), true)
+        .unwrap()
+        .iter()
+        .any(|w| w.id == wake_id));
+
+    store
+        .set_wake_policy(&WakePolicy {
+            default_requires_human_gate: false,
+            allow_auto_wake: false,
+        })
+        .unwrap();
+    post(&store, "human", "codex", "channel:general", "@codex again");
+    let mention = &store.list_mentions("codex", false, 10).unwrap()[0];
+    assert!(mention.wake_id.is_none());
+    assert!(mention.wake_error.as_deref().unwrap().contains("auto-wake"));
+}
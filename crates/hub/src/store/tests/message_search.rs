@@ -0,0 +1,231 @@
+//! Message search tests.
+
+use super::super::*;
+use crate::settings::SecretReference;
+use tempfile::tempdir;
+
+fn send(
+    store: &HubStore,
+    from: &str,
+    to: &str,
+    subject: Option<&str>,
+    body: &str,
+) -> MessageRecord {
+    store
+        .send_message(from, to, MessageKind::Message, body, subject, None, None)
+        .unwrap()
+}
+
+fn search(query: &str) -> MessageSearch {
+    MessageSearch {
+        query: query.into(),
+        limit: 20,
+        ..MessageSearch::default()
+    }
+}
+
+fn ids(hits: &[MessageSearchHit]) -> Vec<&str> {
+    hits.iter().map(|h| h.message.id.as_str()).collect()
+}
+
+#[test]
+fn search_ranks_matches_and_applies_filters() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let weak = send(
+        &store,
+        "claude",
+        "human",
+        Some("channel:general:a"),
+        "the deploy went fine, nothing else to report from the release train today",
+    );
+    let strong = send(
+        &store,
+        "codex",
+        "human",
+        Some("channel:ops:b"),
+        "deploy deploy deploying",
+    );
+    send(&store, "codex", "human", None, "unrelated chatter");
+
+    let hits = store.search_messages("human", &search("DEPLOY")).unwrap();
+    assert_eq!(ids(&hits), vec![strong.id.as_str(), weak.id.as_str()]);
+    assert!(hits[0].score > hits[1].score);
+    assert!(hits[1].snippet.contains("deploy went fine"));
+    assert!(store
+        .search_messages("human", &search("deploy release"))
+        .unwrap()
+        .iter()
+        .all(|h| h.message.id == weak.id));
+
+    let filtered = |search: MessageSearch| {
+        ids(&store.search_messages("human", &search).unwrap())
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        filtered(MessageSearch {
+            from_agent: Some("claude".into()),
+            ..search("deploy")
+        }),
+        vec![weak.id.clone()]
+    );
+    assert_eq!(
+        filtered(MessageSearch {
+            scope: Some("#ops".into()),
+            ..search("deploy")
+        }),
+        vec![strong.id.clone()]
+    );
+    assert!(filtered(MessageSearch {
+        kind: Some(MessageKind::Handoff),
+        ..search("deploy")
+    })
+    .is_empty());
+    let later = (chrono::Utc::now() + chrono::Duration::seconds(1)).to_rfc3339();
+    assert!(filtered(MessageSearch {
+        since: Some(later),
+        ..search("deploy")
+    })
+    .is_empty());
+
+    store
+        .update_broadcast(&strong.id, "rollback instead")
+        .unwrap();
+    assert_eq!(
+        ids(&store.search_messages("human", &search("rollback")).unwrap()),
+        vec![strong.id.as_str()]
+    );
+    assert_eq!(
+        store
+            .search_messages("human", &search("deploy"))
+            .unwrap()
+            .len(),
+        1
+    );
+    store.archive_message("human", &weak.id).unwrap();
+    assert!(store
+        .search_messages("human", &search("deploy"))
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        store.search_messages("human", &search(" ?! ")),
+        Err(HubError::Invalid(_))
+    ));
+}
+
+#[test]
+fn session_posts_only_match_for_members() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let session = store.create_work_session("incident").unwrap();
+    store
+        .add_work_session_member(&session.id, "claude")
+        .unwrap();
+    let post = send(
+        &store,
+        "human",
+        "claude",
+        Some(&format!("channel:session:{}:x", session.id)),
+        "the incident password rotation",
+    );
+
+    let hits = store
+        .search_messages("claude", &search("incident"))
+        .unwrap();
+    assert_eq!(ids(&hits), vec![post.id.as_str()]);
+    assert!(store
+        .search_messages("outsider", &search("incident"))
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        store
+            .search_messages(
+                "claude",
+                &MessageSearch {
+                    scope: Some(format!("session:{}", session.id)),
+                    ..search("rotation")
+                }
+            )
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn encrypted_hubs_drop_the_index_and_still_search() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let message = send(&store, "human", "claude", None, "canary phrase in the body");
+    assert_eq!(
+        store
+            .search_messages("human", &search("canary"))
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let key = dir.path().join("hub.key");
+    fs::write(&key, generate_hub_key()).unwrap();
+    store
+        .enable_encryption(&SecretReference::KeyFile {
+            path: key.to_string_lossy().to_string(),
+        })
+        .unwrap();
+    let indexed: i64 = store
+        .conn
+        .query_row("SELECT COUNT(*) FROM messages_fts", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(indexed, 0, "no plaintext terms stay behind");
+
+    let hits = store.search_messages("human", &search("canary")).unwrap();
+    assert_eq!(ids(&hits), vec![message.id.as_str()]);
+    assert!(hits[0].snippet.contains("canary phrase"));
+    assert!(store
+        .search_messages("human", &search("missing"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn broadcast_copies_collapse_to_one_hit_preferring_the_searchers_copy() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    for agent in ["claude", "grok"] {
+        store.set_team_member(agent, true).unwrap();
+    }
+    let copies = store
+        .send_message_to_team(
+            "human",
+            MessageKind::Message,
+            "freeze the release branch",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(copies.len() >= 2);
+    let repeat = send(&store, "human", "claude", None, "freeze the release branch");
+
+    let hits = store.search_messages("human", &search("freeze")).unwrap();
+    assert_eq!(hits.len(), 2, "one broadcast hit plus the direct message");
+    assert!(ids(&hits).contains(&repeat.id.as_str()));
+
+    let hits = store
+        .search_messages(
+            "grok",
+            &MessageSearch {
+                from_agent: Some("human".into()),
+                ..search("release branch")
+            },
+        )
+        .unwrap();
+    let broadcast: Vec<_> = hits
+        .iter()
+        .filter(|h| h.message.subject == copies[0].subject)
+        .collect();
+    assert_eq!(broadcast.len(), 1);
+    assert_eq!(broadcast[0].message.to_agent, "grok");
+}
@@ -1,7 +1,40 @@
+mod archive;
+mod attachment_search;
+mod attachments;
+mod backup;
 mod core;
+mod encryption;
+mod escalations;
+mod hooks;
+mod hub_export;
 mod integration;
+mod memory_citations;
+mod memory_dedup;
+mod memory_graph;
+mod memory_scopes;
+mod memory_usage;
+mod mentions;
+mod message_search;
+mod pagination;
+mod payloads;
+mod questions;
+mod receipts;
+mod redaction;
+mod retention;
 mod roster;
 mod roster_audit;
 mod roster_memory;
+mod threads;
+mod unread;
+mod webhooks;
 mod workflow_policy;
 mod workflows;
+
+/// Run a record-lifecycle check against every [`HubBackend`]: a fresh
+/// SQLite [`HubStore`] and an [`InMemoryHub`]. Tests of behaviour the
+/// traits cover go through this so the backends can't drift apart.
+fn for_each_backend(check: impl Fn(&dyn super::HubBackend)) {
+    let dir = tempfile::tempdir().unwrap();
+    check(&super::HubStore::open(dir.path()).unwrap());
+    check(&super::InMemoryHub::new());
+}
@@ -0,0 +1,182 @@
+//! Keyset pagination tests for the `*_page` list APIs.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn send(store: &HubStore, body: &str) -> MessageRecord {
+    store
+        .send_message(
+            "codex",
+            "claude",
+            MessageKind::Message,
+            body,
+            Some("channel:release"),
+            None,
+            None,
+        )
+        .unwrap()
+}
+
+#[test]
+fn pages_walk_every_message_once_in_both_directions() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let sent: Vec<MessageRecord> = (0..5).map(|i| send(&store, &format!("m{i}"))).collect();
+    // Ties on created_at must still page deterministically by id.
+    store
+        .conn
+        .execute(
+            "UPDATE messages SET created_at = ?1",
+            params![sent[0].created_at],
+        )
+        .unwrap();
+
+    let mut page = PageRequest {
+        limit: Some(2),
+        ..PageRequest::default()
+    };
+    let mut older = Vec::new();
+    loop {
+        let next = store.list_channel_messages_page("release", &page).unwrap();
+        older.extend(next.items.iter().map(|m| m.id.clone()));
+        if !next.has_more {
+            break;
+        }
+        page.before = next.before;
+    }
+    let mut expected: Vec<String> = sent.iter().map(|m| m.id.clone()).collect();
+    expected.sort();
+    expected.reverse();
+    assert_eq!(older, expected, "newest first, no gaps or repeats");
+
+    let oldest = page_cursor(&sent[0].created_at, &expected[4]);
+    let newer = store
+        .list_messages_page(
+            Some("claude"),
+            None,
+            &PageRequest {
+                after: Some(oldest),
+                limit: Some(2),
+                ..PageRequest::default()
+            },
+        )
+        .unwrap();
+    assert!(newer.has_more);
+    assert_eq!(
+        newer.items.iter().map(|m| &m.id).collect::<Vec<_>>(),
+        vec![&expected[2], &expected[3]],
+        "`after` returns the rows nearest the cursor, newest first"
+    );
+}
+
+#[test]
+fn memory_pages_reach_past_the_legacy_cap() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    for i in 0..205 {
+        store
+            .write_memory(
+                MemoryTier::Episodic,
+                MemoryScope::Global,
+                Some("claude"),
+                None,
+                None,
+                &format!("note {i}"),
+                &[],
+            )
+            .unwrap();
+    }
+    assert_eq!(
+        store.list_memories(None, None, None, false).unwrap().len(),
+        200
+    );
+
+    let first = store
+        .list_memories_page_as(None, None, None, None, false, &PageRequest::latest(150))
+        .unwrap();
+    assert!(first.has_more);
+    let rest = store
+        .list_memories_page_as(
+            None,
+            None,
+            None,
+            None,
+            false,
+            &PageRequest {
+                before: first.before.clone(),
+                limit: Some(150),
+                ..PageRequest::default()
+            },
+        )
+        .unwrap();
+    assert!(!rest.has_more);
+    assert_eq!(first.items.len() + rest.items.len(), 205);
+    assert!(rest.items.iter().all(|m| m.relevance.is_some()));
+
+    let hits = store
+        .search_memories_page_as(None, "note 1", &PageRequest::latest(5))
+        .unwrap();
+    assert_eq!(hits.items.len(), 5);
+    assert!(hits.has_more);
+}
+
+#[test]
+fn other_lists_page_and_bad_cursors_are_rejected() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    for target in ["claude", "codex", "gemini"] {
+        store
+            .request_wake(target, Some("review"), None, false)
+            .unwrap();
+        store
+            .create_task(
+                "Ship",
+                None,
+                &[WorkflowStep {
+                    agent: "claude".into(),
+                    role: None,
+                    instruction: "Ship it".into(),
+                    max_retries: 0,
+                    parallel_group: None,
+                    payload: None,
+                }],
+            )
+            .unwrap();
+    }
+    let wakes = store
+        .list_wakes_page(None, false, &PageRequest::latest(2))
+        .unwrap();
+    assert_eq!((wakes.items.len(), wakes.has_more), (2, true));
+    let tasks = store
+        .list_tasks_page(None, &PageRequest::latest(5))
+        .unwrap();
+    assert_eq!((tasks.items.len(), tasks.has_more), (3, false));
+
+    // A bare timestamp bounds by time alone.
+    let everything = store
+        .list_wakes_page(
+            None,
+            false,
+            &PageRequest {
+                before: Some("9999".into()),
+                ..PageRequest::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(everything.items.len(), 3);
+    assert!(store
+        .list_audit_events_page(false, &PageRequest::latest(10))
+        .unwrap()
+        .items
+        .is_empty());
+    assert!(matches!(
+        store.list_tasks_page(
+            None,
+            &PageRequest {
+                after: Some("|id".into()),
+                ..PageRequest::default()
+            }
+        ),
+        Err(HubError::Invalid(_))
+    ));
+}
@@ -0,0 +1,266 @@
+//! Typed message payload tests.
+
+use super::super::*;
+use crate::settings::SecretReference;
+use serde_json::json;
+use tempfile::tempdir;
+
+fn register(store: &HubStore, agent: &str, schema: serde_json::Value, output: Option<&str>) {
+    store
+        .upsert_agent_card(
+            agent,
+            &AgentCard {
+                name: agent.into(),
+                description: format!("{agent} test card"),
+                specializations: Vec::new(),
+                input_schema: Some(schema),
+                output_format: output.map(str::to_string),
+            },
+        )
+        .unwrap();
+}
+
+fn review_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "required": ["action", "files"],
+        "properties": {
+            "action": {"enum": ["review", "fix"]},
+            "files": {"type": "array", "items": {"type": "string"}, "minItems": 1}
+        },
+        "additionalProperties": false
+    })
+}
+
+#[test]
+fn payloads_are_checked_against_the_recipient_schema_and_sealed() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    register(&store, "claude", review_schema(), None);
+
+    let bad = json!({"action": "deploy", "files": [3], "force": true});
+    let error = store
+        .send_message_with_payload(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "please look",
+            Some(&bad),
+            None,
+            None,
+            None,
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("claude's input_schema"), "{error}");
+    assert!(error.contains("payload/action: must be one of"), "{error}");
+    assert!(
+        error.contains("payload/files/0: expected string"),
+        "{error}"
+    );
+    assert!(error.contains("unexpected field `force`"), "{error}");
+    assert!(store
+        .list_messages(Some("claude"), None)
+        .unwrap()
+        .is_empty());
+
+    let good = json!({"action": "review", "files": ["src/lib.rs"]});
+    let sent = store
+        .send_message_with_payload(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "please look",
+            Some(&good),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(sent.payload.as_ref(), Some(&good));
+    let plain = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Message,
+            "free text",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(plain.payload, None, "payloads stay optional");
+
+    let key = dir.path().join("hub.key");
+    fs::write(&key, generate_hub_key()).unwrap();
+    store
+        .enable_encryption(&SecretReference::KeyFile {
+            path: key.to_string_lossy().to_string(),
+        })
+        .unwrap();
+    let stored: String = store
+        .conn
+        .query_row(
+            "SELECT payload FROM messages WHERE id = ?1",
+            params![sent.id],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert!(!stored.contains("src/lib.rs"), "payload is sealed at rest");
+    let reread = store.get_message(&sent.id).unwrap().unwrap();
+    assert_eq!(reread.payload, Some(good));
+}
+
+#[test]
+fn tagged_sends_record_schema_rejections_per_recipient() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    register(&store, "claude", review_schema(), None);
+    for agent in ["claude", "grok"] {
+        store.set_team_member(agent, true).unwrap();
+    }
+
+    let outcomes = store
+        .send_tagged_message_with_payload(
+            "human",
+            &["claude".into(), "grok".into()],
+            true,
+            false,
+            "structured task",
+            Some(&json!({"action": "review"})),
+            Some("tagged:typed"),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let claude = outcomes.iter().find(|o| o.to_agent == "claude").unwrap();
+    assert!(!claude.accepted);
+    assert_eq!(claude.policy_decision, "payload_rejected_schema");
+    assert!(claude
+        .reason
+        .as_deref()
+        .unwrap()
+        .contains("missing required field `files`"));
+    assert!(claude.message_id.is_none());
+    let grok = outcomes.iter().find(|o| o.to_agent == "grok").unwrap();
+    assert!(grok.accepted, "grok declares no input_schema");
+
+    let recorded = store.list_tagged_send_outcomes("tagged:typed").unwrap();
+    assert_eq!(recorded.len(), 2);
+    assert!(recorded
+        .iter()
+        .any(|o| o.policy_decision == "payload_rejected_schema"));
+}
+
+#[test]
+fn replies_follow_output_format_and_steps_carry_payloads() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    register(
+        &store,
+        "claude",
+        review_schema(),
+        Some(r#"{"type": "object", "required": ["verdict"]}"#),
+    );
+    register(&store, "grok", json!({"type": "object"}), Some("markdown"));
+
+    let error = store.check_reply_payload("claude", None).unwrap_err();
+    assert!(error.to_string().contains("requires replies to carry"));
+    assert!(store
+        .check_reply_payload("claude", Some(&json!({"notes": "lgtm"})))
+        .unwrap_err()
+        .to_string()
+        .contains("missing required field `verdict`"));
+    store
+        .check_reply_payload("claude", Some(&json!({"verdict": "approve"})))
+        .unwrap();
+    store.check_reply_payload("grok", None).unwrap();
+    store.check_reply_payload("human", None).unwrap();
+
+    // The store enforces it on every reply, not just `ca msg send`.
+    let ask = store
+        .send_message(
+            "human",
+            "claude",
+            MessageKind::Question,
+            "ship it?",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let reply = |payload: Option<&serde_json::Value>| {
+        store.send_reply(
+            &ask.id,
+            "claude",
+            "human",
+            MessageKind::Message,
+            "done",
+            payload,
+            None,
+            None,
+            None,
+        )
+    };
+    assert!(matches!(reply(None), Err(HubError::Invalid(_))));
+    let verdict = json!({"verdict": "approve"});
+    assert_eq!(reply(Some(&verdict)).unwrap().payload, Some(verdict.clone()));
+
+    // A team reply carries the payload on every copy, so it can satisfy
+    // the sender's format too.
+    for agent in ["claude", "grok"] {
+        store.set_team_member(agent, true).unwrap();
+    }
+    let team_reply = |payload: Option<&serde_json::Value>| {
+        store.send_reply_to_team(
+            &ask.id,
+            "claude",
+            MessageKind::Message,
+            "done",
+            payload,
+            None,
+            None,
+            None,
+        )
+    };
+    let before = store.list_messages(None, None).unwrap().len();
+    assert!(matches!(team_reply(None), Err(HubError::Invalid(_))));
+    assert_eq!(store.list_messages(None, None).unwrap().len(), before);
+    let copies = team_reply(Some(&verdict)).unwrap();
+    assert!(!copies.is_empty());
+    assert!(copies.iter().all(|c| c.payload.as_ref() == Some(&verdict)));
+
+    let step = |payload| WorkflowStep {
+        agent: "claude".into(),
+        role: None,
+        instruction: "Review the change.".into(),
+        max_retries: 0,
+        parallel_group: None,
+        payload: Some(payload),
+    };
+    let task = store
+        .create_task_with_parallel(
+            "typed review",
+            None,
+            &[step(json!({"action": "review", "files": ["a.rs"]}))],
+            1,
+            false,
+        )
+        .unwrap();
+    let task = store.advance_task(&task.id, Some("human"), None).unwrap();
+    let handoff = store
+        .get_message(task.last_message_id.as_deref().unwrap())
+        .unwrap()
+        .unwrap();
+    assert_eq!(handoff.kind, "handoff");
+    assert_eq!(handoff.payload.unwrap()["files"][0], "a.rs");
+
+    let task = store
+        .create_task_with_parallel("bad step", None, &[step(json!({"files": []}))], 1, false)
+        .unwrap();
+    let error = store
+        .advance_task(&task.id, Some("human"), None)
+        .unwrap_err();
+    assert!(error.to_string().contains("too few items"), "{error}");
+}
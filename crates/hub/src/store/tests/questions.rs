@@ -0,0 +1,187 @@
+//! Question tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn choices(list: &[&str]) -> Vec<String> {
+    list.iter().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn an_answer_is_threaded_back_and_injected_into_the_askers_harness() {
+    let dir = tempdir().unwrap();
+    let workspace = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let deadline = (Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+    let asked = store
+        .ask_question(
+            "chat",
+            "human",
+            "Drop the legacy v1 endpoints?",
+            &choices(&["yes", "no"]),
+            Some(&deadline),
+            Some(&workspace.path().to_string_lossy()),
+            None,
+        )
+        .unwrap();
+    assert_eq!(asked.question.kind, "question");
+    assert_eq!(asked.state, QuestionState::Open);
+    let open = store.list_questions(Some("human"), true).unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].choices, choices(&["yes", "no"]));
+
+    let id = &asked.question.id;
+    let error = store.answer_question(id, "human", "maybe").unwrap_err();
+    assert!(error.to_string().contains("yes, no"), "{error}");
+    assert!(store.answer_question(id, "grok", "yes").is_err());
+
+    let answered = store.answer_question(id, "human", " YES ").unwrap();
+    let reply = answered.question.answer.clone().unwrap();
+    assert_eq!(answered.question.state, QuestionState::Answered);
+    assert_eq!(answered.question.question.status, "done");
+    assert_eq!(reply.body, "yes", "the answer takes the choice's spelling");
+    assert_eq!(
+        (reply.from_agent.as_str(), reply.to_agent.as_str()),
+        ("human", "chat")
+    );
+    assert_eq!(reply.reply_to.as_deref(), Some(id.as_str()));
+    let delivery = answered.delivery.expect("chat is a harness agent");
+    let receipts = store.message_receipts(&reply.id).unwrap();
+    assert_eq!(
+        receipts.receipts[0].inject_status.as_deref(),
+        Some(delivery.status.as_str())
+    );
+
+    assert!(store
+        .list_questions(Some("human"), true)
+        .unwrap()
+        .is_empty());
+    assert_eq!(store.list_questions(None, false).unwrap().len(), 1);
+    assert!(store.answer_question(id, "human", "no").is_err());
+}
+
+#[test]
+fn expired_and_malformed_questions_are_rejected() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let past = (Utc::now() - chrono::Duration::minutes(1)).to_rfc3339();
+    assert!(store
+        .ask_question("grok", "human", "ship it?", &[], Some(&past), None, None)
+        .is_err());
+    assert!(store
+        .ask_question(
+            "grok",
+            "human",
+            "pick",
+            &choices(&["a", "A"]),
+            None,
+            None,
+            None
+        )
+        .is_err());
+
+    let asked = store
+        .ask_question("grok", "human", "which branch?", &[], None, None, None)
+        .unwrap();
+    store
+        .conn
+        .execute(
+            "UPDATE message_questions SET deadline = ?1 WHERE message_id = ?2",
+            params![past, asked.question.id],
+        )
+        .unwrap();
+    assert_eq!(
+        store.get_question(&asked.question.id).unwrap().state,
+        QuestionState::Expired
+    );
+    assert!(store.list_questions(None, true).unwrap().is_empty());
+    let error = store
+        .answer_question(&asked.question.id, "human", "main")
+        .unwrap_err();
+    assert!(error.to_string().contains("expired"), "{error}");
+
+    let plain = store
+        .send_message(
+            "grok",
+            "human",
+            MessageKind::Question,
+            "free text?",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let answered = store
+        .answer_question(&plain.id, "human", "anything")
+        .unwrap();
+    assert!(answered.delivery.is_none(), "no workspace to inject into");
+    assert_eq!(answered.question.answer.unwrap().body, "anything");
+}
+
+#[test]
+fn a_failed_answer_leaves_the_question_open_and_unknown_harnesses_are_delivery_errors() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempdir().unwrap();
+    let workspace = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let hooks = store.hooks_dir();
+    fs::create_dir_all(&hooks).unwrap();
+    fs::write(
+        hooks.join("pre-send"),
+        "#!/bin/sh\ncase \"$(cat)\" in *veto*) echo vetoed; exit 1;; esac\n",
+    )
+    .unwrap();
+    fs::set_permissions(hooks.join("pre-send"), fs::Permissions::from_mode(0o755)).unwrap();
+    let asked = store
+        .ask_question(
+            "reviewer-bot",
+            "human",
+            "Merge now?",
+            &[],
+            None,
+            Some(&workspace.path().to_string_lossy()),
+            None,
+        )
+        .unwrap();
+    let id = &asked.question.id;
+
+    assert!(store.answer_question(id, "human", "veto it").is_err());
+    let record = store.get_question(id).unwrap();
+    assert_eq!(record.state, QuestionState::Open);
+    assert_eq!(record.question.status, "pending");
+    assert_eq!(store.list_thread(id).unwrap().len(), 1);
+
+    // An answer already claimed by someone else's in-flight send.
+    let claim = |by: Option<&str>| {
+        store
+            .conn
+            .execute(
+                "UPDATE message_questions SET answered_by = ?1 WHERE message_id = ?2",
+                params![by, id],
+            )
+            .unwrap()
+    };
+    claim(Some("human"));
+    let error = store.answer_question(id, "human", "ship it").unwrap_err();
+    assert!(error.to_string().contains("being answered"), "{error}");
+    claim(None);
+
+    // A vetoed question is not recorded at all.
+    let before = store.list_messages(None, None).unwrap().len();
+    assert!(store
+        .ask_question("reviewer-bot", "human", "veto?", &[], None, None, None)
+        .is_err());
+    assert_eq!(store.list_messages(None, None).unwrap().len(), before);
+    let rows: i64 = store
+        .conn
+        .query_row("SELECT COUNT(*) FROM message_questions", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(rows, 1);
+
+    let answered = store.answer_question(id, "human", "ship it").unwrap();
+    assert_eq!(answered.question.state, QuestionState::Answered);
+    assert!(answered.delivery.is_none());
+    let error = answered.delivery_error.expect("reviewer-bot is not a harness");
+    assert!(error.contains("unknown harness"), "{error}");
+    assert_eq!(store.list_thread(id).unwrap().len(), 2);
+}
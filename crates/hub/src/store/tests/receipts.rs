@@ -0,0 +1,130 @@
+//! Delivery receipt tests.
+
+use super::super::*;
+use crate::{HarnessInjectRequest, HarnessInjectResult};
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+fn receipt<'a>(summary: &'a ReceiptSummary, agent: &str) -> &'a DeliveryReceipt {
+    summary
+        .receipts
+        .iter()
+        .find(|r| r.to_agent == agent)
+        .unwrap()
+}
+
+#[test]
+fn broadcast_receipts_track_each_recipient_through_the_states() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    for agent in ["claude", "chat", "grok"] {
+        store.set_team_member(agent, true).unwrap();
+    }
+    let copies = store
+        .send_message_to_team(
+            "human",
+            MessageKind::Message,
+            "please rebase onto main",
+            Some("channel:general"),
+            None,
+            None,
+        )
+        .unwrap();
+    let copy = |agent: &str| copies.iter().find(|m| m.to_agent == agent).unwrap();
+
+    let summary = store.message_receipts(&copies[0].id).unwrap();
+    assert_eq!(summary.recipients, 3);
+    assert_eq!(summary.queued, 3);
+    assert!(summary
+        .receipts
+        .iter()
+        .all(|r| r.state == DeliveryState::Queued));
+
+    store
+        .record_injection(
+            &copy("claude").id,
+            &Ok(HarnessInjectResult {
+                harness: "claude".into(),
+                pid: None,
+                status: "delivered".into(),
+                detail: "sent to the live session".into(),
+            }),
+        )
+        .unwrap();
+    store
+        .mark_read("grok", "channel:general", Some(&Utc::now().to_rfc3339()))
+        .unwrap();
+    store
+        .set_message_status(&copy("chat").id, MessageStatus::Acked)
+        .unwrap();
+
+    let summary = store.message_receipts(&copy("grok").id).unwrap();
+    assert_eq!(
+        (
+            summary.queued,
+            summary.injected,
+            summary.read,
+            summary.acked
+        ),
+        (0, 1, 1, 1)
+    );
+    let claude = receipt(&summary, "claude");
+    assert_eq!(claude.state, DeliveryState::Injected);
+    assert_eq!(claude.inject_status.as_deref(), Some("delivered"));
+    assert!(receipt(&summary, "grok").read_at.is_some());
+    let chat = receipt(&summary, "chat");
+    assert_eq!(chat.state, DeliveryState::Acked);
+    assert!(chat.acked_at.is_some());
+}
+
+#[test]
+fn harness_injection_is_recorded_on_the_message() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let message = store
+        .send_message(
+            "human",
+            "chat",
+            MessageKind::Message,
+            "review this",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let request = |message_id: &str| HarnessInjectRequest {
+        harness: "chat".into(),
+        workspace: PathBuf::from("/tmp/workspace-for-receipts"),
+        session_id: None,
+        message_id: Some(message_id.into()),
+        body: "review this".into(),
+        is_task: true,
+        is_wake: false,
+    };
+
+    let result = crate::inject_harness_with_store(&store, &request(&message.id)).unwrap();
+    let summary = store.message_receipts(&message.id).unwrap();
+    let receipt = receipt(&summary, "chat");
+    assert_eq!(receipt.state, DeliveryState::Injected);
+    assert_eq!(
+        receipt.inject_status.as_deref(),
+        Some(result.status.as_str())
+    );
+    assert_eq!(
+        receipt.inject_detail.as_deref(),
+        Some(result.detail.as_str())
+    );
+
+    store
+        .record_injection(&message.id, &Err(HubError::Invalid("harness gone".into())))
+        .unwrap();
+    let summary = store.message_receipts(&message.id).unwrap();
+    assert_eq!(summary.receipts[0].inject_status.as_deref(), Some("error"));
+
+    crate::inject_harness_with_store(&store, &request("no-such-message")).unwrap();
+    let rows: i64 = store
+        .conn
+        .query_row("SELECT COUNT(*) FROM message_deliveries", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(rows, 1, "unknown message ids leave no receipt");
+}
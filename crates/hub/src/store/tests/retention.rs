@@ -0,0 +1,191 @@
+//! Retention sweep tests.
+
+use super::super::*;
+use crate::settings::SettingsStore;
+use tempfile::tempdir;
+
+/// Move every row of `table` matching `id` back `days` days.
+fn backdate(store: &HubStore, table: &str, id: &str, days: i64) {
+    let old = (Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+    let column = if table == "memories" {
+        "updated_at"
+    } else {
+        "created_at"
+    };
+    store
+        .conn
+        .execute(
+            &format!("UPDATE {table} SET {column} = ?1 WHERE id = ?2"),
+            params![old, id],
+        )
+        .unwrap();
+}
+
+fn send(store: &HubStore, workspace: Option<&str>) -> MessageRecord {
+    store
+        .send_message(
+            "codex",
+            "claude",
+            MessageKind::Message,
+            "status update",
+            None,
+            workspace,
+            None,
+        )
+        .unwrap()
+}
+
+fn count(store: &HubStore, table: &str) -> i64 {
+    store
+        .conn
+        .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0))
+        .unwrap()
+}
+
+#[test]
+fn sweep_honours_workspace_overrides_and_archives_first() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let mut settings = SettingsStore::open(dir.path());
+    settings.set_retention_days(Some(30)).unwrap();
+    settings
+        .set_workspace_retention_days("/work/keep", 365)
+        .unwrap();
+
+    let old = send(&store, None);
+    let kept_by_override = send(&store, Some("/work/keep"));
+    let recent = send(&store, None);
+    let captured = store
+        .record_harness_capture("codex", "codex", None, "captured reply", None)
+        .unwrap()
+        .unwrap();
+    let delivered = store
+        .request_wake("claude", None, Some(&old.id), false)
+        .unwrap();
+    store
+        .set_wake_status(&delivered.id, WakeStatus::Delivered)
+        .unwrap();
+    let pending = store
+        .request_wake("gemini", None, Some(&old.id), false)
+        .unwrap();
+    let short = store
+        .write_memory(
+            MemoryTier::ShortTerm,
+            MemoryScope::Global,
+            None,
+            None,
+            None,
+            "scratch",
+            &[],
+        )
+        .unwrap();
+    let semantic = store
+        .write_memory(
+            MemoryTier::Semantic,
+            MemoryScope::Global,
+            None,
+            None,
+            None,
+            "lasting fact",
+            &[],
+        )
+        .unwrap();
+    let unread = send(&store, None);
+    let open_question = store
+        .ask_question("codex", "human", "ship it?", &[], None, None, None)
+        .unwrap()
+        .question;
+    for id in [&old.id, &kept_by_override.id, &captured.id, &open_question.id] {
+        store.ack_message(id).unwrap();
+    }
+    for (table, id) in [
+        ("messages", &old.id),
+        ("messages", &kept_by_override.id),
+        ("messages", &captured.id),
+        ("messages", &unread.id),
+        ("messages", &open_question.id),
+        ("wake_requests", &delivered.id),
+        ("wake_requests", &pending.id),
+        ("memories", &short.id),
+        ("memories", &semantic.id),
+    ] {
+        backdate(&store, table, id, 40);
+    }
+    store
+        .conn
+        .execute(
+            "UPDATE harness_captures SET created_at = ?1",
+            params![(Utc::now() - chrono::Duration::days(40)).to_rfc3339()],
+        )
+        .unwrap();
+
+    let dry = store.sweep_retention(&settings, true, None).unwrap();
+    assert_eq!(dry.expired["messages"], 2);
+    assert_eq!(dry.expired["harness_captures"], 1);
+    assert_eq!(dry.expired["wake_requests"], 1);
+    assert_eq!(dry.expired["memories"], 1);
+    assert!(dry.archive.is_none());
+    assert!(!store.retention_archive_dir().exists());
+    assert_eq!(count(&store, "messages"), 6);
+    let keep = dry
+        .workspaces
+        .iter()
+        .find(|w| w.workspace.as_deref() == Some("/work/keep"))
+        .unwrap();
+    assert_eq!(keep.retention_days, 365);
+
+    let report = store
+        .sweep_retention(&settings, false, Some(RetentionExport::Jsonl))
+        .unwrap();
+    assert_eq!(report.total_expired(), 5);
+    let export = fs::read_to_string(report.export.unwrap()).unwrap();
+    assert!(export.contains("lasting fact") && export.contains(&old.id));
+    let archive = fs::read_to_string(report.archive.unwrap()).unwrap();
+    let lines: Vec<serde_json::Value> = archive
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines[0]["format"], RETENTION_ARCHIVE_FORMAT);
+    assert_eq!(lines.len(), 6);
+    assert!(lines
+        .iter()
+        .any(|l| l["table"] == "messages" && l["record"]["id"] == old.id.as_str()));
+
+    assert!(store.get_message(&old.id).unwrap().is_none());
+    assert!(store.get_message(&kept_by_override.id).unwrap().is_some());
+    assert!(store.get_message(&recent.id).unwrap().is_some());
+    assert!(
+        store.get_message(&unread.id).unwrap().is_some(),
+        "pending messages are never swept"
+    );
+    assert_eq!(
+        store.get_question(&open_question.id).unwrap().state,
+        QuestionState::Open,
+        "nor are open questions"
+    );
+    assert!(store.get_memory(&short.id).unwrap().is_none());
+    assert!(store.get_memory(&semantic.id).unwrap().is_some());
+    assert_eq!(count(&store, "harness_captures"), 0);
+    let wakes = store.list_wakes(None, false).unwrap();
+    assert_eq!(wakes.len(), 1);
+    assert_eq!(wakes[0].id, pending.id);
+}
+
+#[test]
+fn nothing_expires_without_a_retention_setting() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let settings = SettingsStore::open(dir.path());
+    let message = send(&store, Some("/work/any"));
+    backdate(&store, "messages", &message.id, 3650);
+
+    let report = store.sweep_retention(&settings, false, None).unwrap();
+    assert_eq!(report.total_expired(), 0);
+    assert!(report.workspaces.is_empty());
+    assert!(report.archive.is_none());
+    assert!(store.get_message(&message.id).unwrap().is_some());
+    assert!(matches!(
+        RetentionExport::parse("csv"),
+        Err(HubError::Invalid(_))
+    ));
+}
@@ -1,4 +1,5 @@
 use super::super::*;
+use super::for_each_backend;
 use tempfile::tempdir;
 
 #[test]
@@ -121,8 +122,8 @@ fn memory_message_wake_roundtrip() {
     let journal = store
         .append_private_journal("grok", "Private note: do not share.")
         .unwrap();
-    assert!(journal.exists());
-    // private journal must not appear in shared memory tables
+    assert_eq!(journal.scope, MemoryScope::AgentPrivate.as_str());
+    // private journal must not appear in shared memory searches
     assert!(store.search_memories("do not share").unwrap().is_empty());
 
     let export = store.export_markdown(None).unwrap();
@@ -130,6 +131,54 @@ fn memory_message_wake_roundtrip() {
     assert!(text.contains("first handoff"));
 }
 
+#[test]
+fn memory_and_handoff_records_round_trip_on_every_backend() {
+    for_each_backend(|hub| {
+        let mem = hub
+            .write_memory(
+                MemoryTier::Episodic,
+                MemoryScope::Global,
+                Some("grok"),
+                None,
+                Some("first handoff"),
+                "Grok left a note for Claude about the hub schema.",
+                &["hub".into(), "schema".into()],
+            )
+            .unwrap();
+        assert_eq!(mem.tier, "episodic");
+        assert_eq!(hub.search_memories("hub schema").unwrap().len(), 1);
+
+        let msg = hub
+            .send_message(
+                "grok",
+                "claude",
+                MessageKind::Handoff,
+                "Please review the hub schema.",
+                Some("schema review"),
+                None,
+                Some("task-1"),
+            )
+            .unwrap();
+        assert_eq!(msg.status, "pending");
+        let polled = hub.poll_messages("claude", true).unwrap();
+        assert!(polled
+            .iter()
+            .any(|message| message.id == msg.id && message.status == "acked"));
+
+        hub.write_memory(
+            MemoryTier::ShortTerm,
+            MemoryScope::AgentPrivate,
+            Some("grok"),
+            None,
+            None,
+            "Private note: do not share.",
+            &[],
+        )
+        .unwrap();
+        assert!(hub.search_memories("do not share").unwrap().is_empty());
+    });
+}
+
 #[test]
 fn wake_policy_and_retention() {
     let dir = tempdir().unwrap();
@@ -308,3 +357,65 @@ fn m6_cross_agent_handoff_acceptance_flow() {
     assert_eq!(linked[0].id, memory.id);
     assert!(team_coord[0].body.contains(&linked[0].id));
 }
+
+#[test]
+fn roles_are_upserted_assigned_and_protected() {
+    for_each_backend(|hub| {
+        let reviewer = hub
+            .upsert_role(
+                "reviewer",
+                "Reviewer",
+                Some(5),
+                None,
+                false,
+                false,
+                false,
+                &["reviewer".to_string()],
+            )
+            .unwrap();
+        assert!(!reviewer.is_builtin);
+        let renamed = hub
+            .upsert_role(
+                "reviewer",
+                "Code Reviewer",
+                None,
+                None,
+                true,
+                false,
+                false,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(renamed.created_at, reviewer.created_at);
+        assert!(hub.list_roles().unwrap().iter().any(|r| r.id == "reviewer"));
+        assert!(matches!(
+            hub.upsert_role("cto", "CTO", None, None, true, true, true, &[]),
+            Err(HubError::Invalid(_))
+        ));
+
+        hub.assign_agent_role("codex", "reviewer").unwrap();
+        hub.assign_agent_role("codex", "reviewer").unwrap();
+        let roles = hub.list_agent_roles("codex").unwrap();
+        assert_eq!(roles.len(), 1);
+        assert!(roles[0].can_archive_messages);
+        assert!(matches!(
+            hub.assign_agent_role("codex", "missing"),
+            Err(HubError::NotFound(_))
+        ));
+        assert!(matches!(
+            hub.delete_role("reviewer"),
+            Err(HubError::Invalid(_))
+        ));
+        hub.unassign_agent_role("codex", "reviewer").unwrap();
+        hub.delete_role("reviewer").unwrap();
+        assert!(hub.get_role("reviewer").unwrap().is_none());
+
+        assert!(hub
+            .list_agent_roles("human")
+            .unwrap()
+            .iter()
+            .any(|r| r.id == "cto" && r.is_builtin));
+        assert!(hub.unassign_agent_role("human", "cto").is_err());
+        assert!(hub.delete_role("cto").is_err());
+    });
+}
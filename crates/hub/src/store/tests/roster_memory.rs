@@ -2,6 +2,7 @@
 //! tests/roster.rs for the 500-LoC cap, #158).
 
 use super::super::*;
+use super::for_each_backend;
 use tempfile::tempdir;
 
 #[test]
@@ -57,6 +58,43 @@ fn promote_and_compact_short_term() {
     assert!(store.get_memory(&semantic.id).unwrap().is_none());
 }
 
+#[test]
+fn every_backend_lists_memories_by_relevance() {
+    for_each_backend(|hub| {
+        let mut written = Vec::new();
+        for i in 0..3 {
+            written.push(
+                hub.write_memory(
+                    MemoryTier::ShortTerm,
+                    MemoryScope::Global,
+                    Some("grok"),
+                    None,
+                    Some(&format!("note-{i}")),
+                    &format!("short body {i}"),
+                    &[],
+                )
+                .unwrap(),
+            );
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let listed = hub.list_memories(None, None, None, false).unwrap();
+        let scores: Vec<f64> = listed
+            .iter()
+            .map(|m| m.relevance.as_ref().expect("relevance is filled in").score)
+            .collect();
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]), "{scores:?}");
+        // Nothing used or linked yet, so relevance is recency: newest first.
+        let ids: Vec<&str> = listed.iter().map(|m| m.id.as_str()).collect();
+        let newest_first: Vec<&str> = written.iter().rev().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, newest_first);
+
+        let found = hub.search_memories("short body").unwrap();
+        assert_eq!(found.len(), 3);
+        assert!(found.iter().all(|m| m.relevance.is_some()));
+    });
+}
+
 #[test]
 fn memory_links_connect_related_memories_across_authors() {
     let dir = tempdir().unwrap();
@@ -118,14 +156,14 @@ fn memory_links_connect_related_memories_across_authors() {
     // Listing from the *other* endpoint finds the same edges (undirected lookup).
     assert_eq!(store.list_memory_links(&grok_note.id).unwrap().len(), 2);
 
-    let related = store.related_memories(&claude_note.id, 1).unwrap();
+    let related = store.related_memories(None, &claude_note.id, 1).unwrap();
     assert_eq!(related.len(), 1);
     assert_eq!(related[0].id, grok_note.id);
     assert!(!related.iter().any(|m| m.id == unrelated.id));
 
     // depth = 0 is a deliberate no-op, not "unbounded."
     assert!(store
-        .related_memories(&claude_note.id, 0)
+        .related_memories(None, &claude_note.id, 0)
         .unwrap()
         .is_empty());
 
@@ -222,3 +260,79 @@ fn suggest_links_scores_shared_tags_and_terms_above_unrelated_memories() {
     let after_auto = store.suggest_links_for_memory(&source.id, 10).unwrap();
     assert!(!after_auto.iter().any(|s| s.candidate.id == strong_match.id));
 }
+
+#[test]
+fn memories_are_written_updated_searched_and_deleted() {
+    for_each_backend(|hub| {
+        let tags = vec!["deploy".to_string()];
+        let memory = hub
+            .write_memory(
+                MemoryTier::Semantic,
+                MemoryScope::Global,
+                None,
+                None,
+                Some("Release"),
+                "ship on fridays",
+                &tags,
+            )
+            .unwrap();
+        hub.write_memory(
+            MemoryTier::ShortTerm,
+            MemoryScope::AgentPrivate,
+            Some("claude"),
+            None,
+            None,
+            "private fridays",
+            &[],
+        )
+        .unwrap();
+        assert!(matches!(
+            hub.write_memory(
+                MemoryTier::ShortTerm,
+                MemoryScope::Workspace,
+                None,
+                None,
+                None,
+                "no workspace",
+                &[],
+            ),
+            Err(HubError::Invalid(_))
+        ));
+
+        let found = hub.search_memories("FRIDAYS").unwrap();
+        assert_eq!(
+            found.iter().map(|m| &m.id).collect::<Vec<_>>(),
+            vec![&memory.id],
+            "private memories stay out of shared reads"
+        );
+        assert_eq!(hub.search_memories("deploy").unwrap().len(), 1);
+
+        let updated = hub
+            .update_memory(&memory.id, None, "ship on mondays", None)
+            .unwrap();
+        assert_eq!(updated.body, "ship on mondays");
+        assert_eq!(updated.tags_json, r#"["deploy"]"#);
+        assert!(matches!(
+            hub.update_memory("missing", None, "x", None),
+            Err(HubError::NotFound(_))
+        ));
+
+        hub.mark_memory_stale(&memory.id, true).unwrap();
+        assert!(hub
+            .list_memories(None, None, None, false)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            hub.list_memories(Some(MemoryScope::Global), None, None, true)
+                .unwrap()
+                .len(),
+            1
+        );
+        hub.delete_memory(&memory.id).unwrap();
+        assert!(hub.get_memory(&memory.id).unwrap().is_none());
+        assert!(matches!(
+            hub.delete_memory(&memory.id),
+            Err(HubError::NotFound(_))
+        ));
+    });
+}
@@ -0,0 +1,179 @@
+//! Threaded reply tests.
+
+use super::super::*;
+use crate::record_channel_reply;
+use std::os::unix::fs::PermissionsExt;
+use tempfile::tempdir;
+
+fn send(
+    store: &HubStore,
+    from: &str,
+    to: &str,
+    subject: Option<&str>,
+    body: &str,
+) -> MessageRecord {
+    store
+        .send_message(from, to, MessageKind::Message, body, subject, None, None)
+        .unwrap()
+}
+
+#[test]
+fn harness_replies_are_threaded_under_the_message_they_answer() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let question = send(&store, "grok", "claude", None, "question");
+
+    let reply = record_channel_reply(&store, Some(&question.id), None, "answer").unwrap();
+    assert_eq!(reply.reply_to.as_deref(), Some(question.id.as_str()));
+    assert_eq!(reply.thread_id.as_deref(), Some(question.id.as_str()));
+
+    let follow_up = send(&store, "grok", "claude", None, "thanks");
+    let follow_up = store.link_reply(&follow_up.id, &reply.id).unwrap();
+    assert_eq!(follow_up.reply_to.as_deref(), Some(reply.id.as_str()));
+    assert_eq!(
+        follow_up.thread_id.as_deref(),
+        Some(question.id.as_str()),
+        "a reply to a reply joins the same thread"
+    );
+
+    let thread = store.list_thread(&follow_up.id).unwrap();
+    assert_eq!(
+        thread.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+        vec![&*question.id, &*reply.id, &*follow_up.id]
+    );
+
+    let unthreaded = record_channel_reply(&store, None, None, "note").unwrap();
+    assert!(unthreaded.thread_id.is_none());
+    assert!(matches!(
+        store.link_reply(&question.id, &follow_up.id),
+        Err(HubError::Invalid(_))
+    ));
+    assert!(matches!(
+        store.link_reply(&question.id, "missing"),
+        Err(HubError::NotFound(_))
+    ));
+}
+
+#[test]
+fn replies_to_different_broadcast_copies_share_one_thread() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    store.set_team_member("claude", true).unwrap();
+    store.set_team_member("grok", true).unwrap();
+    let copies = store
+        .send_message_to_team("human", MessageKind::Message, "standup?", None, None, None)
+        .unwrap();
+    assert!(copies.len() >= 2);
+
+    let claude_copy = copies.iter().find(|m| m.to_agent == "claude").unwrap();
+    let grok_copy = copies.iter().find(|m| m.to_agent == "grok").unwrap();
+    let a = record_channel_reply(&store, Some(&claude_copy.id), None, "on it").unwrap();
+    let b = send(&store, "grok", "human", None, "me too");
+    let b = store.link_reply(&b.id, &grok_copy.id).unwrap();
+    assert_eq!(a.thread_id, b.thread_id);
+    assert_eq!(store.list_thread(&b.id).unwrap().len(), 3);
+}
+
+#[test]
+fn thread_summaries_count_replies_and_participants() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let general = send(&store, "human", "claude", Some("channel:general"), "plan?");
+    let random = send(&store, "human", "claude", Some("channel:random"), "lunch?");
+    send(
+        &store,
+        "human",
+        "claude",
+        Some("channel:general"),
+        "no replies",
+    );
+    for (from, parent, body) in [
+        ("claude", &general.id, "draft"),
+        ("codex", &general.id, "review"),
+        ("claude", &general.id, "revised"),
+        ("codex", &random.id, "tacos"),
+    ] {
+        store
+            .send_reply(
+                parent,
+                from,
+                "human",
+                MessageKind::Message,
+                body,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    let threads = store.list_threads(Some("#general"), 10).unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].root.id, general.id);
+    assert_eq!(threads[0].reply_count, 3);
+    assert_eq!(threads[0].participants, vec!["human", "claude", "codex"]);
+    assert_eq!(store.list_threads(None, 10).unwrap().len(), 2);
+
+    store.archive_message("human", &general.id).unwrap();
+    assert_eq!(store.list_threads(None, 10).unwrap().len(), 1);
+}
+
+#[test]
+fn replies_are_threaded_before_hooks_see_them() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let hooks = store.hooks_dir();
+    fs::create_dir_all(&hooks).unwrap();
+    fs::write(
+        hooks.join("post-send"),
+        "#!/bin/sh\ncat > \"$CA_HOME/post-send.json\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(hooks.join("post-send"), fs::Permissions::from_mode(0o755)).unwrap();
+    let question = send(&store, "grok", "claude", None, "question");
+
+    let reply = record_channel_reply(&store, Some(&question.id), None, "answer").unwrap();
+    let event: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("post-send.json")).unwrap())
+            .unwrap();
+    assert_eq!(event["data"]["id"], reply.id.as_str());
+    assert_eq!(event["data"]["reply_to"], question.id.as_str());
+    assert_eq!(event["data"]["thread_id"], question.id.as_str());
+
+    store.set_team_member("claude", true).unwrap();
+    store.set_team_member("grok", true).unwrap();
+    let copies = store
+        .send_reply_to_team(
+            &reply.id,
+            "human",
+            MessageKind::Message,
+            "thanks all",
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(copies.len() >= 2);
+    assert!(copies
+        .iter()
+        .all(|copy| copy.thread_id.as_deref() == Some(question.id.as_str())));
+
+    let before = store.list_messages(None, None).unwrap().len();
+    assert!(matches!(
+        store.send_reply(
+            "missing",
+            "grok",
+            "claude",
+            MessageKind::Message,
+            "orphan",
+            None,
+            None,
+            None,
+            None,
+        ),
+        Err(HubError::NotFound(_))
+    ));
+    assert_eq!(store.list_messages(None, None).unwrap().len(), before);
+}
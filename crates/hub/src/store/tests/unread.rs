@@ -0,0 +1,101 @@
+//! Unread summary tests.
+
+use super::super::*;
+use tempfile::tempdir;
+
+fn send(
+    store: &HubStore,
+    from: &str,
+    to: &str,
+    subject: Option<&str>,
+    body: &str,
+) -> MessageRecord {
+    store
+        .send_message(from, to, MessageKind::Message, body, subject, None, None)
+        .unwrap()
+}
+
+#[test]
+fn unread_counts_follow_read_markers_per_scope() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    let session = store.create_work_session("release").unwrap();
+    let session_scope = format!("channel:session:{}", session.id);
+
+    let first = send(&store, "claude", "human", Some("channel:general:a"), "one");
+    send(&store, "grok", "human", Some("channel:general:b"), "two");
+    send(
+        &store,
+        "claude",
+        "human",
+        Some(&format!("{session_scope}:c")),
+        "three",
+    );
+    let dm = send(&store, "codex", "human", None, "psst");
+    send(&store, "human", "claude", Some("channel:general:d"), "mine");
+    let cancelled = send(&store, "grok", "human", Some("channel:general:e"), "oops");
+    store
+        .set_message_status(&cancelled.id, MessageStatus::Cancelled)
+        .unwrap();
+
+    let summary = store.unread_summary("human").unwrap();
+    assert_eq!((summary.unread, summary.mentions), (4, 0));
+    let general = &summary.scopes[0];
+    assert_eq!(
+        (
+            general.scope.as_str(),
+            general.kind.as_str(),
+            general.unread
+        ),
+        ("channel:general", "channel", 2)
+    );
+    assert_eq!(general.first_unread_id.as_deref(), Some(first.id.as_str()));
+    let dms = summary
+        .scopes
+        .iter()
+        .find(|s| s.scope == "channel:dm-codex")
+        .unwrap();
+    assert_eq!((dms.kind.as_str(), dms.unread), ("dm", 1));
+    assert_eq!(dms.first_unread_id.as_deref(), Some(dm.id.as_str()));
+    assert_eq!(summary.unread_of_kind("session"), 1);
+
+    let later = (chrono::Utc::now() + chrono::Duration::seconds(1)).to_rfc3339();
+    store
+        .mark_read("human", "channel:general", Some(&later))
+        .unwrap();
+    store.archive_message("human", &dm.id).unwrap();
+    let summary = store.unread_summary("human").unwrap();
+    assert_eq!(summary.unread, 1);
+    assert_eq!(summary.scopes[0].scope, session_scope);
+}
+
+#[test]
+fn unread_mentions_are_counted_in_their_scope() {
+    let dir = tempdir().unwrap();
+    let store = HubStore::open(dir.path()).unwrap();
+    send(
+        &store,
+        "human",
+        "claude",
+        Some("channel:general:a"),
+        "@claude look",
+    );
+    // Mentioned in a post addressed to someone else.
+    let aside = send(&store, "human", "grok", Some("channel:ops:b"), "cc @claude");
+
+    let summary = store.unread_summary("claude").unwrap();
+    assert_eq!((summary.unread, summary.mentions), (1, 2));
+    let ops = summary
+        .scopes
+        .iter()
+        .find(|s| s.scope == "channel:ops")
+        .unwrap();
+    assert_eq!((ops.unread, ops.mentions), (0, 1));
+    assert_eq!(ops.first_unread_id.as_deref(), Some(aside.id.as_str()));
+
+    let later = (chrono::Utc::now() + chrono::Duration::seconds(1)).to_rfc3339();
+    store
+        .mark_read("claude", "channel:ops", Some(&later))
+        .unwrap();
+    assert_eq!(store.unread_summary("claude").unwrap().mentions, 1);
+}
@@ -0,0 +1,212 @@
+//! Webhook delivery tests, against a local HTTP stand-in server.
+
+use super::super::*;
+use crate::{SecretReference, SettingsStore, WebhookEvent, WebhookSink};
+use chrono::Duration;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use tempfile::tempdir;
+
+#[derive(Debug)]
+struct Received {
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// Answer one request per connection with each of `statuses` in turn and
+/// keep what was received.
+fn stand_in_server(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<Received>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&received);
+    std::thread::spawn(move || {
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = HashMap::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+                }
+            }
+            let length = headers
+                .get("content-length")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            log.lock().unwrap().push(Received { headers, body });
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 {status} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+        }
+    });
+    (url, received)
+}
+
+fn add_sink(home: &std::path::Path, sink: WebhookSink) {
+    let mut settings = SettingsStore::open(home);
+    settings.upsert_webhook(sink).unwrap();
+    settings.save().unwrap();
+}
+
+fn sink(name: &str, url: &str, events: Vec<WebhookEvent>) -> WebhookSink {
+    WebhookSink {
+        name: name.into(),
+        url: url.into(),
+        events,
+        template: None,
+        secret: None,
+        enabled: true,
+    }
+}
+
+#[test]
+fn signature_is_rfc_4231_hmac_sha256() {
+    assert_eq!(
+        webhook_signature(b"Jefe", b"what do ya want for nothing?"),
+        "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+}
+
+#[test]
+fn messages_to_human_are_templated_signed_and_retried_until_delivered() {
+    let dir = tempdir().unwrap();
+    let key_file = dir.path().join("webhook.key");
+    std::fs::write(&key_file, "s3cret\n").unwrap();
+    let (url, received) = stand_in_server(vec![500, 204]);
+    add_sink(
+        dir.path(),
+        WebhookSink {
+            template: Some(
+                r#"{"text": "{{data.from_agent}}: {{data.body}}", "id": "{{data.id}}", "event": "{{event}}"}"#
+                    .into(),
+            ),
+            secret: Some(SecretReference::KeyFile {
+                path: key_file.to_string_lossy().into(),
+            }),
+            ..sink("ops", &url, vec![WebhookEvent::MessageToHuman])
+        },
+    );
+    add_sink(
+        dir.path(),
+        sink(
+            "tasks",
+            "http://127.0.0.1:9/unused",
+            vec![WebhookEvent::TaskFailed],
+        ),
+    );
+    let store = HubStore::open(dir.path()).unwrap();
+    let message = store
+        .send_message(
+            "grok",
+            "human",
+            MessageKind::Message,
+            "build is red",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    store
+        .send_message(
+            "grok",
+            "claude",
+            MessageKind::Message,
+            "fyi",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let queued = store.list_webhook_deliveries(None, 10).unwrap();
+    assert_eq!(queued.len(), 1, "only the ops sink takes messages to human");
+    assert_eq!(queued[0].sink, "ops");
+
+    let now = Utc::now();
+    let first = store.deliver_webhooks(now).unwrap();
+    assert_eq!(first[0].status, WebhookDeliveryStatus::Pending);
+    assert_eq!(first[0].last_status_code, Some(500));
+    assert!(
+        store.deliver_webhooks(now).unwrap().is_empty(),
+        "the retry waits out its backoff"
+    );
+    let second = store
+        .deliver_webhooks(now + Duration::seconds(WEBHOOK_BACKOFF_SECS + 1))
+        .unwrap();
+    assert_eq!(second[0].status, WebhookDeliveryStatus::Delivered);
+    assert!(second[0].delivered_at.is_some());
+    let attempts = store.webhook_attempts(&second[0].id).unwrap();
+    let codes: Vec<_> = attempts.iter().map(|a| a.status_code).collect();
+    assert_eq!(codes, vec![Some(500), Some(204)]);
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 2);
+    let request = &received[1];
+    let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "text": "grok: build is red",
+            "id": message.id,
+            "event": "message_to_human",
+        })
+    );
+    assert_eq!(request.headers["x-hub-event"], "message_to_human");
+    assert_eq!(request.headers["x-hub-delivery"], second[0].id);
+    assert_eq!(
+        request.headers[&WEBHOOK_SIGNATURE_HEADER.to_ascii_lowercase()],
+        webhook_signature(b"s3cret", &request.body)
+    );
+}
+
+#[test]
+fn an_unreachable_sink_fails_after_the_last_attempt() {
+    let dir = tempdir().unwrap();
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", closed.local_addr().unwrap());
+    drop(closed);
+    add_sink(dir.path(), sink("down", &url, vec![]));
+    let store = HubStore::open(dir.path()).unwrap();
+    store
+        .send_message(
+            "claude",
+            "human",
+            MessageKind::Handoff,
+            "over to you",
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+    let mut now = Utc::now();
+    for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+        let pass = store.deliver_webhooks(now).unwrap();
+        assert_eq!(pass.len(), 1, "attempt {attempt}");
+        assert!(pass[0].last_error.is_some());
+        assert_eq!(pass[0].last_status_code, None);
+        now += Duration::days(1);
+    }
+    assert!(store.deliver_webhooks(now).unwrap().is_empty());
+    let delivery = &store.list_webhook_deliveries(Some("down"), 10).unwrap()[0];
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Failed);
+    assert_eq!(delivery.attempts, WEBHOOK_MAX_ATTEMPTS);
+    assert_eq!(delivery.next_attempt_at, None);
+    assert_eq!(
+        store.webhook_attempts(&delivery.id).unwrap().len(),
+        WEBHOOK_MAX_ATTEMPTS as usize
+    );
+}
@@ -56,6 +56,12 @@ impl MemoryTier {
 pub enum MemoryScope {
     Global,
     Workspace,
+    /// Shared context for one work session (`MemoryRecord::session_id`):
+    /// readable by that session's members and the human, nobody else.
+    Session,
+    /// One agent's scratch notes (owner is `MemoryRecord::agent_id`):
+    /// readable only by that agent.
+    AgentPrivate,
 }
 
 impl MemoryScope {
@@ -63,6 +69,8 @@ impl MemoryScope {
         match self {
             Self::Global => "global",
             Self::Workspace => "workspace",
+            Self::Session => "session",
+            Self::AgentPrivate => "agent_private",
         }
     }
 
@@ -70,8 +78,10 @@ impl MemoryScope {
         match s {
             "global" => Ok(Self::Global),
             "workspace" => Ok(Self::Workspace),
+            "session" => Ok(Self::Session),
+            "agent_private" => Ok(Self::AgentPrivate),
             other => Err(HubError::Invalid(format!(
-                "unknown scope: {other} (expected one of: global, workspace)"
+                "unknown scope: {other} (expected one of: global, workspace, session, agent_private)"
             ))),
         }
     }
@@ -84,6 +94,9 @@ pub enum MessageKind {
     Handoff,
     Wake,
     System,
+    /// Asks the recipient for an answer, optionally from fixed choices and
+    /// by a deadline; see [`HubStore::ask_question`](crate::HubStore::ask_question).
+    Question,
 }
 
 impl MessageKind {
@@ -93,6 +106,7 @@ impl MessageKind {
             Self::Handoff => "handoff",
             Self::Wake => "wake",
             Self::System => "system",
+            Self::Question => "question",
         }
     }
 
@@ -102,6 +116,7 @@ impl MessageKind {
             "handoff" => Ok(Self::Handoff),
             "wake" => Ok(Self::Wake),
             "system" => Ok(Self::System),
+            "question" => Ok(Self::Question),
             other => Err(HubError::Invalid(format!("unknown message kind: {other}"))),
         }
     }
@@ -215,6 +230,27 @@ pub struct MemoryRecord {
     pub updated_at: String,
     pub stale: bool,
     pub source_event_id: Option<String>,
+    /// Owning work session for `MemoryScope::Session` rows; `None` otherwise.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Relevance score and usage signals; only filled in by ranked reads
+    /// (`list_memories`, `read_memory`, `compaction_candidates`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relevance: Option<MemoryRelevance>,
+}
+
+/// A memory's relevance score (0.0..=1.0) and the signals behind it.
+/// Like `LinkSuggestion::score`, treat the number as ordering, not
+/// probability.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryRelevance {
+    pub score: f64,
+    pub reads: i64,
+    pub citations: i64,
+    pub dispatches: i64,
+    pub link_degree: i64,
+    /// Most recent read/citation/dispatch, if any.
+    pub last_used_at: Option<String>,
 }
 
 /// A directed edge between two memories (M-links). `relation` is deliberately
@@ -248,11 +284,26 @@ pub struct MessageRecord {
     pub body: String,
     pub created_at: String,
     pub acked_at: Option<String>,
+    /// Set while the message is archived; archived messages are left out
+    /// of the default listings.
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    /// The message this one answers, when it is a threaded reply.
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    /// Root message of the thread this reply belongs to; `None` on roots
+    /// and unthreaded messages.
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Structured content sent alongside `body`; see
+    /// [`HubStore::send_message_with_payload`](crate::HubStore::send_message_with_payload).
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
 }
 
 /// A file pasted or picked in the desktop composer (image or other
-/// attachment). Stored as a plain file under `<hub_home>/attachments/` and
-/// indexed here; messages reference one by embedding an
+/// attachment). Stored as a content-addressed blob under
+/// `<hub_home>/attachments/` and indexed here; messages reference one by embedding an
 /// `attachment://<id>` marker in their body rather than the `messages`
 /// table gaining a column, so no existing send path changes shape.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -263,6 +314,10 @@ pub struct AttachmentRecord {
     pub byte_size: i64,
     pub absolute_path: String,
     pub created_at: String,
+    /// Hex SHA-256 of the bytes; `None` only for a pre-content-addressing
+    /// row whose file was missing when the hub was migrated.
+    #[serde(default)]
+    pub sha256: Option<String>,
 }
 
 /// Per-recipient audit record for a task/wake-tagged send (C11). One row is
@@ -0,0 +1,418 @@
+//! Outbound webhook delivery. Hub events (a message to `human`, a gate
+//! approval waiting, a budget pause, a failed task) are queued in
+//! `webhook_deliveries`, one row per subscribed [`WebhookSink`] from
+//! settings, with the sink's template already applied. Queuing never does
+//! network I/O; [`HubStore::deliver_webhooks`] POSTs what is due, which the
+//! desktop app runs every few seconds and `ca hub webhook deliver` runs on
+//! demand.
+//!
+//! Each POST carries `X-Hub-Event`, `X-Hub-Delivery` and, when the sink
+//! has a key, `X-Hub-Signature-256: sha256=<hex HMAC-SHA256 of the body>`.
+//! A non-2xx answer or a transport error is retried with exponential
+//! backoff ([`WEBHOOK_BACKOFF_SECS`] doubled per attempt) until
+//! [`WEBHOOK_MAX_ATTEMPTS`]; every attempt is kept in `webhook_attempts`.
+//! Payloads may quote message bodies, so they are sealed like them.
+
+use super::*;
+use crate::{SecretReference, SettingsStore, WebhookEvent, WebhookSink};
+use chrono::{DateTime, Duration};
+use serde_json::Value;
+
+/// Attempts before a delivery is given up as failed.
+pub const WEBHOOK_MAX_ATTEMPTS: u32 = 5;
+/// Wait before the first retry; each later retry waits twice as long.
+pub const WEBHOOK_BACKOFF_SECS: i64 = 30;
+/// Header carrying `sha256=<hex>`, the HMAC-SHA256 of the request body.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Hub-Signature-256";
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "delivered" => Self::Delivered,
+            "failed" => Self::Failed,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// One event queued for one sink.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub sink: String,
+    pub event: String,
+    /// The body POSTed, after templating.
+    pub payload: Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    /// When the next attempt is due; `None` once delivered or failed.
+    pub next_attempt_at: Option<String>,
+    pub last_status_code: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+/// One POST of a delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookAttempt {
+    pub delivery_id: String,
+    pub attempt: u32,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub attempted_at: String,
+}
+
+const DELIVERY_COLUMNS: &str = "id, sink, event, hub_plain(payload), status, attempts, \
+     next_attempt_at, last_status_code, last_error, created_at, delivered_at";
+
+fn row_to_delivery(r: &rusqlite::Row<'_>) -> rusqlite::Result<WebhookDelivery> {
+    let payload: String = r.get(3)?;
+    let status: String = r.get(4)?;
+    Ok(WebhookDelivery {
+        id: r.get(0)?,
+        sink: r.get(1)?,
+        event: r.get(2)?,
+        payload: serde_json::from_str(&payload).unwrap_or(Value::Null),
+        status: WebhookDeliveryStatus::parse(&status),
+        attempts: r.get(5)?,
+        next_attempt_at: r.get(6)?,
+        last_status_code: r.get(7)?,
+        last_error: r.get(8)?,
+        created_at: r.get(9)?,
+        delivered_at: r.get(10)?,
+    })
+}
+
+/// HMAC-SHA256 (RFC 2104) over `message`.
+pub(crate) fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.iter().map(|b| b ^ byte).collect::<Vec<u8>>();
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+/// The `X-Hub-Signature-256` value for `body` under `key`.
+pub fn webhook_signature(key: &[u8], body: &[u8]) -> String {
+    let mac = hmac_sha256(key, body);
+    format!(
+        "sha256={}",
+        mac.iter().map(|b| format!("{b:02x}")).collect::<String>()
+    )
+}
+
+fn lookup<'a>(envelope: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(envelope, |value, key| match value {
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => value.get(key),
+        })
+}
+
+fn fill(template: &Value, envelope: &Value) -> Value {
+    match template {
+        Value::String(text) => {
+            let trimmed = text.trim();
+            if let Some(path) = trimmed
+                .strip_prefix("{{")
+                .and_then(|rest| rest.strip_suffix("}}"))
+                .filter(|path| !path.contains("{{") && !path.contains("}}"))
+            {
+                return lookup(envelope, path.trim())
+                    .cloned()
+                    .unwrap_or(Value::Null);
+            }
+            let mut out = String::new();
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                let Some(len) = rest[start..].find("}}") else {
+                    break;
+                };
+                out.push_str(&rest[..start]);
+                match lookup(envelope, rest[start + 2..start + len].trim()) {
+                    Some(Value::String(s)) => out.push_str(s),
+                    Some(Value::Null) | None => {}
+                    Some(other) => out.push_str(&other.to_string()),
+                }
+                rest = &rest[start + len + 2..];
+            }
+            out.push_str(rest);
+            Value::String(out)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| fill(v, envelope)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.clone(), fill(v, envelope)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Apply a sink's template to the event envelope; no template (or one that
+/// no longer parses) posts the envelope as is.
+pub(crate) fn render_webhook_payload(template: Option<&str>, envelope: &Value) -> Value {
+    match template.and_then(|t| serde_json::from_str::<Value>(t).ok()) {
+        Some(template) => fill(&template, envelope),
+        None => envelope.clone(),
+    }
+}
+
+fn resolve_signing_key(secret: &SecretReference) -> Result<String, String> {
+    let raw = match secret {
+        SecretReference::EnvVar { name } => std::env::var(name)
+            .map_err(|_| format!("signing key: environment variable {name} is not set"))?,
+        SecretReference::KeyFile { path } => {
+            fs::read_to_string(path).map_err(|e| format!("signing key: cannot read {path}: {e}"))?
+        }
+        _ => return Err("signing key must be an env_var or key_file reference".into()),
+    };
+    Ok(raw.trim().to_string())
+}
+
+/// POST `body` to `sink`; `Ok` carries the HTTP status, `Err` a transport
+/// or signing failure.
+fn post(
+    client: &reqwest::blocking::Client,
+    sink: &WebhookSink,
+    delivery: &WebhookDelivery,
+    body: Vec<u8>,
+) -> Result<u16, String> {
+    let mut request = client
+        .post(&sink.url)
+        .header("Content-Type", "application/json")
+        .header("X-Hub-Event", &delivery.event)
+        .header("X-Hub-Delivery", &delivery.id);
+    if let Some(secret) = &sink.secret {
+        let key = resolve_signing_key(secret)?;
+        request = request.header(
+            WEBHOOK_SIGNATURE_HEADER,
+            webhook_signature(key.as_bytes(), &body),
+        );
+    }
+    request
+        .body(body)
+        .send()
+        .map(|response| response.status().as_u16())
+        .map_err(|e| e.to_string())
+}
+
+impl HubStore {
+    pub(crate) fn ensure_webhook_tables(&self) -> Result<(), HubError> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS webhook_deliveries (
+                id TEXT PRIMARY KEY,
+                sink TEXT NOT NULL,
+                event TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at TEXT,
+                last_status_code INTEGER,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                delivered_at TEXT
+            );
+            CREATE INDEX IF NOT EXISTS webhook_deliveries_due
+                ON webhook_deliveries(status, next_attempt_at);
+            CREATE TABLE IF NOT EXISTS webhook_attempts (
+                delivery_id TEXT NOT NULL REFERENCES webhook_deliveries(id) ON DELETE CASCADE,
+                attempt INTEGER NOT NULL,
+                status_code INTEGER,
+                error TEXT,
+                attempted_at TEXT NOT NULL,
+                PRIMARY KEY (delivery_id, attempt)
+            );",
+        )?;
+        Ok(())
+    }
+
+    /// Queue `event` for every enabled sink subscribed to it. `data`
+    /// becomes the envelope's `data` field.
+    pub(crate) fn emit_webhook_event(
+        &self,
+        event: WebhookEvent,
+        data: &impl Serialize,
+    ) -> Result<(), HubError> {
+        let sinks: Vec<WebhookSink> = SettingsStore::open(&self.data_dir)
+            .list_webhooks()
+            .into_iter()
+            .filter(|sink| sink.accepts(event))
+            .collect();
+        if sinks.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now().to_rfc3339();
+        let envelope = serde_json::json!({
+            "event": event.as_str(),
+            "occurred_at": now,
+            "data": serde_json::to_value(data)
+                .map_err(|e| HubError::Invalid(format!("webhook event data: {e}")))?,
+        });
+        for sink in sinks {
+            let payload = render_webhook_payload(sink.template.as_deref(), &envelope);
+            self.conn.execute(
+                "INSERT INTO webhook_deliveries(
+                    id, sink, event, payload, status, attempts, next_attempt_at, created_at
+                 ) VALUES (?1, ?2, ?3, hub_seal(?4), 'pending', 0, ?5, ?5)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    sink.name,
+                    event.as_str(),
+                    payload.to_string(),
+                    now,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Deliveries newest first, optionally for one sink.
+    pub fn list_webhook_deliveries(
+        &self,
+        sink: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, HubError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+             WHERE ?1 IS NULL OR sink = ?1
+             ORDER BY created_at DESC, rowid DESC LIMIT ?2"
+        ))?;
+        let rows = stmt.query_map(params![sink, limit as i64], row_to_delivery)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub fn webhook_attempts(&self, delivery_id: &str) -> Result<Vec<WebhookAttempt>, HubError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT delivery_id, attempt, status_code, error, attempted_at
+             FROM webhook_attempts WHERE delivery_id = ?1 ORDER BY attempt ASC",
+        )?;
+        let rows = stmt.query_map(params![delivery_id], |r| {
+            Ok(WebhookAttempt {
+                delivery_id: r.get(0)?,
+                attempt: r.get(1)?,
+                status_code: r.get(2)?,
+                error: r.get(3)?,
+                attempted_at: r.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Attempt every pending delivery due at `now` once and return them as
+    /// they stand afterwards. Sinks are read from settings on each pass,
+    /// so a changed URL or key applies to the next attempt.
+    pub fn deliver_webhooks(&self, now: DateTime<Utc>) -> Result<Vec<WebhookDelivery>, HubError> {
+        let due: Vec<WebhookDelivery> = {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+                 WHERE status = 'pending' AND next_attempt_at <= ?1
+                 ORDER BY created_at ASC, rowid ASC"
+            ))?;
+            let rows = stmt.query_map(params![now.to_rfc3339()], row_to_delivery)?;
+            rows.collect::<Result<_, _>>()?
+        };
+        if due.is_empty() {
+            return Ok(due);
+        }
+        let settings = SettingsStore::open(&self.data_dir);
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| HubError::Invalid(format!("webhook client: {e}")))?;
+        let mut attempted = Vec::with_capacity(due.len());
+        for delivery in due {
+            let attempt = delivery.attempts + 1;
+            let result = match settings.webhook(&delivery.sink) {
+                Some(sink) => post(
+                    &client,
+                    sink,
+                    &delivery,
+                    delivery.payload.to_string().into(),
+                ),
+                None => Err(format!("webhook {} is no longer configured", delivery.sink)),
+            };
+            let (status_code, error) = match result {
+                Ok(code) if (200..300).contains(&code) => (Some(code), None),
+                Ok(code) => (Some(code), Some(format!("HTTP {code}"))),
+                Err(error) => (None, Some(error)),
+            };
+            let gone = settings.webhook(&delivery.sink).is_none();
+            let (status, next_attempt_at) = if error.is_none() {
+                (WebhookDeliveryStatus::Delivered, None)
+            } else if gone || attempt >= WEBHOOK_MAX_ATTEMPTS {
+                (WebhookDeliveryStatus::Failed, None)
+            } else {
+                let wait = WEBHOOK_BACKOFF_SECS << (attempt - 1).min(20);
+                (
+                    WebhookDeliveryStatus::Pending,
+                    Some((now + Duration::seconds(wait)).to_rfc3339()),
+                )
+            };
+            let attempted_at = Utc::now().to_rfc3339();
+            self.conn.execute(
+                "INSERT INTO webhook_attempts(delivery_id, attempt, status_code, error, attempted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![delivery.id, attempt, status_code, error, attempted_at],
+            )?;
+            self.conn.execute(
+                "UPDATE webhook_deliveries
+                 SET status = ?1, attempts = ?2, next_attempt_at = ?3, last_status_code = ?4,
+                     last_error = ?5,
+                     delivered_at = CASE WHEN ?1 = 'delivered' THEN ?6 ELSE delivered_at END
+                 WHERE id = ?7",
+                params![
+                    status.as_str(),
+                    attempt,
+                    next_attempt_at,
+                    status_code,
+                    error,
+                    attempted_at,
+                    delivery.id,
+                ],
+            )?;
+            attempted.push(WebhookDelivery {
+                status,
+                attempts: attempt,
+                next_attempt_at,
+                last_status_code: status_code,
+                delivered_at: (status == WebhookDeliveryStatus::Delivered)
+                    .then(|| attempted_at.clone()),
+                last_error: error,
+                ..delivery
+            });
+        }
+        Ok(attempted)
+    }
+}
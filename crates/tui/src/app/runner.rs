@@ -59,6 +59,10 @@ pub fn run(options: TuiOptions) -> Result<()> {
                 channel_messages: vec![],
                 tasks: vec![],
                 audit_events: vec![],
+                citation_warnings: vec![],
+                unread: None,
+                receipts: Default::default(),
+                open_questions: vec![],
                 effective_settings: effective.clone(),
             },
             true,
@@ -88,11 +88,21 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &AppState) {
 }
 
 fn draw_tabs(frame: &mut Frame, area: Rect, app: &AppState) {
+    let chat = match &app.read_model.unread {
+        Some(unread) if unread.mentions > 0 => {
+            format!(
+                "2: Chat & Memory ({} · @{})",
+                unread.unread, unread.mentions
+            )
+        }
+        Some(unread) if unread.unread > 0 => format!("2: Chat & Memory ({})", unread.unread),
+        _ => "2: Chat & Memory".to_string(),
+    };
     let titles = vec![
-        "1: Orchestrate",
-        "2: Chat & Memory",
-        "3: Shared Hub",
-        "4: Settings",
+        "1: Orchestrate".to_string(),
+        chat,
+        "3: Shared Hub".to_string(),
+        "4: Settings".to_string(),
     ];
     let theme = &app.theme;
     let tabs = Tabs::new(titles)
@@ -147,10 +147,10 @@ pub fn draw_chat_view(frame: &mut Frame, area: Rect, app: &AppState) {
         )),
     ];
 
-    for msg in app
+    for (is_reply, msg) in app
         .read_model
-        .channel_messages
-        .iter()
+        .threaded_channel_messages()
+        .into_iter()
         .skip(app.scroll_offset)
         .take(15)
     {
@@ -159,11 +159,85 @@ pub fn draw_chat_view(frame: &mut Frame, area: Rect, app: &AppState) {
         } else {
             &msg.from_agent
         };
+        let indent = if is_reply { "   ↳" } else { "" };
         let body_preview: String = msg.body.chars().take(80).collect();
-        text.push(Line::from(vec![
-            Span::styled(format!(" [{}] ", sender), Style::default().fg(theme.accent)),
+        let mut line = vec![
+            Span::styled(
+                format!("{indent} [{}] ", sender),
+                Style::default().fg(theme.accent),
+            ),
             Span::raw(body_preview),
-        ]));
+        ];
+        if let Some(receipt) = app.read_model.receipts.get(&msg.id) {
+            line.push(Span::styled(
+                format!(
+                    "  ({}/{} read, {} acked)",
+                    receipt.read + receipt.acked,
+                    receipt.recipients,
+                    receipt.acked
+                ),
+                Style::default().fg(theme.muted),
+            ));
+        }
+        text.push(Line::from(line));
+    }
+
+    let warnings = &app.read_model.citation_warnings;
+    if !warnings.is_empty() {
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            format!("Citation warnings ({}):", warnings.len()),
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for warning in warnings.iter().take(5) {
+            let subject = warning.subject.as_deref().unwrap_or("(no subject)");
+            text.push(Line::from(vec![
+                Span::styled(
+                    format!(
+                        " ! Memory #{} {} ",
+                        warning.reference,
+                        warning.issue.as_str()
+                    ),
+                    Style::default().fg(theme.warning),
+                ),
+                Span::styled(
+                    format!("{} → {}: {subject}", warning.from_agent, warning.to_agent),
+                    Style::default().fg(theme.muted),
+                ),
+            ]));
+        }
+    }
+
+    let questions = &app.read_model.open_questions;
+    if !questions.is_empty() {
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            format!(
+                "Open questions ({}) — answer with `ca msg answer`:",
+                questions.len()
+            ),
+            Style::default()
+                .fg(theme.accent2)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for question in questions.iter().take(5) {
+            let mut line = vec![
+                Span::styled(
+                    format!(" ? [{}] ", question.question.from_agent),
+                    Style::default().fg(theme.accent2),
+                ),
+                Span::raw(question.question.body.chars().take(80).collect::<String>()),
+            ];
+            if !question.choices.is_empty() {
+                line.push(Span::styled(
+                    format!("  ({})", question.choices.join(" / ")),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            text.push(Line::from(line));
+        }
     }
 
     let block = Block::default()
@@ -1,13 +1,16 @@
 //! Shared Hub read model for the Ratatui TUI client (T2 / #136).
 //!
 //! Provides a unified, read-only snapshot of Hub data (work sessions, team roster,
-//! channel messages, tasks, settings audit stream, effective settings) without depending
-//! on Tauri IPC.
+//! channel messages, tasks, settings audit stream, effective settings, citation warnings,
+//! the human's unread summary, receipts for the human's posts, questions
+//! awaiting the human's answer)
+//! without depending on Tauri IPC.
 
 use hub::{
-    AgentRecord, AuditEvent, EffectiveSettings, HubStore, MessageRecord, SettingsStore, TaskRecord,
-    WorkSessionRecord,
+    AgentRecord, AuditEvent, CitationWarning, EffectiveSettings, HubStore, MessageRecord,
+    QuestionRecord, ReceiptSummary, SettingsStore, TaskRecord, UnreadSummary, WorkSessionRecord,
 };
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 #[derive(Debug, Clone)]
@@ -17,6 +20,16 @@ pub struct HubReadModel {
     pub channel_messages: Vec<MessageRecord>,
     pub tasks: Vec<TaskRecord>,
     pub audit_events: Vec<AuditEvent>,
+    /// Messages whose cited memories went stale, were deleted, or were
+    /// revised after sending; newest first.
+    pub citation_warnings: Vec<CitationWarning>,
+    /// What the human operator has not read yet, for the tab badges.
+    pub unread: Option<UnreadSummary>,
+    /// Delivery receipts for the human's posts in `channel_messages`, by
+    /// message id.
+    pub receipts: HashMap<String, ReceiptSummary>,
+    /// Open questions addressed to the human, oldest first.
+    pub open_questions: Vec<QuestionRecord>,
     pub effective_settings: EffectiveSettings,
 }
 
@@ -47,6 +60,14 @@ impl HubReadModel {
 
         let tasks = hub_store.list_tasks(None)?;
         let audit_events = hub_store.list_settings_audit_events()?;
+        let citation_warnings = hub_store.citation_warnings(None)?;
+        let unread = Some(hub_store.unread_summary("human")?);
+        let mut receipts = HashMap::new();
+        for message in channel_messages.iter().filter(|m| m.from_agent == "human") {
+            receipts.insert(message.id.clone(), hub_store.message_receipts(&message.id)?);
+        }
+
+        let open_questions = hub_store.list_questions(Some("human"), true)?;
 
         Ok(Self {
             work_sessions,
@@ -54,7 +75,49 @@ impl HubReadModel {
             channel_messages,
             tasks,
             audit_events,
+            citation_warnings,
+            unread,
+            receipts,
+            open_questions,
             effective_settings,
         })
     }
+
+    /// `channel_messages` with every reply moved right under its thread
+    /// root, oldest reply first, paired with whether it is a reply. A reply
+    /// whose root is not on screen stays where it was, as a top-level line.
+    pub fn threaded_channel_messages(&self) -> Vec<(bool, &MessageRecord)> {
+        let visible: HashSet<&str> = self
+            .channel_messages
+            .iter()
+            .map(|m| m.id.as_str())
+            .collect();
+        let threaded = |m: &MessageRecord| {
+            m.thread_id
+                .as_deref()
+                .filter(|root| visible.contains(root))
+                .map(str::to_owned)
+        };
+        let mut replies: Vec<&MessageRecord> = self
+            .channel_messages
+            .iter()
+            .filter(|m| threaded(m).is_some())
+            .collect();
+        replies.sort_by(|a, b| (&a.created_at, &a.id).cmp(&(&b.created_at, &b.id)));
+        let mut lines = Vec::with_capacity(self.channel_messages.len());
+        for message in self
+            .channel_messages
+            .iter()
+            .filter(|m| threaded(m).is_none())
+        {
+            lines.push((false, message));
+            lines.extend(
+                replies
+                    .iter()
+                    .filter(|r| r.thread_id.as_deref() == Some(message.id.as_str()))
+                    .map(|r| (true, *r)),
+            );
+        }
+        lines
+    }
 }
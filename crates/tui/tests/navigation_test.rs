@@ -16,6 +16,10 @@ fn test_tui_app_state_navigation_and_command_palette() {
         channel_messages: vec![],
         tasks: vec![],
         audit_events: vec![],
+        citation_warnings: vec![],
+        unread: None,
+        receipts: Default::default(),
+        open_questions: vec![],
         effective_settings: effective.clone(),
     };
 
@@ -67,6 +71,10 @@ fn test_refresh_hides_internal_hub_errors() {
         channel_messages: vec![],
         tasks: vec![],
         audit_events: vec![],
+        citation_warnings: vec![],
+        unread: None,
+        receipts: Default::default(),
+        open_questions: vec![],
         effective_settings: effective.clone(),
     };
 
@@ -98,6 +106,10 @@ fn test_tui_preferences_and_prefix_mode() {
         channel_messages: vec![],
         tasks: vec![],
         audit_events: vec![],
+        citation_warnings: vec![],
+        unread: None,
+        receipts: Default::default(),
+        open_questions: vec![],
         effective_settings: effective.clone(),
     };
 
@@ -7,10 +7,16 @@
 //! `commands::quotas`, etc.) are unchanged for callers — only the on-disk
 //! file layout moved.
 
+#[path = "messager/archive.rs"]
+pub mod archive;
 #[path = "messager/attachments.rs"]
 pub mod attachments;
 #[path = "hub/avatar.rs"]
 pub mod avatar;
+#[path = "hub/escalations.rs"]
+pub mod escalations;
+#[path = "messager/inbox.rs"]
+pub mod inbox;
 #[path = "messager/memory.rs"]
 pub mod memory;
 #[path = "messager/messaging.rs"]
@@ -23,8 +29,16 @@ mod quota_codex;
 mod quota_gemini;
 #[path = "quota/grok.rs"]
 mod quota_grok;
+#[path = "hub/questions.rs"]
+pub mod questions;
+#[path = "hub/webhooks.rs"]
+pub mod webhooks;
+#[path = "hub/hooks.rs"]
+pub mod hooks;
 #[path = "quota/quotas.rs"]
 pub mod quotas;
+#[path = "hub/retention.rs"]
+pub mod retention;
 #[path = "settings/roles.rs"]
 pub mod roles;
 #[path = "settings/settings.rs"]
@@ -0,0 +1,48 @@
+//! Escalation of unacked messages: the desktop app takes due steps every
+//! minute, and Settings can read or change the per-kind ack deadlines.
+use super::store::open_store;
+use hub::{EscalationConfig, EscalationRecord};
+use std::time::Duration;
+
+const ESCALATION_PASS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Start the background escalation pass. The config is re-read on every
+/// pass, so changed deadlines apply without a restart.
+pub fn spawn_escalation_runner() {
+    std::thread::spawn(|| loop {
+        let pass = open_store().and_then(|store| {
+            store
+                .run_escalations(chrono::Utc::now())
+                .map_err(|e| e.to_string())
+        });
+        match pass {
+            Ok(taken) if !taken.is_empty() => {
+                println!("escalated {} unacked messages", taken.len());
+            }
+            Ok(_) => {}
+            Err(error) => eprintln!("escalation pass failed: {error}"),
+        }
+        std::thread::sleep(ESCALATION_PASS_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub fn hub_escalation_config() -> Result<EscalationConfig, String> {
+    open_store()?.escalation_config().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_set_escalation_config(config: EscalationConfig) -> Result<EscalationConfig, String> {
+    let store = open_store()?;
+    store
+        .set_escalation_config(&config)
+        .map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn hub_list_escalations(message_id: Option<String>) -> Result<Vec<EscalationRecord>, String> {
+    open_store()?
+        .list_escalations(message_id.as_deref())
+        .map_err(|e| e.to_string())
+}
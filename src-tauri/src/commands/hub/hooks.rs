@@ -0,0 +1,38 @@
+//! Hook scripts: Settings can list the installed hooks, read recent runs,
+//! and change how hooks run. The hooks themselves run inside the hub.
+use super::store::open_store;
+use hub::{HookConfig, HookEvent, HookRun, HookScript};
+
+#[tauri::command]
+pub fn hub_list_hooks(workspace_path: Option<String>) -> Result<Vec<HookScript>, String> {
+    open_store()?
+        .list_hooks(workspace_path.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_list_hook_runs(
+    event: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<HookRun>, String> {
+    let event = event
+        .as_deref()
+        .map(HookEvent::parse)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    open_store()?
+        .list_hook_runs(event, limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_hook_config() -> Result<HookConfig, String> {
+    open_store()?.hook_config().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_set_hook_config(config: HookConfig) -> Result<HookConfig, String> {
+    open_store()?
+        .set_hook_config(&config)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,54 @@
+//! Questions for the human from any hub agent: list the open ones, ask, and
+//! answer (which routes the answer back into the asker's harness).
+use super::store::open_store;
+use crate::harness::blocking::run_blocking;
+use hub::{QuestionAnswer, QuestionRecord};
+
+#[tauri::command]
+pub fn hub_list_questions(
+    to_agent: Option<String>,
+    include_closed: Option<bool>,
+) -> Result<Vec<QuestionRecord>, String> {
+    open_store()?
+        .list_questions(to_agent.as_deref(), !include_closed.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_ask_question(
+    from_agent: String,
+    to_agent: String,
+    body: String,
+    choices: Option<Vec<String>>,
+    deadline: Option<String>,
+    workspace_path: Option<String>,
+    task_id: Option<String>,
+) -> Result<QuestionRecord, String> {
+    open_store()?
+        .ask_question(
+            &from_agent,
+            &to_agent,
+            &body,
+            &choices.unwrap_or_default(),
+            deadline.as_deref(),
+            workspace_path.as_deref(),
+            task_id.as_deref(),
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Injecting the answer can wait on a provider bridge, so it runs off the
+/// IPC thread like `hub_inject_harness`.
+#[tauri::command]
+pub async fn hub_answer_question(
+    id: String,
+    from_agent: Option<String>,
+    answer: String,
+) -> Result<QuestionAnswer, String> {
+    run_blocking("hub_answer_question", move || {
+        open_store()?
+            .answer_question(&id, from_agent.as_deref().unwrap_or("human"), &answer)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
@@ -0,0 +1,47 @@
+//! Retention sweeps (`orchestration.retention_days`): the desktop app runs
+//! one at startup and every few hours after, and Settings can trigger or
+//! preview one on demand.
+use super::store::open_store;
+use hub::{RetentionExport, RetentionReport, SettingsStore};
+use std::time::Duration;
+
+const RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+fn sweep(dry_run: bool, export: Option<RetentionExport>) -> Result<RetentionReport, String> {
+    let settings = SettingsStore::open(hub::default_hub_home());
+    open_store()?
+        .sweep_retention(&settings, dry_run, export)
+        .map_err(|e| e.to_string())
+}
+
+/// Start the background sweeper. Settings are re-read on every pass, so a
+/// changed `retention_days` applies without a restart.
+pub fn spawn_retention_sweeper() {
+    std::thread::spawn(|| loop {
+        match sweep(false, None) {
+            Ok(report) if report.total_expired() > 0 => {
+                println!(
+                    "retention sweep removed {} rows (archive: {:?})",
+                    report.total_expired(),
+                    report.archive
+                );
+            }
+            Ok(_) => {}
+            Err(error) => eprintln!("retention sweep failed: {error}"),
+        }
+        std::thread::sleep(RETENTION_SWEEP_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub fn hub_sweep_retention(
+    apply: Option<bool>,
+    export: Option<String>,
+) -> Result<RetentionReport, String> {
+    let export = export
+        .as_deref()
+        .map(RetentionExport::parse)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    sweep(!apply.unwrap_or(false), export)
+}
@@ -0,0 +1,63 @@
+//! Outbound webhooks: the desktop app POSTs due deliveries every few
+//! seconds, and Settings can manage sinks and read delivery history.
+use super::store::open_store;
+use hub::{SettingsStore, WebhookAttempt, WebhookDelivery, WebhookSink};
+use std::time::Duration;
+
+const WEBHOOK_PASS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Start the background delivery pass. It runs on its own thread because
+/// delivery uses a blocking HTTP client; sinks are re-read on every pass.
+pub fn spawn_webhook_runner() {
+    std::thread::spawn(|| loop {
+        let pass = open_store().and_then(|store| {
+            store
+                .deliver_webhooks(chrono::Utc::now())
+                .map_err(|e| e.to_string())
+        });
+        if let Err(error) = pass {
+            eprintln!("webhook delivery pass failed: {error}");
+        }
+        std::thread::sleep(WEBHOOK_PASS_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub fn hub_list_webhooks() -> Result<Vec<WebhookSink>, String> {
+    Ok(SettingsStore::open(hub::default_hub_home()).list_webhooks())
+}
+
+#[tauri::command]
+pub fn hub_upsert_webhook(sink: WebhookSink) -> Result<WebhookSink, String> {
+    let mut settings = SettingsStore::open(hub::default_hub_home());
+    settings
+        .upsert_webhook(sink.clone())
+        .map_err(|e| e.to_string())?;
+    settings.save().map_err(|e| e.to_string())?;
+    Ok(sink)
+}
+
+#[tauri::command]
+pub fn hub_remove_webhook(name: String) -> Result<WebhookSink, String> {
+    let mut settings = SettingsStore::open(hub::default_hub_home());
+    let removed = settings.remove_webhook(&name).map_err(|e| e.to_string())?;
+    settings.save().map_err(|e| e.to_string())?;
+    Ok(removed)
+}
+
+#[tauri::command]
+pub fn hub_list_webhook_deliveries(
+    sink: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<WebhookDelivery>, String> {
+    open_store()?
+        .list_webhook_deliveries(sink.as_deref(), limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_webhook_attempts(delivery_id: String) -> Result<Vec<WebhookAttempt>, String> {
+    open_store()?
+        .webhook_attempts(&delivery_id)
+        .map_err(|e| e.to_string())
+}
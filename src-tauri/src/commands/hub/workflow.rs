@@ -1,6 +1,6 @@
 //! Workflow, budget, and shutdown commands.
 use super::store::open_store;
-use hub::{BudgetPauseOutcome, BudgetStatus, TaskRecord, TaskStatus, WorkflowStep};
+use hub::{BudgetPauseOutcome, BudgetStatus, PageRequest, TaskRecord, TaskStatus, WorkflowStep};
 #[derive(serde::Deserialize)]
 pub struct CreateTaskArgs {
     pub title: String,
@@ -24,13 +24,22 @@ pub fn hub_create_task(args: CreateTaskArgs) -> Result<TaskRecord, String> {
 }
 
 #[tauri::command]
-pub fn hub_list_tasks(status: Option<String>) -> Result<Vec<TaskRecord>, String> {
+pub fn hub_list_tasks(
+    status: Option<String>,
+    page: Option<PageRequest>,
+) -> Result<Vec<TaskRecord>, String> {
     let status = status
         .as_deref()
         .map(TaskStatus::parse)
         .transpose()
         .map_err(|e| e.to_string())?;
-    open_store()?.list_tasks(status).map_err(|e| e.to_string())
+    let store = open_store()?;
+    // Paged lists order by creation; the unpaged list by last update.
+    match page {
+        Some(page) => store.list_tasks_page(status, &page).map(|page| page.items),
+        None => store.list_tasks(status),
+    }
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -0,0 +1,55 @@
+//! Archive/unarchive commands for messages, channels and work sessions.
+//! `hub_set_archived` is the one mutation; the store checks the actor's
+//! `can_archive_messages` permission. The list commands back the
+//! "Archived" view.
+
+use super::store::open_store;
+use hub::{ArchiveOutcome, ChannelRecord, MessageRecord, PageRequest, WorkSessionRecord};
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetArchivedArgs {
+    /// `message`, `channel` or `work_session`.
+    pub target: String,
+    pub id: String,
+    pub actor: String,
+    pub archived: bool,
+}
+
+#[tauri::command]
+pub fn hub_set_archived(args: SetArchivedArgs) -> Result<ArchiveOutcome, String> {
+    let store = open_store()?;
+    let (actor, id) = (args.actor.as_str(), args.id.as_str());
+    match (args.target.as_str(), args.archived) {
+        ("message", true) => store.archive_message(actor, id),
+        ("message", false) => store.unarchive_message(actor, id),
+        ("channel", true) => store.archive_channel(actor, id),
+        ("channel", false) => store.unarchive_channel(actor, id),
+        ("work_session", true) => store.archive_work_session(actor, id),
+        ("work_session", false) => store.unarchive_work_session(actor, id),
+        (other, _) => return Err(format!("unknown archive target: {other}")),
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_list_archived_messages(page: Option<PageRequest>) -> Result<Vec<MessageRecord>, String> {
+    open_store()?
+        .list_archived_messages_page(&page.unwrap_or_default())
+        .map(|page| page.items)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_list_archived_channels() -> Result<Vec<ChannelRecord>, String> {
+    open_store()?
+        .list_archived_channels()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_list_archived_work_sessions() -> Result<Vec<WorkSessionRecord>, String> {
+    open_store()?
+        .list_archived_work_sessions()
+        .map_err(|e| e.to_string())
+}
@@ -3,11 +3,12 @@
 //! IPC boundary, decoded and written to disk), which returns the record the
 //! frontend embeds into the message body as an `attachment://<id>` marker;
 //! `hub_get_attachment` resolves that marker back into bytes for inline
-//! rendering.
+//! rendering. `hub_search_attachments` backs the memory search's
+//! "include attachments" toggle.
 
 use super::store::open_store;
 use base64::{engine::general_purpose::STANDARD, Engine};
-use hub::AttachmentRecord;
+use hub::{AttachmentRecord, AttachmentSearchHit};
 
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -44,6 +45,16 @@ pub fn hub_get_attachment(id: String) -> Result<Option<AttachmentPayload>, Strin
     }))
 }
 
+#[tauri::command]
+pub fn hub_search_attachments(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<AttachmentSearchHit>, String> {
+    open_store()?
+        .search_attachments(&query, limit.unwrap_or(20))
+        .map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
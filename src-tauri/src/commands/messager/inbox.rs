@@ -0,0 +1,46 @@
+//! Unread summaries, @mentions, message search and delivery receipts for
+//! the chat sidebar.
+
+use super::store::open_store;
+use hub::{MentionRecord, MessageSearch, MessageSearchHit, ReceiptSummary, UnreadSummary};
+
+/// Per-scope unread counts and unread @mentions for `agent`, from its read
+/// markers — see `hub::HubStore::unread_summary`.
+#[tauri::command]
+pub fn hub_unread_summary(agent: String) -> Result<UnreadSummary, String> {
+    open_store()?
+        .unread_summary(&agent)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn hub_list_mentions(
+    agent: String,
+    unread_only: Option<bool>,
+    limit: Option<usize>,
+) -> Result<Vec<MentionRecord>, String> {
+    open_store()?
+        .list_mentions(&agent, unread_only.unwrap_or(false), limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+/// Ranked full-text message search as `agent` sees it — see
+/// `hub::HubStore::search_messages`.
+#[tauri::command]
+pub fn hub_search_messages(
+    agent: String,
+    search: MessageSearch,
+) -> Result<Vec<MessageSearchHit>, String> {
+    open_store()?
+        .search_messages(&agent, &search)
+        .map_err(|e| e.to_string())
+}
+
+/// Per-recipient delivery receipts for a sent message — see
+/// `hub::HubStore::message_receipts`.
+#[tauri::command]
+pub fn hub_message_receipts(id: String) -> Result<ReceiptSummary, String> {
+    open_store()?
+        .message_receipts(&id)
+        .map_err(|e| e.to_string())
+}
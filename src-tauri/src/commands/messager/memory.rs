@@ -2,7 +2,7 @@
 use super::store::open_store;
 use hub::{
     CompactReport, LinkSuggestion, LinkSuggestionMode, MemoryLinkRecord, MemoryRecord, MemoryScope,
-    MemoryTier,
+    MemoryTier, PageRequest,
 };
 #[derive(serde::Deserialize)]
 pub struct WriteMemoryArgs {
@@ -10,6 +10,8 @@ pub struct WriteMemoryArgs {
     pub scope: String,
     pub agent: Option<String>,
     pub workspace: Option<String>,
+    /// Work session id; required when `scope` is `session`.
+    pub session: Option<String>,
     pub title: Option<String>,
     pub body: String,
     pub tags: Option<Vec<String>>,
@@ -21,6 +23,22 @@ pub fn hub_write_memory(args: WriteMemoryArgs) -> Result<MemoryRecord, String> {
     let tier = MemoryTier::parse(&args.tier).map_err(|e| e.to_string())?;
     let scope = MemoryScope::parse(&args.scope).map_err(|e| e.to_string())?;
     let tags = args.tags.unwrap_or_default();
+    if scope == MemoryScope::Session {
+        let session = args
+            .session
+            .as_deref()
+            .ok_or_else(|| "session scope requires a work session".to_string())?;
+        return store
+            .write_session_memory(
+                session,
+                tier,
+                args.agent.as_deref(),
+                args.title.as_deref(),
+                &args.body,
+                &tags,
+            )
+            .map_err(|e| e.to_string());
+    }
     store
         .write_memory(
             tier,
@@ -34,6 +52,12 @@ pub fn hub_write_memory(args: WriteMemoryArgs) -> Result<MemoryRecord, String> {
         .map_err(|e| e.to_string())
 }
 
+/// The desktop app reads as the human operator unless told otherwise, so
+/// work-session memories stay visible there; agent-private notes do not.
+fn memory_reader(reader: Option<String>) -> String {
+    reader.unwrap_or_else(|| "human".to_string())
+}
+
 #[derive(serde::Deserialize)]
 pub struct UpdateMemoryArgs {
     pub id: String,
@@ -57,6 +81,8 @@ pub fn hub_list_memories(
     tier: Option<String>,
     workspace: Option<String>,
     include_stale: Option<bool>,
+    reader: Option<String>,
+    page: Option<PageRequest>,
 ) -> Result<Vec<MemoryRecord>, String> {
     let store = open_store()?;
     let scope = scope
@@ -69,21 +95,46 @@ pub fn hub_list_memories(
         .map(MemoryTier::parse)
         .transpose()
         .map_err(|e| e.to_string())?;
-    store
-        .list_memories(
+    let reader = memory_reader(reader);
+    let include_stale = include_stale.unwrap_or(false);
+    // A page lists newest first; without one, results rank by relevance.
+    match page {
+        Some(page) => store
+            .list_memories_page_as(
+                Some(&reader),
+                scope,
+                tier,
+                workspace.as_deref(),
+                include_stale,
+                &page,
+            )
+            .map(|page| page.items),
+        None => store.list_memories_as(
+            Some(&reader),
             scope,
             tier,
             workspace.as_deref(),
-            include_stale.unwrap_or(false),
-        )
-        .map_err(|e| e.to_string())
+            include_stale,
+        ),
+    }
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn hub_search_memories(query: String) -> Result<Vec<MemoryRecord>, String> {
-    open_store()?
-        .search_memories(&query)
-        .map_err(|e| e.to_string())
+pub fn hub_search_memories(
+    query: String,
+    reader: Option<String>,
+    page: Option<PageRequest>,
+) -> Result<Vec<MemoryRecord>, String> {
+    let store = open_store()?;
+    let reader = memory_reader(reader);
+    match page {
+        Some(page) => store
+            .search_memories_page_as(Some(&reader), &query, &page)
+            .map(|page| page.items),
+        None => store.search_memories_as(Some(&reader), &query),
+    }
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -106,9 +157,9 @@ pub fn hub_promote_memory(id: String, to_tier: String) -> Result<MemoryRecord, S
 }
 
 #[tauri::command]
-pub fn hub_compact_short_term(keep_newest: Option<usize>) -> Result<CompactReport, String> {
+pub fn hub_compact_short_term(keep: Option<usize>) -> Result<CompactReport, String> {
     open_store()?
-        .compact_short_term(keep_newest.unwrap_or(50))
+        .compact_short_term(keep.unwrap_or(50))
         .map_err(|e| e.to_string())
 }
 
@@ -152,9 +203,11 @@ pub fn hub_list_memory_links(memory_id: String) -> Result<Vec<MemoryLinkRecord>,
 pub fn hub_related_memories(
     memory_id: String,
     depth: Option<u8>,
+    reader: Option<String>,
 ) -> Result<Vec<MemoryRecord>, String> {
+    let reader = memory_reader(reader);
     open_store()?
-        .related_memories(&memory_id, depth.unwrap_or(1))
+        .related_memories(Some(&reader), &memory_id, depth.unwrap_or(1))
         .map_err(|e| e.to_string())
 }
 
@@ -2,7 +2,8 @@
 use super::store::open_store;
 use hub::{
     AuditEvent, ChannelRecord, GitExportOutcome, HubStore, MemoryRecord, MessageKind,
-    MessageRecord, MessageStatus, ReadMarker, SettingsStore, WakePolicy, WakeRecord, WakeStatus,
+    MessageRecord, MessageStatus, PageRequest, ReadMarker, SettingsStore, WakePolicy, WakeRecord,
+    WakeStatus,
 };
 
 /// S5 / #131: exports are gated by Settings' global `export_enabled` policy.
@@ -23,6 +24,7 @@ pub struct SendMessageArgs {
     pub workspace: Option<String>,
     pub task: Option<String>,
     pub body: String,
+    pub payload: Option<serde_json::Value>,
 }
 
 #[tauri::command]
@@ -48,6 +50,9 @@ pub(crate) fn hub_send_message_blocking(args: SendMessageArgs) -> Result<Message
         );
     }
     if args.to == "team" {
+        if args.payload.is_some() {
+            return Err("a payload needs a single recipient, not team".into());
+        }
         return store
             .send_message_to_team(
                 &args.from,
@@ -63,11 +68,12 @@ pub(crate) fn hub_send_message_blocking(args: SendMessageArgs) -> Result<Message
             .ok_or_else(|| "team message produced no recipient records".to_string());
     }
     store
-        .send_message(
+        .send_message_with_payload(
             &args.from,
             &args.to,
             kind,
             &args.body,
+            args.payload.as_ref(),
             args.subject.as_deref(),
             args.workspace.as_deref(),
             args.task.as_deref(),
@@ -173,10 +179,13 @@ pub fn hub_poll_messages(
         .map_err(|e| e.to_string())
 }
 
+/// Without `page`, the newest 200 messages; with it, one keyset page (see
+/// `hub::PageRequest`) so older history stays reachable.
 #[tauri::command]
 pub fn hub_list_messages(
     to: Option<String>,
     status: Option<String>,
+    page: Option<PageRequest>,
 ) -> Result<Vec<MessageRecord>, String> {
     let store = open_store()?;
     let status = status
@@ -184,9 +193,13 @@ pub fn hub_list_messages(
         .map(MessageStatus::parse)
         .transpose()
         .map_err(|e| e.to_string())?;
-    store
-        .list_messages(to.as_deref(), status)
-        .map_err(|e| e.to_string())
+    match page {
+        Some(page) => store
+            .list_messages_page(to.as_deref(), status, &page)
+            .map(|page| page.items),
+        None => store.list_messages(to.as_deref(), status),
+    }
+    .map_err(|e| e.to_string())
 }
 
 /// Records that `agent` has read `scope` (a channel id, work session id, or
@@ -233,10 +246,16 @@ pub fn hub_delete_channel(id: String) -> Result<(), String> {
 pub fn hub_list_channel_messages(
     channel: String,
     limit: Option<usize>,
+    page: Option<PageRequest>,
 ) -> Result<Vec<MessageRecord>, String> {
-    open_store()?
-        .list_channel_messages(&channel, limit.unwrap_or(100))
-        .map_err(|error| error.to_string())
+    let store = open_store()?;
+    match page {
+        Some(page) => store
+            .list_channel_messages_page(&channel, &page)
+            .map(|page| page.items),
+        None => store.list_channel_messages(&channel, limit.unwrap_or(100)),
+    }
+    .map_err(|error| error.to_string())
 }
 
 #[tauri::command]
@@ -320,10 +339,17 @@ pub fn hub_request_wake(
 pub fn hub_list_wakes(
     target: Option<String>,
     pending_only: Option<bool>,
+    page: Option<PageRequest>,
 ) -> Result<Vec<WakeRecord>, String> {
-    open_store()?
-        .list_wakes(target.as_deref(), pending_only.unwrap_or(false))
-        .map_err(|e| e.to_string())
+    let store = open_store()?;
+    let pending_only = pending_only.unwrap_or(false);
+    match page {
+        Some(page) => store
+            .list_wakes_page(target.as_deref(), pending_only, &page)
+            .map(|page| page.items),
+        None => store.list_wakes(target.as_deref(), pending_only),
+    }
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -350,11 +376,10 @@ pub fn hub_export_markdown_git(message: Option<String>) -> Result<GitExportOutco
 }
 
 #[tauri::command]
-pub fn hub_append_journal(agent: String, entry: String) -> Result<String, String> {
-    let path = open_store()?
+pub fn hub_append_journal(agent: String, entry: String) -> Result<MemoryRecord, String> {
+    open_store()?
         .append_private_journal(&agent, &entry)
-        .map_err(|e| e.to_string())?;
-    Ok(path.display().to_string())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -433,10 +458,19 @@ pub fn hub_resolve_wake(id: String, status: String) -> Result<(), String> {
 /// opens (`hub::HubStore::list_audit_events`, already implemented — this
 /// just exposes it, plus approve/quarantine, to the Tauri IPC boundary).
 #[tauri::command]
-pub fn hub_list_audit_events(pending_only: Option<bool>) -> Result<Vec<AuditEvent>, String> {
-    open_store()?
-        .list_audit_events(pending_only.unwrap_or(false))
-        .map_err(|e| e.to_string())
+pub fn hub_list_audit_events(
+    pending_only: Option<bool>,
+    page: Option<PageRequest>,
+) -> Result<Vec<AuditEvent>, String> {
+    let store = open_store()?;
+    let pending_only = pending_only.unwrap_or(false);
+    match page {
+        Some(page) => store
+            .list_audit_events_page(pending_only, &page)
+            .map(|page| page.items),
+        None => store.list_audit_events(pending_only),
+    }
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
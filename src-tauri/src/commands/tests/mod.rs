@@ -58,6 +58,7 @@ fn hub_send_message_rejects_untagged_wake_kind() {
         workspace: None,
         task: None,
         body: "must not bypass tagged send".into(),
+        payload: None,
     })
     .expect_err("untagged wake must be rejected");
     assert!(error.contains("hub_send_tagged_message"), "{error}");
@@ -101,12 +102,14 @@ fn tauri_hub_commands_retrieve_what_the_store_wrote() {
         None,
         Some("Coding-Assistants".into()),
         None,
+        None,
+        None,
     )
     .expect("hub_list_memories should succeed");
     assert_eq!(listed.len(), 1);
     assert_eq!(listed[0].title.as_deref(), Some("M6 desktop-layer check"));
 
-    let found = hub_search_memories("desktop-layer check".into())
+    let found = hub_search_memories("desktop-layer check".into(), None, None)
         .expect("hub_search_memories should succeed");
     assert_eq!(found.len(), 1);
     assert_eq!(found[0].id, listed[0].id);
@@ -160,7 +163,7 @@ fn ca102_hub_commands_return_only_the_requested_channel_and_linked_memories() {
         )
         .expect("send_message should succeed");
 
-    let channel = hub_list_channel_messages("general".into(), Some(10))
+    let channel = hub_list_channel_messages("general".into(), Some(10), None)
         .expect("hub_list_channel_messages should succeed");
     assert_eq!(channel.len(), 1);
     assert_eq!(channel[0].id, general.id);
@@ -269,7 +272,7 @@ fn ca111_audit_tab_lists_pending_and_can_approve_or_quarantine() {
         )
         .expect("record_audit_event should succeed");
 
-    let pending = hub_list_audit_events(Some(true)).expect("hub_list_audit_events should succeed");
+    let pending = hub_list_audit_events(Some(true), None).expect("hub_list_audit_events should succeed");
     assert_eq!(pending.len(), 2);
     assert!(pending.iter().all(|e| e.status == "pending"));
 
@@ -277,10 +280,10 @@ fn ca111_audit_tab_lists_pending_and_can_approve_or_quarantine() {
     hub_quarantine_audit(to_quarantine.id.clone()).expect("hub_quarantine_audit should succeed");
 
     let remaining_pending =
-        hub_list_audit_events(Some(true)).expect("hub_list_audit_events should succeed");
+        hub_list_audit_events(Some(true), None).expect("hub_list_audit_events should succeed");
     assert!(remaining_pending.is_empty(), "{remaining_pending:?}");
 
-    let all = hub_list_audit_events(Some(false)).expect("hub_list_audit_events should succeed");
+    let all = hub_list_audit_events(Some(false), None).expect("hub_list_audit_events should succeed");
     assert_eq!(
         all.iter().find(|e| e.id == watched.id).unwrap().status,
         "approved"
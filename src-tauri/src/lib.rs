@@ -317,6 +317,10 @@ pub fn run() {
                 })
                 .build(app)?;
 
+            commands::commands::retention::spawn_retention_sweeper();
+            commands::commands::escalations::spawn_escalation_runner();
+            commands::commands::webhooks::spawn_webhook_runner();
+
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -383,10 +387,18 @@ pub fn run() {
             commands::commands::messaging::hub_list_messages,
             commands::commands::messaging::hub_mark_read,
             commands::commands::messaging::hub_list_read_markers,
+            commands::commands::inbox::hub_unread_summary,
+            commands::commands::inbox::hub_list_mentions,
+            commands::commands::inbox::hub_search_messages,
+            commands::commands::inbox::hub_message_receipts,
             commands::commands::messaging::hub_list_channels,
             commands::commands::messaging::hub_create_channel,
             commands::commands::messaging::hub_delete_channel,
             commands::commands::messaging::hub_list_channel_messages,
+            commands::commands::archive::hub_set_archived,
+            commands::commands::archive::hub_list_archived_messages,
+            commands::commands::archive::hub_list_archived_channels,
+            commands::commands::archive::hub_list_archived_work_sessions,
             commands::commands::messaging::hub_list_message_memories,
             commands::commands::messaging::hub_request_wake,
             commands::commands::messaging::hub_request_team_wakes,
@@ -426,6 +438,22 @@ pub fn run() {
             commands::commands::messaging::hub_append_journal,
             commands::commands::messaging::hub_purge_stale_memories,
             commands::commands::messaging::hub_age_out_short_term,
+            commands::commands::retention::hub_sweep_retention,
+            commands::commands::escalations::hub_escalation_config,
+            commands::commands::escalations::hub_set_escalation_config,
+            commands::commands::escalations::hub_list_escalations,
+            commands::commands::questions::hub_list_questions,
+            commands::commands::questions::hub_ask_question,
+            commands::commands::questions::hub_answer_question,
+            commands::commands::webhooks::hub_list_webhooks,
+            commands::commands::webhooks::hub_upsert_webhook,
+            commands::commands::webhooks::hub_remove_webhook,
+            commands::commands::webhooks::hub_list_webhook_deliveries,
+            commands::commands::webhooks::hub_webhook_attempts,
+            commands::commands::hooks::hub_list_hooks,
+            commands::commands::hooks::hub_list_hook_runs,
+            commands::commands::hooks::hub_hook_config,
+            commands::commands::hooks::hub_set_hook_config,
             commands::commands::messaging::hub_set_message_status,
             commands::commands::messaging::hub_update_message,
             commands::commands::messaging::hub_delete_message,
@@ -490,6 +518,7 @@ pub fn run() {
             commands::commands::roles::hub_resolve_gate_approval,
             commands::commands::attachments::hub_save_attachment,
             commands::commands::attachments::hub_get_attachment,
+            commands::commands::attachments::hub_search_attachments,
             commands::commands::avatar::hub_set_agent_avatar,
             commands::commands::avatar::hub_clear_agent_avatar,
             commands::commands::avatar::hub_read_avatar_preview,
@@ -2,7 +2,7 @@ import { startTransition, useCallback, useEffect, useState } from "react";
 import { invoke } from "../../lib/tauri";
 import { LIVE_QUOTA_AGENT_IDS } from "./hub/HubCharts";
 import HubPanelView from "./hub/HubPanelView";
-import type { AgentRecord, AuditEvent, BudgetStatus, ChannelWorkspace, HubTab, MemoryRecord, MessageRecord, ProviderQuota, WakeRecord } from "./hub/types";
+import type { AgentRecord, AttachmentSearchHit, AuditEvent, BudgetStatus, ChannelWorkspace, HubTab, MemoryRecord, MessageRecord, ProviderQuota, WakeRecord } from "./hub/types";
 
 export default function HubPanel() {
   const [hubTab, setHubTab] = useState<HubTab>("dashboard");
@@ -18,6 +18,8 @@ export default function HubPanel() {
   const [auditShowAll, setAuditShowAll] = useState(false);
 
   const [searchQ, setSearchQ] = useState("");
+  const [includeAttachments, setIncludeAttachments] = useState(false);
+  const [attachmentHits, setAttachmentHits] = useState<AttachmentSearchHit[]>([]);
   const [tierFilter, setTierFilter] = useState<string>("");
   const [memTitle, setMemTitle] = useState("");
   const [memBody, setMemBody] = useState("");
@@ -274,6 +276,7 @@ export default function HubPanel() {
 
   const searchMemories = async () => {
     if (!searchQ.trim()) {
+      setAttachmentHits([]);
       await refreshMemories();
       return;
     }
@@ -281,6 +284,12 @@ export default function HubPanel() {
       invoke<MemoryRecord[]>("hub_search_memories", { query: searchQ })
     );
     if (list) setMemories(list);
+    const hits = includeAttachments
+      ? await run("search done", () =>
+          invoke<AttachmentSearchHit[]>("hub_search_attachments", { query: searchQ })
+        )
+      : [];
+    setAttachmentHits(hits ?? []);
   };
 
   const sendMessage = async () => {
@@ -381,5 +390,5 @@ export default function HubPanel() {
 
   const grokWorkspace = typeof localStorage !== "undefined" ? (localStorage.getItem("ca.workspaceRoot") || "") : "";
 
-  return <HubPanelView {...{ hubTab, dataDir, error, status, setStatus, tabBtn, auditEvents, setAuditShowAll, auditShowAll, refreshAuditEvents, approveAudit, quarantineAudit, memories, searchQ, setSearchQ, searchMemories, refreshMemories, tierFilter, setTierFilter, memTier, setMemTier, memAgent, setMemAgent, memTitle, setMemTitle, memBody, setMemBody, writeMemory, editingMemory, setEditingMemory, editTitle, setEditTitle, editBody, setEditBody, saveEditedMemory, run, invoke, agents, inboxConversation, setInboxConversation, setMsgTo, setPollTo, unreadFor, msgFrom, setMsgFrom, msgTo, msgKind, setMsgKind, msgSubject, setMsgSubject, msgBody, setMsgBody, sendMessage, pollTo, markConversationRead, refreshMessages, inboxSearch, setInboxSearch, inboxMessages, wakeTarget, setWakeTarget, wakeReason, setWakeReason, requestWake, refreshWakes, wakes, budgetAgent, setBudgetAgent, budgetLimit, setBudgetLimit, setBudget, refreshBudgets, refreshQuotas, refreshStaleQuotas, budgets, quotas, refreshingQuotaIds, refreshSingleQuota, budgetSpend, setBudgetSpend, recordSpend, resumeBudget, channelWorkspaces, channelRenameDrafts, setChannelRenameDrafts, renameChannelWorkspace, deleteChannelWorkspace, refreshChannelWorkspaces, channelConnected, channelConnecting, connectChannelWorkspace, grokWorkspace }} />;
+  return <HubPanelView {...{ hubTab, dataDir, error, status, setStatus, tabBtn, auditEvents, setAuditShowAll, auditShowAll, refreshAuditEvents, approveAudit, quarantineAudit, memories, searchQ, setSearchQ, searchMemories, includeAttachments, setIncludeAttachments, attachmentHits, refreshMemories, tierFilter, setTierFilter, memTier, setMemTier, memAgent, setMemAgent, memTitle, setMemTitle, memBody, setMemBody, writeMemory, editingMemory, setEditingMemory, editTitle, setEditTitle, editBody, setEditBody, saveEditedMemory, run, invoke, agents, inboxConversation, setInboxConversation, setMsgTo, setPollTo, unreadFor, msgFrom, setMsgFrom, msgTo, msgKind, setMsgKind, msgSubject, setMsgSubject, msgBody, setMsgBody, sendMessage, pollTo, markConversationRead, refreshMessages, inboxSearch, setInboxSearch, inboxMessages, wakeTarget, setWakeTarget, wakeReason, setWakeReason, requestWake, refreshWakes, wakes, budgetAgent, setBudgetAgent, budgetLimit, setBudgetLimit, setBudget, refreshBudgets, refreshQuotas, refreshStaleQuotas, budgets, quotas, refreshingQuotaIds, refreshSingleQuota, budgetSpend, setBudgetSpend, recordSpend, resumeBudget, channelWorkspaces, channelRenameDrafts, setChannelRenameDrafts, renameChannelWorkspace, deleteChannelWorkspace, refreshChannelWorkspaces, channelConnected, channelConnecting, connectChannelWorkspace, grokWorkspace }} />;
 }
@@ -0,0 +1,35 @@
+import type { AttachmentSearchHit } from "./types";
+import { cardStyle } from "./HubCharts";
+
+/** Attachment matches shown above memory results when the memory search
+ * includes attachments. Each hit lists the messages that embed it. */
+export default function AttachmentHits({ hits }: { hits: AttachmentSearchHit[] }) {
+  if (hits.length === 0) return null;
+  return (
+    <div style={{ display: "flex", flexDirection: "column", gap: "0.75rem" }}>
+      <strong style={{ fontSize: "0.9rem", color: "var(--text-muted)" }}>Attachments ({hits.length})</strong>
+      {hits.map((hit) => (
+        <div key={hit.attachment.id} style={cardStyle}>
+          <div style={{ display: "flex", gap: "0.5rem", alignItems: "center", marginBottom: "0.4rem" }}>
+            <span>📎</span>
+            <strong style={{ color: "var(--primary)" }}>{hit.attachment.filename}</strong>
+            <span style={{ fontSize: "0.7rem", padding: "0.1rem 0.4rem", background: "rgba(255,255,255,0.1)", borderRadius: "4px" }}>
+              {hit.text_mime || hit.attachment.mime}
+            </span>
+            {hit.truncated && <span style={{ fontSize: "0.7rem", color: "var(--text-muted)" }}>(head only)</span>}
+          </div>
+          <p style={{ margin: 0, fontSize: "0.85rem", fontFamily: "monospace", whiteSpace: "pre-wrap" }}>{hit.snippet}</p>
+          {hit.messages.length > 0 && (
+            <ul style={{ margin: "0.5rem 0 0", paddingLeft: "1.2rem", fontSize: "0.8rem", color: "var(--text-muted)" }}>
+              {hit.messages.map((m) => (
+                <li key={m.id}>
+                  {m.from_agent} → {m.to_agent} · {m.subject || m.body.slice(0, 60)} · {new Date(m.created_at).toLocaleString()}
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      ))}
+    </div>
+  );
+}
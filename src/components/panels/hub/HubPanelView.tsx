@@ -1,11 +1,12 @@
 // @ts-nocheck -- the view consumes the HubPanel's local interaction contract.
 import TaskTab from "../../TaskTab";
 import DashboardPanel from "../DashboardPanel";
+import AttachmentHits from "./AttachmentHits";
 import ChannelsTab from "./ChannelsTab";
 import { UsageChart, QuotaChart, cardStyle, inputStyle } from "./HubCharts";
 
 export default function HubPanelView(props: any) {
-  const { hubTab, dataDir, error, status, tabBtn, auditEvents, setAuditShowAll, auditShowAll, refreshAuditEvents, approveAudit, quarantineAudit, memories, searchQ, setSearchQ, searchMemories, refreshMemories, memTier, setMemTier, memAgent, setMemAgent, memTitle, setMemTitle, memBody, setMemBody, writeMemory, editingMemory, setEditingMemory, editTitle, setEditTitle, editBody, setEditBody, saveEditedMemory, run, invoke, agents, inboxConversation, setInboxConversation, setMsgTo, setPollTo, unreadFor, msgFrom, setMsgFrom, msgTo, msgKind, setMsgKind, msgSubject, setMsgSubject, msgBody, setMsgBody, sendMessage, pollTo, markConversationRead, refreshMessages, inboxSearch, setInboxSearch, inboxMessages, wakeTarget, setWakeTarget, wakeReason, setWakeReason, requestWake, refreshWakes, wakes, budgetAgent, setBudgetAgent, budgetLimit, setBudgetLimit, setBudget, refreshBudgets, refreshQuotas, refreshStaleQuotas, budgets, quotas, refreshingQuotaIds, refreshSingleQuota, budgetSpend, setBudgetSpend, recordSpend, resumeBudget, channelWorkspaces, channelRenameDrafts, setChannelRenameDrafts, renameChannelWorkspace, deleteChannelWorkspace, refreshChannelWorkspaces, channelConnected, channelConnecting, connectChannelWorkspace, grokWorkspace } = props;
+  const { hubTab, dataDir, error, status, tabBtn, auditEvents, setAuditShowAll, auditShowAll, refreshAuditEvents, approveAudit, quarantineAudit, memories, searchQ, setSearchQ, searchMemories, includeAttachments, setIncludeAttachments, attachmentHits, refreshMemories, memTier, setMemTier, memAgent, setMemAgent, memTitle, setMemTitle, memBody, setMemBody, writeMemory, editingMemory, setEditingMemory, editTitle, setEditTitle, editBody, setEditBody, saveEditedMemory, run, invoke, agents, inboxConversation, setInboxConversation, setMsgTo, setPollTo, unreadFor, msgFrom, setMsgFrom, msgTo, msgKind, setMsgKind, msgSubject, setMsgSubject, msgBody, setMsgBody, sendMessage, pollTo, markConversationRead, refreshMessages, inboxSearch, setInboxSearch, inboxMessages, wakeTarget, setWakeTarget, wakeReason, setWakeReason, requestWake, refreshWakes, wakes, budgetAgent, setBudgetAgent, budgetLimit, setBudgetLimit, setBudget, refreshBudgets, refreshQuotas, refreshStaleQuotas, budgets, quotas, refreshingQuotaIds, refreshSingleQuota, budgetSpend, setBudgetSpend, recordSpend, resumeBudget, channelWorkspaces, channelRenameDrafts, setChannelRenameDrafts, renameChannelWorkspace, deleteChannelWorkspace, refreshChannelWorkspaces, channelConnected, channelConnecting, connectChannelWorkspace, grokWorkspace } = props;
 
 
   return (
@@ -49,6 +50,10 @@ export default function HubPanelView(props: any) {
               onBlur={e => e.target.style.borderColor = 'var(--border-color)'}
             />
             <button className="btn-secondary" onClick={searchMemories}>Search</button>
+            <label style={{ display: "flex", gap: "0.35rem", alignItems: "center", fontSize: "0.85rem", color: "var(--text-muted)" }}>
+              <input type="checkbox" checked={includeAttachments} onChange={(e) => setIncludeAttachments(e.target.checked)} />
+              Include attachments
+            </label>
             <select value={tierFilter} onChange={(e) => setTierFilter(e.target.value)} style={inputStyle}>
               <option value="">All tiers</option>
               <option value="short_term">short_term</option>
@@ -59,7 +64,7 @@ export default function HubPanelView(props: any) {
             <button
               className="btn-secondary"
               onClick={async () => {
-                await run("compacted", () => invoke("hub_compact_short_term", { keepNewest: 20 }));
+                await run("compacted", () => invoke("hub_compact_short_term", { keep: 20 }));
                 await refreshMemories();
               }}
             >
@@ -121,6 +126,8 @@ export default function HubPanelView(props: any) {
             </div>
           </div>
 
+          <AttachmentHits hits={attachmentHits} />
+
           <div style={{ display: "flex", flexDirection: "column", gap: "1rem", maxHeight: 500, overflowY: "auto", paddingRight: "0.5rem" }}>
             {memories.length === 0 && (
               <div style={{ padding: "3rem", textAlign: "center", background: "rgba(0,0,0,0.2)", borderRadius: "12px", border: "1px dashed var(--border-color)" }}>
@@ -229,6 +236,7 @@ export default function HubPanelView(props: any) {
               <select value={msgKind} onChange={(e) => setMsgKind(e.target.value)} style={{ ...inputStyle, marginLeft: "auto" }}>
                 <option value="message">message</option>
                 <option value="handoff">handoff</option>
+                <option value="question">question</option>
                 <option value="system">system</option>
               </select>
             </div>
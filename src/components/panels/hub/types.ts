@@ -21,6 +21,70 @@ export interface MessageRecord {
   subject?: string | null;
   body: string;
   created_at: string;
+  archived_at?: string | null;
+  reply_to?: string | null;
+  thread_id?: string | null;
+  payload?: unknown;
+}
+
+export interface UnreadScope {
+  scope: string;
+  kind: "channel" | "session" | "dm";
+  unread: number;
+  first_unread_id?: string | null;
+  mentions: number;
+}
+
+export interface UnreadSummary {
+  agent_id: string;
+  scopes: UnreadScope[];
+  unread: number;
+  mentions: number;
+}
+
+export interface MessageSearch {
+  query: string;
+  from_agent?: string | null;
+  to_agent?: string | null;
+  scope?: string | null;
+  kind?: string | null;
+  task_id?: string | null;
+  workspace_path?: string | null;
+  since?: string | null;
+  until?: string | null;
+  limit?: number;
+}
+
+export interface MessageSearchHit {
+  message: MessageRecord;
+  snippet: string;
+  score: number;
+}
+
+export type DeliveryState = "queued" | "injected" | "read" | "acked";
+
+export interface DeliveryReceipt {
+  message_id: string;
+  to_agent: string;
+  state: DeliveryState;
+  status: string;
+  inject_status: string | null;
+  inject_detail: string | null;
+  injected_at: string | null;
+  read_at: string | null;
+  acked_at: string | null;
+}
+
+export interface ReceiptSummary {
+  message_id: string;
+  from_agent: string;
+  subject: string | null;
+  recipients: number;
+  queued: number;
+  injected: number;
+  read: number;
+  acked: number;
+  receipts: DeliveryReceipt[];
 }
 
 export interface WakeRecord {
@@ -102,3 +166,112 @@ export interface GrokConnectResult {
   live_standalone?: ActiveGrokSession | null;
   detail: string;
 }
+
+export interface AttachmentSearchHit {
+  attachment: { id: string; filename: string; mime: string; byte_size: number };
+  text_mime?: string | null;
+  snippet: string;
+  truncated: boolean;
+  messages: MessageRecord[];
+}
+
+export interface EscalationConfig {
+  ack_within_minutes: Record<string, number>;
+}
+
+export interface EscalationRecord {
+  id: string;
+  message_id: string;
+  level: number;
+  step: "rewake" | "allocators" | "human";
+  notified: string[];
+  notice_ids: string[];
+  wake_id: string | null;
+  error: string | null;
+  created_at: string;
+}
+
+export interface QuestionRecord {
+  question: MessageRecord;
+  choices: string[];
+  deadline: string | null;
+  state: "open" | "answered" | "expired";
+  answer: MessageRecord | null;
+}
+
+export interface QuestionAnswer {
+  question: QuestionRecord;
+  delivery: { harness: string; pid: number | null; status: string; detail: string } | null;
+  delivery_error: string | null;
+}
+
+export type WebhookEvent =
+  | 'message_to_human'
+  | 'gate_approval_pending'
+  | 'budget_paused'
+  | 'task_failed';
+
+export interface WebhookSink {
+  name: string;
+  url: string;
+  events: WebhookEvent[];
+  template: string | null;
+  secret: { kind: 'env_var'; name: string } | { kind: 'key_file'; path: string } | null;
+  enabled: boolean;
+}
+
+export interface WebhookDelivery {
+  id: string;
+  sink: string;
+  event: WebhookEvent;
+  payload: unknown;
+  status: 'pending' | 'delivered' | 'failed';
+  attempts: number;
+  next_attempt_at: string | null;
+  last_status_code: number | null;
+  last_error: string | null;
+  created_at: string;
+  delivered_at: string | null;
+}
+
+export interface WebhookAttempt {
+  delivery_id: string;
+  attempt: number;
+  status_code: number | null;
+  error: string | null;
+  attempted_at: string;
+}
+
+export type HookEvent =
+  | 'pre-send'
+  | 'post-send'
+  | 'pre-task-advance'
+  | 'post-task-advance'
+  | 'wake-resolved'
+  | 'memory-written'
+  | 'audit-event';
+
+export interface HookScript {
+  event: HookEvent;
+  path: string;
+  scope: 'hub' | 'workspace';
+}
+
+export interface HookRun {
+  id: string;
+  event: HookEvent;
+  hook_path: string;
+  exit_code: number | null;
+  timed_out: boolean;
+  vetoed: boolean;
+  output: string;
+  started_at: string;
+  duration_ms: number;
+}
+
+export interface HookConfig {
+  enabled: boolean;
+  workspace_hooks: boolean;
+  trusted_workspaces: string[];
+  timeout_secs: number;
+}
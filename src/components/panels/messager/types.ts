@@ -122,6 +122,7 @@ export interface AttachmentRecord {
   byte_size: number;
   absolute_path: string;
   created_at: string;
+  sha256?: string | null;
 }
 
 export interface AttachmentPayload {
@@ -19,12 +19,14 @@ export type SettingsField =
 export type SandboxStrictness = "strict" | "standard" | "permissive";
 
 // Mirrors `hub::SecretSourceKind`. Never a credential value.
-export type SecretSourceKind = "keychain" | "env_var" | "provider_login";
+export type SecretSourceKind = "keychain" | "env_var" | "key_file" | "provider_login";
 
-// Mirrors `hub::SecretReference`. Keychain ids and env-var *names* only.
+// Mirrors `hub::SecretReference`. Keychain ids, env-var *names* and key-file
+// paths only.
 export type SecretReference =
   | { kind: "keychain"; id: string }
   | { kind: "env_var"; name: string }
+  | { kind: "key_file"; path: string }
   | { kind: "provider_login" };
 
 // Mirrors `hub::ProfileSnapshot`.